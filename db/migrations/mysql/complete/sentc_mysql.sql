@@ -112,7 +112,8 @@ CREATE TABLE `sentc_app_group_options`
 (
 	`app_id`                 varchar(36) NOT NULL,
 	`max_key_rotation_month` int(11)     NOT NULL,
-	`min_rank_key_rotation`  int(11)     NOT NULL
+	`min_rank_key_rotation`  int(11)     NOT NULL,
+	`require_signed_keys`    int(11)     NOT NULL DEFAULT 0 COMMENT '1 = public keys must be signed by the verify key of the group'
 ) ENGINE = InnoDB
   DEFAULT CHARSET = utf8mb4
   COLLATE = utf8mb4_general_ci;
@@ -130,7 +131,8 @@ CREATE TABLE `sentc_app_jwt_keys`
 	`sign_key`   text        NOT NULL,
 	`verify_key` text        NOT NULL,
 	`alg`        text        NOT NULL,
-	`time`       bigint(20)  NOT NULL
+	`time`       bigint(20)  NOT NULL,
+	`retire_at`  bigint(20)  NOT NULL DEFAULT 0 COMMENT 'the key is deleted after this time, 0 = active'
 ) ENGINE = InnoDB
   DEFAULT CHARSET = utf8mb4
   COLLATE = utf8mb4_general_ci COMMENT ='multiple per app';
@@ -314,7 +316,9 @@ CREATE TABLE `sentc_file`
 	`status`              int(11)     NOT NULL COMMENT '0 = to delete, 1 = avalible, 2 = disabled',
 	`delete_at`           bigint(20)  NOT NULL COMMENT '0 = not deleted, time when the file was deleted',
 	`time`                bigint(20)  NOT NULL,
-	`encrypted_key_alg`   text        NOT NULL
+	`encrypted_key_alg`   text        NOT NULL,
+	`expires_at`          bigint(20)  NOT NULL DEFAULT 0 COMMENT 'time in ms when the file is deleted, 0 = never',
+	`version`             int(11)     NOT NULL DEFAULT 1 COMMENT 'the current version of the file'
 ) ENGINE = InnoDB
   DEFAULT CHARSET = utf8mb4
   COLLATE = utf8mb4_general_ci;
@@ -347,10 +351,21 @@ CREATE TRIGGER `file_session_delete`
 
 CREATE TABLE `sentc_file_options`
 (
-	`app_id`       varchar(36) NOT NULL,
-	`file_storage` int(11)     NOT NULL COMMENT '0 = our backend; 1 = customer backend',
-	`storage_url`  text        DEFAULT NULL COMMENT 'when file_storage != 0',
-	`auth_token`   varchar(50) DEFAULT NULL
+	`app_id`            varchar(36) NOT NULL,
+	`file_storage`      int(11)     NOT NULL COMMENT '0 = our backend; 1 = customer backend',
+	`storage_url`       text        DEFAULT NULL COMMENT 'when file_storage != 0',
+	`auth_token`        varchar(50) DEFAULT NULL,
+	`max_storage_app`   bigint(20)  NOT NULL DEFAULT 0 COMMENT 'max bytes for the app, 0 = no limit',
+	`max_storage_user`  bigint(20)  NOT NULL DEFAULT 0 COMMENT 'max bytes for each user, 0 = no limit',
+	`max_storage_group` bigint(20)  NOT NULL DEFAULT 0 COMMENT 'max bytes for each group, 0 = no limit',
+	`max_files_app`     int(11)     NOT NULL DEFAULT 0 COMMENT 'max files for the app, 0 = no limit',
+	`max_files_user`    int(11)     NOT NULL DEFAULT 0 COMMENT 'max files for each user, 0 = no limit',
+	`max_files_group`   int(11)     NOT NULL DEFAULT 0 COMMENT 'max files for each group, 0 = no limit',
+	`session_lifetime`  int(11)     NOT NULL DEFAULT 86400 COMMENT 'seconds until an upload session expires',
+	`default_ttl`       bigint(20)  NOT NULL DEFAULT 0 COMMENT 'seconds until a new file expires, 0 = never',
+	`max_ttl`           bigint(20)  NOT NULL DEFAULT 0 COMMENT 'max seconds until a file expires, 0 = no limit',
+	`version_keep_last` int(11)     NOT NULL DEFAULT 0 COMMENT 'old versions of a file to keep, 0 = all',
+	`version_keep_days` int(11)     NOT NULL DEFAULT 0 COMMENT 'days until an old version is deleted, 0 = never'
 ) ENGINE = InnoDB
   DEFAULT CHARSET = utf8mb4
   COLLATE = utf8mb4_general_ci;
@@ -363,12 +378,15 @@ CREATE TABLE `sentc_file_options`
 
 CREATE TABLE `sentc_file_part`
 (
-	`id`       varchar(36) NOT NULL,
-	`file_id`  varchar(36) NOT NULL,
-	`app_id`   varchar(36) NOT NULL,
-	`size`     bigint(20)  NOT NULL COMMENT 'only set when using our backend',
-	`sequence` int(11)     NOT NULL,
-	`extern`   tinyint(1)  NOT NULL
+	`id`        varchar(36) NOT NULL,
+	`file_id`   varchar(36) NOT NULL,
+	`app_id`    varchar(36) NOT NULL,
+	`size`      bigint(20)  NOT NULL COMMENT 'only set when using our backend',
+	`sequence`  int(11)     NOT NULL,
+	`extern`    tinyint(1)  NOT NULL,
+	`checksum`  varchar(64) DEFAULT NULL COMMENT 'sha256 of the encrypted part as hex',
+	`corrupted` tinyint(1)  NOT NULL DEFAULT 0 COMMENT 'set by the part check worker when the checksum does not match',
+	`version`   int(11)     NOT NULL DEFAULT 1
 ) ENGINE = InnoDB
   DEFAULT CHARSET = utf8mb4
   COLLATE = utf8mb4_general_ci;
@@ -386,7 +404,8 @@ CREATE TABLE `sentc_file_session`
 	`app_id`         varchar(36) NOT NULL,
 	`created_at`     bigint(20)  NOT NULL,
 	`expected_size`  int(11)     NOT NULL,
-	`max_chunk_size` int(11)     NOT NULL
+	`max_chunk_size` int(11)     NOT NULL,
+	`version`        int(11)     NOT NULL DEFAULT 1 COMMENT 'the version which is uploaded'
 ) ENGINE = InnoDB
   DEFAULT CHARSET = utf8mb4
   COLLATE = utf8mb4_general_ci;
@@ -493,7 +512,9 @@ CREATE TABLE `sentc_group_keys`
 	`signed_by_user_sign_key_id`     varchar(36) DEFAULT NULL,
 	`group_key_sig`                  text        DEFAULT NULL,
 	`public_key_sig`                 text        DEFAULT NULL,
-	`public_key_sig_key_id`          varchar(36) DEFAULT NULL COMMENT 'the key id which was used to create the sig'
+	`public_key_sig_key_id`          varchar(36) DEFAULT NULL COMMENT 'the key id which was used to create the sig',
+	`not_after`                      bigint(20)  DEFAULT NULL COMMENT 'the key should not be used after this time',
+	`key_status`                     int(11)     NOT NULL DEFAULT 0 COMMENT '0 = active; 1 = deprecated; 2 = compromised'
 ) ENGINE = InnoDB
   DEFAULT CHARSET = utf8mb4
   COLLATE = utf8mb4_general_ci;
@@ -619,7 +640,11 @@ CREATE TABLE `sentc_sym_key_management`
 	`creator_id`     varchar(36) NOT NULL,
 	`encrypted_key`  text        NOT NULL,
 	`master_key_alg` text        NOT NULL,
-	`time`           bigint(20)  NOT NULL
+	`time`           bigint(20)  NOT NULL,
+	`group_id`       varchar(36) DEFAULT NULL COMMENT 'only members of this group can fetch the key',
+	`label`          text        DEFAULT NULL,
+	`not_after`      bigint(20)  DEFAULT NULL COMMENT 'the key should not be used after this time',
+	`key_status`     int(11)     NOT NULL DEFAULT 0 COMMENT '0 = active; 1 = deprecated; 2 = compromised'
 ) ENGINE = InnoDB
   DEFAULT CHARSET = utf8mb4
   COLLATE = utf8mb4_general_ci COMMENT ='Symmetric key created by the sdk';
@@ -793,6 +818,480 @@ CREATE TABLE `test`
   DEFAULT CHARSET = utf8mb4
   COLLATE = utf8mb4_general_ci;
 
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_app_key_endpoint_options`
+--
+
+CREATE TABLE `sentc_app_key_endpoint_options`
+(
+	`app_id`           varchar(36)  NOT NULL,
+	`key_endpoint_url` text         DEFAULT NULL,
+	`auth_token`       varchar(100) DEFAULT NULL COMMENT 'to sign the requests to the key endpoint',
+	`timeout`          int(11)      NOT NULL COMMENT 'in ms',
+	`fallback`         int(11)      NOT NULL COMMENT '0 = no fallback; 1 = local key store'
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci;
+
+--
+-- Trigger `sentc_app` for `sentc_app_key_endpoint_options`
+--
+
+CREATE TRIGGER `delete_key_endpoint_options`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_app_key_endpoint_options
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_kt_leaf`
+--
+
+CREATE TABLE `sentc_kt_leaf`
+(
+	`app_id`     varchar(36)  NOT NULL,
+	`leaf_index` bigint(20)   NOT NULL,
+	`key_type`   int(11)      NOT NULL COMMENT '0 = device public key; 1 = device verify key; 2 = group public key; 3 = group verify key',
+	`owner_id`   varchar(36)  NOT NULL COMMENT 'user or group id',
+	`key_id`     varchar(36)  NOT NULL,
+	`key_hash`   varchar(100) NOT NULL,
+	`leaf_hash`  varchar(100) NOT NULL,
+	`time`       bigint(20)   NOT NULL
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='append only log for the key transparency';
+
+--
+-- Trigger `sentc_app` for `sentc_kt_leaf`
+--
+
+CREATE TRIGGER `delete_kt_leaf`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_kt_leaf
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_file_usage`
+--
+
+CREATE TABLE `sentc_file_usage`
+(
+	`app_id`       varchar(36) NOT NULL,
+	`usage_type`   int(11)     NOT NULL COMMENT '0 = app; 1 = user; 2 = group',
+	`id`           varchar(36) NOT NULL COMMENT 'app, user or group id',
+	`used_storage` bigint(20)  NOT NULL DEFAULT 0 COMMENT 'bytes',
+	`file_count`   int(11)     NOT NULL DEFAULT 0
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='usage counter for the file quotas';
+
+--
+-- Trigger `sentc_app` for `sentc_file_usage`
+--
+
+CREATE TRIGGER `delete_file_usage`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_file_usage
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_file_share`
+--
+
+CREATE TABLE `sentc_file_share`
+(
+	`file_id`           varchar(36) NOT NULL,
+	`app_id`            varchar(36) NOT NULL,
+	`share_to`          varchar(36) NOT NULL COMMENT 'user or group id',
+	`share_to_type`     int(11)     NOT NULL COMMENT '1 = group; 2 = user',
+	`shared_by`         varchar(36) NOT NULL,
+	`encrypted_key`     text        NOT NULL COMMENT 'the file key encrypted by a key of the user or group',
+	`encrypted_key_alg` text        NOT NULL,
+	`master_key_id`     varchar(36) NOT NULL COMMENT 'the key which encrypted the file key',
+	`time`              bigint(20)  NOT NULL
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='files which are shared with other users or groups';
+
+--
+-- Trigger for `sentc_file_share`
+--
+
+CREATE TRIGGER `file_delete_shares`
+	AFTER DELETE
+	ON `sentc_file`
+	FOR EACH ROW DELETE
+				 FROM sentc_file_share
+				 WHERE file_id = OLD.id
+;
+
+CREATE TRIGGER `user_delete_file_shares`
+	AFTER DELETE
+	ON `sentc_user`
+	FOR EACH ROW DELETE
+				 FROM sentc_file_share
+				 WHERE share_to = OLD.id
+;
+
+CREATE TRIGGER `group_delete_file_shares`
+	AFTER DELETE
+	ON `sentc_group`
+	FOR EACH ROW DELETE
+				 FROM sentc_file_share
+				 WHERE share_to = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_file_version`
+--
+
+CREATE TABLE `sentc_file_version`
+(
+	`file_id`   varchar(36) NOT NULL,
+	`version`   int(11)     NOT NULL,
+	`app_id`    varchar(36) NOT NULL,
+	`owner`     varchar(36) NOT NULL COMMENT 'the user who uploaded the version',
+	`time`      bigint(20)  NOT NULL,
+	`status`    int(11)     NOT NULL COMMENT '0 = to delete, 1 = available',
+	`delete_at` bigint(20)  NOT NULL COMMENT '0 = not deleted, time when the version was deleted'
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='all uploaded versions of a file';
+
+--
+-- Trigger for `sentc_file_version`
+--
+
+CREATE TRIGGER `file_delete_versions`
+	AFTER DELETE
+	ON `sentc_file`
+	FOR EACH ROW DELETE
+				 FROM sentc_file_version
+				 WHERE file_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_file_external_delete`
+--
+
+CREATE TABLE `sentc_file_external_delete`
+(
+	`part_id`    varchar(36) NOT NULL,
+	`app_id`     varchar(36) NOT NULL,
+	`time`       bigint(20)  NOT NULL COMMENT 'when the part was deleted',
+	`attempts`   int(11)     NOT NULL DEFAULT 0 COMMENT 'failed delete requests to the storage url',
+	`next_try`   bigint(20)  NOT NULL COMMENT 'time of the next delete request',
+	`last_error` text        DEFAULT NULL COMMENT 'the error of the last failed request'
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='parts in the external storage which are not confirmed as deleted';
+
+--
+-- Trigger `sentc_app` for `sentc_file_external_delete`
+--
+
+CREATE TRIGGER `delete_file_external_delete`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_file_external_delete
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_app_stats`
+--
+
+CREATE TABLE `sentc_app_stats`
+(
+	`app_id`        varchar(36) NOT NULL,
+	`period_type`   int(11)     NOT NULL COMMENT '0 = day, 1 = month',
+	`period_start`  bigint(20)  NOT NULL COMMENT 'begin of the day or month in utc',
+	`active_users`  int(11)     NOT NULL DEFAULT 0 COMMENT 'users with a login, refresh or init',
+	`logins`        int(11)     NOT NULL DEFAULT 0,
+	`refreshes`     int(11)     NOT NULL DEFAULT 0,
+	`registrations` int(11)     NOT NULL DEFAULT 0,
+	`key_rotations` int(11)     NOT NULL DEFAULT 0,
+	`used_storage`  bigint(20)  NOT NULL DEFAULT 0 COMMENT 'bytes at the last rollup of the period',
+	`file_count`    int(11)     NOT NULL DEFAULT 0,
+	`part_count`    int(11)     NOT NULL DEFAULT 0,
+	`group_count`   int(11)     NOT NULL DEFAULT 0,
+	`member_count`  int(11)     NOT NULL DEFAULT 0
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='daily and monthly usage of an app, computed by the app stats worker';
+
+--
+-- Trigger `sentc_app` for `sentc_app_stats`
+--
+
+CREATE TRIGGER `delete_app_stats`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_app_stats
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_app_metering`
+--
+
+CREATE TABLE `sentc_app_metering`
+(
+	`app_id`               varchar(36) NOT NULL,
+	`day`                  bigint(20)  NOT NULL COMMENT 'begin of the day in utc',
+	`monthly_active_users` int(11)     NOT NULL DEFAULT 0 COMMENT 'active users from the begin of the month until the end of the day',
+	`stored_bytes`         bigint(20)  NOT NULL DEFAULT 0,
+	`transferred_bytes`    bigint(20)  NOT NULL DEFAULT 0 COMMENT 'downloaded parts',
+	`key_rotation_members` bigint(20)  NOT NULL DEFAULT 0 COMMENT 'members of the rotated groups'
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='billable units of an app per day, not deleted with the app';
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_app_token`
+--
+
+CREATE TABLE `sentc_app_token`
+(
+	`id`           varchar(36)  NOT NULL,
+	`app_id`       varchar(36)  NOT NULL,
+	`name`         text         NOT NULL,
+	`token_type`   int(11)      NOT NULL COMMENT '0 = public, 1 = secret',
+	`hashed_token` varchar(100) NOT NULL,
+	`hash_alg`     text         NOT NULL,
+	`time`         bigint(20)   NOT NULL,
+	`expires_at`   bigint(20)   NOT NULL DEFAULT 0 COMMENT '0 = no expiry',
+	`last_used`    bigint(20)   NOT NULL DEFAULT 0,
+	`scope`           text              DEFAULT NULL COMMENT 'the app options as json, null = all endpoints of the app options',
+	`allowed_origins` text              DEFAULT NULL COMMENT 'comma separated, null = the access options of the app',
+	`allowed_ips`     text              DEFAULT NULL COMMENT 'comma separated ip or cidr ranges, null = the access options of the app'
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='named tokens of an app besides the default public and secret token';
+
+--
+-- Trigger `sentc_app` for `sentc_app_token`
+--
+
+CREATE TRIGGER `delete_app_token`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_app_token
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_app_access_options`
+--
+
+CREATE TABLE `sentc_app_access_options`
+(
+	`app_id`          varchar(36) NOT NULL,
+	`allowed_origins` text        NOT NULL COMMENT 'comma separated, empty = all origins',
+	`allowed_ips`     text        NOT NULL COMMENT 'comma separated ip or cidr ranges, empty = all ips'
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='only set when the customer restricts the app tokens';
+
+--
+-- Trigger `sentc_app` for `sentc_app_access_options`
+--
+
+CREATE TRIGGER `delete_app_access_options`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_app_access_options
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_app_rate_limit_options`
+--
+
+CREATE TABLE `sentc_app_rate_limit_options`
+(
+	`app_id`            varchar(36) NOT NULL,
+	`endpoint_group`    int(11)     NOT NULL COMMENT '0 = auth, 1 = group, 2 = file, 3 = key',
+	`capacity`          int(11)     NOT NULL COMMENT 'max requests in the bucket',
+	`refill_per_minute` int(11)     NOT NULL,
+	`limit_by`          int(11)     NOT NULL COMMENT '0 = app token, 1 = user, 2 = ip'
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='only set for the endpoint groups with a rate limit';
+
+--
+-- Trigger `sentc_app` for `sentc_app_rate_limit_options`
+--
+
+CREATE TRIGGER `delete_app_rate_limit_options`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_app_rate_limit_options
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_app_rate_limit_throttled`
+--
+
+CREATE TABLE `sentc_app_rate_limit_throttled`
+(
+	`app_id`         varchar(36) NOT NULL,
+	`endpoint_group` int(11)     NOT NULL COMMENT '0 = auth, 1 = group, 2 = file, 3 = key',
+	`day`            bigint(20)  NOT NULL COMMENT 'begin of the day in utc',
+	`count`          bigint(20)  NOT NULL DEFAULT 0
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='requests which were rejected by the rate limit';
+
+--
+-- Trigger `sentc_app` for `sentc_app_rate_limit_throttled`
+--
+
+CREATE TRIGGER `delete_app_rate_limit_throttled`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_app_rate_limit_throttled
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_customer_audit_log`
+--
+
+CREATE TABLE `sentc_customer_audit_log`
+(
+	`id`          varchar(36) NOT NULL,
+	`target_type` int(11)     NOT NULL COMMENT '0 = app, 1 = customer group',
+	`target_id`   varchar(36) NOT NULL COMMENT 'the app id or the group id',
+	`actor_id`    varchar(36) NOT NULL COMMENT 'the customer who made the change',
+	`time`        bigint(20)  NOT NULL,
+	`action`      varchar(50) NOT NULL,
+	`before_data` text DEFAULT NULL COMMENT 'json of the changed values before the change',
+	`after_data`  text DEFAULT NULL COMMENT 'json of the changed values after the change'
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='changes of the app configuration and the customer groups';
+
+--
+-- Trigger `sentc_app` for `sentc_customer_audit_log`
+--
+
+CREATE TRIGGER `delete_app_audit_log`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_customer_audit_log
+				 WHERE target_type = 0 AND target_id = OLD.id
+;
+
+--
+-- Trigger `sentc_customer_group` for `sentc_customer_audit_log`
+--
+
+CREATE TRIGGER `delete_customer_group_audit_log`
+	AFTER DELETE
+	ON `sentc_customer_group`
+	FOR EACH ROW DELETE
+				 FROM sentc_customer_audit_log
+				 WHERE target_type = 1 AND target_id = OLD.sentc_group_id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_app_jwt_rotation_options`
+--
+
+CREATE TABLE `sentc_app_jwt_rotation_options`
+(
+	`app_id`        varchar(36) NOT NULL,
+	`rotation_days` int(11)     NOT NULL COMMENT 'create a new jwt key every n days, 0 = no rotation',
+	`grace_hours`   int(11)     NOT NULL COMMENT 'hours until the old verify keys are deleted after a rotation'
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='only set when the customer changed the rotation';
+
+--
+-- Trigger `sentc_app` for `sentc_app_jwt_rotation_options`
+--
+
+CREATE TRIGGER `delete_app_jwt_rotation_options`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_app_jwt_rotation_options
+				 WHERE app_id = OLD.id
+;
+
+-- --------------------------------------------------------
+
+--
+-- Tabellenstruktur für Tabelle `sentc_kt_node`
+--
+
+CREATE TABLE `sentc_kt_node`
+(
+	`app_id`     varchar(36)  NOT NULL,
+	`level`      int(11)      NOT NULL COMMENT '0 = leaf hash; n = hash of the complete sub tree with 2^n leaves',
+	`node_index` bigint(20)   NOT NULL COMMENT 'the sub tree starts at leaf node_index * 2^level',
+	`hash`       varchar(100) NOT NULL
+) ENGINE = InnoDB
+  DEFAULT CHARSET = utf8mb4
+  COLLATE = utf8mb4_general_ci COMMENT ='hashes of the complete sub trees of the key transparency log, they never change';
+
+--
+-- Trigger `sentc_app` for `sentc_kt_node`
+--
+
+CREATE TRIGGER `delete_kt_node`
+	AFTER DELETE
+	ON `sentc_app`
+	FOR EACH ROW DELETE
+				 FROM sentc_kt_node
+				 WHERE app_id = OLD.id
+;
+
 --
 -- Indizes der exportierten Tabellen
 --
@@ -816,7 +1315,8 @@ ALTER TABLE `sentc_app_group_options`
 --
 ALTER TABLE `sentc_app_jwt_keys`
 	ADD PRIMARY KEY (`id`),
-	ADD KEY `app_id` (`app_id`);
+	ADD KEY `app_id` (`app_id`),
+	ADD KEY `retire_at` (`retire_at`);
 
 --
 -- Indizes für die Tabelle `sentc_app_options`
@@ -944,7 +1444,9 @@ ALTER TABLE `sentc_internally_db_version`
 ALTER TABLE `sentc_sym_key_management`
 	ADD PRIMARY KEY (`id`),
 	ADD KEY `master_key` (`master_key_id`, `app_id`) USING BTREE,
-	ADD KEY `by_user` (`app_id`, `creator_id`);
+	ADD KEY `by_user` (`app_id`, `creator_id`),
+	ADD KEY `by_group` (`app_id`, `group_id`),
+	ADD KEY `not_after` (`not_after`);
 
 --
 -- Indizes für die Tabelle `sentc_user`
@@ -992,6 +1494,105 @@ ALTER TABLE `sentc_user_token`
 --
 ALTER TABLE `test`
 	ADD PRIMARY KEY (`id`);
+
+--
+-- Indizes für die Tabelle `sentc_app_key_endpoint_options`
+--
+ALTER TABLE `sentc_app_key_endpoint_options`
+	ADD PRIMARY KEY (`app_id`);
+
+--
+-- Indizes für die Tabelle `sentc_kt_leaf`
+--
+ALTER TABLE `sentc_kt_leaf`
+	ADD PRIMARY KEY (`app_id`, `leaf_index`),
+	ADD KEY `key_id` (`app_id`, `key_type`, `key_id`);
+
+--
+-- Indizes für die Tabelle `sentc_file_usage`
+--
+ALTER TABLE `sentc_file_usage`
+	ADD PRIMARY KEY (`app_id`, `usage_type`, `id`);
+
+--
+-- Indizes für die Tabelle `sentc_file_share`
+--
+ALTER TABLE `sentc_file_share`
+	ADD PRIMARY KEY (`file_id`, `share_to`),
+	ADD KEY `share_to` (`app_id`, `share_to`);
+
+--
+-- Indizes für die Tabelle `sentc_file_version`
+--
+ALTER TABLE `sentc_file_version`
+	ADD PRIMARY KEY (`file_id`, `version`),
+	ADD KEY `status` (`status`, `delete_at`);
+
+--
+-- Indizes für die Tabelle `sentc_file_external_delete`
+--
+ALTER TABLE `sentc_file_external_delete`
+	ADD PRIMARY KEY (`part_id`),
+	ADD KEY `app_id` (`app_id`, `next_try`);
+
+--
+-- Indizes für die Tabelle `sentc_app_stats`
+--
+ALTER TABLE `sentc_app_stats`
+	ADD PRIMARY KEY (`app_id`, `period_type`, `period_start`);
+
+--
+-- Indizes für die Tabelle `sentc_app_metering`
+--
+ALTER TABLE `sentc_app_metering`
+	ADD PRIMARY KEY (`app_id`, `day`),
+	ADD KEY `day` (`day`);
+
+--
+-- Indizes für die Tabelle `sentc_app_token`
+--
+ALTER TABLE `sentc_app_token`
+	ADD PRIMARY KEY (`id`),
+	ADD UNIQUE KEY `hashed_token` (`hashed_token`),
+	ADD KEY `app_id` (`app_id`, `time`);
+
+--
+-- Indizes für die Tabelle `sentc_app_access_options`
+--
+ALTER TABLE `sentc_app_access_options`
+	ADD PRIMARY KEY (`app_id`);
+
+--
+-- Indizes für die Tabelle `sentc_app_rate_limit_options`
+--
+ALTER TABLE `sentc_app_rate_limit_options`
+	ADD PRIMARY KEY (`app_id`, `endpoint_group`);
+
+--
+-- Indizes für die Tabelle `sentc_app_rate_limit_throttled`
+--
+ALTER TABLE `sentc_app_rate_limit_throttled`
+	ADD PRIMARY KEY (`app_id`, `endpoint_group`, `day`);
+
+--
+-- Indizes für die Tabelle `sentc_customer_audit_log`
+--
+ALTER TABLE `sentc_customer_audit_log`
+	ADD PRIMARY KEY (`id`),
+	ADD KEY `target` (`target_type`, `target_id`, `time`);
+
+--
+-- Indizes für die Tabelle `sentc_app_jwt_rotation_options`
+--
+ALTER TABLE `sentc_app_jwt_rotation_options`
+	ADD PRIMARY KEY (`app_id`),
+	ADD KEY `rotation_days` (`rotation_days`);
+
+--
+-- Indizes für die Tabelle `sentc_kt_node`
+--
+ALTER TABLE `sentc_kt_node`
+	ADD PRIMARY KEY (`app_id`, `level`, `node_index`);
 COMMIT;
 
 /*!40101 SET CHARACTER_SET_CLIENT = @OLD_CHARACTER_SET_CLIENT */;
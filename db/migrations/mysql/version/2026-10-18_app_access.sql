@@ -0,0 +1,12 @@
+CREATE TABLE `sentc_app_access_options` (
+	`app_id` varchar(36) NOT NULL,
+	`allowed_origins` text NOT NULL COMMENT 'comma separated, empty = all origins',
+	`allowed_ips` text NOT NULL COMMENT 'comma separated ip or cidr ranges, empty = all ips',
+	PRIMARY KEY (`app_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='only set when the customer restricts the app tokens';
+
+CREATE TRIGGER `delete_app_access_options` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_app_access_options WHERE app_id = OLD.id;
+
+ALTER TABLE `sentc_app_token`
+	ADD `allowed_origins` text DEFAULT NULL COMMENT 'comma separated, null = the access options of the app' AFTER `scope`,
+	ADD `allowed_ips` text DEFAULT NULL COMMENT 'comma separated ip or cidr ranges, null = the access options of the app' AFTER `allowed_origins`;
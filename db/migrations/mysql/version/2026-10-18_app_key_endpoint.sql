@@ -0,0 +1,10 @@
+CREATE TABLE `sentc_app_key_endpoint_options` (
+	`app_id` varchar(36) NOT NULL,
+	`key_endpoint_url` text DEFAULT NULL,
+	`auth_token` varchar(100) DEFAULT NULL COMMENT 'to sign the requests to the key endpoint',
+	`timeout` int(11) NOT NULL COMMENT 'in ms',
+	`fallback` int(11) NOT NULL COMMENT '0 = no fallback; 1 = local key store',
+	PRIMARY KEY (`app_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TRIGGER `delete_key_endpoint_options` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_app_key_endpoint_options WHERE app_id = OLD.id;
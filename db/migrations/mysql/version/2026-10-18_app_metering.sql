@@ -0,0 +1,10 @@
+CREATE TABLE `sentc_app_metering` (
+	`app_id` varchar(36) NOT NULL,
+	`day` bigint(20) NOT NULL COMMENT 'begin of the day in utc',
+	`monthly_active_users` int(11) NOT NULL DEFAULT 0 COMMENT 'active users from the begin of the month until the end of the day',
+	`stored_bytes` bigint(20) NOT NULL DEFAULT 0,
+	`transferred_bytes` bigint(20) NOT NULL DEFAULT 0 COMMENT 'downloaded parts',
+	`key_rotation_members` bigint(20) NOT NULL DEFAULT 0 COMMENT 'members of the rotated groups',
+	PRIMARY KEY (`app_id`, `day`),
+	KEY `day` (`day`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='billable units of an app per day, not deleted with the app';
@@ -0,0 +1,20 @@
+CREATE TABLE `sentc_app_rate_limit_options` (
+	`app_id` varchar(36) NOT NULL,
+	`endpoint_group` int(11) NOT NULL COMMENT '0 = auth, 1 = group, 2 = file, 3 = key',
+	`capacity` int(11) NOT NULL COMMENT 'max requests in the bucket',
+	`refill_per_minute` int(11) NOT NULL,
+	`limit_by` int(11) NOT NULL COMMENT '0 = app token, 1 = user, 2 = ip',
+	PRIMARY KEY (`app_id`, `endpoint_group`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='only set for the endpoint groups with a rate limit';
+
+CREATE TABLE `sentc_app_rate_limit_throttled` (
+	`app_id` varchar(36) NOT NULL,
+	`endpoint_group` int(11) NOT NULL COMMENT '0 = auth, 1 = group, 2 = file, 3 = key',
+	`day` bigint(20) NOT NULL COMMENT 'begin of the day in utc',
+	`count` bigint(20) NOT NULL DEFAULT 0,
+	PRIMARY KEY (`app_id`, `endpoint_group`, `day`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='requests which were rejected by the rate limit';
+
+CREATE TRIGGER `delete_app_rate_limit_options` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_app_rate_limit_options WHERE app_id = OLD.id;
+
+CREATE TRIGGER `delete_app_rate_limit_throttled` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_app_rate_limit_throttled WHERE app_id = OLD.id;
@@ -0,0 +1,18 @@
+CREATE TABLE `sentc_app_stats` (
+	`app_id` varchar(36) NOT NULL,
+	`period_type` int(11) NOT NULL COMMENT '0 = day, 1 = month',
+	`period_start` bigint(20) NOT NULL COMMENT 'begin of the day or month in utc',
+	`active_users` int(11) NOT NULL DEFAULT 0 COMMENT 'users with a login, refresh or init',
+	`logins` int(11) NOT NULL DEFAULT 0,
+	`refreshes` int(11) NOT NULL DEFAULT 0,
+	`registrations` int(11) NOT NULL DEFAULT 0,
+	`key_rotations` int(11) NOT NULL DEFAULT 0,
+	`used_storage` bigint(20) NOT NULL DEFAULT 0 COMMENT 'bytes at the last rollup of the period',
+	`file_count` int(11) NOT NULL DEFAULT 0,
+	`part_count` int(11) NOT NULL DEFAULT 0,
+	`group_count` int(11) NOT NULL DEFAULT 0,
+	`member_count` int(11) NOT NULL DEFAULT 0,
+	PRIMARY KEY (`app_id`, `period_type`, `period_start`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='daily and monthly usage of an app, computed by the app stats worker';
+
+CREATE TRIGGER `delete_app_stats` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_app_stats WHERE app_id = OLD.id;
@@ -0,0 +1,17 @@
+CREATE TABLE `sentc_app_token` (
+	`id` varchar(36) NOT NULL,
+	`app_id` varchar(36) NOT NULL,
+	`name` text NOT NULL,
+	`token_type` int(11) NOT NULL COMMENT '0 = public, 1 = secret',
+	`hashed_token` varchar(100) NOT NULL,
+	`hash_alg` text NOT NULL,
+	`time` bigint(20) NOT NULL,
+	`expires_at` bigint(20) NOT NULL DEFAULT 0 COMMENT '0 = no expiry',
+	`last_used` bigint(20) NOT NULL DEFAULT 0,
+	`scope` text DEFAULT NULL COMMENT 'the app options as json, null = all endpoints of the app options',
+	PRIMARY KEY (`id`),
+	UNIQUE KEY `hashed_token` (`hashed_token`),
+	KEY `app_id` (`app_id`, `time`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='named tokens of an app besides the default public and secret token';
+
+CREATE TRIGGER `delete_app_token` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_app_token WHERE app_id = OLD.id;
@@ -0,0 +1,16 @@
+CREATE TABLE `sentc_customer_audit_log` (
+	`id` varchar(36) NOT NULL,
+	`target_type` int(11) NOT NULL COMMENT '0 = app, 1 = customer group',
+	`target_id` varchar(36) NOT NULL COMMENT 'the app id or the group id',
+	`actor_id` varchar(36) NOT NULL COMMENT 'the customer who made the change',
+	`time` bigint(20) NOT NULL,
+	`action` varchar(50) NOT NULL,
+	`before_data` text DEFAULT NULL COMMENT 'json of the changed values before the change',
+	`after_data` text DEFAULT NULL COMMENT 'json of the changed values after the change',
+	PRIMARY KEY (`id`),
+	KEY `target` (`target_type`, `target_id`, `time`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='changes of the app configuration and the customer groups';
+
+CREATE TRIGGER `delete_app_audit_log` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_customer_audit_log WHERE target_type = 0 AND target_id = OLD.id;
+
+CREATE TRIGGER `delete_customer_group_audit_log` AFTER DELETE ON `sentc_customer_group` FOR EACH ROW DELETE FROM sentc_customer_audit_log WHERE target_type = 1 AND target_id = OLD.sentc_group_id;
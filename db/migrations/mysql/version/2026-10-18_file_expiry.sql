@@ -0,0 +1,3 @@
+ALTER TABLE `sentc_file` ADD `expires_at` BIGINT NOT NULL DEFAULT '0' COMMENT 'time in ms when the file is deleted, 0 = never' AFTER `encrypted_key_alg`;
+
+ALTER TABLE `sentc_file_options` ADD `default_ttl` BIGINT NOT NULL DEFAULT '0' COMMENT 'seconds until a new file expires, 0 = never' AFTER `session_lifetime`, ADD `max_ttl` BIGINT NOT NULL DEFAULT '0' COMMENT 'max seconds until a file expires, 0 = no limit' AFTER `default_ttl`;
@@ -0,0 +1,12 @@
+CREATE TABLE `sentc_file_external_delete` (
+	`part_id` varchar(36) NOT NULL,
+	`app_id` varchar(36) NOT NULL,
+	`time` bigint(20) NOT NULL COMMENT 'when the part was deleted',
+	`attempts` int(11) NOT NULL DEFAULT 0 COMMENT 'failed delete requests to the storage url',
+	`next_try` bigint(20) NOT NULL COMMENT 'time of the next delete request',
+	`last_error` text DEFAULT NULL COMMENT 'the error of the last failed request',
+	PRIMARY KEY (`part_id`),
+	KEY `app_id` (`app_id`, `next_try`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='parts in the external storage which are not confirmed as deleted';
+
+CREATE TRIGGER `delete_file_external_delete` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_file_external_delete WHERE app_id = OLD.id;
@@ -0,0 +1 @@
+ALTER TABLE `sentc_file_part` ADD `checksum` VARCHAR(64) NULL DEFAULT NULL COMMENT 'sha256 of the encrypted part as hex' AFTER `extern`, ADD `corrupted` TINYINT(1) NOT NULL DEFAULT '0' COMMENT 'set by the part check worker when the checksum does not match' AFTER `checksum`;
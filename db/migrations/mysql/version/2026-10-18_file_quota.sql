@@ -0,0 +1,12 @@
+ALTER TABLE `sentc_file_options` ADD `max_storage_app` BIGINT NOT NULL DEFAULT '0' COMMENT 'max bytes for the app, 0 = no limit' AFTER `auth_token`, ADD `max_storage_user` BIGINT NOT NULL DEFAULT '0' COMMENT 'max bytes for each user, 0 = no limit' AFTER `max_storage_app`, ADD `max_storage_group` BIGINT NOT NULL DEFAULT '0' COMMENT 'max bytes for each group, 0 = no limit' AFTER `max_storage_user`, ADD `max_files_app` INT NOT NULL DEFAULT '0' COMMENT 'max files for the app, 0 = no limit' AFTER `max_storage_group`, ADD `max_files_user` INT NOT NULL DEFAULT '0' COMMENT 'max files for each user, 0 = no limit' AFTER `max_files_app`, ADD `max_files_group` INT NOT NULL DEFAULT '0' COMMENT 'max files for each group, 0 = no limit' AFTER `max_files_user`;
+
+CREATE TABLE `sentc_file_usage` (
+	`app_id` varchar(36) NOT NULL,
+	`usage_type` int(11) NOT NULL COMMENT '0 = app; 1 = user; 2 = group',
+	`id` varchar(36) NOT NULL COMMENT 'app, user or group id',
+	`used_storage` bigint(20) NOT NULL DEFAULT 0 COMMENT 'bytes',
+	`file_count` int(11) NOT NULL DEFAULT 0,
+	PRIMARY KEY (`app_id`, `usage_type`, `id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='usage counter for the file quotas';
+
+CREATE TRIGGER `delete_file_usage` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_file_usage WHERE app_id = OLD.id;
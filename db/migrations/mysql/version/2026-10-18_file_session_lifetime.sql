@@ -0,0 +1 @@
+ALTER TABLE `sentc_file_options` ADD `session_lifetime` INT NOT NULL DEFAULT '86400' COMMENT 'seconds until an upload session expires' AFTER `max_files_group`;
@@ -0,0 +1,19 @@
+CREATE TABLE `sentc_file_share` (
+	`file_id` varchar(36) NOT NULL,
+	`app_id` varchar(36) NOT NULL,
+	`share_to` varchar(36) NOT NULL COMMENT 'user or group id',
+	`share_to_type` int(11) NOT NULL COMMENT '1 = group; 2 = user',
+	`shared_by` varchar(36) NOT NULL,
+	`encrypted_key` text NOT NULL COMMENT 'the file key encrypted by a key of the user or group',
+	`encrypted_key_alg` text NOT NULL,
+	`master_key_id` varchar(36) NOT NULL COMMENT 'the key which encrypted the file key',
+	`time` bigint(20) NOT NULL,
+	PRIMARY KEY (`file_id`, `share_to`),
+	KEY `share_to` (`app_id`, `share_to`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='files which are shared with other users or groups';
+
+CREATE TRIGGER `file_delete_shares` AFTER DELETE ON `sentc_file` FOR EACH ROW DELETE FROM sentc_file_share WHERE file_id = OLD.id;
+
+CREATE TRIGGER `user_delete_file_shares` AFTER DELETE ON `sentc_user` FOR EACH ROW DELETE FROM sentc_file_share WHERE share_to = OLD.id;
+
+CREATE TRIGGER `group_delete_file_shares` AFTER DELETE ON `sentc_group` FOR EACH ROW DELETE FROM sentc_file_share WHERE share_to = OLD.id;
@@ -0,0 +1,24 @@
+ALTER TABLE `sentc_file` ADD `version` INT NOT NULL DEFAULT '1' COMMENT 'the current version of the file' AFTER `expires_at`;
+
+ALTER TABLE `sentc_file_part` ADD `version` INT NOT NULL DEFAULT '1' AFTER `corrupted`;
+
+ALTER TABLE `sentc_file_session` ADD `version` INT NOT NULL DEFAULT '1' COMMENT 'the version which is uploaded' AFTER `max_chunk_size`;
+
+ALTER TABLE `sentc_file_options` ADD `version_keep_last` INT NOT NULL DEFAULT '0' COMMENT 'old versions of a file to keep, 0 = all' AFTER `max_ttl`, ADD `version_keep_days` INT NOT NULL DEFAULT '0' COMMENT 'days until an old version is deleted, 0 = never' AFTER `version_keep_last`;
+
+CREATE TABLE `sentc_file_version` (
+	`file_id` varchar(36) NOT NULL,
+	`version` int(11) NOT NULL,
+	`app_id` varchar(36) NOT NULL,
+	`owner` varchar(36) NOT NULL COMMENT 'the user who uploaded the version',
+	`time` bigint(20) NOT NULL,
+	`status` int(11) NOT NULL COMMENT '0 = to delete, 1 = available',
+	`delete_at` bigint(20) NOT NULL COMMENT '0 = not deleted, time when the version was deleted',
+	PRIMARY KEY (`file_id`, `version`),
+	KEY `status` (`status`, `delete_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='all uploaded versions of a file';
+
+CREATE TRIGGER `file_delete_versions` AFTER DELETE ON `sentc_file` FOR EACH ROW DELETE FROM sentc_file_version WHERE file_id = OLD.id;
+
+-- the existing files are the first version
+INSERT INTO sentc_file_version (file_id, version, app_id, owner, time, status, delete_at) SELECT id, 1, app_id, owner, time, 1, 0 FROM sentc_file WHERE status = 1;
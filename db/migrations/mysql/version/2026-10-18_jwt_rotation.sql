@@ -0,0 +1,12 @@
+ALTER TABLE `sentc_app_jwt_keys` ADD `retire_at` BIGINT NOT NULL DEFAULT '0' COMMENT 'the key is deleted after this time, 0 = active' AFTER `time`;
+ALTER TABLE `sentc_app_jwt_keys` ADD INDEX `retire_at` (`retire_at`);
+
+CREATE TABLE `sentc_app_jwt_rotation_options` (
+	`app_id` varchar(36) NOT NULL,
+	`rotation_days` int(11) NOT NULL COMMENT 'create a new jwt key every n days, 0 = no rotation',
+	`grace_hours` int(11) NOT NULL COMMENT 'hours until the old verify keys are deleted after a rotation',
+	PRIMARY KEY (`app_id`),
+	KEY `rotation_days` (`rotation_days`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='only set when the customer changed the rotation';
+
+CREATE TRIGGER `delete_app_jwt_rotation_options` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_app_jwt_rotation_options WHERE app_id = OLD.id;
@@ -0,0 +1,3 @@
+ALTER TABLE `sentc_sym_key_management` ADD `not_after` BIGINT NULL DEFAULT NULL COMMENT 'the key should not be used after this time' AFTER `label`, ADD `key_status` INT NOT NULL DEFAULT '0' COMMENT '0 = active; 1 = deprecated; 2 = compromised' AFTER `not_after`;
+ALTER TABLE `sentc_group_keys` ADD `not_after` BIGINT NULL DEFAULT NULL COMMENT 'the key should not be used after this time', ADD `key_status` INT NOT NULL DEFAULT '0' COMMENT '0 = active; 1 = deprecated; 2 = compromised' AFTER `not_after`;
+ALTER TABLE `sentc_sym_key_management` ADD INDEX `not_after` (`not_after`);
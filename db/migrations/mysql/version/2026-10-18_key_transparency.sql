@@ -0,0 +1,24 @@
+CREATE TABLE `sentc_kt_leaf` (
+	`app_id` varchar(36) NOT NULL,
+	`leaf_index` bigint(20) NOT NULL,
+	`key_type` int(11) NOT NULL COMMENT '0 = device public key; 1 = device verify key; 2 = group public key; 3 = group verify key',
+	`owner_id` varchar(36) NOT NULL COMMENT 'user or group id',
+	`key_id` varchar(36) NOT NULL,
+	`key_hash` varchar(100) NOT NULL,
+	`leaf_hash` varchar(100) NOT NULL,
+	`time` bigint(20) NOT NULL,
+	PRIMARY KEY (`app_id`, `leaf_index`),
+	KEY `key_id` (`app_id`, `key_type`, `key_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='append only log for the key transparency';
+
+CREATE TRIGGER `delete_kt_leaf` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_kt_leaf WHERE app_id = OLD.id;
+
+CREATE TABLE `sentc_kt_node` (
+	`app_id` varchar(36) NOT NULL,
+	`level` int(11) NOT NULL COMMENT '0 = leaf hash; n = hash of the complete sub tree with 2^n leaves',
+	`node_index` bigint(20) NOT NULL COMMENT 'the sub tree starts at leaf node_index * 2^level',
+	`hash` varchar(100) NOT NULL,
+	PRIMARY KEY (`app_id`, `level`, `node_index`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='hashes of the complete sub trees of the key transparency log, they never change';
+
+CREATE TRIGGER `delete_kt_node` AFTER DELETE ON `sentc_app` FOR EACH ROW DELETE FROM sentc_kt_node WHERE app_id = OLD.id;
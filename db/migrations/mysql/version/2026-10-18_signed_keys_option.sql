@@ -0,0 +1 @@
+ALTER TABLE `sentc_app_group_options` ADD `require_signed_keys` INT NOT NULL DEFAULT '0' COMMENT '1 = public keys must be signed by the verify key of the group' AFTER `min_rank_key_rotation`;
@@ -0,0 +1,2 @@
+ALTER TABLE `sentc_sym_key_management` ADD `group_id` VARCHAR(36) NULL DEFAULT NULL COMMENT 'only members of this group can fetch the key' AFTER `time`, ADD `label` TEXT NULL DEFAULT NULL AFTER `group_id`;
+ALTER TABLE `sentc_sym_key_management` ADD INDEX `by_group` (`app_id`, `group_id`);
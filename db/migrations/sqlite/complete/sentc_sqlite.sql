@@ -34,7 +34,8 @@ CREATE TABLE "sentc_app_jwt_keys"
 	sign_key   text,
 	verify_key text,
 	alg        text,
-	time       text
+	time       text,
+	retire_at  text default '0' not null
 );
 
 ----
@@ -58,7 +59,7 @@ CREATE TABLE sentc_group_keys
 	encrypted_ephemeral_key        text,
 	encrypted_group_key_by_eph_key text,
 	time                           text
-, 'previous_group_key_id' TEXT, 'ephemeral_alg' TEXT, 'app_id' TEXT, 'encrypted_sign_key' TEXT, 'verify_key' TEXT, 'keypair_sign_alg' TEXT, 'signed_by_user_id' TEXT, 'signed_by_user_sign_key_id' TEXT, 'signed_by_user_sign_key_alg' TEXT, 'public_key_sig' TEXT, 'public_key_sig_key_id' TEXT, group_key_sig TEXT);
+, 'previous_group_key_id' TEXT, 'ephemeral_alg' TEXT, 'app_id' TEXT, 'encrypted_sign_key' TEXT, 'verify_key' TEXT, 'keypair_sign_alg' TEXT, 'signed_by_user_id' TEXT, 'signed_by_user_sign_key_id' TEXT, 'signed_by_user_sign_key_alg' TEXT, 'public_key_sig' TEXT, 'public_key_sig_key_id' TEXT, group_key_sig TEXT, 'not_after' TEXT DEFAULT NULL, 'key_status' INTEGER DEFAULT 0);
 
 ----
 -- Data dump for sentc_group_keys, a total of 0 rows
@@ -129,7 +130,7 @@ CREATE TABLE 'sentc_customer' ('id' TEXT PRIMARY KEY NOT NULL, 'email' TEXT, 'em
 ----
 -- Table structure for sentc_sym_key_management
 ----
-CREATE TABLE 'sentc_sym_key_management' ('id' TEXT PRIMARY KEY NOT NULL, 'app_id' TEXT, 'master_key_id' TEXT, 'creator_id' TEXT, 'encrypted_key' TEXT, 'master_key_alg' TEXT,'time' TEXT);
+CREATE TABLE 'sentc_sym_key_management' ('id' TEXT PRIMARY KEY NOT NULL, 'app_id' TEXT, 'master_key_id' TEXT, 'creator_id' TEXT, 'encrypted_key' TEXT, 'master_key_alg' TEXT,'time' TEXT, 'group_id' TEXT DEFAULT NULL, 'label' TEXT DEFAULT NULL, 'not_after' TEXT DEFAULT NULL, 'key_status' INTEGER DEFAULT 0);
 
 ----
 -- Data dump for sentc_sym_key_management, a total of 0 rows
@@ -170,7 +171,7 @@ CREATE TABLE 'sentc_group_user' (
 ----
 -- Table structure for sentc_file_session
 ----
-CREATE TABLE 'sentc_file_session' ('id' TEXT PRIMARY KEY NOT NULL, 'file_id' TEXT, 'app_id' TEXT, 'created_at' TEXT, 'expected_size' INTEGER, 'max_chunk_size' TEXT);
+CREATE TABLE 'sentc_file_session' ('id' TEXT PRIMARY KEY NOT NULL, 'file_id' TEXT, 'app_id' TEXT, 'created_at' TEXT, 'expected_size' INTEGER, 'max_chunk_size' TEXT, 'version' INTEGER DEFAULT 1);
 
 ----
 -- Data dump for sentc_file_session, a total of 0 rows
@@ -179,7 +180,7 @@ CREATE TABLE 'sentc_file_session' ('id' TEXT PRIMARY KEY NOT NULL, 'file_id' TEX
 ----
 -- Table structure for sentc_file_part
 ----
-CREATE TABLE 'sentc_file_part' ('id' TEXT PRIMARY KEY NOT NULL, 'file_id' TEXT, 'app_id' TEXT, 'size' TEXT, 'sequence' INTEGER, 'extern' BOOLEAN);
+CREATE TABLE 'sentc_file_part' ('id' TEXT PRIMARY KEY NOT NULL, 'file_id' TEXT, 'app_id' TEXT, 'size' TEXT, 'sequence' INTEGER, 'extern' BOOLEAN, 'checksum' TEXT, 'corrupted' BOOLEAN DEFAULT 0, 'version' INTEGER DEFAULT 1);
 
 ----
 -- Data dump for sentc_file_part, a total of 0 rows
@@ -188,7 +189,7 @@ CREATE TABLE 'sentc_file_part' ('id' TEXT PRIMARY KEY NOT NULL, 'file_id' TEXT,
 ----
 -- Table structure for sentc_file_options
 ----
-CREATE TABLE 'sentc_file_options' ('app_id' TEXT PRIMARY KEY NOT NULL, 'file_storage' INTEGER, 'storage_url' TEXT, 'auth_token' TEXT);
+CREATE TABLE 'sentc_file_options' ('app_id' TEXT PRIMARY KEY NOT NULL, 'file_storage' INTEGER, 'storage_url' TEXT, 'auth_token' TEXT, 'max_storage_app' INTEGER DEFAULT 0, 'max_storage_user' INTEGER DEFAULT 0, 'max_storage_group' INTEGER DEFAULT 0, 'max_files_app' INTEGER DEFAULT 0, 'max_files_user' INTEGER DEFAULT 0, 'max_files_group' INTEGER DEFAULT 0, 'session_lifetime' INTEGER DEFAULT 86400, 'default_ttl' INTEGER DEFAULT 0, 'max_ttl' INTEGER DEFAULT 0, 'version_keep_last' INTEGER DEFAULT 0, 'version_keep_days' INTEGER DEFAULT 0);
 
 ----
 -- Data dump for sentc_file_options, a total of 1 rows
@@ -286,7 +287,7 @@ CREATE TABLE 'sentc_customer_group' ('sentc_group_id' TEXT PRIMARY KEY NOT NULL,
 ----
 -- Table structure for sentc_app_group_options
 ----
-CREATE TABLE 'sentc_app_group_options' ('app_id' TEXT PRIMARY KEY NOT NULL, 'max_key_rotation_month' INTEGER, 'min_rank_key_rotation' INTEGER);
+CREATE TABLE 'sentc_app_group_options' ('app_id' TEXT PRIMARY KEY NOT NULL, 'max_key_rotation_month' INTEGER, 'min_rank_key_rotation' INTEGER, 'require_signed_keys' INTEGER DEFAULT 0);
 
 ----
 -- Data dump for sentc_app_group_options, a total of 1 rows
@@ -296,7 +297,7 @@ INSERT INTO "sentc_app_group_options" ("app_id","max_key_rotation_month","min_ra
 ----
 -- Table structure for sentc_file
 ----
-CREATE TABLE 'sentc_file' ('id' TEXT PRIMARY KEY NOT NULL, 'owner' TEXT, 'belongs_to' TEXT, 'belongs_to_type' INTEGER, 'app_id' TEXT,'encrypted_key' TEXT, 'time' TEXT, 'status' INTEGER, 'delete_at' TEXT, 'encrypted_file_name' TEXT DEFAULT NULL, 'master_key_id' TEXT, 'encrypted_key_alg' TEXT);
+CREATE TABLE 'sentc_file' ('id' TEXT PRIMARY KEY NOT NULL, 'owner' TEXT, 'belongs_to' TEXT, 'belongs_to_type' INTEGER, 'app_id' TEXT,'encrypted_key' TEXT, 'time' TEXT, 'status' INTEGER, 'delete_at' TEXT, 'encrypted_file_name' TEXT DEFAULT NULL, 'master_key_id' TEXT, 'encrypted_key_alg' TEXT, 'expires_at' TEXT DEFAULT '0', 'version' INTEGER DEFAULT 1);
 
 ----
 -- Data dump for sentc_file, a total of 0 rows
@@ -497,6 +498,8 @@ CREATE INDEX 'get_group' ON "sentc_group_keys" ("group_id" ASC, "app_id" ASC);
 -- structure for index by_user on table sentc_sym_key_management
 ----
 CREATE INDEX 'by_user' ON "sentc_sym_key_management" ("creator_id" ASC, "app_id" ASC);
+CREATE INDEX 'by_group' ON "sentc_sym_key_management" ("group_id" ASC, "app_id" ASC);
+CREATE INDEX 'sym_key_not_after' ON "sentc_sym_key_management" ("not_after" ASC);
 
 ----
 -- structure for index app_id on table sentc_user
@@ -676,4 +679,165 @@ CREATE TRIGGER 'delete_file_options' AFTER DELETE ON "sentc_app" FOR EACH ROW BE
 -- structure for trigger delete_app_content on table sentc_app
 ----
 CREATE TRIGGER 'delete_app_content' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_content WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_app_key_endpoint_options
+----
+CREATE TABLE 'sentc_app_key_endpoint_options' ('app_id' TEXT PRIMARY KEY NOT NULL, 'key_endpoint_url' TEXT, 'auth_token' TEXT, 'timeout' INTEGER, 'fallback' INTEGER);
+
+----
+-- structure for trigger delete_key_endpoint_options on table sentc_app
+----
+CREATE TRIGGER 'delete_key_endpoint_options' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_app_key_endpoint_options WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_kt_leaf
+----
+CREATE TABLE 'sentc_kt_leaf' ('app_id' TEXT NOT NULL, 'leaf_index' INTEGER NOT NULL, 'key_type' INTEGER, 'owner_id' TEXT, 'key_id' TEXT, 'key_hash' TEXT, 'leaf_hash' TEXT, 'time' TEXT, PRIMARY KEY ('app_id', 'leaf_index'));
+CREATE INDEX 'kt_leaf_key_id' ON "sentc_kt_leaf" ("app_id" ASC, "key_type" ASC, "key_id" ASC);
+
+----
+-- structure for trigger delete_kt_leaf on table sentc_app
+----
+CREATE TRIGGER 'delete_kt_leaf' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_kt_leaf WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_file_usage
+----
+CREATE TABLE 'sentc_file_usage' ('app_id' TEXT NOT NULL, 'usage_type' INTEGER NOT NULL, 'id' TEXT NOT NULL, 'used_storage' INTEGER DEFAULT 0, 'file_count' INTEGER DEFAULT 0, PRIMARY KEY ('app_id', 'usage_type', 'id'));
+
+----
+-- structure for trigger delete_file_usage on table sentc_app
+----
+CREATE TRIGGER 'delete_file_usage' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_file_usage WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_file_share
+----
+CREATE TABLE 'sentc_file_share' ('file_id' TEXT NOT NULL, 'app_id' TEXT NOT NULL, 'share_to' TEXT NOT NULL, 'share_to_type' INTEGER NOT NULL, 'shared_by' TEXT NOT NULL, 'encrypted_key' TEXT NOT NULL, 'encrypted_key_alg' TEXT NOT NULL, 'master_key_id' TEXT NOT NULL, 'time' TEXT NOT NULL, PRIMARY KEY ('file_id', 'share_to'));
+
+----
+-- structure for trigger file_delete_shares on table sentc_file
+----
+CREATE TRIGGER 'file_delete_shares' AFTER DELETE ON "sentc_file" FOR EACH ROW BEGIN DELETE FROM sentc_file_share WHERE file_id = OLD.id; END;
+
+----
+-- structure for trigger user_delete_file_shares on table sentc_user
+----
+CREATE TRIGGER 'user_delete_file_shares' AFTER DELETE ON "sentc_user" FOR EACH ROW BEGIN DELETE FROM sentc_file_share WHERE share_to = OLD.id; END;
+
+----
+-- structure for trigger group_delete_file_shares on table sentc_group
+----
+CREATE TRIGGER 'group_delete_file_shares' AFTER DELETE ON "sentc_group" FOR EACH ROW BEGIN DELETE FROM sentc_file_share WHERE share_to = OLD.id; END;
+
+----
+-- Table structure for sentc_file_version
+----
+CREATE TABLE 'sentc_file_version' ('file_id' TEXT NOT NULL, 'version' INTEGER NOT NULL, 'app_id' TEXT NOT NULL, 'owner' TEXT NOT NULL, 'time' TEXT NOT NULL, 'status' INTEGER NOT NULL, 'delete_at' TEXT NOT NULL, PRIMARY KEY ('file_id', 'version'));
+
+----
+-- structure for trigger file_delete_versions on table sentc_file
+----
+CREATE TRIGGER 'file_delete_versions' AFTER DELETE ON "sentc_file" FOR EACH ROW BEGIN DELETE FROM sentc_file_version WHERE file_id = OLD.id; END;
+
+----
+-- Table structure for sentc_file_external_delete
+----
+CREATE TABLE 'sentc_file_external_delete' ('part_id' TEXT PRIMARY KEY NOT NULL, 'app_id' TEXT NOT NULL, 'time' TEXT NOT NULL, 'attempts' INTEGER DEFAULT 0, 'next_try' TEXT NOT NULL, 'last_error' TEXT);
+
+----
+-- structure for trigger delete_file_external_delete on table sentc_app
+----
+CREATE TRIGGER 'delete_file_external_delete' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_file_external_delete WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_app_stats
+----
+CREATE TABLE 'sentc_app_stats' ('app_id' TEXT NOT NULL, 'period_type' INTEGER NOT NULL, 'period_start' TEXT NOT NULL, 'active_users' INTEGER DEFAULT 0, 'logins' INTEGER DEFAULT 0, 'refreshes' INTEGER DEFAULT 0, 'registrations' INTEGER DEFAULT 0, 'key_rotations' INTEGER DEFAULT 0, 'used_storage' INTEGER DEFAULT 0, 'file_count' INTEGER DEFAULT 0, 'part_count' INTEGER DEFAULT 0, 'group_count' INTEGER DEFAULT 0, 'member_count' INTEGER DEFAULT 0, PRIMARY KEY ('app_id', 'period_type', 'period_start'));
+
+----
+-- structure for trigger delete_app_stats on table sentc_app
+----
+CREATE TRIGGER 'delete_app_stats' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_app_stats WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_app_metering
+----
+CREATE TABLE 'sentc_app_metering' ('app_id' TEXT NOT NULL, 'day' TEXT NOT NULL, 'monthly_active_users' INTEGER DEFAULT 0, 'stored_bytes' INTEGER DEFAULT 0, 'transferred_bytes' INTEGER DEFAULT 0, 'key_rotation_members' INTEGER DEFAULT 0, PRIMARY KEY ('app_id', 'day'));
+
+----
+-- Table structure for sentc_app_token
+----
+CREATE TABLE 'sentc_app_token' ('id' TEXT PRIMARY KEY NOT NULL, 'app_id' TEXT NOT NULL, 'name' TEXT NOT NULL, 'token_type' INTEGER NOT NULL, 'hashed_token' TEXT NOT NULL UNIQUE, 'hash_alg' TEXT NOT NULL, 'time' TEXT NOT NULL, 'expires_at' TEXT DEFAULT '0', 'last_used' TEXT DEFAULT '0', 'scope' TEXT, 'allowed_origins' TEXT, 'allowed_ips' TEXT);
+
+----
+-- structure for trigger delete_app_token on table sentc_app
+----
+CREATE TRIGGER 'delete_app_token' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_app_token WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_app_access_options
+----
+CREATE TABLE 'sentc_app_access_options' ('app_id' TEXT PRIMARY KEY NOT NULL, 'allowed_origins' TEXT NOT NULL, 'allowed_ips' TEXT NOT NULL);
+
+----
+-- structure for trigger delete_app_access_options on table sentc_app
+----
+CREATE TRIGGER 'delete_app_access_options' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_app_access_options WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_app_rate_limit_options
+----
+CREATE TABLE 'sentc_app_rate_limit_options' ('app_id' TEXT NOT NULL, 'endpoint_group' INTEGER NOT NULL, 'capacity' INTEGER NOT NULL, 'refill_per_minute' INTEGER NOT NULL, 'limit_by' INTEGER NOT NULL, PRIMARY KEY ('app_id', 'endpoint_group'));
+
+----
+-- structure for trigger delete_app_rate_limit_options on table sentc_app
+----
+CREATE TRIGGER 'delete_app_rate_limit_options' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_app_rate_limit_options WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_app_rate_limit_throttled
+----
+CREATE TABLE 'sentc_app_rate_limit_throttled' ('app_id' TEXT NOT NULL, 'endpoint_group' INTEGER NOT NULL, 'day' TEXT NOT NULL, 'count' INTEGER DEFAULT 0, PRIMARY KEY ('app_id', 'endpoint_group', 'day'));
+
+----
+-- structure for trigger delete_app_rate_limit_throttled on table sentc_app
+----
+CREATE TRIGGER 'delete_app_rate_limit_throttled' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_app_rate_limit_throttled WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_customer_audit_log
+----
+CREATE TABLE 'sentc_customer_audit_log' ('id' TEXT PRIMARY KEY NOT NULL, 'target_type' INTEGER NOT NULL, 'target_id' TEXT NOT NULL, 'actor_id' TEXT NOT NULL, 'time' TEXT NOT NULL, 'action' TEXT NOT NULL, 'before_data' TEXT, 'after_data' TEXT);
+
+----
+-- structure for trigger delete_app_audit_log on table sentc_app
+----
+CREATE TRIGGER 'delete_app_audit_log' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_customer_audit_log WHERE target_type = 0 AND target_id = OLD.id; END;
+
+----
+-- structure for trigger delete_customer_group_audit_log on table sentc_customer_group
+----
+CREATE TRIGGER 'delete_customer_group_audit_log' AFTER DELETE ON "sentc_customer_group" FOR EACH ROW BEGIN DELETE FROM sentc_customer_audit_log WHERE target_type = 1 AND target_id = OLD.sentc_group_id; END;
+
+----
+-- Table structure for sentc_app_jwt_rotation_options
+----
+CREATE TABLE 'sentc_app_jwt_rotation_options' ('app_id' TEXT PRIMARY KEY NOT NULL, 'rotation_days' INTEGER NOT NULL, 'grace_hours' INTEGER NOT NULL);
+
+----
+-- structure for trigger delete_app_jwt_rotation_options on table sentc_app
+----
+CREATE TRIGGER 'delete_app_jwt_rotation_options' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_app_jwt_rotation_options WHERE app_id = OLD.id; END;
+
+----
+-- Table structure for sentc_kt_node
+----
+CREATE TABLE 'sentc_kt_node' ('app_id' TEXT NOT NULL, 'level' INTEGER NOT NULL, 'node_index' INTEGER NOT NULL, 'hash' TEXT NOT NULL, PRIMARY KEY ('app_id', 'level', 'node_index'));
+
+----
+-- structure for trigger delete_kt_node on table sentc_app
+----
+CREATE TRIGGER 'delete_kt_node' AFTER DELETE ON "sentc_app" FOR EACH ROW BEGIN DELETE FROM sentc_kt_node WHERE app_id = OLD.id; END;
 COMMIT;
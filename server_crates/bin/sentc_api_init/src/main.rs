@@ -3,7 +3,9 @@ use std::fs::File;
 use std::io::Read;
 
 use rustgram_server_util::db::{exec_string_non_param, query_first_non_param, StringEntity};
+use rustgram_server_util::input_helper::{bytes_to_json, json_to_string};
 use server_api_common::SENTC_ROOT_APP;
+use server_dashboard_common::app::{AppConfig, AppConfigCreateInput};
 
 #[tokio::main]
 async fn main()
@@ -21,7 +23,13 @@ async fn main()
 			"root" => {
 				create_root_app().await;
 			},
-			_ => panic!("Wrong args, please choose `db` or `root`"),
+			"metering" => {
+				export_metering(&args[2..]).await;
+			},
+			"config" => {
+				app_config(&args[2..]).await;
+			},
+			_ => panic!("Wrong args, please choose `db`, `root`, `metering` or `config`"),
 		}
 	} else {
 		//if no args set -> do everything
@@ -49,6 +57,100 @@ async fn create_root_app()
 	}
 }
 
+/**
+Print the metering of all apps or all customer groups to stdout.
+
+Args: `metering <from> <to> <apps|groups> <csv|json>` with the time in milliseconds.
+ */
+async fn export_metering(args: &[String])
+{
+	if args.len() < 4 {
+		panic!("Wrong args, please use `metering <from> <to> <apps|groups> <csv|json>`");
+	}
+
+	let from: u128 = args[0]
+		.parse()
+		.expect("From must be a time in milliseconds");
+	let to: u128 = args[1].parse().expect("To must be a time in milliseconds");
+
+	let format = server_api_customer::metering::metering_service::get_format(&args[3]).unwrap();
+
+	let out = match args[2].as_str() {
+		"apps" => server_api_customer::metering::metering_service::export_all_apps(from, to, &format).await,
+		"groups" => server_api_customer::metering::metering_service::export_all_groups(from, to, &format).await,
+		_ => panic!("Wrong args, please choose `apps` or `groups`"),
+	};
+
+	print!("{}", out.unwrap());
+}
+
+/**
+Export the config of an app to stdout or import a config file into an existing or a new app.
+
+Args:
+- `config export <app_id>`
+- `config import <app_id> <file>`
+- `config create <customer_id> <file> [<customer_group_id>]` prints the tokens and the jwt keys of the new app
+ */
+async fn app_config(args: &[String])
+{
+	let wrong_args =
+		"Wrong args, please use `config export <app_id>`, `config import <app_id> <file>` or `config create <customer_id> <file> [<group_id>]`";
+
+	if args.len() < 2 {
+		panic!("{}", wrong_args);
+	}
+
+	match args[0].as_str() {
+		"export" => {
+			let config = server_api_customer::customer_app::app_service::export_config(&args[1])
+				.await
+				.unwrap();
+
+			print!("{}", json_to_string(&config).unwrap());
+		},
+		"import" => {
+			let file = args.get(2).expect(wrong_args);
+
+			let app = server_api_customer::customer_app::app_service::get_app_general_internal(&args[1])
+				.await
+				.unwrap();
+
+			server_api_customer::customer_app::app_service::import_config(&app, read_config(file))
+				.await
+				.unwrap();
+
+			println!("Config successfully imported.");
+		},
+		"create" => {
+			let file = args.get(2).expect(wrong_args);
+
+			let input = AppConfigCreateInput {
+				identifier: None,
+				config: read_config(file),
+			};
+
+			let out = server_api_customer::customer_app::app_service::create_app_from_config(input, &args[1], args.get(3))
+				.await
+				.unwrap();
+
+			print!("{}", json_to_string(&out).unwrap());
+		},
+		_ => panic!("{}", wrong_args),
+	}
+}
+
+fn read_config(path: &str) -> AppConfig
+{
+	let mut file = File::open(path).expect("Config file not found");
+
+	let mut config = String::new();
+
+	file.read_to_string(&mut config).unwrap();
+
+	bytes_to_json(config.as_bytes()).expect("Wrong config file")
+}
+
 #[cfg(feature = "mysql")]
 async fn check_db()
 {
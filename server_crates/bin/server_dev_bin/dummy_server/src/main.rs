@@ -1,4 +1,5 @@
 mod test_external_file;
+mod test_key_endpoint;
 
 use rustgram::{r, Request, Router};
 
@@ -14,6 +15,11 @@ async fn main()
 
 	router.get("/file_part/upload/:part_id", r(test_external_file::upload_part));
 	router.post("/file_part/delete", r(test_external_file::delete));
+	router.post("/file_part/delete_retry/:mode", r(test_external_file::delete_retry));
+
+	router.post("/key_endpoint/get", r(test_key_endpoint::get));
+	router.post("/key_endpoint/store", r(test_key_endpoint::store));
+	router.post("/key_endpoint/delete", r(test_key_endpoint::delete));
 
 	let addr = format!("{}:{}", "127.0.0.1", 3003).parse().unwrap();
 
@@ -1,6 +1,8 @@
-use rustgram::Request;
+use hyper::{Body, StatusCode};
+use rustgram::{Request, Response};
 use rustgram_server_util::input_helper::{bytes_to_json, get_raw_body};
 use rustgram_server_util::url_helper::get_name_param_from_req;
+use serde_json::json;
 use tokio::sync::{OnceCell, RwLock};
 
 static PART_IDS: OnceCell<RwLock<Vec<String>>> = OnceCell::const_new();
@@ -20,7 +22,7 @@ pub(crate) async fn upload_part(req: Request) -> &'static str
 	""
 }
 
-pub(crate) async fn delete(mut req: Request) -> &'static str
+pub(crate) async fn delete(mut req: Request) -> Response
 {
 	println!("delete");
 
@@ -36,12 +38,44 @@ pub(crate) async fn delete(mut req: Request) -> &'static str
 
 	println!("input ids: {:?}", input);
 
-	for id in input {
+	for id in &input {
 		//every part id must be found
 		let res = ids.iter().find(|x| x.as_str() == id.as_str());
 
 		assert_ne!(res, None);
 	}
 
-	""
+	//confirm the delete, otherwise the parts are sent again
+	let out = json!({ "deleted": input });
+
+	hyper::Response::builder()
+		.status(StatusCode::OK)
+		.header("Content-Type", "application/json")
+		.body(Body::from(out.to_string()))
+		.unwrap()
+}
+
+/**
+Respond to the delete like a storage which is down (fail), which doesn't confirm the parts (not_confirmed)
+or which confirms all parts (confirmed), to test the retry of the external delete worker.
+ */
+pub(crate) async fn delete_retry(mut req: Request) -> Response
+{
+	let mode = get_name_param_from_req(&req, "mode").unwrap().to_string();
+
+	let body = get_raw_body(&mut req).await.unwrap();
+
+	let input: Vec<String> = bytes_to_json(&body).unwrap();
+
+	let (status, out) = match mode.as_str() {
+		"fail" => (StatusCode::INTERNAL_SERVER_ERROR, json!({})),
+		"not_confirmed" => (StatusCode::OK, json!({ "deleted": [] })),
+		_ => (StatusCode::OK, json!({ "deleted": input })),
+	};
+
+	hyper::Response::builder()
+		.status(status)
+		.header("Content-Type", "application/json")
+		.body(Body::from(out.to_string()))
+		.unwrap()
 }
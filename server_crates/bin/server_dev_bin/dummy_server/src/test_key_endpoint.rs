@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use hyper::{Body, StatusCode};
+use ring::hmac;
+use rustgram::{Request, Response};
+use rustgram_server_util::input_helper::{bytes_to_json, get_raw_body};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OnceCell, RwLock};
+
+/**
+Must be the same token as the app key endpoint options in the integration test
+ */
+const AUTH_TOKEN: &str = "sentc_test_key_endpoint_token";
+
+static KEYS: OnceCell<RwLock<HashMap<String, String>>> = OnceCell::const_new();
+
+#[derive(Serialize, Deserialize)]
+struct KeyStorage
+{
+	id: String,
+	key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeysInput
+{
+	keys: Vec<String>,
+}
+
+async fn get_keys() -> &'static RwLock<HashMap<String, String>>
+{
+	KEYS.get_or_init(|| async move { RwLock::new(HashMap::new()) })
+		.await
+}
+
+fn res(status: StatusCode, body: String) -> Response
+{
+	hyper::Response::builder()
+		.status(status)
+		.header("Content-Type", "application/json")
+		.body(Body::from(body))
+		.unwrap()
+}
+
+/**
+Check the signature of the request like a customer endpoint should do it.
+ */
+async fn verify_req(req: &mut Request) -> Option<Vec<u8>>
+{
+	let headers = req.headers();
+
+	let signature = headers.get("x-sentc-signature")?.to_str().ok()?.to_string();
+	let timestamp = headers.get("x-sentc-timestamp")?.to_str().ok()?.to_string();
+	let app_id = headers.get("x-sentc-app-id")?.to_str().ok()?.to_string();
+
+	let body = get_raw_body(req).await.ok()?;
+
+	let key = hmac::Key::new(hmac::HMAC_SHA256, AUTH_TOKEN.as_bytes());
+
+	let mut msg = format!("{timestamp}.{app_id}.").into_bytes();
+	msg.extend_from_slice(&body);
+
+	let signature = base64::decode(signature).ok()?;
+
+	hmac::verify(&key, &msg, &signature).ok()?;
+
+	Some(body)
+}
+
+pub(crate) async fn get(mut req: Request) -> Response
+{
+	let body = match verify_req(&mut req).await {
+		Some(b) => b,
+		None => return res(StatusCode::UNAUTHORIZED, "".to_string()),
+	};
+
+	let input: KeysInput = bytes_to_json(&body).unwrap();
+
+	let keys = get_keys().await.read().await;
+
+	let out: Vec<KeyStorage> = input
+		.keys
+		.into_iter()
+		.filter_map(|id| {
+			keys.get(&id).map(|k| {
+				KeyStorage {
+					id,
+					key: k.to_string(),
+				}
+			})
+		})
+		.collect();
+
+	res(StatusCode::OK, serde_json::to_string(&out).unwrap())
+}
+
+pub(crate) async fn store(mut req: Request) -> Response
+{
+	let body = match verify_req(&mut req).await {
+		Some(b) => b,
+		None => return res(StatusCode::UNAUTHORIZED, "".to_string()),
+	};
+
+	let input: Vec<KeyStorage> = bytes_to_json(&body).unwrap();
+
+	let mut keys = get_keys().await.write().await;
+
+	for key in input {
+		keys.insert(key.id, key.key);
+	}
+
+	res(StatusCode::OK, "".to_string())
+}
+
+pub(crate) async fn delete(mut req: Request) -> Response
+{
+	let body = match verify_req(&mut req).await {
+		Some(b) => b,
+		None => return res(StatusCode::UNAUTHORIZED, "".to_string()),
+	};
+
+	let input: KeysInput = bytes_to_json(&body).unwrap();
+
+	let mut keys = get_keys().await.write().await;
+
+	for id in input.keys {
+		keys.remove(&id);
+	}
+
+	res(StatusCode::OK, "".to_string())
+}
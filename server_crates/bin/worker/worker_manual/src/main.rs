@@ -9,6 +9,34 @@ async fn main()
 
 	match args[1].as_str() {
 		"file" => server_api_file::file_worker::start().await.unwrap(),
-		_ => panic!("Wrong args, please choose file"),
+		"file_session" => server_api_file::file_session_worker::start().await.unwrap(),
+		"file_part_check" => {
+			server_api_file::file_part_check_worker::start()
+				.await
+				.unwrap()
+		},
+		"file_expire" => server_api_file::file_expire_worker::start().await.unwrap(),
+		"file_external_delete" => {
+			server_api_file::file_external_delete_worker::start()
+				.await
+				.unwrap()
+		},
+		"key" => server_api::sentc_key_worker::start().await.unwrap(),
+		"app_stats" => {
+			server_api_customer::customer_app::app_stats_worker::start()
+				.await
+				.unwrap()
+		},
+		"metering" => {
+			server_api_customer::metering::metering_worker::start()
+				.await
+				.unwrap()
+		},
+		"jwt_rotation" => {
+			server_api_customer::customer_app::jwt_rotation_worker::start()
+				.await
+				.unwrap()
+		},
+		_ => panic!("Wrong args, please choose file, file_session, file_part_check, file_expire, file_external_delete, key, app_stats, metering or jwt_rotation"),
 	}
 }
@@ -16,6 +16,7 @@ use crate::group::group_user_service::NewUserType;
 use crate::group::{group_model, group_service};
 use crate::user::user_entities::UserPublicKeyDataEntity;
 use crate::util::api_res::ApiErrorCodes;
+use crate::util::key_sig::check_public_key_sig;
 
 pub enum GroupCreateType
 {
@@ -88,6 +89,8 @@ async fn create_group(
 
 	let input: CreateData = bytes_to_json(&body)?;
 
+	check_public_key_sig(app, (&input).into())?;
+
 	let group_id = group_service::create_group(
 		&app.app_data.app_id,
 		&user.id,
@@ -166,6 +169,8 @@ async fn create_group_force(
 
 	let input: CreateData = bytes_to_json(&body)?;
 
+	check_public_key_sig(app, (&input).into())?;
+
 	let group_id = group_service::create_group(
 		&app.app_data.app_id,
 		user_id,
@@ -144,6 +144,11 @@ pub struct GroupUserKeys
 	pub public_key_sig: Option<String>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub public_key_sig_key_id: Option<String>,
+
+	//the sdk should not encrypt with deprecated or compromised keys
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub not_after: Option<u128>,
+	pub key_status: i32,
 }
 
 impl Into<GroupKeyServerOutput> for GroupUserKeys
@@ -256,6 +261,19 @@ pub struct KeyRotationWorkerKey
 	pub encrypted_ephemeral_key: String,
 }
 
+/**
+The algs of a rotated key to check the public key sig before the user accepts the key.
+
+The keys itself are in the key store.
+ */
+#[derive(DB)]
+pub struct GroupKeySigData
+{
+	pub keypair_encrypt_alg: String,
+	pub keypair_sign_alg: Option<String>,
+	pub public_key_sig: Option<String>,
+}
+
 //__________________________________________________________________________________________________
 
 /**
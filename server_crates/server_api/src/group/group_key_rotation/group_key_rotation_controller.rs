@@ -1,5 +1,6 @@
 use rustgram::Request;
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::get_time;
 use rustgram_server_util::input_helper::{bytes_to_json, get_raw_body};
 use rustgram_server_util::res::{echo, echo_success, JRes, ServerSuccessOutput};
 use rustgram_server_util::url_helper::get_name_param_from_req;
@@ -9,8 +10,10 @@ use server_api_common::group::get_group_user_data_from_req;
 
 use crate::group::group_entities::GroupKeyUpdate;
 use crate::group::group_key_rotation::group_key_rotation_service;
+use crate::key_management::key_entity::KeyDeprecateInput;
 use crate::user::user_service;
 use crate::util::api_res::ApiErrorCodes;
+use crate::util::key_sig::check_public_key_sig;
 
 pub async fn start_key_rotation(mut req: Request) -> JRes<KeyRotationStartServerOutput>
 {
@@ -43,6 +46,8 @@ pub async fn start_key_rotation(mut req: Request) -> JRes<KeyRotationStartServer
 
 	let input: KeyRotationData = bytes_to_json(&body)?;
 
+	check_public_key_sig(app, (&input).into())?;
+
 	let out = group_key_rotation_service::start_key_rotation(
 		&group_data.group_data.app_id,
 		&group_data.group_data.id,
@@ -73,7 +78,8 @@ pub async fn get_keys_for_update(req: Request) -> JRes<Vec<GroupKeyUpdate>>
 
 pub async fn done_key_rotation_for_user(mut req: Request) -> JRes<ServerSuccessOutput>
 {
-	check_endpoint_with_req(&req, Endpoint::GroupUserDataGet)?;
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::GroupUserDataGet)?;
 
 	//called from the user
 	let body = get_raw_body(&mut req).await?;
@@ -84,6 +90,7 @@ pub async fn done_key_rotation_for_user(mut req: Request) -> JRes<ServerSuccessO
 	let input: DoneKeyRotationData = bytes_to_json(&body)?;
 
 	group_key_rotation_service::done_key_rotation_for_user(
+		app,
 		&group_data.group_data.id,
 		&group_data.user_data.user_id,
 		key_id,
@@ -93,3 +100,79 @@ pub async fn done_key_rotation_for_user(mut req: Request) -> JRes<ServerSuccessO
 
 	echo_success()
 }
+
+/**
+Mark a group key as deprecated. Needs the same rank as the key rotation.
+ */
+pub async fn deprecate_group_key(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::GroupKeyRotation)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	if group_data.user_data.rank > app.group_options.min_rank_key_rotation {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::GroupUserRank,
+			"Wrong group rank for this action",
+		));
+	}
+
+	let key_id = get_name_param_from_req(&req, "key_id")?;
+	let input: KeyDeprecateInput = bytes_to_json(&body)?;
+
+	if let Some(not_after) = input.not_after {
+		if not_after <= get_time()? {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::KeyNotAfter,
+				"The not after time must be in the future",
+			));
+		}
+	}
+
+	group_key_rotation_service::deprecate_group_key(
+		&group_data.group_data.app_id,
+		&group_data.group_data.id,
+		key_id,
+		input.not_after,
+	)
+	.await?;
+
+	echo_success()
+}
+
+/**
+Mark a group key as compromised from the backend and start a key rotation as the given user.
+
+The rotation keys must be created by a member of the group with the sdk, the server can't create group keys.
+The monthly rotation limit is not checked here.
+ */
+pub async fn group_key_compromised_forced(mut req: Request) -> JRes<KeyRotationStartServerOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::ForceServer)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+	let key_id = get_name_param_from_req(&req, "key_id")?;
+
+	let input: KeyRotationData = bytes_to_json(&body)?;
+
+	check_public_key_sig(app, (&input).into())?;
+
+	let out = group_key_rotation_service::group_key_compromised(
+		&group_data.group_data.app_id,
+		&group_data.group_data.id,
+		&group_data.user_data.user_id,
+		key_id,
+		input,
+	)
+	.await?;
+
+	echo(out)
+}
@@ -1,12 +1,13 @@
 use rustgram_server_util::db::id_handling::{check_id_format, create_id};
-use rustgram_server_util::db::{bulk_insert, exec, exec_transaction, query, query_first, query_string, TransactionData};
+use rustgram_server_util::db::{bulk_insert, exec, exec_transaction, query, query_first, query_string, I32Entity, TransactionData};
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
 use rustgram_server_util::res::AppRes;
 use rustgram_server_util::{get_time, set_params};
 use sentc_crypto_common::group::{DoneKeyRotationData, KeyRotationData};
 use sentc_crypto_common::{AppId, DeviceId, GroupId, SymKeyId, UserId};
 
-use crate::group::group_entities::{GroupKeyUpdate, KeyRotationWorkerKey, UserEphKeyOut, UserGroupPublicKeyData};
+use crate::group::group_entities::{GroupKeySigData, GroupKeyUpdate, KeyRotationWorkerKey, UserEphKeyOut, UserGroupPublicKeyData};
+use crate::key_management::{KEY_STATUS_COMPROMISED, KEY_STATUS_DEPRECATED};
 use crate::util::api_res::ApiErrorCodes;
 
 pub(super) async fn start_key_rotation(
@@ -153,7 +154,19 @@ ORDER BY gk.time";
 	query(sql, set_params!(user_id.into(), group_id.into(), app_id.into())).await
 }
 
-pub async fn done_key_rotation_for_user(
+pub(super) async fn get_key_sig_data(
+	app_id: impl Into<AppId>,
+	group_id: impl Into<GroupId>,
+	key_id: impl Into<SymKeyId>,
+) -> AppRes<Option<GroupKeySigData>>
+{
+	//language=SQL
+	let sql = "SELECT private_key_pair_alg, keypair_sign_alg, public_key_sig FROM sentc_group_keys WHERE app_id = ? AND group_id = ? AND id = ?";
+
+	query_first(sql, set_params!(app_id.into(), group_id.into(), key_id.into())).await
+}
+
+pub(super) async fn done_key_rotation_for_user(
 	group_id: impl Into<GroupId>,
 	user_id: impl Into<UserId>,
 	key_id: impl Into<SymKeyId>,
@@ -522,3 +535,75 @@ pub(super) async fn delete_eph_key(group_id: impl Into<GroupId>, key_id: impl In
 
 	Ok(())
 }
+
+pub(super) async fn deprecate_group_key(
+	app_id: impl Into<AppId>,
+	group_id: impl Into<GroupId>,
+	key_id: impl Into<SymKeyId>,
+	not_after: Option<u128>,
+) -> AppRes<()>
+{
+	let app_id = app_id.into();
+	let group_id = group_id.into();
+	let key_id = key_id.into();
+
+	check_group_key_exists(&app_id, &group_id, &key_id).await?;
+
+	//don't overwrite the compromised status
+	//language=SQL
+	let sql = "UPDATE sentc_group_keys SET key_status = ?, not_after = ? WHERE app_id = ? AND group_id = ? AND id = ? AND key_status <> ?";
+
+	exec(
+		sql,
+		set_params!(
+			KEY_STATUS_DEPRECATED,
+			not_after.map(|t| t.to_string()),
+			app_id,
+			group_id,
+			key_id,
+			KEY_STATUS_COMPROMISED
+		),
+	)
+	.await?;
+
+	Ok(())
+}
+
+pub(super) async fn set_group_key_compromised(app_id: impl Into<AppId>, group_id: impl Into<GroupId>, key_id: impl Into<SymKeyId>) -> AppRes<()>
+{
+	let app_id = app_id.into();
+	let group_id = group_id.into();
+	let key_id = key_id.into();
+
+	//language=SQL
+	let sql = "UPDATE sentc_group_keys SET key_status = ? WHERE app_id = ? AND group_id = ? AND id = ?";
+
+	exec(sql, set_params!(KEY_STATUS_COMPROMISED, app_id, group_id, key_id)).await?;
+
+	Ok(())
+}
+
+/**
+The key must be a key of this group, otherwise the update would silently do nothing
+ */
+pub(super) async fn check_group_key_exists(app_id: &str, group_id: &str, key_id: &str) -> AppRes<()>
+{
+	//language=SQL
+	let sql = "SELECT 1 FROM sentc_group_keys WHERE app_id = ? AND group_id = ? AND id = ? LIMIT 1";
+
+	let exists: Option<I32Entity> = query_first(
+		sql,
+		set_params!(app_id.to_string(), group_id.to_string(), key_id.to_string()),
+	)
+	.await?;
+
+	if exists.is_none() {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::GroupKeyNotFound,
+			"Group key not found",
+		));
+	}
+
+	Ok(())
+}
@@ -1,11 +1,17 @@
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
 use rustgram_server_util::res::AppRes;
-use sentc_crypto_common::group::{KeyRotationData, KeyRotationStartServerOutput};
-use sentc_crypto_common::{AppId, GroupId, UserId};
+use sentc_crypto_common::group::{DoneKeyRotationData, KeyRotationData, KeyRotationStartServerOutput};
+use sentc_crypto_common::{AppId, GroupId, SymKeyId, UserId};
+use server_api_common::customer_app::app_entities::AppData;
 use server_key_store::KeyStorage;
 
-pub use self::group_key_rotation_model::{done_key_rotation_for_user, get_keys_for_key_update as get_keys_for_update};
+pub use self::group_key_rotation_model::get_keys_for_key_update as get_keys_for_update;
 use crate::group::group_key_rotation::group_key_rotation_model;
 use crate::group::group_key_rotation_worker;
+use crate::key_transparency::key_transparency_entities::KtLeafInput;
+use crate::key_transparency::{key_transparency_service, KT_KEY_TYPE_GROUP_PUBLIC, KT_KEY_TYPE_GROUP_VERIFY};
+use crate::util::api_res::ApiErrorCodes;
+use crate::util::key_sig::{check_public_key_sig, PublicKeySigCheck};
 
 pub async fn start_key_rotation(
 	app_id: impl Into<AppId>,
@@ -65,6 +71,8 @@ pub async fn start_key_rotation(
 
 	let key_id = group_key_rotation_model::start_key_rotation(&app_id, &group_id, starter_id, key_data).await?;
 
+	let mut kt_keys = vec![KtLeafInput::new(KT_KEY_TYPE_GROUP_PUBLIC, &group_id, &key_id, &input.public_group_key)];
+
 	let mut keys_to_fetch = vec![
 		KeyStorage {
 			key: input.public_group_key,
@@ -77,6 +85,13 @@ pub async fn start_key_rotation(
 	];
 
 	if let (Some(sign_key), Some(verify_key)) = (encrypted_sign_key_for_storage, verify_key_for_storage) {
+		kt_keys.push(KtLeafInput::new(
+			KT_KEY_TYPE_GROUP_VERIFY,
+			&group_id,
+			&key_id,
+			&verify_key,
+		));
+
 		keys_to_fetch.push(KeyStorage {
 			key: verify_key,
 			id: format!("vk_{key_id}"),
@@ -103,6 +118,8 @@ pub async fn start_key_rotation(
 
 	server_key_store::upload_key(keys_to_fetch).await?;
 
+	key_transparency_service::append_keys(&app_id, kt_keys).await?;
+
 	//don't wait for the response
 	tokio::task::spawn(group_key_rotation_worker::start(
 		app_id,
@@ -118,3 +135,93 @@ pub async fn start_key_rotation(
 
 	Ok(out)
 }
+
+/**
+Accept the rotated key.
+
+When the app requires signed keys, the public key of the new key is checked before the user stores the key.
+ */
+pub async fn done_key_rotation_for_user(
+	app_data: &AppData,
+	group_id: impl Into<GroupId>,
+	user_id: impl Into<UserId>,
+	key_id: impl Into<SymKeyId>,
+	input: DoneKeyRotationData,
+) -> AppRes<()>
+{
+	let group_id = group_id.into();
+	let key_id = key_id.into();
+
+	if app_data.group_options.require_signed_keys == 1 {
+		check_rotated_key_sig(app_data, &group_id, &key_id).await?;
+	}
+
+	group_key_rotation_model::done_key_rotation_for_user(group_id, user_id, key_id, input).await
+}
+
+pub async fn deprecate_group_key(
+	app_id: impl Into<AppId>,
+	group_id: impl Into<GroupId>,
+	key_id: impl Into<SymKeyId>,
+	not_after: Option<u128>,
+) -> AppRes<()>
+{
+	group_key_rotation_model::deprecate_group_key(app_id, group_id, key_id, not_after).await
+}
+
+/**
+Start a new key rotation and mark the group key as compromised, so the compromised key is not used for new data anymore.
+
+The key is only marked after the rotation was started, a failed rotation leaves the key as it was.
+ */
+pub async fn group_key_compromised(
+	app_id: impl Into<AppId>,
+	group_id: impl Into<GroupId>,
+	starter_id: impl Into<UserId>,
+	key_id: impl Into<SymKeyId>,
+	input: KeyRotationData,
+) -> AppRes<KeyRotationStartServerOutput>
+{
+	let app_id = app_id.into();
+	let group_id = group_id.into();
+	let key_id: SymKeyId = key_id.into();
+
+	group_key_rotation_model::check_group_key_exists(&app_id, &group_id, &key_id).await?;
+
+	let out = start_key_rotation(app_id.clone(), group_id.clone(), starter_id, input, None).await?;
+
+	group_key_rotation_model::set_group_key_compromised(app_id, group_id, key_id).await?;
+
+	Ok(out)
+}
+
+async fn check_rotated_key_sig(app_data: &AppData, group_id: &str, key_id: &str) -> AppRes<()>
+{
+	let key = group_key_rotation_model::get_key_sig_data(&app_data.app_data.app_id, group_id, key_id)
+		.await?
+		.ok_or_else(|| ServerCoreError::new_msg(400, ApiErrorCodes::GroupKeyNotFound, "Group key not found"))?;
+
+	let pk_id = format!("pk_{key_id}");
+	let vk_id = format!("vk_{key_id}");
+	let sig_id = format!("sig_pk_{key_id}");
+
+	let mut keys = server_key_store::get_keys(&[pk_id.clone(), vk_id.clone(), sig_id.clone()]).await?;
+
+	let public_key = keys
+		.remove(&pk_id)
+		.ok_or_else(|| ServerCoreError::new_msg(400, ApiErrorCodes::GroupKeyNotFound, "Group key not found"))?;
+	let verify_key = keys.remove(&vk_id);
+	//the sig is only in the key store when it was set
+	let public_key_sig = if key.public_key_sig.is_some() { keys.remove(&sig_id) } else { None };
+
+	check_public_key_sig(
+		app_data,
+		PublicKeySigCheck {
+			public_key: &public_key,
+			public_key_alg: &key.keypair_encrypt_alg,
+			public_key_sig: public_key_sig.as_ref(),
+			verify_key: verify_key.as_ref(),
+			verify_key_alg: key.keypair_sign_alg.as_ref(),
+		},
+	)
+}
@@ -5,9 +5,12 @@ use rustgram_server_util::res::AppRes;
 use sentc_crypto::traverse_keys;
 use sentc_crypto::util::server::encrypt_ephemeral_group_key_with_public_key;
 use sentc_crypto_common::{AppId, GroupId, SymKeyId};
+use server_api_common::customer_app::app_entities::AppData;
+use server_api_common::customer_app::get_app_data_from_id;
 
 use crate::group::group_entities::{KeyRotationWorkerKey, UserEphKeyOut, UserGroupPublicKeyData};
 use crate::group::group_key_rotation::group_key_rotation_model;
+use crate::user::key_endpoint::key_endpoint_service;
 use crate::user::user_service;
 use crate::util::api_res::ApiErrorCodes;
 
@@ -23,8 +26,22 @@ pub async fn start(app_id: AppId, group_id: GroupId, key_id: SymKeyId, user_grou
 
 	let key_arc = Arc::new(key);
 
+	//only the user group key rotation uses the device keys, which can be stored at the key endpoint of the app
+	let app_data = match &user_group {
+		Some(_) => Some(get_app_data_from_id(&app_id).await?),
+		None => None,
+	};
+
 	//get all for the user
-	let mut total_len = loop_user(&group_id, &key_id, key_arc.clone(), LoopType::User, &user_group).await?;
+	let mut total_len = loop_user(
+		&group_id,
+		&key_id,
+		key_arc.clone(),
+		LoopType::User,
+		&user_group,
+		app_data.as_ref(),
+	)
+	.await?;
 
 	//don't call parent key rotation or group as member key rotation for user groups
 	if user_group.is_none() {
@@ -34,6 +51,7 @@ pub async fn start(app_id: AppId, group_id: GroupId, key_id: SymKeyId, user_grou
 			key_arc.clone(),
 			LoopType::GroupAsMember,
 			&user_group,
+			None,
 		)
 		.await?;
 	}
@@ -42,7 +60,7 @@ pub async fn start(app_id: AppId, group_id: GroupId, key_id: SymKeyId, user_grou
 	if let Some(item) = group_key_rotation_model::get_parent_group_and_public_key(&group_id, &key_id).await? {
 		let mut vec = vec![item];
 
-		fetch_extern_public_key(&mut vec).await?;
+		fetch_extern_public_key(&mut vec, None).await?;
 
 		let user_keys = tokio::task::spawn_blocking(move || encrypt(&key_arc, vec))
 			.await
@@ -80,6 +98,7 @@ async fn loop_user(
 	key_arc: Arc<KeyRotationWorkerKey>,
 	loop_type: LoopType,
 	user_group: &Option<String>,
+	app_data: Option<&AppData>,
 ) -> AppRes<usize>
 {
 	let mut last_time_fetched = 0;
@@ -121,7 +140,7 @@ async fn loop_user(
 		last_user_id = users[len - 1].user_id.clone();
 
 		//check if public key is extern
-		fetch_extern_public_key(&mut users).await?;
+		fetch_extern_public_key(&mut users, app_data).await?;
 
 		//encrypt for each user
 		let user_keys = tokio::task::spawn_blocking(move || encrypt(&key_cap, users))
@@ -147,7 +166,10 @@ async fn loop_user(
 	Ok(total_len)
 }
 
-async fn fetch_extern_public_key(users: &mut [UserGroupPublicKeyData]) -> AppRes<()>
+/**
+The device keys are fetched with the app data from the key endpoint of the app, the group keys are always in the key store.
+ */
+async fn fetch_extern_public_key(users: &mut [UserGroupPublicKeyData], device_keys_of: Option<&AppData>) -> AppRes<()>
 {
 	//check if public key is extern
 	let mut keys_to_fetch = vec![];
@@ -162,7 +184,10 @@ async fn fetch_extern_public_key(users: &mut [UserGroupPublicKeyData]) -> AppRes
 		return Ok(());
 	}
 
-	let mut fetched_keys = server_key_store::get_keys(&keys_to_fetch).await?;
+	let mut fetched_keys = match device_keys_of {
+		Some(app_data) => key_endpoint_service::get_keys(app_data, &keys_to_fetch).await?,
+		None => server_key_store::get_keys(&keys_to_fetch).await?,
+	};
 
 	for user in users {
 		if user.public_key == "extern" {
@@ -135,7 +135,9 @@ SELECT
     keypair_sign_alg,
     k_id AS keypair_sign_id,
     public_key_sig,
-    public_key_sig_key_id
+    public_key_sig_key_id,
+    not_after,
+    key_status
 FROM 
     sentc_group_keys k, 
     sentc_group_user_keys uk
@@ -197,7 +199,9 @@ SELECT
     keypair_sign_alg,
     k_id AS keypair_sign_id,
     public_key_sig,
-    public_key_sig_key_id
+    public_key_sig_key_id,
+    not_after,
+    key_status
 FROM 
     sentc_group_keys k, 
     sentc_group_user_keys uk
@@ -765,7 +769,7 @@ User here the cache from the mw
 
 Then check if the rank fits
 */
-pub(super) fn check_group_rank(user_rank: i32, req_rank: i32) -> AppRes<()>
+pub(crate) fn check_group_rank(user_rank: i32, req_rank: i32) -> AppRes<()>
 {
 	if user_rank > req_rank {
 		return Err(ServerCoreError::new_msg(
@@ -12,6 +12,8 @@ pub use self::group_model::{create_light as create_group_light, get_first_level_
 use crate::group::group_entities::GroupServerData;
 use crate::group::group_model;
 use crate::group::group_model::{check_group_rank, GroupForDelete};
+use crate::key_transparency::key_transparency_entities::KtLeafInput;
+use crate::key_transparency::{key_transparency_service, KT_KEY_TYPE_GROUP_PUBLIC, KT_KEY_TYPE_GROUP_VERIFY};
 use crate::sentc_group_entities::GroupUserKeys;
 use crate::sentc_user_entities::UserPublicKeyDataEntity;
 use crate::sentc_user_service::get_public_key_extern;
@@ -162,6 +164,8 @@ pub async fn create_group(
 	is_connected_group: bool,
 ) -> AppRes<(GroupId, SymKeyId)>
 {
+	let app_id = app_id.into();
+
 	let (encrypted_sign_key_for_model, encrypted_sign_key_for_storage, verify_key_for_model, verify_key_for_storage) =
 		if let (Some(encrypted_sign_key), Some(verify_key)) = (data.encrypted_sign_key, data.verify_key) {
 			(
@@ -208,7 +212,7 @@ pub async fn create_group(
 	};
 
 	let (group_id, key_id) = group_model::create(
-		app_id,
+		&app_id,
 		user_id,
 		create_data,
 		group_type,
@@ -219,6 +223,9 @@ pub async fn create_group(
 	)
 	.await?;
 
+	//log the public keys of the group, for user groups these are the user public keys
+	let mut kt_keys = vec![KtLeafInput::new(KT_KEY_TYPE_GROUP_PUBLIC, &group_id, &key_id, &data.public_group_key)];
+
 	let mut keys_to_fetch = vec![
 		KeyStorage {
 			key: data.public_group_key,
@@ -231,6 +238,13 @@ pub async fn create_group(
 	];
 
 	if let (Some(sign_key), Some(verify_key)) = (encrypted_sign_key_for_storage, verify_key_for_storage) {
+		kt_keys.push(KtLeafInput::new(
+			KT_KEY_TYPE_GROUP_VERIFY,
+			&group_id,
+			&key_id,
+			&verify_key,
+		));
+
 		keys_to_fetch.push(KeyStorage {
 			key: verify_key,
 			id: format!("vk_{key_id}"),
@@ -257,6 +271,8 @@ pub async fn create_group(
 
 	server_key_store::upload_key(keys_to_fetch).await?;
 
+	key_transparency_service::append_keys(app_id, kt_keys).await?;
+
 	Ok((group_id, key_id))
 }
 
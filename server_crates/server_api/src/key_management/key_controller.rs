@@ -1,24 +1,69 @@
 use rustgram::Request;
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::get_time;
 use rustgram_server_util::input_helper::{bytes_to_json, get_raw_body};
-use rustgram_server_util::res::{echo, echo_success, JRes, ServerSuccessOutput};
+use rustgram_server_util::res::{echo, echo_success, AppRes, JRes, ServerSuccessOutput};
 use rustgram_server_util::url_helper::{get_name_param_from_params, get_name_param_from_req, get_params, get_time_from_url_param};
-use sentc_crypto_common::crypto::{GeneratedSymKeyHeadServerInput, GeneratedSymKeyHeadServerRegisterOutput};
+use sentc_crypto_common::crypto::GeneratedSymKeyHeadServerRegisterOutput;
+use sentc_crypto_common::SymKeyId;
 use server_api_common::customer_app::{check_endpoint_with_app_options, get_app_data_from_req, Endpoint};
+use server_api_common::group::get_group_user_data_from_req;
 use server_api_common::user::get_jwt_data_from_param;
 
-use crate::key_management::key_entity::SymKeyEntity;
+use crate::group::group_model::check_group_rank;
+use crate::key_management::key_entity::{KeyDeprecateInput, SymKeyEntity, SymKeyRegisterInput};
 use crate::key_management::key_model;
+use crate::util::api_res::ApiErrorCodes;
+
+const MAX_LABEL_LEN: usize = 200;
+const MAX_BULK_KEYS: usize = 100;
 
 pub async fn register_sym_key(mut req: Request) -> JRes<GeneratedSymKeyHeadServerRegisterOutput>
 {
 	let body = get_raw_body(&mut req).await?;
-	let input: GeneratedSymKeyHeadServerInput = bytes_to_json(&body)?;
+	let input: SymKeyRegisterInput = bytes_to_json(&body)?;
 
 	let app = get_app_data_from_req(&req)?;
 	check_endpoint_with_app_options(app, Endpoint::KeyRegister)?;
 	let user = get_jwt_data_from_param(&req)?;
 
-	let key_id = key_model::register_sym_key(&app.app_data.app_id, &user.id, input).await?;
+	check_label(&input)?;
+	check_not_after(input.not_after)?;
+
+	let key_id = key_model::register_sym_key(&app.app_data.app_id, &user.id, None, input).await?;
+
+	let out = GeneratedSymKeyHeadServerRegisterOutput {
+		key_id,
+	};
+
+	echo(out)
+}
+
+/**
+Register a key that is only readable by the members of the group.
+
+Called with the group mw. Only members who can write in the group (rank <= 3) can register keys.
+ */
+pub async fn register_group_sym_key(mut req: Request) -> JRes<GeneratedSymKeyHeadServerRegisterOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+	let input: SymKeyRegisterInput = bytes_to_json(&body)?;
+
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::KeyRegister)?;
+	let group_data = get_group_user_data_from_req(&req)?;
+	check_group_rank(group_data.user_data.rank, 3)?;
+
+	check_label(&input)?;
+	check_not_after(input.not_after)?;
+
+	let key_id = key_model::register_sym_key(
+		&app.app_data.app_id,
+		&group_data.user_data.user_id,
+		Some(group_data.group_data.id.clone()),
+		input,
+	)
+	.await?;
 
 	let out = GeneratedSymKeyHeadServerRegisterOutput {
 		key_id,
@@ -41,6 +86,45 @@ pub async fn delete_sym_key(req: Request) -> JRes<ServerSuccessOutput>
 	echo_success()
 }
 
+/**
+Mark the key as deprecated. Clients should not encrypt new data with this key.
+
+When a not after time is set, the key gets deleted by the key worker after this time plus the grace period.
+ */
+pub async fn deprecate_sym_key(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+	let input: KeyDeprecateInput = bytes_to_json(&body)?;
+
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::KeyRegister)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+
+	let key_id = get_name_param_from_req(&req, "key_id")?;
+
+	check_not_after(input.not_after)?;
+
+	key_model::deprecate_sym_key(&app.app_data.app_id, &user.id, key_id, input.not_after).await?;
+
+	echo_success()
+}
+
+/**
+Mark a key as compromised from the backend. Only with the secret token.
+ */
+pub async fn sym_key_compromised_forced(req: Request) -> JRes<ServerSuccessOutput>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::ForceServer)?;
+
+	let key_id = get_name_param_from_req(&req, "key_id")?;
+
+	key_model::set_sym_key_compromised(&app.app_data.app_id, key_id).await?;
+
+	echo_success()
+}
+
 pub async fn get_sym_key_by_id(req: Request) -> JRes<SymKeyEntity>
 {
 	let app_data = get_app_data_from_req(&req)?;
@@ -48,11 +132,66 @@ pub async fn get_sym_key_by_id(req: Request) -> JRes<SymKeyEntity>
 
 	let key_id = get_name_param_from_req(&req, "key_id")?;
 
-	let key = key_model::get_sym_key_by_id(&app_data.app_data.app_id, key_id).await?;
+	let key = key_model::get_sym_key_by_id(&app_data.app_data.app_id, key_id, None).await?;
+
+	echo(key)
+}
+
+pub async fn get_group_sym_key_by_id(req: Request) -> JRes<SymKeyEntity>
+{
+	let app_data = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app_data, Endpoint::KeyGet)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+	let key_id = get_name_param_from_req(&req, "key_id")?;
+
+	let key = key_model::get_sym_key_by_id(
+		&app_data.app_data.app_id,
+		key_id,
+		Some(group_data.group_data.id.clone()),
+	)
+	.await?;
 
 	echo(key)
 }
 
+pub async fn get_sym_keys_by_ids(mut req: Request) -> JRes<Vec<SymKeyEntity>>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app_data = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app_data, Endpoint::KeyGet)?;
+
+	let input: Vec<SymKeyId> = bytes_to_json(&body)?;
+	check_bulk_ids(&input)?;
+
+	let keys = key_model::get_sym_keys_by_ids(&app_data.app_data.app_id, input, None).await?;
+
+	echo(keys)
+}
+
+pub async fn get_group_sym_keys_by_ids(mut req: Request) -> JRes<Vec<SymKeyEntity>>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app_data = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app_data, Endpoint::KeyGet)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	let input: Vec<SymKeyId> = bytes_to_json(&body)?;
+	check_bulk_ids(&input)?;
+
+	let keys = key_model::get_sym_keys_by_ids(
+		&app_data.app_data.app_id,
+		input,
+		Some(group_data.group_data.id.clone()),
+	)
+	.await?;
+
+	echo(keys)
+}
+
 pub async fn get_all_sym_keys_to_master_key(req: Request) -> JRes<Vec<SymKeyEntity>>
 {
 	let app_data = get_app_data_from_req(&req)?;
@@ -75,3 +214,86 @@ pub async fn get_all_sym_keys_to_master_key(req: Request) -> JRes<Vec<SymKeyEnti
 
 	echo(keys)
 }
+
+pub async fn get_all_sym_keys_for_user(req: Request) -> JRes<Vec<SymKeyEntity>>
+{
+	let app_data = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app_data, Endpoint::KeyGet)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+
+	let params = get_params(&req)?;
+	let last_id = get_name_param_from_params(params, "last_id")?;
+	let last_fetched_time = get_name_param_from_params(params, "last_fetched_time")?;
+	let last_fetched_time = get_time_from_url_param(last_fetched_time)?;
+
+	let keys = key_model::get_all_sym_keys_for_user(&app_data.app_data.app_id, &user.id, last_fetched_time, last_id).await?;
+
+	echo(keys)
+}
+
+pub async fn get_all_sym_keys_for_group(req: Request) -> JRes<Vec<SymKeyEntity>>
+{
+	let app_data = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app_data, Endpoint::KeyGet)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	let params = get_params(&req)?;
+	let last_id = get_name_param_from_params(params, "last_id")?;
+	let last_fetched_time = get_name_param_from_params(params, "last_fetched_time")?;
+	let last_fetched_time = get_time_from_url_param(last_fetched_time)?;
+
+	let keys = key_model::get_all_sym_keys_for_group(
+		&app_data.app_data.app_id,
+		&group_data.group_data.id,
+		last_fetched_time,
+		last_id,
+	)
+	.await?;
+
+	echo(keys)
+}
+
+fn check_label(input: &SymKeyRegisterInput) -> AppRes<()>
+{
+	if let Some(label) = &input.label {
+		if label.len() > MAX_LABEL_LEN {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::KeyLabelTooLong,
+				"The label of the key is too long. Max 200 characters",
+			));
+		}
+	}
+
+	Ok(())
+}
+
+fn check_not_after(not_after: Option<u128>) -> AppRes<()>
+{
+	if let Some(not_after) = not_after {
+		if not_after <= get_time()? {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::KeyNotAfter,
+				"The not after time must be in the future",
+			));
+		}
+	}
+
+	Ok(())
+}
+
+fn check_bulk_ids(input: &[SymKeyId]) -> AppRes<()>
+{
+	if input.is_empty() || input.len() > MAX_BULK_KEYS {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::KeyTooManyIds,
+			"Fetch between 1 and 100 keys at once",
+		));
+	}
+
+	Ok(())
+}
@@ -1,7 +1,7 @@
 use rustgram_server_util::DB;
 use sentc_crypto_common::crypto::GeneratedSymKeyHeadServerOutput;
-use sentc_crypto_common::SymKeyId;
-use serde::Serialize;
+use sentc_crypto_common::{GroupId, SymKeyId, UserId};
+use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, DB)]
 pub struct SymKeyEntity
@@ -11,6 +11,11 @@ pub struct SymKeyEntity
 	encrypted_key_string: String,
 	alg: String,
 	time: u128,
+	owner_id: UserId,
+	group_id: Option<GroupId>,
+	label: Option<String>,
+	not_after: Option<u128>,
+	key_status: i32,
 }
 
 impl Into<GeneratedSymKeyHeadServerOutput> for SymKeyEntity
@@ -28,3 +33,31 @@ impl Into<GeneratedSymKeyHeadServerOutput> for SymKeyEntity
 }
 
 //__________________________________________________________________________________________________
+
+/**
+The same as the register input from the sdk but with an optional label.
+
+Old clients can still register keys without the label.
+ */
+#[derive(Deserialize)]
+pub struct SymKeyRegisterInput
+{
+	pub master_key_id: SymKeyId,
+	pub encrypted_key_string: String,
+	pub alg: String,
+	pub label: Option<String>,
+	pub not_after: Option<u128>,
+}
+
+/**
+Mark a key as deprecated. The sdk should not encrypt with deprecated keys anymore.
+
+With not after the key gets deleted after the grace period of the key worker (only for sym keys).
+ */
+#[derive(Deserialize)]
+pub struct KeyDeprecateInput
+{
+	pub not_after: Option<u128>,
+}
+
+//__________________________________________________________________________________________________
@@ -1,18 +1,19 @@
 use rustgram_server_util::db::id_handling::create_id;
-use rustgram_server_util::db::{exec, query_first, query_string};
+use rustgram_server_util::db::{exec, get_in, query_first, query_string, I32Entity, TupleEntity};
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
 use rustgram_server_util::res::AppRes;
-use rustgram_server_util::{get_time, set_params};
-use sentc_crypto_common::crypto::GeneratedSymKeyHeadServerInput;
-use sentc_crypto_common::{AppId, SymKeyId, UserId};
+use rustgram_server_util::{get_time, set_params, set_params_vec};
+use sentc_crypto_common::{AppId, GroupId, SymKeyId, UserId};
 
-use crate::key_management::key_entity::SymKeyEntity;
+use crate::key_management::key_entity::{SymKeyEntity, SymKeyRegisterInput};
+use crate::key_management::{KEY_STATUS_COMPROMISED, KEY_STATUS_DEPRECATED};
 use crate::util::api_res::ApiErrorCodes;
 
 pub(super) async fn register_sym_key(
 	app_id: impl Into<AppId>,
 	creator_id: impl Into<UserId>,
-	input: GeneratedSymKeyHeadServerInput,
+	group_id: Option<GroupId>,
+	input: SymKeyRegisterInput,
 ) -> AppRes<SymKeyId>
 {
 	let key_id = create_id();
@@ -28,9 +29,12 @@ INSERT INTO sentc_sym_key_management
      creator_id,
      encrypted_key, 
      master_key_alg, 
-     time
+     time,
+     group_id,
+     label,
+     not_after
      ) 
-VALUES (?,?,?,?,?,?,?)";
+VALUES (?,?,?,?,?,?,?,?,?,?)";
 
 	exec(
 		sql,
@@ -41,7 +45,10 @@ VALUES (?,?,?,?,?,?,?)";
 			creator_id.into(),
 			input.encrypted_key_string,
 			input.alg,
-			time.to_string()
+			time.to_string(),
+			group_id,
+			input.label,
+			input.not_after.map(|t| t.to_string())
 		),
 	)
 	.await?;
@@ -59,23 +66,138 @@ pub(super) async fn delete_sym_key(app_id: impl Into<AppId>, creator_id: impl In
 	Ok(())
 }
 
-pub(super) async fn get_sym_key_by_id(app_id: impl Into<AppId>, key_id: impl Into<SymKeyId>) -> AppRes<SymKeyEntity>
+pub(super) async fn deprecate_sym_key(
+	app_id: impl Into<AppId>,
+	creator_id: impl Into<UserId>,
+	key_id: impl Into<SymKeyId>,
+	not_after: Option<u128>,
+) -> AppRes<()>
 {
+	let app_id = app_id.into();
+	let creator_id = creator_id.into();
+	let key_id = key_id.into();
+
+	//only the creator can deprecate the key
 	//language=SQL
-	let sql = "SELECT id, master_key_id, encrypted_key, master_key_alg, time FROM sentc_sym_key_management WHERE app_id = ? AND id = ?";
-
-	let key: Option<SymKeyEntity> = query_first(sql, set_params!(app_id.into(), key_id.into())).await?;
-
-	match key {
-		Some(k) => Ok(k),
-		None => {
-			Err(ServerCoreError::new_msg(
-				400,
-				ApiErrorCodes::KeyNotFound,
-				"Key not found",
-			))
-		},
+	let sql = "SELECT 1 FROM sentc_sym_key_management WHERE app_id = ? AND creator_id = ? AND id = ? LIMIT 1";
+
+	let exists: Option<I32Entity> = query_first(sql, set_params!(app_id.clone(), creator_id.clone(), key_id.clone())).await?;
+
+	if exists.is_none() {
+		return Err(key_not_found());
 	}
+
+	//don't overwrite the compromised status
+	//language=SQL
+	let sql = "UPDATE sentc_sym_key_management SET key_status = ?, not_after = ? WHERE app_id = ? AND creator_id = ? AND id = ? AND key_status <> ?";
+
+	exec(
+		sql,
+		set_params!(
+			KEY_STATUS_DEPRECATED,
+			not_after.map(|t| t.to_string()),
+			app_id,
+			creator_id,
+			key_id,
+			KEY_STATUS_COMPROMISED
+		),
+	)
+	.await?;
+
+	Ok(())
+}
+
+pub(super) async fn set_sym_key_compromised(app_id: impl Into<AppId>, key_id: impl Into<SymKeyId>) -> AppRes<()>
+{
+	let app_id = app_id.into();
+	let key_id = key_id.into();
+
+	//language=SQL
+	let sql = "SELECT 1 FROM sentc_sym_key_management WHERE app_id = ? AND id = ? LIMIT 1";
+
+	let exists: Option<I32Entity> = query_first(sql, set_params!(app_id.clone(), key_id.clone())).await?;
+
+	if exists.is_none() {
+		return Err(key_not_found());
+	}
+
+	//language=SQL
+	let sql = "UPDATE sentc_sym_key_management SET key_status = ? WHERE app_id = ? AND id = ?";
+
+	exec(sql, set_params!(KEY_STATUS_COMPROMISED, app_id, key_id)).await?;
+
+	Ok(())
+}
+
+/**
+Delete all keys which are expired before the given time. Used by the key worker.
+ */
+pub(super) async fn delete_expired_sym_keys(time: u128) -> AppRes<()>
+{
+	//language=SQL
+	let sql = "DELETE FROM sentc_sym_key_management WHERE not_after IS NOT NULL AND not_after < ?";
+
+	exec(sql, set_params!(time.to_string())).await?;
+
+	Ok(())
+}
+
+/**
+Get a key by id.
+
+Without a group id only keys without group scope are returned, group keys must be fetched via the group.
+ */
+pub(super) async fn get_sym_key_by_id(app_id: impl Into<AppId>, key_id: impl Into<SymKeyId>, group_id: Option<GroupId>) -> AppRes<SymKeyEntity>
+{
+	//language=SQL
+	let sql = r"
+SELECT id, master_key_id, encrypted_key, master_key_alg, time, creator_id, group_id, label, not_after, key_status 
+FROM sentc_sym_key_management 
+WHERE app_id = ? AND id = ?"
+		.to_string();
+
+	let key: Option<SymKeyEntity> = if let Some(group_id) = group_id {
+		let sql = sql + " AND group_id = ?";
+
+		query_first(sql, set_params!(app_id.into(), key_id.into(), group_id)).await?
+	} else {
+		let sql = sql + " AND group_id IS NULL";
+
+		query_first(sql, set_params!(app_id.into(), key_id.into())).await?
+	};
+
+	key.ok_or_else(key_not_found)
+}
+
+pub(super) async fn get_sym_keys_by_ids(app_id: impl Into<AppId>, key_ids: Vec<SymKeyId>, group_id: Option<GroupId>) -> AppRes<Vec<SymKeyEntity>>
+{
+	let ins = get_in(&key_ids);
+
+	let mut params = Vec::with_capacity(key_ids.len() + 2);
+	params.push(TupleEntity(app_id.into()));
+
+	for key_id in key_ids {
+		params.push(TupleEntity(key_id));
+	}
+
+	//language=SQLx
+	let sql = format!(
+		r"
+SELECT id, master_key_id, encrypted_key, master_key_alg, time, creator_id, group_id, label, not_after, key_status 
+FROM sentc_sym_key_management 
+WHERE app_id = ? AND id IN ({})",
+		ins
+	);
+
+	let sql = if let Some(group_id) = group_id {
+		params.push(TupleEntity(group_id));
+
+		sql + " AND group_id = ?"
+	} else {
+		sql + " AND group_id IS NULL"
+	};
+
+	query_string(sql, set_params_vec!(params)).await
 }
 
 pub(super) async fn get_all_sym_keys_to_master_key(
@@ -86,7 +208,10 @@ pub(super) async fn get_all_sym_keys_to_master_key(
 ) -> AppRes<Vec<SymKeyEntity>>
 {
 	//language=SQL
-	let sql = "SELECT id, master_key_id, encrypted_key, master_key_alg, time FROM sentc_sym_key_management WHERE app_id = ? AND master_key_id = ?"
+	let sql = r"
+SELECT id, master_key_id, encrypted_key, master_key_alg, time, creator_id, group_id, label, not_after, key_status 
+FROM sentc_sym_key_management 
+WHERE app_id = ? AND master_key_id = ? AND group_id IS NULL"
 		.to_string();
 
 	let (sql, params) = if last_fetched_time > 0 {
@@ -113,3 +238,89 @@ pub(super) async fn get_all_sym_keys_to_master_key(
 
 	Ok(keys)
 }
+
+/**
+All keys which were registered by the user, with and without group scope.
+ */
+pub(super) async fn get_all_sym_keys_for_user(
+	app_id: impl Into<AppId>,
+	creator_id: impl Into<UserId>,
+	last_fetched_time: u128,
+	last_id: impl Into<SymKeyId>,
+) -> AppRes<Vec<SymKeyEntity>>
+{
+	//language=SQL
+	let sql = r"
+SELECT id, master_key_id, encrypted_key, master_key_alg, time, creator_id, group_id, label, not_after, key_status 
+FROM sentc_sym_key_management 
+WHERE app_id = ? AND creator_id = ?"
+		.to_string();
+
+	let (sql, params) = if last_fetched_time > 0 {
+		let sql = sql + " AND time <= ? AND (time < ? OR (time = ? AND id > ?)) ORDER BY time DESC, id LIMIT 50";
+
+		(
+			sql,
+			set_params!(
+				app_id.into(),
+				creator_id.into(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_id.into()
+			),
+		)
+	} else {
+		let sql = sql + " ORDER BY time DESC, id LIMIT 50";
+
+		(sql, set_params!(app_id.into(), creator_id.into(),))
+	};
+
+	let keys: Vec<SymKeyEntity> = query_string(sql, params).await?;
+
+	Ok(keys)
+}
+
+pub(super) async fn get_all_sym_keys_for_group(
+	app_id: impl Into<AppId>,
+	group_id: impl Into<GroupId>,
+	last_fetched_time: u128,
+	last_id: impl Into<SymKeyId>,
+) -> AppRes<Vec<SymKeyEntity>>
+{
+	//language=SQL
+	let sql = r"
+SELECT id, master_key_id, encrypted_key, master_key_alg, time, creator_id, group_id, label, not_after, key_status 
+FROM sentc_sym_key_management 
+WHERE app_id = ? AND group_id = ?"
+		.to_string();
+
+	let (sql, params) = if last_fetched_time > 0 {
+		let sql = sql + " AND time <= ? AND (time < ? OR (time = ? AND id > ?)) ORDER BY time DESC, id LIMIT 50";
+
+		(
+			sql,
+			set_params!(
+				app_id.into(),
+				group_id.into(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_id.into()
+			),
+		)
+	} else {
+		let sql = sql + " ORDER BY time DESC, id LIMIT 50";
+
+		(sql, set_params!(app_id.into(), group_id.into(),))
+	};
+
+	let keys: Vec<SymKeyEntity> = query_string(sql, params).await?;
+
+	Ok(keys)
+}
+
+fn key_not_found() -> ServerCoreError
+{
+	ServerCoreError::new_msg(400, ApiErrorCodes::KeyNotFound, "Key not found")
+}
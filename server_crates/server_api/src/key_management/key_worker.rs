@@ -0,0 +1,25 @@
+use std::env;
+
+use rustgram_server_util::get_time;
+use rustgram_server_util::res::AppRes;
+
+use crate::key_management::key_model;
+
+const DAY_MS: u128 = 1000 * 60 * 60 * 24;
+
+/**
+Delete all sym keys which are expired longer than the grace period.
+
+Clients can still fetch expired keys within the grace period to decrypt old data.
+ */
+pub async fn start() -> AppRes<()>
+{
+	let grace_days: u128 = env::var("SYM_KEY_DELETE_GRACE_DAYS")
+		.unwrap_or_else(|_| "30".into())
+		.parse()
+		.unwrap_or(30);
+
+	let time = get_time()?.saturating_sub(grace_days * DAY_MS);
+
+	key_model::delete_expired_sym_keys(time).await
+}
@@ -1,5 +1,10 @@
 pub mod key_controller;
 pub mod key_entity;
 mod key_model;
+pub mod key_worker;
 
 pub(crate) use key_controller::*;
+
+pub const KEY_STATUS_ACTIVE: i32 = 0;
+pub const KEY_STATUS_DEPRECATED: i32 = 1;
+pub const KEY_STATUS_COMPROMISED: i32 = 2;
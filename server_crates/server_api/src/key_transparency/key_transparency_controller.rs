@@ -0,0 +1,66 @@
+use rustgram::Request;
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::res::{echo, AppRes, JRes};
+use rustgram_server_util::url_helper::{get_name_param_from_params, get_params};
+use server_api_common::customer_app::{check_endpoint_with_app_options, get_app_data_from_req, Endpoint};
+
+use crate::key_transparency::key_transparency_entities::{KtConsistencyProofOutput, KtInclusionProofOutput, KtSignedTreeHeadOutput};
+use crate::key_transparency::key_transparency_service;
+use crate::util::api_res::ApiErrorCodes;
+
+pub async fn get_tree_head(req: Request) -> JRes<KtSignedTreeHeadOutput>
+{
+	let app_data = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app_data, Endpoint::UserPublicData)?;
+
+	let out = key_transparency_service::get_signed_tree_head(app_data).await?;
+
+	echo(out)
+}
+
+pub async fn get_inclusion_proof(req: Request) -> JRes<KtInclusionProofOutput>
+{
+	let app_data = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app_data, Endpoint::UserPublicData)?;
+
+	let params = get_params(&req)?;
+	let key_type = get_name_param_from_params(params, "key_type")?;
+	let key_type: i32 = key_type.parse().map_err(|_e| {
+		ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::KtLeafNotFound,
+			"Parameter key type has a wrong format",
+		)
+	})?;
+	let key_id = get_name_param_from_params(params, "key_id")?;
+	let tree_size = parse_tree_size(get_name_param_from_params(params, "tree_size")?)?;
+
+	let out = key_transparency_service::get_inclusion_proof(&app_data.app_data.app_id, key_type, key_id, tree_size).await?;
+
+	echo(out)
+}
+
+pub async fn get_consistency_proof(req: Request) -> JRes<KtConsistencyProofOutput>
+{
+	let app_data = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app_data, Endpoint::UserPublicData)?;
+
+	let params = get_params(&req)?;
+	let first = parse_tree_size(get_name_param_from_params(params, "first")?)?;
+	let second = parse_tree_size(get_name_param_from_params(params, "second")?)?;
+
+	let out = key_transparency_service::get_consistency_proof(&app_data.app_data.app_id, first, second).await?;
+
+	echo(out)
+}
+
+fn parse_tree_size(size: &str) -> AppRes<i64>
+{
+	size.parse().map_err(|_e| {
+		ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::KtTreeSize,
+			"Parameter tree size has a wrong format",
+		)
+	})
+}
@@ -0,0 +1,98 @@
+use rustgram_server_util::DB;
+use sentc_crypto_common::AppId;
+use serde::{Deserialize, Serialize};
+
+/**
+A published public or verify key in the log.
+
+The key itself is not stored in the log, only the sha256 hash (base64) of the key string.
+The client can hash the fetched key and compare it.
+
+leaf hash = sha256(0x00 || "<key_type>.<owner_id>.<key_id>.<key_hash>.<time>")
+ */
+#[derive(Serialize, Deserialize, DB)]
+pub struct KtLeafEntity
+{
+	pub leaf_index: i64,
+	pub key_type: i32,
+	pub owner_id: String,
+	pub key_id: String,
+	pub key_hash: String,
+	pub leaf_hash: String,
+	pub time: u128,
+}
+
+/**
+The hash (base64) of a complete sub tree of the log
+ */
+#[derive(DB)]
+pub struct KtNodeEntity
+{
+	pub level: i32,
+	pub node_index: i64,
+	pub hash: String,
+}
+
+/**
+The data that is signed with the jwt key of the app.
+
+The signature is a jwt (with the kid of the app jwt key) and can be verified with the app verify key.
+ */
+#[derive(Serialize, Deserialize)]
+pub struct KtTreeHead
+{
+	pub app_id: AppId,
+	pub tree_size: i64,
+	pub root_hash: String,
+	pub time: u128,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KtSignedTreeHeadOutput
+{
+	pub tree_size: i64,
+	pub root_hash: String,
+	pub time: u128,
+	pub signature: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KtInclusionProofOutput
+{
+	pub leaf: KtLeafEntity,
+	pub tree_size: i64,
+	pub audit_path: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KtConsistencyProofOutput
+{
+	pub first: i64,
+	pub second: i64,
+	pub proof: Vec<String>,
+}
+
+//__________________________________________________________________________________________________
+
+pub struct KtLeafInput
+{
+	pub key_type: i32,
+	pub owner_id: String,
+	pub key_id: String,
+	pub key_hash: String,
+}
+
+impl KtLeafInput
+{
+	pub fn new(key_type: i32, owner_id: impl Into<String>, key_id: impl Into<String>, key: &str) -> Self
+	{
+		let key_hash = ring::digest::digest(&ring::digest::SHA256, key.as_bytes());
+
+		Self {
+			key_type,
+			owner_id: owner_id.into(),
+			key_id: key_id.into(),
+			key_hash: base64::encode(key_hash),
+		}
+	}
+}
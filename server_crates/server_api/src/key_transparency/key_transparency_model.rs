@@ -0,0 +1,110 @@
+use rustgram_server_util::db::{exec_transaction, query_first, query_string, I64Entity, TransactionData, TupleEntity};
+use rustgram_server_util::res::AppRes;
+use rustgram_server_util::{set_params, set_params_vec};
+use sentc_crypto_common::AppId;
+
+use crate::key_transparency::key_transparency_entities::{KtLeafEntity, KtNodeEntity};
+use crate::key_transparency::merkle_tree::NodeId;
+
+/**
+Append the leaf with the index and the hashes of the sub trees which are completed by the leaf.
+
+When two leaves are appended at the same time, the primary key (app_id, leaf_index) fails for one of them
+and the whole transaction is rolled back.
+ */
+pub(super) async fn append_leaf(app_id: impl Into<AppId>, leaf: KtLeafEntity, nodes: Vec<(NodeId, String)>) -> AppRes<()>
+{
+	let app_id = app_id.into();
+
+	//language=SQL
+	let sql = r"
+INSERT INTO sentc_kt_leaf 
+    (app_id, leaf_index, key_type, owner_id, key_id, key_hash, leaf_hash, time) 
+VALUES (?,?,?,?,?,?,?,?)";
+
+	let mut data = Vec::with_capacity(nodes.len() + 1);
+
+	data.push(TransactionData {
+		sql,
+		params: set_params!(
+			app_id.clone(),
+			leaf.leaf_index,
+			leaf.key_type,
+			leaf.owner_id,
+			leaf.key_id,
+			leaf.key_hash,
+			leaf.leaf_hash,
+			leaf.time.to_string()
+		),
+	});
+
+	for ((level, node_index), hash) in nodes {
+		//language=SQL
+		let sql = "INSERT INTO sentc_kt_node (app_id, level, node_index, hash) VALUES (?,?,?,?)";
+
+		data.push(TransactionData {
+			sql,
+			params: set_params!(app_id.clone(), level, node_index, hash),
+		});
+	}
+
+	exec_transaction(data).await?;
+
+	Ok(())
+}
+
+pub(super) async fn get_tree_size(app_id: impl Into<AppId>) -> AppRes<i64>
+{
+	//language=SQL
+	let sql = "SELECT COALESCE(MAX(leaf_index) + 1, 0) FROM sentc_kt_leaf WHERE app_id = ?";
+
+	let size: Option<I64Entity> = query_first(sql, set_params!(app_id.into())).await?;
+
+	Ok(size.map(|s| s.0).unwrap_or(0))
+}
+
+/**
+The hashes of the complete sub trees
+ */
+pub(super) async fn get_nodes(app_id: impl Into<AppId>, node_ids: &[NodeId]) -> AppRes<Vec<KtNodeEntity>>
+{
+	if node_ids.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	let mut params = Vec::with_capacity(node_ids.len() * 2 + 1);
+	params.push(TupleEntity(app_id.into()));
+
+	let mut ins = Vec::with_capacity(node_ids.len());
+
+	for (level, node_index) in node_ids {
+		params.push(TupleEntity(level.to_string()));
+		params.push(TupleEntity(node_index.to_string()));
+
+		ins.push("(level = ? AND node_index = ?)");
+	}
+
+	//language=SQLx
+	let sql = format!(
+		"SELECT level, node_index, hash FROM sentc_kt_node WHERE app_id = ? AND ({})",
+		ins.join(" OR ")
+	);
+
+	query_string(sql, set_params_vec!(params)).await
+}
+
+/**
+Get the first leaf of a key. A key id is only published once.
+ */
+pub(super) async fn get_leaf_by_key(app_id: impl Into<AppId>, key_type: i32, key_id: impl Into<String>) -> AppRes<Option<KtLeafEntity>>
+{
+	//language=SQL
+	let sql = r"
+SELECT leaf_index, key_type, owner_id, key_id, key_hash, leaf_hash, time 
+FROM sentc_kt_leaf 
+WHERE app_id = ? AND key_type = ? AND key_id = ? 
+ORDER BY leaf_index 
+LIMIT 1";
+
+	query_first(sql, set_params!(app_id.into(), key_type, key_id.into())).await
+}
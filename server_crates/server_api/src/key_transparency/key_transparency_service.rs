@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use rustgram_server_util::cache::LONG_TTL;
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::input_helper::{bytes_to_json, json_to_string};
+use rustgram_server_util::res::AppRes;
+use rustgram_server_util::{cache, get_time};
+use sentc_crypto_common::AppId;
+use server_api_common::customer_app::app_entities::AppData;
+use server_api_common::user::jwt::sign_with_app_jwt_key;
+use server_api_common::util::get_kt_tree_head_key;
+
+use crate::key_transparency::key_transparency_entities::{
+	KtConsistencyProofOutput,
+	KtInclusionProofOutput,
+	KtLeafEntity,
+	KtLeafInput,
+	KtSignedTreeHeadOutput,
+	KtTreeHead,
+};
+use crate::key_transparency::merkle_tree::{self, Hash, NodeId};
+use crate::key_transparency::{key_transparency_model, KT_KEY_TYPE_DEVICE_PUBLIC, KT_KEY_TYPE_DEVICE_VERIFY};
+use crate::user::key_endpoint::key_endpoint_service;
+use crate::util::api_res::ApiErrorCodes;
+
+const MAX_APPEND_TRIES: usize = 5;
+
+/**
+Append the published keys to the log of the app.
+
+Call it every time a public or verify key is created (register, new device, group creation and key rotation).
+ */
+pub(crate) async fn append_keys(app_id: impl Into<AppId>, keys: Vec<KtLeafInput>) -> AppRes<()>
+{
+	let app_id = app_id.into();
+
+	for key in keys {
+		let time = get_time()?;
+
+		let leaf_data = format!(
+			"{}.{}.{}.{}.{}",
+			key.key_type, key.owner_id, key.key_id, key.key_hash, time
+		);
+		let leaf_hash = merkle_tree::hash_leaf(leaf_data.as_bytes());
+
+		let mut tries = 0;
+
+		loop {
+			let leaf_index = key_transparency_model::get_tree_size(&app_id).await?;
+
+			let sibling_ids = merkle_tree::append_siblings(leaf_index as usize);
+			let siblings = load_nodes(&app_id, &sibling_ids).await?;
+			let siblings: Vec<Hash> = sibling_ids.iter().map(|id| siblings[id]).collect();
+
+			let nodes = merkle_tree::append_nodes(leaf_index as usize, leaf_hash, &siblings)
+				.into_iter()
+				.map(|(id, hash)| (id, base64::encode(hash)))
+				.collect();
+
+			let leaf = KtLeafEntity {
+				leaf_index,
+				key_type: key.key_type,
+				owner_id: key.owner_id.clone(),
+				key_id: key.key_id.clone(),
+				key_hash: key.key_hash.clone(),
+				leaf_hash: base64::encode(leaf_hash),
+				time,
+			};
+
+			let res = key_transparency_model::append_leaf(&app_id, leaf, nodes).await;
+
+			match res {
+				Ok(_) => break,
+				Err(e) => {
+					tries += 1;
+
+					//only try again when another leaf got the same index, every other error is returned
+					let index_taken = key_transparency_model::get_tree_size(&app_id).await? > leaf_index;
+
+					if !index_taken || tries >= MAX_APPEND_TRIES {
+						return Err(ServerCoreError::new_msg_and_debug(
+							500,
+							ApiErrorCodes::KtAppend,
+							"Can't append the key to the key transparency log",
+							e.debug_msg,
+						));
+					}
+				},
+			}
+		}
+	}
+
+	cache::delete(&get_kt_tree_head_key(&app_id)).await?;
+
+	Ok(())
+}
+
+/**
+Append the keys of a device which was registered before.
+
+The keys are already in the key store (or at the key endpoint) when the device registration is done.
+ */
+pub(crate) async fn append_device_keys(app_data: &AppData, user_id: &str, device_id: &str) -> AppRes<()>
+{
+	let pk_id = format!("pk_{device_id}");
+	let vk_id = format!("vk_{device_id}");
+
+	let keys = key_endpoint_service::get_keys(app_data, &[pk_id.clone(), vk_id.clone()]).await?;
+
+	let mut kt_keys = Vec::with_capacity(2);
+
+	if let Some(k) = keys.get(&pk_id) {
+		kt_keys.push(KtLeafInput::new(KT_KEY_TYPE_DEVICE_PUBLIC, user_id, device_id, k));
+	}
+
+	if let Some(k) = keys.get(&vk_id) {
+		kt_keys.push(KtLeafInput::new(KT_KEY_TYPE_DEVICE_VERIFY, user_id, device_id, k));
+	}
+
+	append_keys(&app_data.app_data.app_id, kt_keys).await
+}
+
+/**
+The latest tree head, signed with the newest jwt key of the app.
+
+The tree head is cached until a new key is appended or the jwt keys of the app are changed.
+ */
+pub async fn get_signed_tree_head(app_data: &AppData) -> AppRes<KtSignedTreeHeadOutput>
+{
+	let app_id = &app_data.app_data.app_id;
+	let cache_key = get_kt_tree_head_key(app_id);
+
+	if let Some(c) = cache::get(&cache_key).await? {
+		return bytes_to_json(c.as_bytes());
+	}
+
+	let tree_size = key_transparency_model::get_tree_size(app_id).await?;
+
+	let mut node_ids = Vec::new();
+	merkle_tree::root(tree_size as usize, &mut |id| collect_node_id(&mut node_ids, id));
+	let nodes = load_nodes(app_id, &node_ids).await?;
+
+	let head = KtTreeHead {
+		app_id: app_id.to_string(),
+		tree_size,
+		root_hash: base64::encode(merkle_tree::root(tree_size as usize, &mut |id| nodes[&id])),
+		time: get_time()?,
+	};
+
+	let jwt_data = app_data.jwt_data.first().ok_or_else(|| {
+		ServerCoreError::new_msg(
+			500,
+			ApiErrorCodes::AppAction,
+			"No jwt key found to sign the tree head",
+		)
+	})?;
+
+	let signature = sign_with_app_jwt_key(&head, jwt_data).await?;
+
+	let out = KtSignedTreeHeadOutput {
+		tree_size: head.tree_size,
+		root_hash: head.root_hash,
+		time: head.time,
+		signature,
+	};
+
+	cache::add(cache_key, json_to_string(&out)?, LONG_TTL).await?;
+
+	Ok(out)
+}
+
+pub async fn get_inclusion_proof(app_id: impl Into<AppId>, key_type: i32, key_id: &str, tree_size: i64) -> AppRes<KtInclusionProofOutput>
+{
+	let app_id = app_id.into();
+
+	let leaf = key_transparency_model::get_leaf_by_key(&app_id, key_type, key_id)
+		.await?
+		.ok_or_else(|| {
+			ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::KtLeafNotFound,
+				"Key not found in the key transparency log",
+			)
+		})?;
+
+	if leaf.leaf_index >= tree_size {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::KtLeafNotFound,
+			"Key is not in the tree with this size",
+		));
+	}
+
+	check_tree_size(&app_id, tree_size).await?;
+
+	let m = leaf.leaf_index as usize;
+
+	let mut node_ids = Vec::new();
+	merkle_tree::inclusion_proof(m, tree_size as usize, &mut |id| collect_node_id(&mut node_ids, id));
+	let nodes = load_nodes(&app_id, &node_ids).await?;
+
+	let audit_path = merkle_tree::inclusion_proof(m, tree_size as usize, &mut |id| nodes[&id])
+		.into_iter()
+		.map(base64::encode)
+		.collect();
+
+	Ok(KtInclusionProofOutput {
+		leaf,
+		tree_size,
+		audit_path,
+	})
+}
+
+pub async fn get_consistency_proof(app_id: impl Into<AppId>, first: i64, second: i64) -> AppRes<KtConsistencyProofOutput>
+{
+	let app_id = app_id.into();
+
+	if first <= 0 || first > second {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::KtTreeSize,
+			"The first tree size must be between 1 and the second tree size",
+		));
+	}
+
+	check_tree_size(&app_id, second).await?;
+
+	let mut node_ids = Vec::new();
+	merkle_tree::consistency_proof(first as usize, second as usize, &mut |id| {
+		collect_node_id(&mut node_ids, id)
+	});
+	let nodes = load_nodes(&app_id, &node_ids).await?;
+
+	let proof = merkle_tree::consistency_proof(first as usize, second as usize, &mut |id| nodes[&id])
+		.into_iter()
+		.map(base64::encode)
+		.collect();
+
+	Ok(KtConsistencyProofOutput {
+		first,
+		second,
+		proof,
+	})
+}
+
+//__________________________________________________________________________________________________
+
+async fn check_tree_size(app_id: &str, tree_size: i64) -> AppRes<()>
+{
+	let actual_size = key_transparency_model::get_tree_size(app_id).await?;
+
+	if tree_size <= 0 || tree_size > actual_size {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::KtTreeSize,
+			"Tree size is bigger than the log",
+		));
+	}
+
+	Ok(())
+}
+
+fn collect_node_id(node_ids: &mut Vec<NodeId>, id: NodeId) -> Hash
+{
+	node_ids.push(id);
+
+	//only the ids are needed in the first run
+	[0u8; 32]
+}
+
+/**
+Load the hashes of the sub trees. Every node must exist because the nodes are inserted together with the leaf.
+ */
+async fn load_nodes(app_id: &str, node_ids: &[NodeId]) -> AppRes<HashMap<NodeId, Hash>>
+{
+	let wrong_format = || {
+		ServerCoreError::new_msg(
+			500,
+			ApiErrorCodes::KtAppend,
+			"Wrong node hash format in the key transparency log",
+		)
+	};
+
+	let nodes = key_transparency_model::get_nodes(app_id, node_ids).await?;
+
+	let mut out = HashMap::with_capacity(nodes.len());
+
+	for node in nodes {
+		let bytes = base64::decode(node.hash).map_err(|_e| wrong_format())?;
+
+		out.insert(
+			(node.level, node.node_index),
+			bytes.try_into().map_err(|_e| wrong_format())?,
+		);
+	}
+
+	if node_ids.iter().any(|id| !out.contains_key(id)) {
+		return Err(ServerCoreError::new_msg(
+			500,
+			ApiErrorCodes::KtAppend,
+			"Node not found in the key transparency log",
+		));
+	}
+
+	Ok(out)
+}
@@ -0,0 +1,300 @@
+/*
+Merkle tree like in certificate transparency (rfc 6962).
+
+leaf hash = sha256(0x00 || leaf data)
+node hash = sha256(0x01 || left || right)
+
+The split point k for n leaves is the largest power of two smaller than n.
+ */
+
+use ring::digest;
+
+pub(super) type Hash = [u8; 32];
+
+pub(super) fn hash_leaf(data: &[u8]) -> Hash
+{
+	let mut ctx = digest::Context::new(&digest::SHA256);
+	ctx.update(&[0x00]);
+	ctx.update(data);
+
+	to_hash(ctx.finish())
+}
+
+fn hash_node(left: &Hash, right: &Hash) -> Hash
+{
+	let mut ctx = digest::Context::new(&digest::SHA256);
+	ctx.update(&[0x01]);
+	ctx.update(left);
+	ctx.update(right);
+
+	to_hash(ctx.finish())
+}
+
+fn to_hash(d: digest::Digest) -> Hash
+{
+	let mut out = [0u8; 32];
+	out.copy_from_slice(d.as_ref());
+	out
+}
+
+fn split(n: usize) -> usize
+{
+	let mut k = 1;
+
+	while k << 1 < n {
+		k <<= 1;
+	}
+
+	k
+}
+
+/**
+The hashes of the complete sub trees are stored, they never change when new leaves are appended.
+
+A node is the hash of 2^level leaves, starting at leaf node_index * 2^level. Level 0 are the leaf hashes.
+
+All functions only ask for complete sub trees, so the callers can load the needed nodes first
+(by calling the function with a node fn which only collects the ids) and then call it again with the loaded nodes.
+ */
+pub(super) type NodeId = (i32, i64);
+
+fn range_root<F: FnMut(NodeId) -> Hash>(start: usize, end: usize, node: &mut F) -> Hash
+{
+	let n = end - start;
+
+	if n == 0 {
+		return to_hash(digest::digest(&digest::SHA256, &[]));
+	}
+
+	if n.is_power_of_two() {
+		//the sub trees are always aligned to their size
+		return node((n.trailing_zeros() as i32, (start / n) as i64));
+	}
+
+	let k = split(n);
+
+	hash_node(&range_root(start, start + k, node), &range_root(start + k, end, node))
+}
+
+/**
+The root hash of the tree with the size. The hash of the empty tree is sha256 of the empty string.
+ */
+pub(super) fn root<F: FnMut(NodeId) -> Hash>(tree_size: usize, node: &mut F) -> Hash
+{
+	range_root(0, tree_size, node)
+}
+
+/**
+The audit path for the leaf at index m in the tree with the size.
+ */
+pub(super) fn inclusion_proof<F: FnMut(NodeId) -> Hash>(m: usize, tree_size: usize, node: &mut F) -> Vec<Hash>
+{
+	inclusion_path(m, 0, tree_size, node)
+}
+
+fn inclusion_path<F: FnMut(NodeId) -> Hash>(m: usize, start: usize, end: usize, node: &mut F) -> Vec<Hash>
+{
+	if end - start <= 1 {
+		return Vec::new();
+	}
+
+	let k = split(end - start);
+
+	if m < start + k {
+		let mut path = inclusion_path(m, start, start + k, node);
+		path.push(range_root(start + k, end, node));
+		path
+	} else {
+		let mut path = inclusion_path(m, start + k, end, node);
+		path.push(range_root(start, start + k, node));
+		path
+	}
+}
+
+/**
+Proof that the tree with m leaves is a prefix of the tree with the size.
+ */
+pub(super) fn consistency_proof<F: FnMut(NodeId) -> Hash>(m: usize, tree_size: usize, node: &mut F) -> Vec<Hash>
+{
+	if m == 0 || m >= tree_size {
+		return Vec::new();
+	}
+
+	sub_proof(m, 0, tree_size, true, node)
+}
+
+fn sub_proof<F: FnMut(NodeId) -> Hash>(m: usize, start: usize, end: usize, complete_sub_tree: bool, node: &mut F) -> Vec<Hash>
+{
+	let n = end - start;
+
+	if m == n {
+		if complete_sub_tree {
+			return Vec::new();
+		}
+
+		return vec![range_root(start, end, node)];
+	}
+
+	let k = split(n);
+
+	if m <= k {
+		let mut proof = sub_proof(m, start, start + k, complete_sub_tree, node);
+		proof.push(range_root(start + k, end, node));
+		proof
+	} else {
+		let mut proof = sub_proof(m - k, start + k, end, false, node);
+		proof.push(range_root(start, start + k, node));
+		proof
+	}
+}
+
+/**
+The left siblings which are needed to build the sub trees which are completed by the new leaf at index n
+ */
+pub(super) fn append_siblings(n: usize) -> Vec<NodeId>
+{
+	let mut siblings = Vec::new();
+	let mut level = 1;
+
+	while (n + 1) & ((1 << level) - 1) == 0 {
+		let node_index = ((n + 1) >> level) - 1;
+
+		siblings.push((level - 1, (node_index * 2) as i64));
+
+		level += 1;
+	}
+
+	siblings
+}
+
+/**
+The new leaf and every sub tree which is completed by it. The siblings are from append_siblings in the same order.
+ */
+pub(super) fn append_nodes(n: usize, leaf: Hash, siblings: &[Hash]) -> Vec<(NodeId, Hash)>
+{
+	let mut nodes = Vec::with_capacity(siblings.len() + 1);
+	nodes.push(((0, n as i64), leaf));
+
+	let mut current = leaf;
+
+	for (i, sibling) in siblings.iter().enumerate() {
+		let level = i + 1;
+
+		current = hash_node(sibling, &current);
+
+		nodes.push(((level as i32, (((n + 1) >> level) - 1) as i64), current));
+	}
+
+	nodes
+}
+
+#[cfg(test)]
+mod test
+{
+	use std::collections::HashMap;
+
+	use super::*;
+
+	fn leaves(n: usize) -> Vec<Hash>
+	{
+		(0..n)
+			.map(|i| hash_leaf(i.to_string().as_bytes()))
+			.collect()
+	}
+
+	//the root from all leaves without stored nodes
+	fn naive_root(leaves: &[Hash]) -> Hash
+	{
+		match leaves.len() {
+			1 => leaves[0],
+			n => {
+				let k = split(n);
+				hash_node(&naive_root(&leaves[..k]), &naive_root(&leaves[k..]))
+			},
+		}
+	}
+
+	//the stored nodes like they are created by appending the leaves
+	fn nodes(leaves: &[Hash]) -> HashMap<NodeId, Hash>
+	{
+		let mut nodes = HashMap::new();
+
+		for (n, leaf) in leaves.iter().enumerate() {
+			let siblings: Vec<Hash> = append_siblings(n).iter().map(|id| nodes[id]).collect();
+
+			nodes.extend(append_nodes(n, *leaf, &siblings));
+		}
+
+		nodes
+	}
+
+	//verify like a client would do it (rfc 9162 2.1.3.2)
+	fn verify_inclusion(m: usize, n: usize, leaf: Hash, path: &[Hash], root_hash: Hash) -> bool
+	{
+		let (mut f_n, mut s_n) = (m, n - 1);
+		let mut r = leaf;
+
+		for p in path {
+			if s_n == 0 {
+				return false;
+			}
+
+			if f_n & 1 == 1 || f_n == s_n {
+				r = hash_node(p, &r);
+
+				while f_n & 1 == 0 && f_n != 0 {
+					f_n >>= 1;
+					s_n >>= 1;
+				}
+			} else {
+				r = hash_node(&r, p);
+			}
+
+			f_n >>= 1;
+			s_n >>= 1;
+		}
+
+		s_n == 0 && r == root_hash
+	}
+
+	#[test]
+	fn test_inclusion_proof()
+	{
+		for n in 1..20 {
+			let l = leaves(n);
+			let nodes = nodes(&l);
+			let r = root(n, &mut |id: NodeId| nodes[&id]);
+
+			assert_eq!(r, naive_root(&l));
+
+			for (m, leaf) in l.iter().enumerate() {
+				let path = inclusion_proof(m, n, &mut |id: NodeId| nodes[&id]);
+
+				assert!(verify_inclusion(m, n, *leaf, &path, r));
+			}
+		}
+	}
+
+	#[test]
+	fn test_consistency_proof()
+	{
+		//the examples from rfc 6962 2.1.3
+		let l = leaves(7);
+		let nodes = nodes(&l);
+		let mut node = |id: NodeId| nodes[&id];
+
+		assert_eq!(
+			consistency_proof(3, 7, &mut node),
+			vec![l[2], l[3], naive_root(&l[..2]), naive_root(&l[4..])]
+		);
+
+		assert_eq!(consistency_proof(4, 7, &mut node), vec![naive_root(&l[4..])]);
+
+		assert_eq!(
+			consistency_proof(6, 7, &mut node),
+			vec![naive_root(&l[4..6]), l[6], naive_root(&l[..4])]
+		);
+
+		assert_eq!(consistency_proof(7, 7, &mut node).len(), 0);
+	}
+}
@@ -0,0 +1,12 @@
+pub mod key_transparency_controller;
+pub mod key_transparency_entities;
+mod key_transparency_model;
+pub mod key_transparency_service;
+mod merkle_tree;
+
+pub(crate) use key_transparency_controller::*;
+
+pub const KT_KEY_TYPE_DEVICE_PUBLIC: i32 = 0;
+pub const KT_KEY_TYPE_DEVICE_VERIFY: i32 = 1;
+pub const KT_KEY_TYPE_GROUP_PUBLIC: i32 = 2;
+pub const KT_KEY_TYPE_GROUP_VERIFY: i32 = 3;
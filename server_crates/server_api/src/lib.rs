@@ -6,6 +6,7 @@ use crate::routes::routes;
 
 mod group;
 mod key_management;
+mod key_transparency;
 mod routes;
 mod user;
 pub mod util;
@@ -19,7 +20,11 @@ pub use group::{
 	group_user_controller as sentc_group_user_controller,
 	group_user_service as sentc_group_user_service,
 };
-pub use key_management::{key_controller as sentc_key_controller, key_entity as sentc_key_entities};
+pub use key_management::{key_controller as sentc_key_controller, key_entity as sentc_key_entities, key_worker as sentc_key_worker};
+pub use key_transparency::{
+	key_transparency_entities as sentc_key_transparency_entities,
+	key_transparency_service as sentc_key_transparency_service,
+};
 pub use user::auth::auth_service as sentc_auth_service;
 pub use user::light::{user_light_controller as sentc_user_light_controller, user_light_service as sentc_user_light_service};
 pub use user::{user_controller as sentc_user_controller, user_entities as sentc_user_entities, user_service as sentc_user_service};
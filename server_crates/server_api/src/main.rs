@@ -6,6 +6,7 @@ use server_api::rest_routes;
 pub async fn main()
 {
 	server_api_common::start().await;
+	server_api_common::rate_limit::start_worker();
 	let mut router = server_api_common::rest_routes();
 
 	rest_routes(&mut router);
@@ -18,5 +19,5 @@ pub async fn main()
 	.parse()
 	.unwrap();
 
-	rustgram::start(router, addr).await;
+	server_api_common::start_server(router, addr).await;
 }
@@ -10,110 +10,178 @@ pub(crate) fn routes(router: &mut Router)
 {
 	router.get(
 		"/api/v1/user/:user_id/public_key",
-		r(crate::user::get_public_key_data).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::get_public_key_data)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/user/:user_id/public_key/:key_id",
-		r(crate::user::get_public_key_by_id).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::get_public_key_by_id)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/user/:user_id/verify_key/:key_id",
-		r(crate::user::get_verify_key_by_id).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::get_verify_key_by_id)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/group/:group_id/public_key",
-		r(crate::group::get_public_key_data).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::group::get_public_key_data)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/kt/tree_head",
+		r(crate::key_transparency::get_tree_head)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/kt/inclusion/:key_type/:key_id/:tree_size",
+		r(crate::key_transparency::get_inclusion_proof)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/kt/consistency/:first/:second",
+		r(crate::key_transparency::get_consistency_proof)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/exists",
-		r(crate::user::exists).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::exists)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/register",
-		r(crate::user::register).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::register)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/register_light",
-		r(crate::user::register_light).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::register_light)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/prepare_login",
-		r(crate::user::prepare_login).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::prepare_login)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/done_login",
-		r(crate::user::done_login).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::done_login)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/validate_mfa",
-		r(crate::user::validate_mfa).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::validate_mfa)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/validate_recovery_otp",
-		r(crate::user::validate_recovery_otp).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::validate_recovery_otp)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/verify_login",
-		r(crate::user::verify_login).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::verify_login)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/verify_login_light",
-		r(crate::user::verify_login_light).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::verify_login_light)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/user/prepare_register_device",
-		r(crate::user::prepare_register_device).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::prepare_register_device)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/reset_pw_light",
-		r(crate::user::reset_password_light).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::reset_password_light)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/forced/delete",
-		r(crate::user::delete_user).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::delete_user)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/user/forced/delete/:user_id",
-		r(crate::user::delete_user_by_id).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::delete_user_by_id)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/forced/reset_user",
-		r(crate::user::reset_user).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::reset_user)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/forced/disable_otp",
-		r(crate::user::disable_otp_forced).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::disable_otp_forced)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/user/forced/sessions",
-		r(crate::user::delete_all_sessions).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::delete_all_sessions)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/user/forced/login",
-		r(crate::user::verify_login_forced).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::verify_login_forced)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/user/forced/login_light",
-		r(crate::user::verify_login_light_forced).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::user::verify_login_light_forced)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/group/forced/:group_id",
-		r(crate::group::delete_forced).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::group::delete_forced)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/group/forced/:group_id/user/:user_id",
-		r(crate::group::get_single_group_member).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::group::get_single_group_member)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/group/forced/:user_id",
 		r(crate::group::create_force)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/group/forced/:user_id/light",
 		r(crate::group::create_light_force)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
@@ -121,6 +189,7 @@ pub(crate) fn routes(router: &mut Router)
 		r(crate::group::create_child_group_force)
 			.add(server_api_common::middleware::group::group_force_transform)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
@@ -128,6 +197,7 @@ pub(crate) fn routes(router: &mut Router)
 		r(crate::group::create_child_group_light_force)
 			.add(server_api_common::middleware::group::group_force_transform)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
@@ -135,6 +205,7 @@ pub(crate) fn routes(router: &mut Router)
 		r(crate::group::create_connected_group_from_group_force)
 			.add(server_api_common::middleware::group::group_force_transform)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
@@ -142,6 +213,7 @@ pub(crate) fn routes(router: &mut Router)
 		r(crate::group::create_connected_group_from_group_light_force)
 			.add(server_api_common::middleware::group::group_force_transform)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
@@ -149,6 +221,15 @@ pub(crate) fn routes(router: &mut Router)
 		r(crate::group::kick_user_from_group_forced)
 			.add(server_api_common::middleware::group::group_force_transform)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.put(
+		"/api/v1/group/forced/:user_id/:group_id/key/:key_id/compromised",
+		r(crate::group::group_key_compromised_forced)
+			.add(server_api_common::middleware::group::group_force_transform)
+			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
@@ -156,6 +237,7 @@ pub(crate) fn routes(router: &mut Router)
 		r(crate::group::invite_user_to_group_from_server)
 			.add(server_api_common::middleware::group::group_force_transform)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
@@ -163,6 +245,7 @@ pub(crate) fn routes(router: &mut Router)
 		r(crate::group::invite_user_to_group_from_server_light)
 			.add(server_api_common::middleware::group::group_force_transform)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
@@ -170,6 +253,7 @@ pub(crate) fn routes(router: &mut Router)
 		r(crate::group::invite_group_to_group_from_server)
 			.add(server_api_common::middleware::group::group_force_transform)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
@@ -177,205 +261,268 @@ pub(crate) fn routes(router: &mut Router)
 		r(crate::group::invite_group_to_group_from_server_light)
 			.add(server_api_common::middleware::group::group_force_transform)
 			.add(server_api_common::middleware::user_check_force::user_check_force_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/refresh",
 		r(crate::user::refresh_jwt)
 			.add(server_api_common::middleware::jwt::jwt_expire_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/init",
 		r(crate::user::init_user)
 			.add(server_api_common::middleware::jwt::jwt_expire_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/keys/sym_key",
 		r(crate::key_management::register_sym_key)
 			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/keys/sym_key/:key_id",
 		r(crate::key_management::delete_sym_key)
 			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.put(
+		"/api/v1/keys/sym_key/:key_id/deprecate",
+		r(crate::key_management::deprecate_sym_key)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.put(
+		"/api/v1/keys/sym_key/forced/:key_id/compromised",
+		r(crate::key_management::sym_key_compromised_forced)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/keys/sym_key/mine/:last_fetched_time/:last_id",
+		r(crate::key_management::get_all_sym_keys_for_user)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/keys/sym_key/master_key/:master_key_id/:last_fetched_time/:last_key_id",
-		r(crate::key_management::get_all_sym_keys_to_master_key).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::key_management::get_all_sym_keys_to_master_key)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.post(
+		"/api/v1/keys/sym_key/bulk",
+		r(crate::key_management::get_sym_keys_by_ids)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/keys/sym_key/:key_id",
-		r(crate::key_management::get_sym_key_by_id).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::key_management::get_sym_key_by_id)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/user/device/:last_fetched_time/:last_id",
 		r(crate::user::get_devices)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/user/jwt",
 		r(crate::user::get_user_data_from_jwt)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user",
 		r(crate::user::update)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/done_register_device",
 		r(crate::user::done_register_device)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/done_register_device_light",
 		r(crate::user::done_register_device_light)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.patch(
 		"/api/v1/user/register_otp",
 		r(crate::user::register_otp)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/update_pw",
 		r(crate::user::change_password)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/reset_pw",
 		r(crate::user::reset_password)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.patch(
 		"/api/v1/user/reset_otp",
 		r(crate::user::reset_otp)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.patch(
 		"/api/v1/user/disable_otp",
 		r(crate::user::disable_otp)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/user/otp_recovery_keys",
 		r(crate::user::get_otp_recovery_keys)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/user",
 		r(crate::user::delete)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/user/device/:device_id",
 		r(crate::user::delete_device)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/user/user_keys/rotation",
 		r(crate::user::user_group_key_rotation)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/user/user_keys/rotation",
 		r(crate::user::get_user_group_keys_for_update)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/user_keys/rotation/:key_id",
 		r(crate::user::done_key_rotation_for_device)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/user/user_keys/key/:key_id",
 		r(crate::user::get_user_key)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/user/user_keys/keys/:last_fetched_time/:last_k_id",
 		r(crate::user::get_user_keys)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/user/user_keys/session/:key_session_id",
 		r(crate::user::device_key_upload)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/group",
 		r(crate::group::create)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/group/light",
 		r(crate::group::create_light)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/group/all/:last_fetched_time/:last_group_id",
 		r(crate::group::get_all_groups_for_user)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/group/invite/:last_fetched_time/:last_group_id",
 		r(crate::group::get_invite_req)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/group/joins/:last_fetched_time/:last_group_id",
 		r(crate::group::get_sent_join_req_for_user)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/group/joins/:join_req_id",
 		r(crate::group::delete_sent_join_req_for_user)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.patch(
 		"/api/v1/group/:group_id/invite",
 		r(crate::group::accept_invite)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/group/:group_id/invite",
 		r(crate::group::reject_invite)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.patch(
 		"/api/v1/group/:group_id/join_req",
 		r(crate::group::join_req)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -383,6 +530,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id",
 		r(crate::group::get_user_group_data)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -390,6 +538,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/light",
 		r(crate::group::get_user_group_light_data)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -397,6 +546,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/update_check",
 		r(crate::group::get_key_update_for_user)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -404,6 +554,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/update_check_light",
 		r(crate::group::get_update_for_user_light)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -411,6 +562,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/keys/:last_fetched_time/:last_k_id",
 		r(crate::group::get_user_group_keys)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -418,6 +570,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/key/:key_id",
 		r(crate::group::get_user_group_key)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -425,6 +578,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/member/:last_fetched_time/:last_user_id",
 		r(crate::group::get_group_member)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -432,6 +586,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/child",
 		r(crate::group::create_child_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -439,6 +594,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/child/light",
 		r(crate::group::create_child_group_light)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -446,6 +602,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/connected",
 		r(crate::group::create_connected_group_from_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -453,6 +610,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/connected/light",
 		r(crate::group::create_connected_group_from_group_light)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -460,6 +618,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id",
 		r(crate::group::delete)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -467,6 +626,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/leave",
 		r(crate::group::leave_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -474,6 +634,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/kick/:user_id",
 		r(crate::group::kick_user_from_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -481,6 +642,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/children/:last_fetched_time/:last_id",
 		r(crate::group::get_all_first_level_children)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -488,6 +650,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/all/:last_fetched_time/:last_group_id",
 		r(crate::group::get_all_groups_for_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -495,6 +658,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite/:last_fetched_time/:last_group_id",
 		r(crate::group::get_invite_req_for_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -502,6 +666,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/joins/:last_fetched_time/:last_group_id",
 		r(crate::group::get_sent_join_req_for_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -509,6 +674,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/joins/:join_req_id",
 		r(crate::group::delete_sent_join_req_for_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -516,6 +682,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite/:group_id_to_reject",
 		r(crate::group::reject_invite_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -523,6 +690,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite/:group_id_to_join",
 		r(crate::group::accept_invite_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -530,6 +698,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite/:invited_user",
 		r(crate::group::invite_request)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -537,6 +706,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite/:invited_user/light",
 		r(crate::group::invite_request_light)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -544,6 +714,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite_group/:invited_group",
 		r(crate::group::invite_request_to_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -551,6 +722,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite_group/:invited_group/light",
 		r(crate::group::invite_request_to_group_light)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -558,6 +730,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite_auto/:invited_user",
 		r(crate::group::invite_auto)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -565,6 +738,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite_auto/:invited_user/light",
 		r(crate::group::invite_auto_light)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -572,6 +746,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite_group_auto/:invited_group",
 		r(crate::group::invite_auto_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -579,6 +754,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite_group_auto/:invited_group/light",
 		r(crate::group::invite_auto_group_light)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -586,6 +762,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite_group_auto_force/:invited_group",
 		r(crate::group::invite_auto_group_force)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -593,6 +770,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite_group_auto_force/:invited_group/light",
 		r(crate::group::invite_auto_group_force_light)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -600,6 +778,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/re_invite/:invited_user",
 		r(crate::group::re_invite_auto)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -607,6 +786,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/re_invite_group/:invited_group",
 		r(crate::group::re_invite_auto_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -614,6 +794,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/re_invite_group_force/:invited_group",
 		r(crate::group::re_invite_force)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -621,6 +802,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/invite/session/:key_session_id",
 		r(crate::group::insert_user_keys_via_session_invite)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -628,6 +810,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/change_rank",
 		r(crate::group::change_rank)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -635,6 +818,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/change_invite",
 		r(crate::group::stop_invite)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -642,6 +826,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/join_req/:group_id_to_join",
 		r(crate::group::join_req_as_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -649,6 +834,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/join_req/:last_fetched_time/:last_user_id",
 		r(crate::group::get_join_req)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -656,6 +842,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/join_req/:join_user",
 		r(crate::group::accept_join_req)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -663,6 +850,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/join_req/:join_user/light",
 		r(crate::group::accept_join_req_light)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -670,6 +858,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/join_req/:join_user",
 		r(crate::group::reject_join_req)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -677,6 +866,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/join_req/session/:key_session_id",
 		r(crate::group::insert_user_keys_via_session_join_req)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -684,6 +874,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/key_rotation",
 		r(crate::group::start_key_rotation)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -691,6 +882,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/key_rotation",
 		r(crate::group::get_keys_for_update)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -698,6 +890,47 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/key_rotation/:key_id",
 		r(crate::group::done_key_rotation_for_user)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.put(
+		"/api/v1/group/:group_id/key_rotation/:key_id/deprecate",
+		r(crate::group::deprecate_group_key)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.post(
+		"/api/v1/group/:group_id/sym_key",
+		r(crate::key_management::register_group_sym_key)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.post(
+		"/api/v1/group/:group_id/sym_key/bulk",
+		r(crate::key_management::get_group_sym_keys_by_ids)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/group/:group_id/sym_key/all/:last_fetched_time/:last_id",
+		r(crate::key_management::get_all_sym_keys_for_group)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/group/:group_id/sym_key/:key_id",
+		r(crate::key_management::get_group_sym_key_by_id)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
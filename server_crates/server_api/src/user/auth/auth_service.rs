@@ -14,6 +14,7 @@ use server_api_common::util::hash_token_to_string;
 use crate::sentc_user_entities::{DoneLoginServerOutput, DoneLoginServerReturn, VerifyLoginEntity, VerifyLoginForcedEntity, SERVER_RANDOM_VALUE};
 use crate::sentc_user_service::create_refresh_token;
 use crate::user::auth::auth_model;
+use crate::user::key_endpoint::key_endpoint_service;
 use crate::user::otp;
 use crate::util::api_res::ApiErrorCodes;
 
@@ -28,16 +29,16 @@ Prepare the challenge and give the device keys back.
 Use this fn directly after done login if the user does not enable 2fa-
 But if so then use it after validate otp
  */
-pub(crate) async fn prepare_done_login(app_id: impl Into<AppId>, identifier: impl Into<String>) -> AppRes<DoneLoginServerOutput>
+pub(crate) async fn prepare_done_login(app_data: &AppData, identifier: impl Into<String>) -> AppRes<DoneLoginServerOutput>
 {
-	let app_id = app_id.into();
+	let app_id = &app_data.app_data.app_id;
 
 	//if correct -> fetch and return the user data
-	let mut device_keys = auth_model::get_done_login_data(&app_id, identifier)
+	let mut device_keys = auth_model::get_done_login_data(app_id, identifier)
 		.await?
 		.ok_or_else(|| ServerCoreError::new_msg(401, ApiErrorCodes::Login, "Wrong username or password"))?;
 
-	//fetch the keys from key store
+	//fetch the keys from the key endpoint of the app or from the key store
 	let mut keys_to_fetch = vec![];
 
 	if device_keys.public_key_string == "extern" {
@@ -56,7 +57,7 @@ pub(crate) async fn prepare_done_login(app_id: impl Into<AppId>, identifier: imp
 		keys_to_fetch.push(format!("vk_{}", device_keys.keypair_sign_id));
 	}
 
-	let mut fetched_keys = server_key_store::get_keys(&keys_to_fetch).await?;
+	let mut fetched_keys = key_endpoint_service::get_keys(app_data, &keys_to_fetch).await?;
 
 	if device_keys.public_key_string == "extern" {
 		if let Some(fetched_key) = fetched_keys.remove(&format!("pk_{}", device_keys.keypair_encrypt_id)) {
@@ -84,8 +85,6 @@ pub(crate) async fn prepare_done_login(app_id: impl Into<AppId>, identifier: imp
 
 	let challenge = create_refresh_token()?;
 
-	//TODO make req to key endpoint if the app owner added one
-
 	//only rust keys without external c dep
 	#[cfg(not(feature = "external_c_keys"))]
 	let encrypted_challenge = traverse_keys!(
@@ -142,7 +141,7 @@ pub async fn done_login(app_data: &AppData, done_login: DoneLoginServerInput) ->
 
 	if sec.is_none() {
 		Ok(DoneLoginServerReturn::Direct(
-			prepare_done_login(&app_data.app_data.app_id, identifier).await?,
+			prepare_done_login(app_data, identifier).await?,
 		))
 	} else {
 		Ok(DoneLoginServerReturn::Otp)
@@ -170,7 +169,7 @@ pub async fn validate_mfa(app_data: &AppData, input: OtpInput) -> AppRes<DoneLog
 
 	//if we add more factors for the auth in the future then validate them in this fn, get it from auth_user_otp
 
-	prepare_done_login(&app_data.app_data.app_id, identifier).await
+	prepare_done_login(app_data, identifier).await
 }
 
 pub async fn validate_recovery_otp(app_data: &AppData, input: OtpInput) -> AppRes<DoneLoginServerOutput>
@@ -183,7 +182,7 @@ pub async fn validate_recovery_otp(app_data: &AppData, input: OtpInput) -> AppRe
 
 	let token_id = auth_model::get_otp_recovery_token(&app_data.app_data.app_id, &identifier, hashed_token).await?;
 
-	let done_login = prepare_done_login(&app_data.app_data.app_id, identifier).await?;
+	let done_login = prepare_done_login(app_data, identifier).await?;
 
 	//now delete the token but only after done login fetch makes no problems
 	auth_model::delete_otp_recovery_token(token_id).await?;
@@ -0,0 +1,19 @@
+use rustgram_server_util::db::{query_first, StringEntity};
+use rustgram_server_util::res::AppRes;
+use rustgram_server_util::set_params;
+use sentc_crypto_common::AppId;
+
+/**
+Get the auth token to sign the requests to the key endpoint.
+
+The token is not cached with the app data.
+ */
+pub(super) async fn get_auth_token(app_id: impl Into<AppId>) -> AppRes<Option<String>>
+{
+	//language=SQL
+	let sql = "SELECT auth_token FROM sentc_app_key_endpoint_options WHERE app_id = ? AND auth_token IS NOT NULL";
+
+	let token: Option<StringEntity> = query_first(sql, set_params!(app_id.into())).await?;
+
+	Ok(token.map(|t| t.0))
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use ring::hmac;
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::get_time_in_sec;
+use rustgram_server_util::input_helper::{bytes_to_json, json_to_string};
+use rustgram_server_util::res::AppRes;
+use serde::{Deserialize, Serialize};
+use server_api_common::customer_app::app_entities::{AppData, AppKeyEndpointOptions};
+use server_dashboard_common::app::KEY_ENDPOINT_FALLBACK_LOCAL;
+use server_key_store::KeyStorage;
+
+use crate::user::key_endpoint::key_endpoint_model;
+use crate::util::api_res::ApiErrorCodes;
+
+/*
+The customer hosted key endpoint.
+
+The api sends a POST request to:
+- <url>/get with the key ids and expects a list of KeyStorage back
+- <url>/store with a list of KeyStorage
+- <url>/delete with the key ids
+
+Every request is signed with the auth token of the endpoint:
+signature = base64(hmac_sha256(auth_token, "<timestamp>.<app_id>.<body>"))
+
+The signature is sent in the x-sentc-signature header, the timestamp (in sec) in x-sentc-timestamp and the app id in x-sentc-app-id.
+The endpoint should reject requests with an old timestamp to prevent replays.
+ */
+
+pub const KEY_ENDPOINT_HEADER_SIGNATURE: &str = "x-sentc-signature";
+pub const KEY_ENDPOINT_HEADER_TIMESTAMP: &str = "x-sentc-timestamp";
+pub const KEY_ENDPOINT_HEADER_APP_ID: &str = "x-sentc-app-id";
+
+#[derive(Serialize, Deserialize)]
+pub struct KeyEndpointKeysInput
+{
+	pub keys: Vec<String>,
+}
+
+/**
+Fetch the device keys.
+
+Use the key endpoint of the app if the customer set one, else the key store of the server.
+When the endpoint is not reachable and the fallback is local, the key store is used.
+ */
+pub(crate) async fn get_keys(app_data: &AppData, keys: &[String]) -> AppRes<HashMap<String, String>>
+{
+	let options = match &app_data.key_endpoint_options {
+		Some(o) => o,
+		None => return server_key_store::get_keys(keys).await,
+	};
+
+	let body = json_to_string(&KeyEndpointKeysInput {
+		keys: keys.to_vec(),
+	})?;
+
+	let res = match send_req(&app_data.app_data.app_id, options, "get", body).await {
+		Ok(r) => r,
+		Err(e) => {
+			if options.fallback == KEY_ENDPOINT_FALLBACK_LOCAL {
+				return server_key_store::get_keys(keys).await;
+			}
+
+			return Err(e);
+		},
+	};
+
+	let fetched_keys: Vec<KeyStorage> = bytes_to_json(res.as_bytes()).map_err(|_e| {
+		ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::UserKeyEndpoint,
+			"Wrong response format from the key endpoint",
+		)
+	})?;
+
+	Ok(fetched_keys.into_iter().map(|k| (k.id, k.key)).collect())
+}
+
+/**
+Store the device keys at the key endpoint.
+
+With the local fallback a copy is also stored in the key store of the server,
+so the keys are stored even when the endpoint is not reachable.
+ */
+pub(crate) async fn upload_keys(app_data: &AppData, keys: Vec<KeyStorage>) -> AppRes<()>
+{
+	let options = match &app_data.key_endpoint_options {
+		Some(o) => o,
+		None => return server_key_store::upload_key(keys).await,
+	};
+
+	let body = json_to_string(&keys)?;
+
+	let res = send_req(&app_data.app_data.app_id, options, "store", body).await;
+
+	if options.fallback == KEY_ENDPOINT_FALLBACK_LOCAL {
+		return server_key_store::upload_key(keys).await;
+	}
+
+	res.map(|_| ())
+}
+
+pub(crate) async fn delete_keys(app_data: &AppData, keys: &[String]) -> AppRes<()>
+{
+	let options = match &app_data.key_endpoint_options {
+		Some(o) => o,
+		None => return server_key_store::delete_key(keys).await,
+	};
+
+	let body = json_to_string(&KeyEndpointKeysInput {
+		keys: keys.to_vec(),
+	})?;
+
+	let res = send_req(&app_data.app_data.app_id, options, "delete", body).await;
+
+	//always delete the local copy, even if the endpoint is not reachable
+	server_key_store::delete_key(keys).await?;
+
+	res.map(|_| ())
+}
+
+//__________________________________________________________________________________________________
+
+pub fn sign_req(auth_token: &str, timestamp: u64, app_id: &str, body: &str) -> String
+{
+	let key = hmac::Key::new(hmac::HMAC_SHA256, auth_token.as_bytes());
+
+	let tag = hmac::sign(&key, format!("{timestamp}.{app_id}.{body}").as_bytes());
+
+	base64::encode(tag.as_ref())
+}
+
+async fn send_req(app_id: &str, options: &AppKeyEndpointOptions, action: &str, body: String) -> AppRes<String>
+{
+	let auth_token = key_endpoint_model::get_auth_token(app_id)
+		.await?
+		.ok_or_else(|| {
+			ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::UserKeyEndpoint,
+				"No auth token for the key endpoint found",
+			)
+		})?;
+
+	let timestamp = get_time_in_sec()?;
+
+	let signature = sign_req(&auth_token, timestamp, app_id, &body);
+
+	let url = format!("{}/{}", options.key_endpoint_url.trim_end_matches('/'), action);
+
+	let client = reqwest::Client::new();
+
+	let res = client
+		.post(url)
+		.timeout(Duration::from_millis(options.timeout as u64))
+		.header("content-type", "application/json")
+		.header(KEY_ENDPOINT_HEADER_APP_ID, app_id)
+		.header(KEY_ENDPOINT_HEADER_TIMESTAMP, timestamp.to_string())
+		.header(KEY_ENDPOINT_HEADER_SIGNATURE, signature)
+		.body(body)
+		.send()
+		.await
+		.map_err(|e| {
+			ServerCoreError::new_msg_and_debug(
+				400,
+				ApiErrorCodes::UserKeyEndpoint,
+				"Key endpoint is not reachable",
+				Some(e.to_string()),
+			)
+		})?;
+
+	if !res.status().is_success() {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::UserKeyEndpoint,
+			"Key endpoint returned an error",
+		));
+	}
+
+	res.text().await.map_err(|_e| {
+		ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::UserKeyEndpoint,
+			"Wrong response format from the key endpoint",
+		)
+	})
+}
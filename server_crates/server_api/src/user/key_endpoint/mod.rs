@@ -0,0 +1,2 @@
+mod key_endpoint_model;
+pub mod key_endpoint_service;
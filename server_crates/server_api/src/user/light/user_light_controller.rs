@@ -23,7 +23,7 @@ pub(crate) async fn register_light(mut req: Request) -> JRes<RegisterServerOutpu
 
 	check_endpoint_with_app_options(app_data, Endpoint::UserRegister)?;
 
-	let out = user_light_service::register_light(&app_data.app_data.app_id, register_input, true).await?;
+	let out = user_light_service::register_light(app_data, register_input, true).await?;
 
 	echo(out)
 }
@@ -37,7 +37,7 @@ pub(crate) async fn done_register_device_light(mut req: Request) -> JRes<ServerS
 
 	check_endpoint_with_app_options(app, Endpoint::UserDeviceRegister)?;
 
-	user_light_service::done_register_device_light(&app.app_data.app_id, &user.id, &user.group_id, input).await?;
+	user_light_service::done_register_device_light(app, &user.id, &user.group_id, input).await?;
 
 	echo_success()
 }
@@ -15,16 +15,19 @@ use server_api_common::util::{get_user_in_app_key, hash_token_to_string};
 use server_key_store::KeyStorage;
 
 use crate::group::{group_service, group_user_service};
+use crate::key_transparency::key_transparency_entities::KtLeafInput;
+use crate::key_transparency::{key_transparency_service, KT_KEY_TYPE_DEVICE_PUBLIC, KT_KEY_TYPE_DEVICE_VERIFY};
 use crate::sentc_group_user_service::NewUserType;
 use crate::sentc_user_entities::{LoginForcedLightOutput, VerifyLoginEntity};
 use crate::sentc_user_service::internal_group_data;
 use crate::user::auth::auth_service;
+use crate::user::key_endpoint::key_endpoint_service;
 use crate::user::light::user_light_model;
 use crate::user::user_model;
 
-pub async fn register_light(app_id: impl Into<AppId>, input: UserDeviceRegisterInput, user: bool) -> AppRes<RegisterServerOutput>
+pub async fn register_light(app_data: &AppData, input: UserDeviceRegisterInput, user: bool) -> AppRes<RegisterServerOutput>
 {
-	let app_id = app_id.into();
+	let app_id = &app_data.app_data.app_id;
 
 	let identifier = hash_token_to_string(input.device_identifier.as_bytes())?;
 
@@ -40,36 +43,62 @@ pub async fn register_light(app_id: impl Into<AppId>, input: UserDeviceRegisterI
 		keypair_sign_alg: input.derived.keypair_sign_alg,
 	};
 
-	let (user_id, device_id) = user_light_model::register_light(&app_id, identifier, input.master_key, derived).await?;
+	let (user_id, device_id) = user_light_model::register_light(app_id, identifier, input.master_key, derived).await?;
+
+	let kt_keys = vec![
+		KtLeafInput::new(
+			KT_KEY_TYPE_DEVICE_PUBLIC,
+			&user_id,
+			&device_id,
+			&input.derived.public_key,
+		),
+		KtLeafInput::new(
+			KT_KEY_TYPE_DEVICE_VERIFY,
+			&user_id,
+			&device_id,
+			&input.derived.verify_key,
+		),
+	];
+
+	if let Err(e) = key_endpoint_service::upload_keys(
+		app_data,
+		vec![
+			KeyStorage {
+				key: input.derived.public_key,
+				id: format!("pk_{device_id}"),
+			},
+			KeyStorage {
+				key: input.derived.encrypted_private_key,
+				id: format!("sk_{device_id}"),
+			},
+			KeyStorage {
+				key: input.derived.verify_key,
+				id: format!("vk_{device_id}"),
+			},
+			KeyStorage {
+				key: input.derived.encrypted_sign_key,
+				id: format!("sign_k_{device_id}"),
+			},
+		],
+	)
+	.await
+	{
+		//remove the user again, the keys of the device are not stored
+		user_model::delete(&user_id, app_id).await?;
 
-	server_key_store::upload_key(vec![
-		KeyStorage {
-			key: input.derived.public_key,
-			id: format!("pk_{device_id}"),
-		},
-		KeyStorage {
-			key: input.derived.encrypted_private_key,
-			id: format!("sk_{device_id}"),
-		},
-		KeyStorage {
-			key: input.derived.verify_key,
-			id: format!("vk_{device_id}"),
-		},
-		KeyStorage {
-			key: input.derived.encrypted_sign_key,
-			id: format!("sign_k_{device_id}"),
-		},
-	])
-	.await?;
+		return Err(e);
+	}
+
+	key_transparency_service::append_keys(app_id, kt_keys).await?;
 
 	//delete the user in app check cache from the jwt mw
 	//it can happen that a user id was used before which doesn't exist yet
-	let cache_key = get_user_in_app_key(&app_id, &user_id);
+	let cache_key = get_user_in_app_key(app_id, &user_id);
 	cache::delete(&cache_key).await?;
 
 	if user {
 		//creat the user group for the user devices
-		let group_id = group_service::create_group_light(&app_id, &device_id, GROUP_TYPE_USER, None, None, None, false).await?;
+		let group_id = group_service::create_group_light(app_id, &device_id, GROUP_TYPE_USER, None, None, None, false).await?;
 
 		user_model::register_update_user_group_id(app_id, &user_id, group_id).await?;
 	}
@@ -82,25 +111,28 @@ pub async fn register_light(app_id: impl Into<AppId>, input: UserDeviceRegisterI
 }
 
 pub async fn done_register_device_light(
-	app_id: impl Into<AppId>,
+	app_data: &AppData,
 	user_id: impl Into<UserId>,
 	user_group_id: impl Into<GroupId>,
 	input: UserDeviceDoneRegisterInputLight,
 ) -> AppRes<()>
 {
-	let app_id = app_id.into();
+	let app_id = &app_data.app_data.app_id;
+	let user_id = user_id.into();
 
-	let device_id = user_model::get_done_register_device(&app_id, input.token).await?;
+	let device_id = user_model::get_done_register_device(app_id, input.token).await?;
 
 	group_user_service::invite_auto_light(
-		&internal_group_data(&app_id, user_group_id, 0),
+		&internal_group_data(app_id, user_group_id, 0),
 		input.user_group,
 		&device_id, //invite the new device
 		NewUserType::Normal,
 	)
 	.await?;
 
-	user_model::done_register_device(app_id, user_id, device_id).await?;
+	user_model::done_register_device(app_id, &user_id, &device_id).await?;
+
+	key_transparency_service::append_device_keys(app_data, &user_id, &device_id).await?;
 
 	Ok(())
 }
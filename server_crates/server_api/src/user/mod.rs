@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod key_endpoint;
 pub mod light;
 pub mod otp;
 pub mod user_controller;
@@ -50,6 +50,7 @@ use crate::user::user_entities::{UserDeviceList, UserInitEntity, UserPublicKeyDa
 use crate::user::user_service::UserAction;
 use crate::user::{user_model, user_service};
 use crate::util::api_res::ApiErrorCodes;
+use crate::util::key_sig::check_public_key_sig;
 
 pub(crate) async fn exists(mut req: Request) -> JRes<UserIdentifierAvailableServerOutput>
 {
@@ -73,7 +74,7 @@ pub(crate) async fn register(mut req: Request) -> JRes<RegisterServerOutput>
 
 	check_endpoint_with_app_options(app_data, Endpoint::UserRegister)?;
 
-	let out = user_service::register(&app_data.app_data.app_id, register_input).await?;
+	let out = user_service::register(app_data, register_input).await?;
 
 	echo(out)
 }
@@ -86,7 +87,7 @@ pub(crate) async fn prepare_register_device(mut req: Request) -> JRes<UserDevice
 
 	check_endpoint_with_app_options(app_data, Endpoint::UserDeviceRegister)?;
 
-	let out = user_service::prepare_register_device(&app_data.app_data.app_id, input).await?;
+	let out = user_service::prepare_register_device(app_data, input).await?;
 
 	echo(out)
 }
@@ -100,7 +101,7 @@ pub(crate) async fn done_register_device(mut req: Request) -> JRes<GroupAcceptJo
 
 	check_endpoint_with_app_options(app, Endpoint::UserDeviceRegister)?;
 
-	let session_id = user_service::done_register_device(&app.app_data.app_id, &user.id, &user.group_id, input).await?;
+	let session_id = user_service::done_register_device(app, &user.id, &user.group_id, input).await?;
 
 	let out = GroupAcceptJoinReqServerOutput {
 		session_id,
@@ -368,7 +369,7 @@ pub(crate) async fn delete(req: Request) -> JRes<ServerSuccessOutput>
 
 	let user = get_jwt_data_from_param(&req)?;
 
-	user_service::delete(user, &app.app_data.app_id).await?;
+	user_service::delete(user, app).await?;
 
 	user_model::save_user_action(&app.app_data.app_id, &user.id, UserAction::Delete, 1).await?;
 
@@ -383,7 +384,7 @@ pub(crate) async fn delete_device(req: Request) -> JRes<ServerSuccessOutput>
 	let user = get_jwt_data_from_param(&req)?;
 	let device_id = get_name_param_from_req(&req, "device_id")?;
 
-	user_service::delete_device(user, &app.app_data.app_id, device_id).await?;
+	user_service::delete_device(user, app, device_id).await?;
 
 	echo_success()
 }
@@ -522,6 +523,8 @@ pub(crate) async fn user_group_key_rotation(mut req: Request) -> JRes<KeyRotatio
 		input.keypair_sign_alg
 	);
 
+	check_public_key_sig(app_data, (&input).into())?;
+
 	let out = group_key_rotation_service::start_key_rotation(
 		&app_data.app_data.app_id,
 		&user.group_id,
@@ -559,7 +562,7 @@ pub(crate) async fn done_key_rotation_for_device(mut req: Request) -> JRes<Serve
 
 	let input: DoneKeyRotationData = bytes_to_json(&body)?;
 
-	group_key_rotation_service::done_key_rotation_for_user(&user.group_id, &user.device_id, key_id, input).await?;
+	group_key_rotation_service::done_key_rotation_for_user(app_data, &user.group_id, &user.device_id, key_id, input).await?;
 
 	echo_success()
 }
@@ -607,7 +610,7 @@ pub(crate) async fn delete_user(mut req: Request) -> JRes<ServerSuccessOutput>
 
 	let jwt = prepare_user_forced_action(&app_data.app_data.app_id, user_identifier.user_identifier).await?;
 
-	user_service::delete(&jwt, &app_data.app_data.app_id).await?;
+	user_service::delete(&jwt, app_data).await?;
 
 	echo_success()
 }
@@ -638,7 +641,7 @@ pub(crate) async fn delete_user_by_id(req: Request) -> JRes<ServerSuccessOutput>
 		fresh: true, //must be a fresh jwt
 	};
 
-	user_service::delete(&jwt, &app_data.app_data.app_id).await?;
+	user_service::delete(&jwt, app_data).await?;
 
 	echo_success()
 }
@@ -695,6 +698,8 @@ pub(crate) async fn reset_user(mut req: Request) -> JRes<ServerSuccessOutput>
 		group_data.keypair_sign_alg
 	);
 
+	check_public_key_sig(app_data, (&group_data).into())?;
+
 	let device_data = input.device;
 	let identifier = hash_token_to_string(device_data.device_identifier.as_bytes())?;
 
@@ -320,6 +320,19 @@ pub(super) async fn register_device(
 	Ok(device_id)
 }
 
+/**
+Delete the device again when the keys of the device couldn't be stored
+ */
+pub(super) async fn delete_not_registered_device(app_id: impl Into<AppId>, device_id: impl Into<DeviceId>) -> AppRes<()>
+{
+	//language=SQL
+	let sql = "DELETE FROM sentc_user_device WHERE id = ? AND app_id = ? AND user_id = 'not_registered'";
+
+	exec(sql, set_params!(device_id.into(), app_id.into())).await?;
+
+	Ok(())
+}
+
 pub(super) async fn get_done_register_device(app_id: impl Into<AppId>, token: String) -> AppRes<DeviceId>
 {
 	if token.as_str() == "NULL" || token.as_str() == "null" {
@@ -34,12 +34,16 @@ pub use self::user_model::{get_devices, get_group_key_rotations_in_actual_month,
 use crate::group::group_entities::GroupUserKeys;
 use crate::group::group_user_service::NewUserType;
 use crate::group::{group_service, group_user_service};
+use crate::key_transparency::key_transparency_entities::KtLeafInput;
+use crate::key_transparency::{key_transparency_service, KT_KEY_TYPE_DEVICE_PUBLIC, KT_KEY_TYPE_DEVICE_VERIFY};
 use crate::sentc_user_entities::{LoginForcedOutput, UserPublicKeyDataEntity, UserVerifyKeyDataEntity, VerifyLoginOutput};
 use crate::user::auth::auth_service::{auth_user, verify_login_forced_internally, verify_login_internally};
+use crate::user::key_endpoint::key_endpoint_service;
 use crate::user::user_entities::UserInitEntity;
 use crate::user::user_model::DeviceForDelete;
 use crate::user::{otp, user_model};
 use crate::util::api_res::ApiErrorCodes;
+use crate::util::key_sig::check_public_key_sig;
 
 #[macro_export]
 macro_rules! check_user_group_keys_set {
@@ -95,7 +99,7 @@ pub async fn exists(app_id: impl Into<AppId>, data: UserIdentifierAvailableServe
 	Ok(out)
 }
 
-pub async fn register(app_id: impl Into<AppId>, register_input: RegisterData) -> AppRes<RegisterServerOutput>
+pub async fn register(app_data: &AppData, register_input: RegisterData) -> AppRes<RegisterServerOutput>
 {
 	let mut group_data = register_input.group;
 
@@ -106,9 +110,11 @@ pub async fn register(app_id: impl Into<AppId>, register_input: RegisterData) ->
 		group_data.keypair_sign_alg
 	);
 
+	check_public_key_sig(app_data, (&group_data).into())?;
+
 	let device_data = register_input.device;
 
-	let app_id = app_id.into();
+	let app_id = &app_data.app_data.app_id;
 
 	//save the data
 
@@ -128,34 +134,60 @@ pub async fn register(app_id: impl Into<AppId>, register_input: RegisterData) ->
 		keypair_sign_alg: device_data.derived.keypair_sign_alg,
 	};
 
-	let (user_id, device_id) = user_model::register(&app_id, identifier, device_data.master_key, derived).await?;
+	let (user_id, device_id) = user_model::register(app_id, identifier, device_data.master_key, derived).await?;
+
+	let kt_keys = vec![
+		KtLeafInput::new(
+			KT_KEY_TYPE_DEVICE_PUBLIC,
+			&user_id,
+			&device_id,
+			&device_data.derived.public_key,
+		),
+		KtLeafInput::new(
+			KT_KEY_TYPE_DEVICE_VERIFY,
+			&user_id,
+			&device_id,
+			&device_data.derived.verify_key,
+		),
+	];
+
+	if let Err(e) = key_endpoint_service::upload_keys(
+		app_data,
+		vec![
+			KeyStorage {
+				key: device_data.derived.public_key,
+				id: format!("pk_{device_id}"),
+			},
+			KeyStorage {
+				key: device_data.derived.encrypted_private_key,
+				id: format!("sk_{device_id}"),
+			},
+			KeyStorage {
+				key: device_data.derived.verify_key,
+				id: format!("vk_{device_id}"),
+			},
+			KeyStorage {
+				key: device_data.derived.encrypted_sign_key,
+				id: format!("sign_k_{device_id}"),
+			},
+		],
+	)
+	.await
+	{
+		//remove the user again, the keys of the device are not stored
+		user_model::delete(&user_id, app_id).await?;
 
-	server_key_store::upload_key(vec![
-		KeyStorage {
-			key: device_data.derived.public_key,
-			id: format!("pk_{device_id}"),
-		},
-		KeyStorage {
-			key: device_data.derived.encrypted_private_key,
-			id: format!("sk_{device_id}"),
-		},
-		KeyStorage {
-			key: device_data.derived.verify_key,
-			id: format!("vk_{device_id}"),
-		},
-		KeyStorage {
-			key: device_data.derived.encrypted_sign_key,
-			id: format!("sign_k_{device_id}"),
-		},
-	])
-	.await?;
+		return Err(e);
+	}
+
+	key_transparency_service::append_keys(app_id, kt_keys).await?;
 
 	//update creator public key id in group data (with the device id), this is needed to know what public key was used to encrypt the group key
 	group_data.creator_public_key_id = device_id.to_string();
 
 	//create user group, insert the device not the user id because the devices are in the group not the user!
 	let group_id = group_service::create_group(
-		&app_id,
+		app_id,
 		&device_id,
 		group_data,
 		GROUP_TYPE_USER,
@@ -169,7 +201,7 @@ pub async fn register(app_id: impl Into<AppId>, register_input: RegisterData) ->
 
 	//delete the user in app check cache from the jwt mw
 	//it can happen that a user id was used before which doesn't exist yet
-	let cache_key = get_user_in_app_key(&app_id, &user_id);
+	let cache_key = get_user_in_app_key(app_id, &user_id);
 	cache::delete(&cache_key).await?;
 
 	//now update the user group id
@@ -194,13 +226,13 @@ In the client:
 - transport the token to the active device
 - call done register device with the device id and the token
 */
-pub async fn prepare_register_device(app_id: impl Into<AppId>, input: UserDeviceRegisterInput) -> AppRes<UserDeviceRegisterOutput>
+pub async fn prepare_register_device(app_data: &AppData, input: UserDeviceRegisterInput) -> AppRes<UserDeviceRegisterOutput>
 {
-	let app_id = app_id.into();
+	let app_id = &app_data.app_data.app_id;
 
 	let identifier = hash_token_to_string(input.device_identifier.as_bytes())?;
 
-	let check = user_model::check_user_exists(&app_id, &identifier).await?;
+	let check = user_model::check_user_exists(app_id, &identifier).await?;
 
 	if check {
 		//check true == user exists
@@ -230,25 +262,34 @@ pub async fn prepare_register_device(app_id: impl Into<AppId>, input: UserDevice
 
 	let device_id = user_model::register_device(app_id, identifier, input.master_key, derived, &token).await?;
 
-	server_key_store::upload_key(vec![
-		KeyStorage {
-			key: input.derived.public_key,
-			id: format!("pk_{device_id}"),
-		},
-		KeyStorage {
-			key: input.derived.encrypted_private_key,
-			id: format!("sk_{device_id}"),
-		},
-		KeyStorage {
-			key: input.derived.verify_key,
-			id: format!("vk_{device_id}"),
-		},
-		KeyStorage {
-			key: input.derived.encrypted_sign_key,
-			id: format!("sign_k_{device_id}"),
-		},
-	])
-	.await?;
+	if let Err(e) = key_endpoint_service::upload_keys(
+		app_data,
+		vec![
+			KeyStorage {
+				key: input.derived.public_key,
+				id: format!("pk_{device_id}"),
+			},
+			KeyStorage {
+				key: input.derived.encrypted_private_key,
+				id: format!("sk_{device_id}"),
+			},
+			KeyStorage {
+				key: input.derived.verify_key,
+				id: format!("vk_{device_id}"),
+			},
+			KeyStorage {
+				key: input.derived.encrypted_sign_key,
+				id: format!("sign_k_{device_id}"),
+			},
+		],
+	)
+	.await
+	{
+		//remove the device again, the keys of the device are not stored
+		user_model::delete_not_registered_device(app_id, &device_id).await?;
+
+		return Err(e);
+	}
 
 	Ok(UserDeviceRegisterOutput {
 		device_id,
@@ -269,19 +310,20 @@ In the client:
 2. same as group auto invite
 */
 pub async fn done_register_device(
-	app_id: impl Into<AppId>,
+	app_data: &AppData,
 	user_id: impl Into<UserId>,
 	user_group_id: impl Into<GroupId>,
 	input: UserDeviceDoneRegisterInput,
 ) -> AppRes<Option<String>>
 {
-	let app_id = app_id.into();
+	let app_id = &app_data.app_data.app_id;
+	let user_id = user_id.into();
 
-	let device_id = user_model::get_done_register_device(&app_id, input.token).await?;
+	let device_id = user_model::get_done_register_device(app_id, input.token).await?;
 
 	//for the auto invite we only need the group id and the group user rank
 	let session_id = group_user_service::invite_auto(
-		&internal_group_data(&app_id, user_group_id, 0),
+		&internal_group_data(app_id, user_group_id, 0),
 		input.user_keys,
 		&device_id, //invite the new device
 		NewUserType::Normal,
@@ -289,7 +331,9 @@ pub async fn done_register_device(
 	)
 	.await?;
 
-	user_model::done_register_device(app_id, user_id, device_id).await?;
+	user_model::done_register_device(app_id, &user_id, &device_id).await?;
+
+	key_transparency_service::append_device_keys(app_data, &user_id, &device_id).await?;
 
 	Ok(session_id)
 }
@@ -513,9 +557,9 @@ pub async fn refresh_jwt(app_data: &AppData, device_id: impl Into<DeviceId>, inp
 	Ok(out)
 }
 
-pub async fn delete(user: &UserJwtEntity, app_id: impl Into<AppId>) -> AppRes<()>
+pub async fn delete(user: &UserJwtEntity, app_data: &AppData) -> AppRes<()>
 {
-	let app_id = app_id.into();
+	let app_id = &app_data.app_data.app_id;
 
 	//the user needs a jwt which was created from login and no refreshed jwt
 	if !user.fresh {
@@ -529,20 +573,21 @@ pub async fn delete(user: &UserJwtEntity, app_id: impl Into<AppId>) -> AppRes<()
 	let user_id = &user.id;
 	let group_id = &user.group_id;
 
-	delete_user_external_devices(user_id, &app_id).await?;
+	delete_user_external_devices(user_id, app_data).await?;
 
-	user_model::delete(user_id, &app_id).await?;
+	user_model::delete(user_id, app_id).await?;
 
 	//delete the user in-app check cache from the jwt mw
-	let cache_key = get_user_in_app_key(&app_id, user_id);
+	let cache_key = get_user_in_app_key(app_id, user_id);
 	cache::delete(&cache_key).await?;
 
 	//delete the user group
 	group_service::delete_user_group(app_id, group_id).await
 }
 
-async fn delete_user_external_devices(user_id: &str, app_id: &str) -> AppRes<()>
+async fn delete_user_external_devices(user_id: &str, app_data: &AppData) -> AppRes<()>
 {
+	let app_id = &app_data.app_data.app_id;
 	let mut last_id = String::new();
 
 	loop {
@@ -574,7 +619,7 @@ async fn delete_user_external_devices(user_id: &str, app_id: &str) -> AppRes<()>
 		}
 
 		if !keys_to_delete.is_empty() {
-			server_key_store::delete_key(&keys_to_delete).await?;
+			key_endpoint_service::delete_keys(app_data, &keys_to_delete).await?;
 		}
 
 		if keys.len() < 50 {
@@ -590,9 +635,9 @@ async fn delete_user_external_devices(user_id: &str, app_id: &str) -> AppRes<()>
 	Ok(())
 }
 
-pub async fn delete_device(user: &UserJwtEntity, app_id: impl Into<AppId>, device_id: impl Into<DeviceId>) -> AppRes<()>
+pub async fn delete_device(user: &UserJwtEntity, app_data: &AppData, device_id: impl Into<DeviceId>) -> AppRes<()>
 {
-	let app_id = app_id.into();
+	let app_id = &app_data.app_data.app_id;
 
 	//this can be any device don't need to be the device to delete
 	if !user.fresh {
@@ -606,11 +651,11 @@ pub async fn delete_device(user: &UserJwtEntity, app_id: impl Into<AppId>, devic
 	let user_id = &user.id;
 	let device_id = device_id.into();
 
-	let keys = DeviceForDelete::get_device_for_external_key_storage(user_id, &app_id, &device_id).await?;
+	let keys = DeviceForDelete::get_device_for_external_key_storage(user_id, app_id, &device_id).await?;
 
-	user_model::delete_device(user_id, &app_id, &device_id).await?;
+	user_model::delete_device(user_id, app_id, &device_id).await?;
 
-	group_user_service::leave_group(&internal_group_data(&app_id, &user.group_id, 4), None).await?;
+	group_user_service::leave_group(&internal_group_data(app_id, &user.group_id, 4), None).await?;
 
 	//delete the external device keys
 	let mut keys_to_delete = vec![];
@@ -632,7 +677,7 @@ pub async fn delete_device(user: &UserJwtEntity, app_id: impl Into<AppId>, devic
 	}
 
 	if !keys_to_delete.is_empty() {
-		server_key_store::delete_key(&keys_to_delete).await?;
+		key_endpoint_service::delete_keys(app_data, &keys_to_delete).await?;
 	}
 
 	Ok(())
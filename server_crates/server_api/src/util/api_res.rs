@@ -12,6 +12,7 @@ pub enum ApiErrorCodes
 	UserDeviceDelete,
 	UserDeviceNotFound,
 	UserKeysNotFound,
+	UserKeyEndpoint,
 	UserExists,
 	Login,
 	WrongJwtAction,
@@ -46,8 +47,16 @@ pub enum ApiErrorCodes
 	GroupConnectedFromConnected,
 	GroupJoinAsConnectedGroup,
 	GroupReInviteMemberNotFound,
+	GroupPublicKeySig,
 
 	KeyNotFound,
+	KeyLabelTooLong,
+	KeyTooManyIds,
+	KeyNotAfter,
+
+	KtTreeSize,
+	KtLeafNotFound,
+	KtAppend,
 
 	ContentItemNotSet,
 	ContentItemTooBig,
@@ -86,6 +95,7 @@ impl ServerErrorCodes for ApiErrorCodes
 			ApiErrorCodes::UserDeviceDelete => 115,
 			ApiErrorCodes::UserDeviceNotFound => 116,
 			ApiErrorCodes::UserKeysNotFound => 117,
+			ApiErrorCodes::UserKeyEndpoint => 118,
 
 			ApiErrorCodes::AppTokenWrongFormat => 201,
 
@@ -114,8 +124,16 @@ impl ServerErrorCodes for ApiErrorCodes
 			ApiErrorCodes::GroupJoinAsConnectedGroup => 319,
 			ApiErrorCodes::GroupReInviteMemberNotFound => 320,
 			ApiErrorCodes::GroupKeyRotationLimit => 321,
+			ApiErrorCodes::GroupPublicKeySig => 322,
 
 			ApiErrorCodes::KeyNotFound => 400,
+			ApiErrorCodes::KeyLabelTooLong => 401,
+			ApiErrorCodes::KeyTooManyIds => 402,
+			ApiErrorCodes::KeyNotAfter => 403,
+
+			ApiErrorCodes::KtTreeSize => 410,
+			ApiErrorCodes::KtLeafNotFound => 411,
+			ApiErrorCodes::KtAppend => 412,
 
 			ApiErrorCodes::ContentItemNotSet => 700,
 			ApiErrorCodes::ContentItemTooBig => 701,
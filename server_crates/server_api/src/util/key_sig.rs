@@ -0,0 +1,131 @@
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::input_helper::json_to_string;
+use rustgram_server_util::res::AppRes;
+use sentc_crypto::util::public::{import_public_key_from_string_into_format, import_verify_key_from_string_into_format};
+use sentc_crypto_common::group::{CreateData, KeyRotationData};
+use sentc_crypto_common::ServerOutput;
+use serde::Serialize;
+use server_api_common::customer_app::app_entities::AppData;
+
+use crate::sentc_user_entities::{UserPublicKeyDataEntity, UserVerifyKeyDataEntity};
+use crate::util::api_res::ApiErrorCodes;
+
+/**
+The key id is only needed to connect the public key with the verify key.
+When the keys are uploaded the real id does not exist yet.
+ */
+const SIG_CHECK_KEY_ID: &str = "sig_check";
+
+pub(crate) struct PublicKeySigCheck<'a>
+{
+	pub public_key: &'a str,
+	pub public_key_alg: &'a str,
+	pub public_key_sig: Option<&'a String>,
+	pub verify_key: Option<&'a String>,
+	pub verify_key_alg: Option<&'a String>,
+}
+
+impl<'a> From<&'a CreateData> for PublicKeySigCheck<'a>
+{
+	fn from(data: &'a CreateData) -> Self
+	{
+		Self {
+			public_key: &data.public_group_key,
+			public_key_alg: &data.keypair_encrypt_alg,
+			public_key_sig: data.public_key_sig.as_ref(),
+			verify_key: data.verify_key.as_ref(),
+			verify_key_alg: data.keypair_sign_alg.as_ref(),
+		}
+	}
+}
+
+impl<'a> From<&'a KeyRotationData> for PublicKeySigCheck<'a>
+{
+	fn from(data: &'a KeyRotationData) -> Self
+	{
+		Self {
+			public_key: &data.public_group_key,
+			public_key_alg: &data.keypair_encrypt_alg,
+			public_key_sig: data.public_key_sig.as_ref(),
+			verify_key: data.verify_key.as_ref(),
+			verify_key_alg: data.keypair_sign_alg.as_ref(),
+		}
+	}
+}
+
+/**
+Check if the public key of a group (or user) was signed by the verify key of the same key pair.
+
+A sent signature is always checked, so no malformed signature is stored.
+When the app requires signed keys every group needs sign keys and a signature,
+otherwise the client could skip the check by not sending the verify key.
+ */
+pub(crate) fn check_public_key_sig(app_data: &AppData, data: PublicKeySigCheck) -> AppRes<()>
+{
+	let (verify_key, public_key_sig, verify_key_alg) = match (data.verify_key, data.public_key_sig, data.verify_key_alg) {
+		(Some(k), Some(s), Some(a)) => (k, s, a),
+		(_, None, _) if app_data.group_options.require_signed_keys != 1 => return Ok(()),
+		_ => {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::GroupPublicKeySig,
+				"The public key must be signed",
+			))
+		},
+	};
+
+	let public_key = UserPublicKeyDataEntity {
+		public_key_id: SIG_CHECK_KEY_ID.to_string(),
+		public_key: data.public_key.to_string(),
+		public_key_alg: data.public_key_alg.to_string(),
+		public_key_sig: Some(public_key_sig.to_string()),
+		public_key_sig_key_id: Some(SIG_CHECK_KEY_ID.to_string()),
+	};
+
+	let verify_key = UserVerifyKeyDataEntity {
+		verify_key_id: SIG_CHECK_KEY_ID.to_string(),
+		verify_key: verify_key.to_string(),
+		verify_key_alg: verify_key_alg.to_string(),
+	};
+
+	//import the keys like a client from the server output
+	let public_key = import_public_key_from_string_into_format(&to_server_output(public_key)?).map_err(|_e| malformed_err())?;
+	let verify_key = import_verify_key_from_string_into_format(&to_server_output(verify_key)?).map_err(|_e| malformed_err())?;
+
+	#[cfg(not(feature = "external_c_keys"))]
+	let valid = sentc_crypto::keys::std::StdUser::verify_user_public_key(&verify_key, &public_key).unwrap_or(false);
+
+	#[cfg(feature = "external_c_keys")]
+	let valid = sentc_crypto::keys::std::StdUser::verify_user_public_key(&verify_key, &public_key).unwrap_or(false) ||
+		sentc_crypto::keys::rec::RecUser::verify_user_public_key(&verify_key, &public_key).unwrap_or(false) ||
+		sentc_crypto::keys::fips::FipsUser::verify_user_public_key(&verify_key, &public_key).unwrap_or(false);
+
+	if !valid {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::GroupPublicKeySig,
+			"The signature of the public key is not valid",
+		));
+	}
+
+	Ok(())
+}
+
+fn to_server_output<T: Serialize>(result: T) -> AppRes<String>
+{
+	json_to_string(&ServerOutput {
+		status: true,
+		err_msg: None,
+		err_code: None,
+		result: Some(result),
+	})
+}
+
+fn malformed_err() -> ServerCoreError
+{
+	ServerCoreError::new_msg(
+		400,
+		ApiErrorCodes::GroupPublicKeySig,
+		"The public key or the verify key has a wrong format",
+	)
+}
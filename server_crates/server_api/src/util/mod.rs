@@ -2,6 +2,7 @@ use chrono::{Datelike, TimeZone, Utc};
 use rustgram_server_util::res::AppRes;
 
 pub mod api_res;
+pub(crate) mod key_sig;
 
 pub(crate) fn get_begin_of_month() -> AppRes<i64>
 {
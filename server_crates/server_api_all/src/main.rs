@@ -11,6 +11,8 @@ merge every server into one
 pub async fn main()
 {
 	server_api_common::start().await;
+	server_api_common::rate_limit::start_worker();
+	server_api_common::metering::start_worker();
 	server_api_customer::start().await;
 	server_dashboard_backend::start().await;
 
@@ -33,5 +35,5 @@ pub async fn main()
 	.parse()
 	.unwrap();
 
-	rustgram::start(router, addr).await;
+	server_api_common::start_server(router, addr).await;
 }
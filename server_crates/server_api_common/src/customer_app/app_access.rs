@@ -0,0 +1,164 @@
+use std::env;
+use std::net::IpAddr;
+
+use rustgram::Request;
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::res::AppRes;
+use server_dashboard_common::app::AppAccessOptions;
+
+use crate::customer_app::app_entities::{AppData, AppTokenData, AuthWithToken};
+use crate::{ApiErrorCodes, PeerAddr};
+
+/**
+The lists are saved comma separated in the db
+ */
+pub fn list_from_db(list: &str) -> Vec<String>
+{
+	list.split(',')
+		.filter(|i| !i.is_empty())
+		.map(|i| i.to_string())
+		.collect()
+}
+
+pub fn list_to_db(list: &[String]) -> String
+{
+	list.join(",")
+}
+
+/**
+Compare the origins without the trailing slash and case insensitive
+ */
+pub fn normalize_origin(origin: &str) -> String
+{
+	origin.trim().trim_end_matches('/').to_lowercase()
+}
+
+/**
+Parse a single ip or a cidr range, a single ip is a range with the full prefix
+ */
+pub fn parse_ip_range(range: &str) -> Option<(IpAddr, u8)>
+{
+	let (ip, prefix) = match range.split_once('/') {
+		Some((ip, prefix)) => (ip, Some(prefix)),
+		None => (range, None),
+	};
+
+	let ip: IpAddr = ip.trim().parse().ok()?;
+	let max = if ip.is_ipv4() { 32 } else { 128 };
+
+	let prefix = match prefix {
+		Some(p) => p.trim().parse::<u8>().ok()?,
+		None => max,
+	};
+
+	if prefix > max {
+		return None;
+	}
+
+	Some((ip, prefix))
+}
+
+fn ip_in_range(ip: &IpAddr, range: &str) -> bool
+{
+	let (net, prefix) = match parse_ip_range(range) {
+		Some(r) => r,
+		None => return false,
+	};
+
+	match (ip, net) {
+		(IpAddr::V4(ip), IpAddr::V4(net)) => {
+			let mask = if prefix == 0 { 0 } else { u32::MAX << (32 - prefix) };
+
+			u32::from(*ip) & mask == u32::from(net) & mask
+		},
+		(IpAddr::V6(ip), IpAddr::V6(net)) => {
+			let mask = if prefix == 0 { 0 } else { u128::MAX << (128 - prefix) };
+
+			u128::from(*ip) & mask == u128::from(net) & mask
+		},
+		_ => false,
+	}
+}
+
+/**
+Get the ip of the client.
+
+The header of the reverse proxy (CLIENT_IP_HEADER) is only used when it is set,
+because without a trusted proxy in front the client can set any ip in the header.
+Otherwise, or when the proxy didn't set the header, the address of the connection is used.
+ */
+pub(crate) fn get_client_ip(req: &Request) -> Option<IpAddr>
+{
+	if let Ok(header) = env::var("CLIENT_IP_HEADER") {
+		let ip = req
+			.headers()
+			.get(header.as_str())
+			.and_then(|v| v.to_str().ok())
+			//the last entry is set by the own proxy, the entries before can be set by the client
+			.and_then(|v| v.rsplit(',').next())
+			.and_then(|v| v.trim().parse().ok());
+
+		if ip.is_some() {
+			return ip;
+		}
+	}
+
+	req.extensions().get::<PeerAddr>().map(|a| a.0.ip())
+}
+
+/**
+Check the origin and the client ip with the access options of the token or the app.
+
+The origin is only checked for the public token, the secret token is only checked with the ip.
+
+Returns the origin when the app only allows some origins, to use it in the cors header instead of *.
+ */
+pub(crate) fn check_app_access(req: &Request, app_data: &AppData) -> AppRes<Option<String>>
+{
+	//the access options of a named token replace the options of the app
+	let access: &AppAccessOptions = match &app_data.token {
+		Some(AppTokenData {
+			access_options: Some(a),
+			..
+		}) => a,
+		_ => &app_data.access_options,
+	};
+
+	if !access.allowed_ips.is_empty() {
+		let allowed = match get_client_ip(req) {
+			Some(ip) => access.allowed_ips.iter().any(|r| ip_in_range(&ip, r)),
+			None => false,
+		};
+
+		if !allowed {
+			return Err(ServerCoreError::new_msg(
+				403,
+				ApiErrorCodes::AppAccessDenied,
+				"The client ip is not allowed for this app token",
+			));
+		}
+	}
+
+	//the secret token is used by the backends of the customer which don't send an origin
+	if access.allowed_origins.is_empty() || matches!(app_data.auth_with_token, AuthWithToken::Secret) {
+		return Ok(None);
+	}
+
+	let origin = req
+		.headers()
+		.get("origin")
+		.and_then(|o| o.to_str().ok())
+		.unwrap_or_default();
+
+	let normalized = normalize_origin(origin);
+
+	if origin.is_empty() || !access.allowed_origins.iter().any(|a| *a == normalized) {
+		return Err(ServerCoreError::new_msg(
+			403,
+			ApiErrorCodes::AppAccessDenied,
+			"The origin is not allowed for this app token",
+		));
+	}
+
+	Ok(Some(origin.to_string()))
+}
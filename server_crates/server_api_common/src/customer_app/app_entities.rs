@@ -1,7 +1,7 @@
 use rustgram_server_util::DB;
 use sentc_crypto_common::{AppId, CustomerId, SignKeyPairId};
 use serde::{Deserialize, Serialize};
-use server_dashboard_common::app::{AppGroupOption, AppOptions};
+use server_dashboard_common::app::{AppAccessOptions, AppGroupOption, AppOptions, AppRateLimitOptions};
 
 /**
 Data which is used to identify the customers app requests.
@@ -21,6 +21,13 @@ pub struct AppData
 	pub options: AppOptions,
 	pub file_options: AppFileOptions,
 	pub group_options: AppGroupOption,
+	pub key_endpoint_options: Option<AppKeyEndpointOptions>,
+	/**
+	Only set when a named token was used and not the default public or secret token
+	 */
+	pub token: Option<AppTokenData>,
+	pub access_options: AppAccessOptions,
+	pub rate_limit_options: AppRateLimitOptions,
 }
 
 #[derive(Serialize, Deserialize, DB)]
@@ -28,6 +35,30 @@ pub struct AppFileOptions
 {
 	pub file_storage: i32,
 	pub storage_url: Option<String>,
+	pub max_storage_app: i64,
+	pub max_storage_user: i64,
+	pub max_storage_group: i64,
+	pub max_files_app: i32,
+	pub max_files_user: i32,
+	pub max_files_group: i32,
+	pub session_lifetime: i32,
+	pub default_ttl: i64,
+	pub max_ttl: i64,
+	pub version_keep_last: i32,
+	pub version_keep_days: i32,
+}
+
+/**
+The customer hosted key endpoint but without the auth token.
+
+Only set when the customer added an endpoint url.
+ */
+#[derive(Serialize, Deserialize, DB)]
+pub struct AppKeyEndpointOptions
+{
+	pub key_endpoint_url: String,
+	pub timeout: i32,
+	pub fallback: i32,
 }
 
 /**
@@ -42,6 +73,61 @@ pub enum AuthWithToken
 
 //__________________________________________________________________________________________________
 
+/**
+A named token of the app.
+
+The scope is saved as json of the app options.
+
+Only internal values from the db
+ */
+#[derive(DB)]
+pub struct AppTokenEntity
+{
+	pub token_id: String,
+	pub app_id: AppId,
+	pub token_type: i32,
+	pub expires_at: u128,
+	pub scope: Option<String>,
+	pub allowed_origins: Option<String>,
+	pub allowed_ips: Option<String>,
+}
+
+/**
+The named token in the cached app data
+ */
+#[derive(Serialize, Deserialize)]
+pub struct AppTokenData
+{
+	pub token_id: String,
+	pub expires_at: u128,
+	pub scope: Option<AppOptions>,
+	pub access_options: Option<AppAccessOptions>,
+}
+
+/**
+The origins and ips of the app, only set when the customer restricts the app tokens
+ */
+#[derive(DB)]
+pub struct AppAccessOptionsEntity
+{
+	pub allowed_origins: String,
+	pub allowed_ips: String,
+}
+
+/**
+The rate limit of one endpoint group, only set when the customer limits this group
+ */
+#[derive(DB)]
+pub struct AppRateLimitEntity
+{
+	pub endpoint_group: i32,
+	pub capacity: i32,
+	pub refill_per_minute: i32,
+	pub limit_by: i32,
+}
+
+//__________________________________________________________________________________________________
+
 /**
 This values can only be exists once
 
@@ -1,13 +1,46 @@
-use rustgram_server_util::db::{query, query_first};
+use rustgram_server_util::db::{exec, query, query_first};
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::input_helper::bytes_to_json;
 use rustgram_server_util::res::AppRes;
-use rustgram_server_util::set_params;
+use rustgram_server_util::{get_time, set_params};
 use sentc_crypto_common::AppId;
-use server_dashboard_common::app::AppOptions;
+use server_dashboard_common::app::{AppAccessOptions, AppOptions, AppRateLimit, AppRateLimitOptions, APP_TOKEN_TYPE_SECRET};
 
-use crate::customer_app::app_entities::{AppData, AppDataGeneral, AuthWithToken};
+use crate::customer_app::app_access::list_from_db;
+use crate::customer_app::app_entities::{
+	AppAccessOptionsEntity,
+	AppData,
+	AppDataGeneral,
+	AppRateLimitEntity,
+	AppTokenData,
+	AppTokenEntity,
+	AuthWithToken,
+};
 use crate::ApiErrorCodes;
 
+/**
+Get the rate limits of all endpoint groups. A group without a row is not limited.
+ */
+pub(super) async fn get_rate_limit_options(app_id: impl Into<AppId>) -> AppRes<AppRateLimitOptions>
+{
+	//language=SQL
+	let sql = "SELECT endpoint_group, capacity, refill_per_minute, limit_by FROM sentc_app_rate_limit_options WHERE app_id = ?";
+
+	let list: Vec<AppRateLimitEntity> = query(sql, set_params!(app_id.into())).await?;
+
+	let mut options = AppRateLimitOptions::default();
+
+	for item in list {
+		*options.get_mut(item.endpoint_group) = AppRateLimit {
+			capacity: item.capacity,
+			refill_per_minute: item.refill_per_minute,
+			limit_by: item.limit_by,
+		};
+	}
+
+	Ok(options)
+}
+
 pub(super) async fn get_app_options(app_id: impl Into<AppId>) -> AppRes<AppOptions>
 {
 	//get the options
@@ -82,26 +115,66 @@ WHERE
 	Ok(options)
 }
 
-async fn get_app_data_private(app_data: AppDataGeneral, auth_with_token: AuthWithToken) -> AppRes<AppData>
+async fn get_app_data_private(app_data: AppDataGeneral, auth_with_token: AuthWithToken, token: Option<AppTokenData>) -> AppRes<AppData>
 {
 	//language=SQL
 	let sql_jwt = "SELECT id, alg, time FROM sentc_app_jwt_keys WHERE app_id = ? ORDER BY time DESC LIMIT 10";
 
 	//get app file options but without the auth token for external storage
 	//language=SQL
-	let sql_file_opt = "SELECT file_storage,storage_url FROM sentc_file_options WHERE app_id = ?";
+	let sql_file_opt = r"
+SELECT 
+    file_storage,
+    storage_url,
+    max_storage_app,
+    max_storage_user,
+    max_storage_group,
+    max_files_app,
+    max_files_user,
+    max_files_group,
+    session_lifetime,
+    default_ttl,
+    max_ttl,
+    version_keep_last,
+    version_keep_days 
+FROM sentc_file_options 
+WHERE app_id = ?";
 
 	//get the group options
 	//language=SQL
-	let sql_group = "SELECT max_key_rotation_month,min_rank_key_rotation FROM sentc_app_group_options WHERE app_id = ?";
+	let sql_group = "SELECT max_key_rotation_month,min_rank_key_rotation,require_signed_keys FROM sentc_app_group_options WHERE app_id = ?";
+
+	//get the key endpoint options without the auth token, only when an endpoint is set
+	//language=SQL
+	let sql_key_endpoint = r"
+SELECT key_endpoint_url,timeout,fallback 
+FROM sentc_app_key_endpoint_options 
+WHERE app_id = ? AND key_endpoint_url IS NOT NULL";
 
-	let (jwt_data, options, file_options, group_options) = tokio::try_join!(
+	//the access options are only set when the customer restricts the app tokens
+	//language=SQL
+	let sql_access = "SELECT allowed_origins, allowed_ips FROM sentc_app_access_options WHERE app_id = ?";
+
+	let (jwt_data, options, file_options, group_options, key_endpoint_options, access_options, rate_limit_options) = tokio::try_join!(
 		query(sql_jwt, set_params!(app_data.app_id.clone())),
 		get_app_options(&app_data.app_id),
 		query_first(sql_file_opt, set_params!(app_data.app_id.clone())),
 		query_first(sql_group, set_params!(app_data.app_id.clone())),
+		query_first(sql_key_endpoint, set_params!(app_data.app_id.clone())),
+		query_first::<AppAccessOptionsEntity>(sql_access, set_params!(app_data.app_id.clone())),
+		get_rate_limit_options(&app_data.app_id),
 	)?;
 
+	let access_options = match access_options {
+		Some(a) => {
+			AppAccessOptions {
+				allowed_origins: list_from_db(&a.allowed_origins),
+				allowed_ips: list_from_db(&a.allowed_ips),
+			}
+		},
+		None => AppAccessOptions::default(),
+	};
+
 	Ok(AppData {
 		app_data,
 		jwt_data,
@@ -109,10 +182,14 @@ async fn get_app_data_private(app_data: AppDataGeneral, auth_with_token: AuthWit
 		options,
 		file_options: file_options.ok_or_else(|| ServerCoreError::new_msg(401, ApiErrorCodes::AppNotFound, "App not found"))?,
 		group_options: group_options.ok_or_else(|| ServerCoreError::new_msg(401, ApiErrorCodes::AppNotFound, "App not found"))?,
+		key_endpoint_options,
+		token,
+		access_options,
+		rate_limit_options,
 	})
 }
 
-pub(crate) async fn get_app_data_from_id(id: impl Into<AppId>) -> AppRes<AppData>
+async fn get_app_general_from_id(id: impl Into<AppId>) -> AppRes<AppDataGeneral>
 {
 	//language=SQL
 	let sql = r"
@@ -132,7 +209,14 @@ WHERE id = ? LIMIT 1";
 		));
 	}
 
-	get_app_data_private(app_data, AuthWithToken::Public).await
+	Ok(app_data)
+}
+
+pub(crate) async fn get_app_data_from_id(id: impl Into<AppId>) -> AppRes<AppData>
+{
+	let app_data = get_app_general_from_id(id).await?;
+
+	get_app_data_private(app_data, AuthWithToken::Public, None).await
 }
 
 /**
@@ -150,9 +234,13 @@ SELECT id as app_id, owner_id, owner_type, hashed_secret_token, hashed_public_to
 FROM sentc_app 
 WHERE hashed_public_token = ? OR hashed_secret_token = ? LIMIT 1";
 
-	let app_data: AppDataGeneral = query_first(sql, set_params!(hashed_token.clone(), hashed_token.clone()))
-		.await?
-		.ok_or_else(|| ServerCoreError::new_msg(401, ApiErrorCodes::AppTokenNotFound, "App token not found"))?;
+	let app_data: Option<AppDataGeneral> = query_first(sql, set_params!(hashed_token.clone(), hashed_token.clone())).await?;
+
+	let app_data = match app_data {
+		Some(d) => d,
+		//not a default token of an app
+		None => return get_app_data_from_named_token(hashed_token).await,
+	};
 
 	if app_data.disabled.is_some() {
 		return Err(ServerCoreError::new_msg(
@@ -174,5 +262,70 @@ WHERE hashed_public_token = ? OR hashed_secret_token = ? LIMIT 1";
 		));
 	};
 
-	get_app_data_private(app_data, auth_with_token).await
+	get_app_data_private(app_data, auth_with_token, None).await
+}
+
+async fn get_app_data_from_named_token(hashed_token: String) -> AppRes<AppData>
+{
+	//language=SQL
+	let sql = r"
+SELECT id as token_id, app_id, token_type, expires_at, scope, allowed_origins, allowed_ips 
+FROM sentc_app_token 
+WHERE hashed_token = ?";
+
+	let token: AppTokenEntity = query_first(sql, set_params!(hashed_token))
+		.await?
+		.ok_or_else(|| ServerCoreError::new_msg(401, ApiErrorCodes::AppTokenNotFound, "App token not found"))?;
+
+	let time = get_time()?;
+
+	if token.expires_at > 0 && token.expires_at < time {
+		return Err(ServerCoreError::new_msg(
+			401,
+			ApiErrorCodes::AppTokenExpired,
+			"App token is expired",
+		));
+	}
+
+	let app_data = get_app_general_from_id(&token.app_id).await?;
+
+	//the last use is only updated when the app data is not in the cache
+	//language=SQL
+	let sql = "UPDATE sentc_app_token SET last_used = ? WHERE id = ?";
+
+	exec(sql, set_params!(time.to_string(), token.token_id.clone())).await?;
+
+	let scope: Option<AppOptions> = match token.scope {
+		Some(s) => Some(bytes_to_json(s.as_bytes())?),
+		None => None,
+	};
+
+	//the token uses the access options of the app when both lists are not set
+	let access_options = match (&token.allowed_origins, &token.allowed_ips) {
+		(None, None) => None,
+		(origins, ips) => {
+			Some(AppAccessOptions {
+				allowed_origins: origins.as_deref().map(list_from_db).unwrap_or_default(),
+				allowed_ips: ips.as_deref().map(list_from_db).unwrap_or_default(),
+			})
+		},
+	};
+
+	let auth_with_token = if token.token_type == APP_TOKEN_TYPE_SECRET {
+		AuthWithToken::Secret
+	} else {
+		AuthWithToken::Public
+	};
+
+	get_app_data_private(
+		app_data,
+		auth_with_token,
+		Some(AppTokenData {
+			token_id: token.token_id,
+			expires_at: token.expires_at,
+			scope,
+			access_options,
+		}),
+	)
+	.await
 }
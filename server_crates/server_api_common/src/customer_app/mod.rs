@@ -4,11 +4,12 @@ use rustgram::Request;
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
 use rustgram_server_util::res::AppRes;
 use sentc_crypto_common::AppId;
-use server_dashboard_common::app::AppOptions;
+use server_dashboard_common::app::{AppOptions, AppRateLimitOptions};
 
-use crate::customer_app::app_entities::{AppData, AuthWithToken};
+use crate::customer_app::app_entities::{AppData, AppTokenData, AuthWithToken};
 use crate::ApiErrorCodes;
 
+pub mod app_access;
 pub mod app_entities;
 pub(crate) mod app_model;
 
@@ -92,12 +93,9 @@ pub enum Endpoint
 	UserGetOtpRecoveryKeys,
 }
 
-pub fn check_endpoint_with_app_options(app_data: &AppData, endpoint: Endpoint) -> AppRes<()>
+fn get_endpoint_option(options: &AppOptions, endpoint: &Endpoint) -> i32
 {
-	let token_used = &app_data.auth_with_token;
-	let options = &app_data.options;
-
-	let token_needed = match endpoint {
+	match endpoint {
 		Endpoint::UserExists => options.user_exists,
 		Endpoint::UserRegister => options.user_register,
 		Endpoint::UserDelete => options.user_delete,
@@ -158,9 +156,29 @@ pub fn check_endpoint_with_app_options(app_data: &AppData, endpoint: Endpoint) -
 		Endpoint::UserResetOtp => options.user_reset_otp,
 		Endpoint::UserDisableOtp => options.user_disable_otp,
 		Endpoint::UserGetOtpRecoveryKeys => options.user_get_otp_recovery_keys,
-	};
+	}
+}
+
+pub fn check_endpoint_with_app_options(app_data: &AppData, endpoint: Endpoint) -> AppRes<()>
+{
+	let token_used = &app_data.auth_with_token;
+
+	//a named token can only use the endpoints of its scope
+	if let Some(AppTokenData {
+		scope: Some(scope),
+		..
+	}) = &app_data.token
+	{
+		if get_endpoint_option(scope, &endpoint) == 0 {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::AppAction,
+				"No access to this action",
+			));
+		}
+	}
 
-	let token_needed = match token_needed {
+	let token_needed = match get_endpoint_option(&app_data.options, &endpoint) {
 		1 => AuthWithToken::Public,
 		2 => AuthWithToken::Secret,
 		_ => {
@@ -206,3 +224,16 @@ pub fn get_app_options<'a>(app_id: impl Into<AppId> + 'a) -> impl Future<Output
 {
 	app_model::get_app_options(app_id)
 }
+
+pub fn get_rate_limit_options<'a>(app_id: impl Into<AppId> + 'a) -> impl Future<Output = AppRes<AppRateLimitOptions>> + 'a
+{
+	app_model::get_rate_limit_options(app_id)
+}
+
+/**
+Load the app data without a token, e.g. for the workers which only know the app id
+ */
+pub fn get_app_data_from_id<'a>(app_id: impl Into<AppId> + 'a) -> impl Future<Output = AppRes<AppData>> + 'a
+{
+	app_model::get_app_data_from_id(app_id)
+}
@@ -1,7 +1,8 @@
-use rustgram_server_util::db::{exec, exec_string, get_in, TupleEntity};
+use rustgram_server_util::db::{exec, exec_string, get_in, query_first, query_string, TupleEntity};
 use rustgram_server_util::res::AppRes;
 use rustgram_server_util::{get_time, set_params, set_params_vec};
 use sentc_crypto_common::{AppId, CustomerId, GroupId};
+use server_dashboard_common::app::{FileExternalDeleteFailure, FileUsage};
 
 use crate::file::{FILE_BELONGS_TO_TYPE_GROUP, FILE_STATUS_TO_DELETE};
 
@@ -134,3 +135,56 @@ WHERE
 
 	Ok(())
 }
+
+pub(super) async fn get_file_usage(app_id: impl Into<AppId>, usage_type: i32, id: impl Into<String>) -> AppRes<FileUsage>
+{
+	//language=SQL
+	let sql = "SELECT used_storage, file_count FROM sentc_file_usage WHERE app_id = ? AND usage_type = ? AND id = ?";
+
+	let usage: Option<FileUsage> = query_first(sql, set_params!(app_id.into(), usage_type, id.into())).await?;
+
+	//no row when nothing was uploaded yet
+	Ok(usage.unwrap_or(FileUsage {
+		used_storage: 0,
+		file_count: 0,
+	}))
+}
+
+pub(super) async fn get_external_delete_failures(
+	app_id: impl Into<AppId>,
+	last_fetched_time: u128,
+	last_part_id: impl Into<String>,
+) -> AppRes<Vec<FileExternalDeleteFailure>>
+{
+	//only the parts where at least one request failed, new parts are not sent yet
+
+	//language=SQL
+	let sql = r"
+SELECT part_id, time, attempts, next_try, last_error 
+FROM sentc_file_external_delete 
+WHERE 
+    app_id = ? AND 
+    attempts > 0"
+		.to_string();
+
+	let (sql, params) = if last_fetched_time > 0 {
+		let sql = sql + " AND time >= ? AND (time > ? OR (time = ? AND part_id > ?)) ORDER BY time, part_id LIMIT 20";
+		(
+			sql,
+			set_params!(
+				app_id.into(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_part_id.into()
+			),
+		)
+	} else {
+		let sql = sql + " ORDER BY time, part_id LIMIT 20";
+		(sql, set_params!(app_id.into()))
+	};
+
+	let list: Vec<FileExternalDeleteFailure> = query_string(sql, params).await?;
+
+	Ok(list)
+}
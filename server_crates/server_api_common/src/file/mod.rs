@@ -2,11 +2,11 @@ use std::future::Future;
 
 use rustgram_server_util::res::AppRes;
 use sentc_crypto_common::{AppId, CustomerId, GroupId};
+use server_dashboard_common::app::{FileExternalDeleteFailure, FileUsage};
 
 pub(crate) mod file_model;
 
 pub const MAX_CHUNK_SIZE: usize = 5 * 1024 * 1024;
-pub const MAX_SESSION_ALIVE_TIME: u128 = 24 * 60 * 60 * 1000;
 pub const FILE_STATUS_AVAILABLE: i32 = 1;
 pub const FILE_STATUS_TO_DELETE: i32 = 0;
 
@@ -14,6 +14,31 @@ pub const FILE_BELONGS_TO_TYPE_NONE: i32 = 0;
 pub const FILE_BELONGS_TO_TYPE_GROUP: i32 = 1;
 pub const FILE_BELONGS_TO_TYPE_USER: i32 = 2;
 
+pub const FILE_USAGE_TYPE_APP: i32 = 0;
+pub const FILE_USAGE_TYPE_USER: i32 = 1;
+pub const FILE_USAGE_TYPE_GROUP: i32 = 2;
+
+/**
+Get the file usage of an app, a user or a group. The id is the app id for the app usage.
+ */
+pub fn get_file_usage<'a>(app_id: impl Into<AppId> + 'a, usage_type: i32, id: impl Into<String> + 'a)
+	-> impl Future<Output = AppRes<FileUsage>> + 'a
+{
+	file_model::get_file_usage(app_id, usage_type, id)
+}
+
+/**
+Get the parts of the external storage of an app which couldn't be deleted yet.
+ */
+pub fn get_external_delete_failures<'a>(
+	app_id: impl Into<AppId> + 'a,
+	last_fetched_time: u128,
+	last_part_id: impl Into<String> + 'a,
+) -> impl Future<Output = AppRes<Vec<FileExternalDeleteFailure>>> + 'a
+{
+	file_model::get_external_delete_failures(app_id, last_fetched_time, last_part_id)
+}
+
 #[allow(clippy::needless_lifetimes)]
 pub fn delete_file_for_customer<'a>(customer_id: impl Into<CustomerId> + 'a) -> impl Future<Output = AppRes<()>> + 'a
 {
@@ -1,14 +1,22 @@
 #![allow(clippy::tabs_in_doc_comments, clippy::from_over_into)]
 
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::header::HeaderValue;
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
 use hyper::Body;
 use rustgram::{r, Request, Response, Router};
-use rustgram_server_util::cors_handler;
 use rustgram_server_util::error::{ServerErrorCodes, ServerErrorConstructor};
 
 pub mod customer_app;
 pub mod file;
 pub mod group;
+pub mod metering;
 pub mod middleware;
+pub mod rate_limit;
 pub mod user;
 pub mod util;
 
@@ -46,6 +54,44 @@ pub fn rest_routes() -> Router
 	router
 }
 
+/**
+The address of the connection, set for every request by start_server.
+
+This is the client ip when no reverse proxy is in front of the server.
+ */
+#[derive(Clone, Copy)]
+pub struct PeerAddr(pub SocketAddr);
+
+/**
+Start the server like rustgram::start but with the address of the connection in the request extensions.
+
+The ip allowlists and the rate limits need the address when there is no trusted proxy.
+ */
+pub async fn start_server(router: Router, addr: SocketAddr)
+{
+	let router = Arc::new(router);
+
+	let make_svc = make_service_fn(move |conn: &AddrStream| {
+		let router = router.clone();
+		let peer = PeerAddr(conn.remote_addr());
+
+		async move {
+			Ok::<_, Infallible>(service_fn(move |mut req: Request| {
+				let router = router.clone();
+				req.extensions_mut().insert(peer);
+
+				async move { router.handle_req(req).await }
+			}))
+		}
+	});
+
+	let server = hyper::Server::bind(&addr).serve(make_svc);
+
+	if let Err(e) = server.await {
+		eprintln!("server error: {}", e);
+	}
+}
+
 pub async fn not_found_handler(_req: Request) -> rustgram_server_util::res::JRes<String>
 {
 	Err(rustgram_server_util::error::ServerCoreError::new_msg(
@@ -55,6 +101,40 @@ pub async fn not_found_handler(_req: Request) -> rustgram_server_util::res::JRes
 	))
 }
 
+/**
+The preflight for every route.
+
+The app is not known in the preflight because the app token header is not sent,
+so the origin of the request is allowed here and the app token middleware only sets the allowed origins of the app.
+ */
+pub async fn cors_handler(req: Request) -> Response
+{
+	let headers = req.headers();
+
+	let origin = headers
+		.get("origin")
+		.cloned()
+		.unwrap_or_else(|| HeaderValue::from_static("*"));
+
+	let allow_headers = headers
+		.get("access-control-request-headers")
+		.cloned()
+		.unwrap_or_else(|| HeaderValue::from_static("*"));
+
+	hyper::Response::builder()
+		.status(hyper::StatusCode::OK)
+		.header("Access-Control-Allow-Origin", origin)
+		.header(
+			"Access-Control-Allow-Methods",
+			"GET, POST, PUT, PATCH, DELETE, OPTIONS",
+		)
+		.header("Access-Control-Allow-Headers", allow_headers)
+		.header("Access-Control-Max-Age", "86400")
+		.header("Vary", "Origin")
+		.body(Body::from(""))
+		.unwrap()
+}
+
 pub async fn index_handler(_req: Request) -> Response
 {
 	hyper::Response::builder()
@@ -84,6 +164,9 @@ pub enum ApiErrorCodes
 	AppNotFound,
 	AppAction,
 	AppDisabled,
+	AppTokenExpired,
+	AppAccessDenied,
+	AppRateLimit,
 
 	GroupAccess,
 }
@@ -109,6 +192,9 @@ impl ServerErrorCodes for ApiErrorCodes
 			ApiErrorCodes::AppNotFound => 202,
 			ApiErrorCodes::AppAction => 203,
 			Self::AppDisabled => 204,
+			Self::AppTokenExpired => 205,
+			Self::AppAccessDenied => 206,
+			Self::AppRateLimit => 207,
 
 			ApiErrorCodes::GroupAccess => 310,
 		}
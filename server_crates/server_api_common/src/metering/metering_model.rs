@@ -0,0 +1,36 @@
+use rustgram_server_util::db::{exec_transaction, TransactionData};
+use rustgram_server_util::res::AppRes;
+use rustgram_server_util::set_params;
+
+use crate::metering::TransferredBytes;
+
+pub(super) async fn add_transferred_bytes(counts: &[TransferredBytes]) -> AppRes<()>
+{
+	#[cfg(feature = "mysql")]
+	//language=SQL
+	let sql_init = "INSERT IGNORE INTO sentc_app_metering (app_id, day) VALUES (?,?)";
+
+	#[cfg(feature = "sqlite")]
+	let sql_init = "INSERT OR IGNORE INTO sentc_app_metering (app_id, day) VALUES (?,?)";
+
+	//language=SQL
+	let sql = "UPDATE sentc_app_metering SET transferred_bytes = transferred_bytes + ? WHERE app_id = ? AND day = ?";
+
+	let mut data = Vec::with_capacity(counts.len() * 2);
+
+	for c in counts {
+		data.push(TransactionData {
+			sql: sql_init,
+			params: set_params!(c.app_id.clone(), c.day.to_string()),
+		});
+
+		data.push(TransactionData {
+			sql,
+			params: set_params!(c.bytes, c.app_id.clone(), c.day.to_string()),
+		});
+	}
+
+	exec_transaction(data).await?;
+
+	Ok(())
+}
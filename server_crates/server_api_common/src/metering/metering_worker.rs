@@ -0,0 +1,26 @@
+use rustgram_server_util::res::AppRes;
+
+use crate::metering::{add_transferred_bytes_of_day, metering_model, take_transferred_bytes};
+
+/**
+Save the transferred bytes which are counted in this server since the last run.
+ */
+pub async fn start() -> AppRes<()>
+{
+	let counts = take_transferred_bytes().await;
+
+	if counts.is_empty() {
+		return Ok(());
+	}
+
+	if let Err(e) = metering_model::add_transferred_bytes(&counts).await {
+		//count them again for the next run
+		for c in counts {
+			add_transferred_bytes_of_day(c.app_id, c.day, c.bytes).await;
+		}
+
+		return Err(e);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+use sentc_crypto_common::AppId;
+use tokio::sync::{Mutex, OnceCell};
+
+pub(crate) mod metering_model;
+pub mod metering_worker;
+
+pub const METERING_DAY: u128 = 24 * 60 * 60 * 1000;
+
+static TRANSFERRED_BYTES: OnceCell<Mutex<HashMap<(AppId, u128), i64>>> = OnceCell::const_new();
+
+pub struct TransferredBytes
+{
+	pub app_id: AppId,
+	pub day: u128,
+	pub bytes: i64,
+}
+
+/**
+The begin of the day in utc, the metering is saved per day.
+ */
+pub fn begin_of_day(time: u128) -> u128
+{
+	time - time % METERING_DAY
+}
+
+async fn transferred_bytes() -> &'static Mutex<HashMap<(AppId, u128), i64>>
+{
+	TRANSFERRED_BYTES
+		.get_or_init(|| async { Mutex::new(HashMap::new()) })
+		.await
+}
+
+/**
+Add the bytes of a downloaded part to the transferred bytes of the app in the day of the download time.
+
+The bytes are only counted in this server and saved by the metering worker, so a download never waits for the db.
+ */
+pub async fn add_transferred_bytes(app_id: impl Into<AppId>, time: u128, bytes: i64)
+{
+	add_transferred_bytes_of_day(app_id.into(), begin_of_day(time), bytes).await;
+}
+
+async fn add_transferred_bytes_of_day(app_id: AppId, day: u128, bytes: i64)
+{
+	*transferred_bytes()
+		.await
+		.lock()
+		.await
+		.entry((app_id, day))
+		.or_default() += bytes;
+}
+
+async fn take_transferred_bytes() -> Vec<TransferredBytes>
+{
+	transferred_bytes()
+		.await
+		.lock()
+		.await
+		.drain()
+		.map(|((app_id, day), bytes)| {
+			TransferredBytes {
+				app_id,
+				day,
+				bytes,
+			}
+		})
+		.collect()
+}
+
+/**
+Run the metering worker in an interval. Call it once when the file server starts.
+ */
+pub fn start_worker()
+{
+	let interval_sec = env::var("METERING_WORKER_INTERVAL_SEC")
+		.ok()
+		.and_then(|s| s.parse().ok())
+		.unwrap_or(60);
+
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(Duration::from_secs(interval_sec));
+
+		loop {
+			interval.tick().await;
+
+			tokio::spawn(metering_worker::start());
+		}
+	});
+}
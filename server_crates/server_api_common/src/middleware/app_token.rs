@@ -1,14 +1,16 @@
 use std::future::Future;
 use std::sync::Arc;
 
+use hyper::header::HeaderValue;
 use rustgram::service::{IntoResponse, Service};
 use rustgram::{Request, Response};
-use rustgram_server_util::cache;
 use rustgram_server_util::cache::{CacheVariant, DEFAULT_TTL, LONG_TTL};
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
 use rustgram_server_util::input_helper::{bytes_to_json, json_to_string};
 use rustgram_server_util::res::AppRes;
+use rustgram_server_util::{cache, get_time};
 
+use crate::customer_app::app_access::check_app_access;
 use crate::customer_app::app_entities::AppData;
 use crate::customer_app::app_model;
 use crate::util::{hash_token_from_string_to_string, APP_TOKEN_CACHE};
@@ -30,12 +32,24 @@ where
 		let next = self.inner.clone();
 
 		async move {
-			match token_check(&mut req).await {
-				Ok(_) => {},
+			let origin = match token_check(&mut req).await {
+				Ok(o) => o,
 				Err(e) => return e.into_response(),
+			};
+
+			let mut res = next.call(req).await;
+
+			//only allow the origin of the request instead of all origins when the app restricts the origins
+			if let Some(origin) = origin {
+				if let Ok(value) = HeaderValue::from_str(&origin) {
+					let headers = res.headers_mut();
+
+					headers.insert("Access-Control-Allow-Origin", value);
+					headers.insert("Vary", HeaderValue::from_static("Origin"));
+				}
 			}
 
-			next.call(req).await
+			res
 		}
 	}
 }
@@ -85,7 +99,10 @@ pub fn app_token_base_app_transform<S>(inner: S) -> AppTokenBaseAppMiddleware<S>
 
 //__________________________________________________________________________________________________
 
-async fn token_check(req: &mut Request) -> Result<(), ServerCoreError>
+/**
+Returns the origin of the request when the app only allows some origins
+ */
+async fn token_check(req: &mut Request) -> Result<Option<String>, ServerCoreError>
 {
 	let app_token = get_from_req(req)?;
 	//hash the app token
@@ -128,9 +145,22 @@ async fn token_check(req: &mut Request) -> Result<(), ServerCoreError>
 		},
 	};
 
+	//a named token can expire while the app data is in the cache
+	if let Some(token) = &entity.token {
+		if token.expires_at > 0 && token.expires_at < get_time()? {
+			return Err(ServerCoreError::new_msg(
+				401,
+				ApiErrorCodes::AppTokenExpired,
+				"App token is expired",
+			));
+		}
+	}
+
+	let origin = check_app_access(req, &entity)?;
+
 	req.extensions_mut().insert(entity);
 
-	Ok(())
+	Ok(origin)
 }
 
 fn get_from_req(req: &Request) -> Result<String, ServerCoreError>
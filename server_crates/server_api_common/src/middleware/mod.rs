@@ -1,4 +1,5 @@
 pub mod app_token;
 pub mod group;
 pub mod jwt;
+pub mod rate_limit;
 pub mod user_check_force;
@@ -0,0 +1,67 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use hyper::header::HeaderValue;
+use rustgram::service::{IntoResponse, Service};
+use rustgram::{Request, Response};
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+
+use crate::rate_limit::check_rate_limit;
+use crate::ApiErrorCodes;
+
+/**
+Middleware for the rate limits of the app. Must run after the app token middleware.
+
+Runs after the jwt middleware too when the app limits the requests per user.
+ */
+pub struct RateLimitMiddleware<S>
+{
+	inner: Arc<S>,
+}
+
+impl<S> Service<Request> for RateLimitMiddleware<S>
+where
+	S: Service<Request, Output = Response>,
+{
+	type Output = S::Output;
+
+	fn call(&self, req: Request) -> impl Future<Output = Self::Output> + Send + 'static
+	{
+		let next = self.inner.clone();
+
+		async move {
+			match check_rate_limit(&req).await {
+				Ok(None) => {},
+				Ok(Some(retry_after)) => {
+					let mut res = ServerCoreError::new_msg(
+						429,
+						ApiErrorCodes::AppRateLimit,
+						"Too many requests, please try again later",
+					)
+					.into_response();
+
+					let headers = res.headers_mut();
+
+					headers.insert("Retry-After", HeaderValue::from(retry_after));
+					//let the sdk in the browser read the header
+					headers.insert(
+						"Access-Control-Expose-Headers",
+						HeaderValue::from_static("Retry-After"),
+					);
+
+					return res;
+				},
+				Err(e) => return e.into_response(),
+			}
+
+			next.call(req).await
+		}
+	}
+}
+
+pub fn rate_limit_transform<S>(inner: S) -> RateLimitMiddleware<S>
+{
+	RateLimitMiddleware {
+		inner: Arc::new(inner),
+	}
+}
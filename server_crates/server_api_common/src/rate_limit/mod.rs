@@ -0,0 +1,173 @@
+use std::env;
+use std::time::Duration;
+
+use rustgram::Request;
+use rustgram_server_util::get_time;
+use rustgram_server_util::res::AppRes;
+use server_dashboard_common::app::{
+	AppRateLimit,
+	RATE_LIMIT_BY_IP,
+	RATE_LIMIT_BY_USER,
+	RATE_LIMIT_GROUP_AUTH,
+	RATE_LIMIT_GROUP_FILE,
+	RATE_LIMIT_GROUP_GROUP,
+	RATE_LIMIT_GROUP_KEY,
+};
+
+use crate::customer_app::app_access::get_client_ip;
+use crate::customer_app::app_entities::{AppData, AuthWithToken};
+use crate::customer_app::get_app_data_from_req;
+use crate::metering::begin_of_day;
+use crate::user::user_entity::UserJwtEntity;
+
+mod rate_limit_cache;
+mod rate_limit_model;
+pub mod rate_limit_worker;
+
+const RATE_LIMIT_CACHE: &str = "rate_limit_";
+
+/**
+Get the endpoint group of the request path to choose the rate limit of the app.
+ */
+pub fn get_endpoint_group(path: &str) -> i32
+{
+	let segments: Vec<&str> = path
+		.trim_start_matches("/api/v1")
+		.split('/')
+		.filter(|s| !s.is_empty())
+		.collect();
+
+	if segments.contains(&"file") {
+		return RATE_LIMIT_GROUP_FILE;
+	}
+
+	if matches!(segments.first(), Some(&"keys") | Some(&"kt")) || segments.contains(&"public_key") || segments.contains(&"verify_key") {
+		return RATE_LIMIT_GROUP_KEY;
+	}
+
+	if segments.first() == Some(&"group") {
+		return RATE_LIMIT_GROUP_GROUP;
+	}
+
+	RATE_LIMIT_GROUP_AUTH
+}
+
+/**
+Returns None when the request should be limited by the ip but the ip is not known,
+so the clients without an ip don't share one bucket.
+ */
+fn get_bucket_id(req: &Request, app_data: &AppData, limit_by: i32) -> Option<String>
+{
+	let ip = || get_client_ip(req).map(|ip| "ip_".to_string() + &ip.to_string());
+
+	match limit_by {
+		RATE_LIMIT_BY_USER => {
+			//the user is only known after the jwt check, requests without a jwt are limited by the ip
+			match req.extensions().get::<UserJwtEntity>() {
+				Some(user) => Some("user_".to_string() + &user.id),
+				None => ip(),
+			}
+		},
+		RATE_LIMIT_BY_IP => ip(),
+		_ => {
+			let id = match (&app_data.token, &app_data.auth_with_token) {
+				(Some(t), _) => "token_".to_string() + &t.token_id,
+				(None, AuthWithToken::Public) => "token_public".to_string(),
+				(None, AuthWithToken::Secret) => "token_secret".to_string(),
+			};
+
+			Some(id)
+		},
+	}
+}
+
+/**
+Take one request from the token bucket.
+
+Returns the seconds until the next request is allowed when the bucket is empty.
+ */
+async fn take_from_bucket(key: &str, limit: &AppRateLimit) -> AppRes<Option<u64>>
+{
+	let now = get_time()?;
+	let capacity = limit.capacity as f64;
+	//requests per ms
+	let refill = limit.refill_per_minute as f64 / 60_000.0;
+
+	//refill and take in one step, so parallel requests can't take the same token
+	let tokens = rate_limit_cache::take_token(key, capacity, refill, now).await?;
+
+	let retry_after = if tokens >= 1.0 {
+		None
+	} else {
+		let ms = (1.0 - tokens) / refill;
+
+		Some((ms / 1000.0).ceil().max(1.0) as u64)
+	};
+
+	Ok(retry_after)
+}
+
+/**
+Check the rate limit of the app for the endpoint group of the request.
+
+Returns the seconds for the Retry-After header when the request is throttled.
+The throttled requests are counted per day in the cache and saved for the dashboard by the rate limit worker.
+ */
+pub(crate) async fn check_rate_limit(req: &Request) -> AppRes<Option<u64>>
+{
+	let app_data = get_app_data_from_req(req)?;
+
+	let endpoint_group = get_endpoint_group(req.uri().path());
+	let limit = app_data.rate_limit_options.get(endpoint_group);
+
+	if limit.capacity <= 0 || limit.refill_per_minute <= 0 {
+		return Ok(None);
+	}
+
+	let bucket_id = match get_bucket_id(req, app_data, limit.limit_by) {
+		Some(id) => id,
+		None => return Ok(None),
+	};
+
+	let key = format!(
+		"{RATE_LIMIT_CACHE}{}_{endpoint_group}_{bucket_id}",
+		app_data.app_data.app_id,
+	);
+
+	let retry_after = take_from_bucket(&key, limit).await?;
+
+	if retry_after.is_some() {
+		rate_limit_cache::add_throttled(
+			&app_data.app_data.app_id,
+			endpoint_group,
+			begin_of_day(get_time()?),
+			1,
+		)
+		.await?;
+	}
+
+	Ok(retry_after)
+}
+
+/**
+Run the rate limit worker in an interval. Call it once when the server starts.
+
+Without the redis cache (CACHE=1) the throttled requests are only counted in this server, so no extra worker process can save them.
+ */
+pub fn start_worker()
+{
+	let interval_sec = env::var("RATE_LIMIT_WORKER_INTERVAL_SEC")
+		.ok()
+		.and_then(|s| s.parse().ok())
+		.unwrap_or(60);
+
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(Duration::from_secs(interval_sec));
+
+		loop {
+			interval.tick().await;
+
+			tokio::spawn(rate_limit_worker::start());
+		}
+	});
+}
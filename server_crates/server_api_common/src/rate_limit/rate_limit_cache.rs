@@ -0,0 +1,296 @@
+/*
+The token buckets and the throttled requests need an atomic update, the normal cache only knows get and set.
+
+The store is chosen like the cache: with CACHE=1 the redis of the cache (REDIS_URL) is used,
+the buckets are updated with a lua script and the throttled requests with HINCRBY, so every server of the app shares the same buckets.
+Otherwise everything is stored in a locked map of this server.
+ */
+
+use std::env;
+
+use redis::aio::MultiplexedConnection;
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::res::AppRes;
+use sentc_crypto_common::AppId;
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::ApiErrorCodes;
+
+/**
+When a bucket was not used for this time it starts full again
+ */
+const BUCKET_TTL_SEC: u64 = 60 * 60;
+
+/**
+The throttled requests of an app for an endpoint group in a day which are not saved yet.
+ */
+pub(super) struct ThrottledCount
+{
+	pub app_id: AppId,
+	pub endpoint_group: i32,
+	pub day: u128,
+	pub count: i64,
+}
+
+mod local
+{
+	use std::collections::HashMap;
+
+	use rustgram_server_util::res::AppRes;
+	use sentc_crypto_common::AppId;
+	use tokio::sync::Mutex;
+
+	use crate::rate_limit::rate_limit_cache::{ThrottledCount, BUCKET_TTL_SEC};
+
+	struct LocalBucket
+	{
+		tokens: f64,
+		time: u128,
+	}
+
+	#[derive(Default)]
+	pub(super) struct LocalStore
+	{
+		buckets: HashMap<String, LocalBucket>,
+		throttled: HashMap<(AppId, i32, u128), i64>,
+	}
+
+	pub(super) async fn take_token(store: &Mutex<LocalStore>, key: &str, capacity: f64, refill: f64, now: u128) -> AppRes<f64>
+	{
+		let mut store = store.lock().await;
+
+		let bucket = store.buckets.entry(key.to_string()).or_insert(LocalBucket {
+			tokens: capacity,
+			time: now,
+		});
+
+		let elapsed = now.saturating_sub(bucket.time) as f64;
+		let tokens = (bucket.tokens + elapsed * refill).min(capacity);
+
+		bucket.tokens = if tokens >= 1.0 { tokens - 1.0 } else { tokens };
+		bucket.time = now;
+
+		Ok(tokens)
+	}
+
+	pub(super) async fn add_throttled(store: &Mutex<LocalStore>, app_id: &str, endpoint_group: i32, day: u128, count: i64) -> AppRes<()>
+	{
+		let mut store = store.lock().await;
+
+		*store
+			.throttled
+			.entry((app_id.to_string(), endpoint_group, day))
+			.or_default() += count;
+
+		Ok(())
+	}
+
+	pub(super) async fn take_throttled(store: &Mutex<LocalStore>, now: u128) -> AppRes<Vec<ThrottledCount>>
+	{
+		let mut store = store.lock().await;
+
+		//remove the buckets which are not used anymore, the map is not cleared like the cache
+		let ttl = BUCKET_TTL_SEC as u128 * 1000;
+		store
+			.buckets
+			.retain(|_, bucket| now.saturating_sub(bucket.time) < ttl);
+
+		let counts = std::mem::take(&mut store.throttled)
+			.into_iter()
+			.map(|((app_id, endpoint_group, day), count)| {
+				ThrottledCount {
+					app_id,
+					endpoint_group,
+					day,
+					count,
+				}
+			})
+			.collect();
+
+		Ok(counts)
+	}
+}
+
+mod redis_store
+{
+	use redis::aio::MultiplexedConnection;
+	use redis::Script;
+	use rustgram_server_util::res::AppRes;
+
+	use crate::rate_limit::rate_limit_cache::{cache_err, ThrottledCount, BUCKET_TTL_SEC};
+
+	const THROTTLED_KEY: &str = "rate_limit_throttled";
+
+	//language=Lua
+	const TAKE_TOKEN_SCRIPT: &str = r"
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'time')
+local capacity = tonumber(ARGV[1])
+local refill = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+
+local tokens = tonumber(bucket[1]) or capacity
+local time = tonumber(bucket[2]) or now
+
+tokens = math.min(capacity, tokens + math.max(0, now - time) * refill)
+
+local left = tokens
+if tokens >= 1 then
+	left = tokens - 1
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(left), 'time', ARGV[3])
+redis.call('EXPIRE', KEYS[1], ARGV[4])
+
+return tostring(tokens)
+";
+
+	//language=Lua
+	const TAKE_THROTTLED_SCRIPT: &str = r"
+local counts = redis.call('HGETALL', KEYS[1])
+redis.call('DEL', KEYS[1])
+
+return counts
+";
+
+	pub(super) async fn connect(url: &str) -> AppRes<MultiplexedConnection>
+	{
+		redis::Client::open(url)
+			.map_err(|e| cache_err(e.to_string()))?
+			.get_multiplexed_tokio_connection()
+			.await
+			.map_err(|e| cache_err(e.to_string()))
+	}
+
+	pub(super) async fn take_token(con: &MultiplexedConnection, key: &str, capacity: f64, refill: f64, now: u128) -> AppRes<f64>
+	{
+		let mut con = con.clone();
+
+		let tokens: String = Script::new(TAKE_TOKEN_SCRIPT)
+			.key(key)
+			.arg(capacity)
+			.arg(refill)
+			.arg(now.to_string())
+			.arg(BUCKET_TTL_SEC)
+			.invoke_async(&mut con)
+			.await
+			.map_err(|e| cache_err(e.to_string()))?;
+
+		Ok(tokens.parse().unwrap_or(0.0))
+	}
+
+	pub(super) async fn add_throttled(con: &MultiplexedConnection, app_id: &str, endpoint_group: i32, day: u128, count: i64) -> AppRes<()>
+	{
+		let mut con = con.clone();
+
+		redis::cmd("HINCRBY")
+			.arg(THROTTLED_KEY)
+			.arg(format!("{app_id}:{endpoint_group}:{day}"))
+			.arg(count)
+			.query_async::<_, i64>(&mut con)
+			.await
+			.map_err(|e| cache_err(e.to_string()))?;
+
+		Ok(())
+	}
+
+	pub(super) async fn take_throttled(con: &MultiplexedConnection) -> AppRes<Vec<ThrottledCount>>
+	{
+		let mut con = con.clone();
+
+		let counts: Vec<(String, i64)> = Script::new(TAKE_THROTTLED_SCRIPT)
+			.key(THROTTLED_KEY)
+			.invoke_async(&mut con)
+			.await
+			.map_err(|e| cache_err(e.to_string()))?;
+
+		let mut out = Vec::with_capacity(counts.len());
+
+		for (field, count) in counts {
+			//the app id is the first part because it is an uuid without a colon
+			let mut parts = field.splitn(3, ':');
+
+			if let (Some(app_id), Some(Ok(endpoint_group)), Some(Ok(day))) = (
+				parts.next(),
+				parts.next().map(str::parse),
+				parts.next().map(str::parse),
+			) {
+				out.push(ThrottledCount {
+					app_id: app_id.to_string(),
+					endpoint_group,
+					day,
+					count,
+				});
+			}
+		}
+
+		Ok(out)
+	}
+}
+
+enum Store
+{
+	Local(Mutex<local::LocalStore>),
+	Redis(MultiplexedConnection),
+}
+
+static RATE_LIMIT_STORE: OnceCell<Store> = OnceCell::const_new();
+
+fn cache_err(debug: String) -> ServerCoreError
+{
+	ServerCoreError::new_msg_and_debug(
+		500,
+		ApiErrorCodes::AppRateLimit,
+		"Can't reach the rate limit cache",
+		Some(debug),
+	)
+}
+
+async fn store() -> AppRes<&'static Store>
+{
+	RATE_LIMIT_STORE
+		.get_or_try_init(|| {
+			async {
+				if env::var("CACHE").as_deref() != Ok("1") {
+					return Ok(Store::Local(Mutex::new(local::LocalStore::default())));
+				}
+
+				let url = env::var("REDIS_URL").map_err(|_| cache_err("REDIS_URL is not set".to_string()))?;
+
+				Ok(Store::Redis(redis_store::connect(&url).await?))
+			}
+		})
+		.await
+}
+
+/**
+Refill the bucket and take one token when there is at least one left.
+
+Returns the tokens in the bucket before the request.
+ */
+pub(super) async fn take_token(key: &str, capacity: f64, refill: f64, now: u128) -> AppRes<f64>
+{
+	match store().await? {
+		Store::Local(s) => local::take_token(s, key, capacity, refill, now).await,
+		Store::Redis(con) => redis_store::take_token(con, key, capacity, refill, now).await,
+	}
+}
+
+pub(super) async fn add_throttled(app_id: &str, endpoint_group: i32, day: u128, count: i64) -> AppRes<()>
+{
+	match store().await? {
+		Store::Local(s) => local::add_throttled(s, app_id, endpoint_group, day, count).await,
+		Store::Redis(con) => redis_store::add_throttled(con, app_id, endpoint_group, day, count).await,
+	}
+}
+
+/**
+Get the counted throttled requests and reset the counts.
+ */
+pub(super) async fn take_throttled(now: u128) -> AppRes<Vec<ThrottledCount>>
+{
+	match store().await? {
+		Store::Local(s) => local::take_throttled(s, now).await,
+		//the buckets are removed by redis with the expire
+		Store::Redis(con) => redis_store::take_throttled(con).await,
+	}
+}
@@ -0,0 +1,36 @@
+use rustgram_server_util::db::{exec_transaction, TransactionData};
+use rustgram_server_util::res::AppRes;
+use rustgram_server_util::set_params;
+
+use crate::rate_limit::rate_limit_cache::ThrottledCount;
+
+pub(super) async fn add_throttled(counts: &[ThrottledCount]) -> AppRes<()>
+{
+	#[cfg(feature = "mysql")]
+	//language=SQL
+	let sql_init = "INSERT IGNORE INTO sentc_app_rate_limit_throttled (app_id, endpoint_group, day) VALUES (?,?,?)";
+
+	#[cfg(feature = "sqlite")]
+	let sql_init = "INSERT OR IGNORE INTO sentc_app_rate_limit_throttled (app_id, endpoint_group, day) VALUES (?,?,?)";
+
+	//language=SQL
+	let sql = "UPDATE sentc_app_rate_limit_throttled SET count = count + ? WHERE app_id = ? AND endpoint_group = ? AND day = ?";
+
+	let mut data = Vec::with_capacity(counts.len() * 2);
+
+	for c in counts {
+		data.push(TransactionData {
+			sql: sql_init,
+			params: set_params!(c.app_id.clone(), c.endpoint_group, c.day.to_string()),
+		});
+
+		data.push(TransactionData {
+			sql,
+			params: set_params!(c.count, c.app_id.clone(), c.endpoint_group, c.day.to_string()),
+		});
+	}
+
+	exec_transaction(data).await?;
+
+	Ok(())
+}
@@ -0,0 +1,27 @@
+use rustgram_server_util::get_time;
+use rustgram_server_util::res::AppRes;
+
+use crate::rate_limit::{rate_limit_cache, rate_limit_model};
+
+/**
+Save the throttled requests which are counted in the cache since the last run.
+ */
+pub async fn start() -> AppRes<()>
+{
+	let counts = rate_limit_cache::take_throttled(get_time()?).await?;
+
+	if counts.is_empty() {
+		return Ok(());
+	}
+
+	if let Err(e) = rate_limit_model::add_throttled(&counts).await {
+		//count them again for the next run
+		for c in counts {
+			rate_limit_cache::add_throttled(&c.app_id, c.endpoint_group, c.day, c.count).await?;
+		}
+
+		return Err(e);
+	}
+
+	Ok(())
+}
@@ -12,6 +12,7 @@ use rustgram_server_util::res::AppRes;
 use rustgram_server_util::{cache, get_time_in_sec};
 use sentc_crypto_common::user::Claims;
 use sentc_crypto_common::{AppId, DeviceId, GroupId, UserId};
+use serde::Serialize;
 
 use crate::customer_app::app_entities::AppJwt;
 use crate::user::user_entity::UserJwtEntity;
@@ -57,6 +58,29 @@ pub async fn create_jwt(
 	})
 }
 
+/**
+Sign other data than the user claims with the jwt key of the app, e.g. the key transparency tree head.
+
+The client can verify it with the verify key of the app like a normal jwt.
+ */
+pub async fn sign_with_app_jwt_key<T: Serialize>(claims: &T, customer_jwt_data: &AppJwt) -> AppRes<String>
+{
+	let mut header = Header::new(Algorithm::from_str(&customer_jwt_data.jwt_alg).unwrap());
+	header.kid = Some(customer_jwt_data.jwt_key_id.clone());
+
+	let sign_key = get_sign_key(&customer_jwt_data.jwt_key_id).await?;
+	let sign_key = decode_jwt_key(sign_key)?;
+
+	encode(&header, claims, &EncodingKey::from_ec_der(&sign_key)).map_err(|e| {
+		ServerCoreError::new_msg_and_debug(
+			401,
+			ApiErrorCodes::JwtCreation,
+			"Can't sign the data",
+			Some(format!("err in jwt sign: {}", e)),
+		)
+	})
+}
+
 pub async fn auth(app_id: impl Into<AppId>, jwt: &str, check_exp: bool) -> Result<(UserJwtEntity, usize), ServerCoreError>
 {
 	let header = decode_header(jwt).map_err(|_e| ServerCoreError::new_msg(401, ApiErrorCodes::JwtWrongFormat, "Can't decode the jwt"))?;
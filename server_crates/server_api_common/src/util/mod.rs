@@ -12,6 +12,7 @@ pub const INTERNAL_GROUP_USER_PARENT_REF_CACHE: &str = "internalgroupuserparentr
 pub const APP_JWT_VERIFY_KEY_CACHE: &str = "appjwtverifykeycache_";
 pub const APP_JWT_SIGN_KEY_CACHE: &str = "appjwtsignkeycache_";
 pub const USER_IN_APP_CACHE: &str = "userinappcache_";
+pub const KT_TREE_HEAD_CACHE: &str = "kttreeheadcache_";
 
 pub fn get_group_cache_key(app_id: &str, group_id: &str) -> String
 {
@@ -48,6 +49,11 @@ pub fn get_user_in_app_key(app_id: &str, user_id: &str) -> String
 	USER_IN_APP_CACHE.to_string() + app_id + "_" + user_id
 }
 
+pub fn get_kt_tree_head_key(app_id: &str) -> String
+{
+	KT_TREE_HEAD_CACHE.to_string() + app_id
+}
+
 pub const HASH_ALG: &str = "SHA256";
 
 pub fn hash_token(token: &[u8]) -> AppRes<[u8; 32]>
@@ -0,0 +1,40 @@
+use rustgram::Request;
+use rustgram_server_util::res::{echo, JRes};
+use rustgram_server_util::url_helper::{get_name_param_from_params, get_params, get_time_from_url_param};
+use server_api_common::group::get_group_user_data_from_req;
+use server_dashboard_common::customer::CustomerAuditLogItem;
+
+use crate::audit::{audit_service, AUDIT_TARGET_APP, AUDIT_TARGET_GROUP};
+use crate::customer_app::app_controller::get_app_general_data;
+
+pub async fn get_app_logs(req: Request) -> JRes<Vec<CustomerAuditLogItem>>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	let params = get_params(&req)?;
+	let last_id = get_name_param_from_params(params, "last_id")?;
+	let last_fetched_time = get_time_from_url_param(get_name_param_from_params(params, "last_fetched_time")?)?;
+
+	let list = audit_service::get_logs(AUDIT_TARGET_APP, &app_general_data.app_id, last_fetched_time, last_id).await?;
+
+	echo(list)
+}
+
+pub async fn get_group_logs(req: Request) -> JRes<Vec<CustomerAuditLogItem>>
+{
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	let params = get_params(&req)?;
+	let last_id = get_name_param_from_params(params, "last_id")?;
+	let last_fetched_time = get_time_from_url_param(get_name_param_from_params(params, "last_fetched_time")?)?;
+
+	let list = audit_service::get_logs(
+		AUDIT_TARGET_GROUP,
+		&group_data.group_data.id,
+		last_fetched_time,
+		last_id,
+	)
+	.await?;
+
+	echo(list)
+}
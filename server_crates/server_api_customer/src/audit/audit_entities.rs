@@ -0,0 +1,17 @@
+use rustgram_server_util::DB;
+use sentc_crypto_common::CustomerId;
+
+/**
+The before and after values are saved as json
+ */
+#[derive(DB)]
+pub struct AuditLogEntity
+{
+	pub id: String,
+	pub actor_id: CustomerId,
+	pub actor_email: Option<String>,
+	pub time: u128,
+	pub action: String,
+	pub before_data: Option<String>,
+	pub after_data: Option<String>,
+}
@@ -0,0 +1,84 @@
+use rustgram_server_util::db::id_handling::create_id;
+use rustgram_server_util::db::{exec, query_string};
+use rustgram_server_util::res::AppRes;
+use rustgram_server_util::{get_time, set_params};
+use sentc_crypto_common::CustomerId;
+
+use crate::audit::audit_entities::AuditLogEntity;
+
+pub(super) async fn save(
+	target_type: i32,
+	target_id: impl Into<String>,
+	actor_id: impl Into<CustomerId>,
+	action: &str,
+	before_data: Option<String>,
+	after_data: Option<String>,
+) -> AppRes<()>
+{
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = r"
+INSERT INTO sentc_customer_audit_log 
+    (id, target_type, target_id, actor_id, time, action, before_data, after_data) 
+VALUES (?,?,?,?,?,?,?,?)";
+
+	exec(
+		sql,
+		set_params!(
+			create_id(),
+			target_type,
+			target_id.into(),
+			actor_id.into(),
+			time.to_string(),
+			action.to_string(),
+			before_data,
+			after_data
+		),
+	)
+	.await?;
+
+	Ok(())
+}
+
+/**
+The newest changes first
+ */
+pub(super) async fn get_logs(
+	target_type: i32,
+	target_id: impl Into<String>,
+	last_fetched_time: u128,
+	last_id: impl Into<String>,
+) -> AppRes<Vec<AuditLogEntity>>
+{
+	//the actor can be deleted in the meantime
+	//language=SQL
+	let sql = r"
+SELECT a.id, a.actor_id, c.email, a.time, a.action, a.before_data, a.after_data 
+FROM sentc_customer_audit_log a 
+    LEFT JOIN sentc_customer c ON c.id = a.actor_id 
+WHERE 
+    a.target_type = ? AND 
+    a.target_id = ?"
+		.to_string();
+
+	let (sql, params) = if last_fetched_time > 0 {
+		let sql = sql + " AND a.time <= ? AND (a.time < ? OR (a.time = ? AND a.id < ?)) ORDER BY a.time DESC, a.id DESC LIMIT 50";
+		(
+			sql,
+			set_params!(
+				target_type,
+				target_id.into(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_id.into()
+			),
+		)
+	} else {
+		let sql = sql + " ORDER BY a.time DESC, a.id DESC LIMIT 50";
+		(sql, set_params!(target_type, target_id.into()))
+	};
+
+	query_string(sql, params).await
+}
@@ -0,0 +1,150 @@
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::input_helper::json_to_string;
+use rustgram_server_util::res::AppRes;
+use sentc_crypto_common::CustomerId;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use server_dashboard_common::customer::CustomerAuditLogItem;
+
+use crate::audit::{audit_model, AuditAction};
+use crate::ApiErrorCodes;
+
+/**
+Values which are never saved in the audit log, only that they were changed
+ */
+const REDACTED_KEYS: [&str; 1] = ["auth_token"];
+
+pub fn to_audit_value<T: Serialize>(value: &T) -> AppRes<Value>
+{
+	serde_json::to_value(value).map_err(|_e| ServerCoreError::new_msg(400, ApiErrorCodes::AppAction, "Can't save the audit log"))
+}
+
+fn redact(value: Value) -> Value
+{
+	match value {
+		Value::Object(map) => {
+			map.into_iter()
+				.map(|(k, v)| {
+					if REDACTED_KEYS.contains(&k.as_str()) && !v.is_null() {
+						(k, Value::String("***".to_string()))
+					} else {
+						(k, v)
+					}
+				})
+				.collect::<Map<String, Value>>()
+				.into()
+		},
+		v => v,
+	}
+}
+
+/**
+Only keep the fields which were changed
+ */
+fn diff(before: Value, after: Value) -> (Option<Value>, Option<Value>)
+{
+	match (before, after) {
+		(Value::Object(mut before), Value::Object(mut after)) => {
+			let keys: Vec<String> = before.keys().chain(after.keys()).cloned().collect();
+
+			let mut changed_before = Map::new();
+			let mut changed_after = Map::new();
+
+			for key in keys {
+				let b = before.remove(&key);
+				let a = after.remove(&key);
+
+				if b.is_none() && a.is_none() {
+					//key was in both maps and is already checked
+					continue;
+				}
+
+				if b != a {
+					changed_before.insert(key.clone(), b.unwrap_or(Value::Null));
+					changed_after.insert(key, a.unwrap_or(Value::Null));
+				}
+			}
+
+			if changed_before.is_empty() {
+				(None, None)
+			} else {
+				(Some(changed_before.into()), Some(changed_after.into()))
+			}
+		},
+		(before, after) => {
+			if before == after {
+				(None, None)
+			} else {
+				(Some(before), Some(after))
+			}
+		},
+	}
+}
+
+/**
+Save an action with the values as they are, e.g. the id of a deleted key
+ */
+pub async fn save(
+	target_type: i32,
+	target_id: impl Into<String>,
+	actor_id: impl Into<CustomerId>,
+	action: AuditAction,
+	before: Option<Value>,
+	after: Option<Value>,
+) -> AppRes<()>
+{
+	let before = match before {
+		Some(v) => Some(json_to_string(&redact(v))?),
+		None => None,
+	};
+
+	let after = match after {
+		Some(v) => Some(json_to_string(&redact(v))?),
+		None => None,
+	};
+
+	audit_model::save(target_type, target_id, actor_id, action.as_str(), before, after).await
+}
+
+/**
+Save a change of options, only the changed fields are saved
+ */
+pub async fn save_change<B: Serialize, A: Serialize>(
+	target_type: i32,
+	target_id: impl Into<String>,
+	actor_id: impl Into<CustomerId>,
+	action: AuditAction,
+	before: &B,
+	after: &A,
+) -> AppRes<()>
+{
+	let (before, after) = diff(to_audit_value(before)?, to_audit_value(after)?);
+
+	save(target_type, target_id, actor_id, action, before, after).await
+}
+
+pub async fn get_logs(
+	target_type: i32,
+	target_id: impl Into<String>,
+	last_fetched_time: u128,
+	last_id: impl Into<String>,
+) -> AppRes<Vec<CustomerAuditLogItem>>
+{
+	let list = audit_model::get_logs(target_type, target_id, last_fetched_time, last_id).await?;
+
+	let mut out = Vec::with_capacity(list.len());
+
+	for item in list {
+		out.push(CustomerAuditLogItem {
+			id: item.id,
+			actor_id: item.actor_id,
+			actor_email: item.actor_email,
+			time: item.time,
+			action: item.action,
+			before: item.before_data.and_then(|b| serde_json::from_str(&b).ok()),
+			after: item.after_data.and_then(|a| serde_json::from_str(&a).ok()),
+		});
+	}
+
+	Ok(out)
+}
@@ -0,0 +1,64 @@
+pub mod audit_controller;
+pub mod audit_entities;
+pub(crate) mod audit_model;
+pub mod audit_service;
+
+pub const AUDIT_TARGET_APP: i32 = 0;
+pub const AUDIT_TARGET_GROUP: i32 = 1;
+
+pub enum AuditAction
+{
+	AppCreate,
+	AppUpdate,
+	AppDelete,
+	AppTransfer,
+	AppReset,
+	AppOptionsUpdate,
+	AppFileOptionsUpdate,
+	AppGroupOptionsUpdate,
+	AppKeyEndpointOptionsUpdate,
+	AppRateLimitOptionsUpdate,
+	AppAccessOptionsUpdate,
+	AppJwtRotationOptionsUpdate,
+	AppConfigImport,
+	AppTokenRenew,
+	AppTokenCreate,
+	AppTokenRevoke,
+	AppJwtKeysAdd,
+	AppJwtKeysDelete,
+
+	GroupMemberInvite,
+	GroupMemberRankUpdate,
+	GroupMemberKick,
+}
+
+impl AuditAction
+{
+	pub fn as_str(&self) -> &'static str
+	{
+		match self {
+			AuditAction::AppCreate => "app_create",
+			AuditAction::AppUpdate => "app_update",
+			AuditAction::AppDelete => "app_delete",
+			AuditAction::AppTransfer => "app_transfer",
+			AuditAction::AppReset => "app_reset",
+			AuditAction::AppOptionsUpdate => "app_options_update",
+			AuditAction::AppFileOptionsUpdate => "app_file_options_update",
+			AuditAction::AppGroupOptionsUpdate => "app_group_options_update",
+			AuditAction::AppKeyEndpointOptionsUpdate => "app_key_endpoint_options_update",
+			AuditAction::AppRateLimitOptionsUpdate => "app_rate_limit_options_update",
+			AuditAction::AppAccessOptionsUpdate => "app_access_options_update",
+			AuditAction::AppJwtRotationOptionsUpdate => "app_jwt_rotation_options_update",
+			AuditAction::AppConfigImport => "app_config_import",
+			AuditAction::AppTokenRenew => "app_token_renew",
+			AuditAction::AppTokenCreate => "app_token_create",
+			AuditAction::AppTokenRevoke => "app_token_revoke",
+			AuditAction::AppJwtKeysAdd => "app_jwt_keys_add",
+			AuditAction::AppJwtKeysDelete => "app_jwt_keys_delete",
+
+			AuditAction::GroupMemberInvite => "group_member_invite",
+			AuditAction::GroupMemberRankUpdate => "group_member_rank_update",
+			AuditAction::GroupMemberKick => "group_member_kick",
+		}
+	}
+}
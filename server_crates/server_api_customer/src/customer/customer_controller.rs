@@ -22,6 +22,7 @@ use sentc_crypto_common::user::{
 	UserUpdateServerInput,
 	VerifyLoginInput,
 };
+use serde_json::{json, Value};
 use server_api::sentc_group_user_service::NewUserType;
 use server_api::sentc_user_entities::{DoneLoginServerOutput, DoneLoginServerReturn};
 use server_api::{sentc_auth_service, sentc_group_service, sentc_group_user_service, sentc_user_light_service, sentc_user_service};
@@ -44,6 +45,7 @@ use server_dashboard_common::customer::{
 	CustomerUpdateInput,
 };
 
+use crate::audit::{audit_service, AuditAction, AUDIT_TARGET_GROUP};
 use crate::customer::customer_entities::CustomerGroupMemberFetch;
 use crate::customer::{customer_model, customer_util};
 #[cfg(feature = "send_mail")]
@@ -102,7 +104,7 @@ pub async fn register(mut req: Request) -> JRes<CustomerRegisterOutput>
 		));
 	}
 
-	let out = sentc_user_light_service::register_light(&app_data.app_data.app_id, register_data.register_data, false).await?;
+	let out = sentc_user_light_service::register_light(app_data, register_data.register_data, false).await?;
 	let customer_id = out.user_id;
 
 	//send the normal token via email
@@ -117,7 +119,14 @@ pub async fn register(mut req: Request) -> JRes<CustomerRegisterOutput>
 	.await?;
 
 	#[cfg(feature = "send_mail")]
-	send_mail::send_mail(email, validate_token, &customer_id, EmailTopic::Register).await;
+	send_mail::send_mail(
+		email,
+		validate_token,
+		&customer_id,
+		EmailTopic::Register,
+		send_mail::get_locale(&req),
+	)
+	.await;
 
 	let out = CustomerRegisterOutput {
 		customer_id,
@@ -161,7 +170,14 @@ pub async fn resend_email(req: Request) -> JRes<ServerSuccessOutput>
 	let _token = customer_model::get_email_token(customer_id).await?;
 
 	#[cfg(feature = "send_mail")]
-	send_mail::send_mail(_token.email, _token.email_token, customer_id, EmailTopic::Register).await;
+	send_mail::send_mail(
+		_token.email,
+		_token.email_token,
+		customer_id,
+		EmailTopic::Register,
+		send_mail::get_locale(&req),
+	)
+	.await;
 
 	echo_success()
 }
@@ -251,7 +267,7 @@ pub async fn delete(req: Request) -> JRes<ServerSuccessOutput>
 
 	let app_data = get_app_data_from_req(&req)?;
 
-	sentc_user_service::delete(user, &app_data.app_data.app_id).await?;
+	sentc_user_service::delete(user, app_data).await?;
 
 	//files must be deleted before customer delete. we need the apps for the customer
 	server_api_common::file::delete_file_for_customer(user.id.as_str()).await?;
@@ -300,7 +316,14 @@ pub async fn update(mut req: Request) -> JRes<ServerSuccessOutput>
 	customer_model::update(update_data, &user.id, validate_token.to_string()).await?;
 
 	#[cfg(feature = "send_mail")]
-	send_mail::send_mail(email, validate_token, &user.id, EmailTopic::EmailUpdate).await;
+	send_mail::send_mail(
+		email,
+		validate_token,
+		&user.id,
+		EmailTopic::EmailUpdate,
+		send_mail::get_locale(&req),
+	)
+	.await;
 
 	echo_success()
 }
@@ -368,7 +391,14 @@ pub async fn prepare_reset_password(mut req: Request) -> JRes<ServerSuccessOutpu
 	customer_model::reset_password_token_save(&email_data.id, &validate_token).await?;
 
 	#[cfg(feature = "send_mail")]
-	send_mail::send_mail(email, validate_token, email_data.id, EmailTopic::PwReset).await;
+	send_mail::send_mail(
+		email,
+		validate_token,
+		email_data.id,
+		EmailTopic::PwReset,
+		send_mail::get_locale(&req),
+	)
+	.await;
 
 	echo_success()
 }
@@ -559,8 +589,12 @@ pub async fn invite_customer_group_member(mut req: Request) -> JRes<ServerSucces
 
 	let input: GroupNewMemberLightInput = bytes_to_json(&body)?;
 
+	let after = json!({"user_id": user_to_invite, "rank": input.rank});
+
 	sentc_group_user_service::invite_auto_light(group_data, input, user_to_invite, NewUserType::Normal).await?;
 
+	audit_group_action(&req, AuditAction::GroupMemberInvite, None, Some(after)).await?;
+
 	echo_success()
 }
 
@@ -589,8 +623,18 @@ pub async fn delete_group_user(req: Request) -> JRes<ServerSuccessOutput>
 
 	let user_to_kick = get_name_param_from_req(&req, "user_id")?;
 
+	let before = get_member_rank(&group_data.group_data.id, user_to_kick).await?;
+
 	sentc_group_user_service::kick_user_from_group(group_data, user_to_kick, false).await?;
 
+	audit_group_action(
+		&req,
+		AuditAction::GroupMemberKick,
+		Some(json!({"user_id": user_to_kick, "rank": before})),
+		None,
+	)
+	.await?;
+
 	echo_success()
 }
 
@@ -602,8 +646,16 @@ pub async fn update_member(mut req: Request) -> JRes<ServerSuccessOutput>
 
 	let input: GroupChangeRankServerInput = bytes_to_json(&body)?;
 
+	let before = json!({
+		"user_id": &input.changed_user_id,
+		"rank": get_member_rank(&group_data.group_data.id, &input.changed_user_id).await?,
+	});
+	let after = json!({"user_id": &input.changed_user_id, "rank": input.new_rank});
+
 	sentc_group_user_service::change_rank(group_data, input.changed_user_id, input.new_rank).await?;
 
+	audit_group_action(&req, AuditAction::GroupMemberRankUpdate, Some(before), Some(after)).await?;
+
 	echo_success()
 }
 
@@ -655,3 +707,31 @@ pub async fn get_group_member_list(req: Request) -> JRes<CustomerGroupMemberFetc
 		customer_data: customer_list,
 	})
 }
+
+//__________________________________________________________________________________________________
+
+async fn get_member_rank(group_id: &str, user_id: &str) -> AppRes<Option<i32>>
+{
+	let member = server_api_common::group::get_internal_group_user_data(group_id, user_id).await?;
+
+	Ok(member.map(|m| m.rank))
+}
+
+/**
+Save the member change in the audit log of the customer group
+ */
+async fn audit_group_action(req: &Request, action: AuditAction, before: Option<Value>, after: Option<Value>) -> AppRes<()>
+{
+	let customer = get_jwt_data_from_param(req)?;
+	let group_data = get_group_user_data_from_req(req)?;
+
+	audit_service::save(
+		AUDIT_TARGET_GROUP,
+		&group_data.group_data.id,
+		&customer.id,
+		action,
+		before,
+		after,
+	)
+	.await
+}
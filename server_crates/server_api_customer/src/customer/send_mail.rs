@@ -1,25 +1,55 @@
 use std::env;
 
+use rustgram::Request;
 use rustgram_server_util::res::AppRes;
 
 use crate::customer::customer_entities::RegisterEmailStatus;
 use crate::customer::{customer_model, EmailTopic};
-use crate::email::send_mail::send_mail_registration;
+use crate::email::send_mail::{send_email, EmailMessage};
+use crate::email::template;
 
 /**
 Send the validation email.
  */
-pub(super) async fn send_mail(email: impl Into<String>, token: String, customer_id: impl Into<sentc_crypto_common::CustomerId>, topic: EmailTopic)
+pub(super) async fn send_mail(
+	email: impl Into<String>,
+	token: String,
+	customer_id: impl Into<sentc_crypto_common::CustomerId>,
+	topic: EmailTopic,
+	locale: Option<String>,
+)
 {
 	//don't wait for the response
-	tokio::task::spawn(process_send_mail(email.into(), customer_id.into(), token, topic));
+	tokio::task::spawn(process_send_mail(
+		email.into(),
+		customer_id.into(),
+		token,
+		topic,
+		locale,
+	));
 }
 
-async fn process_send_mail(email: String, customer_id: sentc_crypto_common::CustomerId, token: String, topic: EmailTopic) -> AppRes<()>
+/**
+The locale of the templates from the accept language header of the dashboard
+ */
+pub(super) fn get_locale(req: &Request) -> Option<String>
+{
+	let header = req.headers().get("accept-language")?.to_str().ok()?;
+
+	template::parse_locale(header)
+}
+
+async fn process_send_mail(
+	email: String,
+	customer_id: sentc_crypto_common::CustomerId,
+	token: String,
+	topic: EmailTopic,
+	locale: Option<String>,
+) -> AppRes<()>
 {
-	let (text_body, html, title) = get_text(token, topic);
+	let message = get_message(email, token, topic, locale.as_deref());
 
-	let status = match send_mail_registration(email.as_str(), title, text_body, html).await {
+	let status = match send_email(&message).await {
 		Ok(_) => RegisterEmailStatus::Success,
 		Err(e) => {
 			match e.error_code {
@@ -33,72 +63,28 @@ async fn process_send_mail(email: String, customer_id: sentc_crypto_common::Cust
 	customer_model::sent_mail(customer_id, status).await
 }
 
-fn get_text(token: String, topic: EmailTopic) -> (String, String, &'static str)
+fn get_message(email: String, token: String, topic: EmailTopic, locale: Option<&str>) -> EmailMessage
 {
 	let url = env::var("PUBLIC_URL").unwrap();
 
-	let (text, title, url) = match topic {
-		EmailTopic::Register => {
-			(
-				"Thanks for registration at sentc. Please verify your Email.",
-				"Sentc Email validation for registration",
-				url + "/dashboard/customer/validation/register",
-			)
-		},
-		EmailTopic::PwReset => {
-			(
-				"Your forgot your password at sentc? Please verify your Email before resetting the password.",
-				"Sentc Password reset",
-				url + "/dashboard/customer/validation/pw_reset",
-			)
-		},
-		EmailTopic::EmailUpdate => {
-			(
-				"You updated your Email address for sentc. Please verify your new Email address.",
-				"Sentc Email update",
-				url + "/dashboard/customer/validation/register",
-			)
-		},
+	let (name, url) = match topic {
+		EmailTopic::Register => ("register", url + "/dashboard/customer/validation/register"),
+		EmailTopic::PwReset => ("pw_reset", url + "/dashboard/customer/validation/pw_reset"),
+		EmailTopic::EmailUpdate => ("email_update", url + "/dashboard/customer/validation/register"),
 	};
 
-	let text_body = format!(
-		r"{}
-Go to {}/?token={} or enter your token: {}
-	",
-		text, url, token, token
-	);
+	let link = format!("{}/?token={}", url, token);
 
-	//language=HTML
-	let html = format!(
-		r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-	<meta charset="UTF-8">
-	<meta name="viewport" content="width=device-width, initial-scale=1.0">
-	<title>Sentc {}</title>
-</head>
-<body>
-	<div style="display: flex; flex-direction: column; align-items: center">
-		<div style="text-align: left;">
-			<h1>{}</h1>
-	
-			<p>
-				{}
-			</p>
-	
-			<p>
-				<a href="{}/?token={}">Click here</a> 
-				<br>
-				<br>
-				Or enter your token: <br>
-				{}
-			</p>
-		</div>
-	</div>
-</body>
-</html>"#,
-		title, title, text, url, token, token
+	let rendered = template::render(
+		name,
+		locale,
+		&[("token", token.as_str()), ("link", link.as_str()), ("url", url.as_str())],
 	);
 
-	(text_body, html, title)
+	EmailMessage {
+		to: email,
+		subject: rendered.subject,
+		body_txt: rendered.body_txt,
+		body_html: rendered.body_html,
+	}
 }
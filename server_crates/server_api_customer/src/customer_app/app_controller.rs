@@ -1,29 +1,49 @@
 use std::future::Future;
 
 use rustgram::Request;
-use rustgram_server_util::cache;
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
 use rustgram_server_util::input_helper::{bytes_to_json, get_raw_body};
 use rustgram_server_util::res::{echo, echo_success, AppRes, JRes, ServerSuccessOutput};
-use rustgram_server_util::url_helper::{get_name_param_from_params, get_name_param_from_req, get_params};
+use rustgram_server_util::url_helper::{get_name_param_from_params, get_name_param_from_req, get_params, get_time_from_url_param};
+use sentc_crypto_common::CustomerId;
+use serde::Serialize;
+use serde_json::{json, Value};
+use server_api_common::file::FILE_USAGE_TYPE_APP;
+use server_api_common::group::get_group_user_data_from_req;
 use server_api_common::user::get_jwt_data_from_param;
 use server_api_common::user::jwt::create_jwt_keys;
-use server_api_common::util::{get_app_jwt_sign_key, get_app_jwt_verify_key, hash_token_to_string, APP_TOKEN_CACHE, HASH_ALG};
+use server_api_common::util::{get_app_jwt_sign_key, get_app_jwt_verify_key, get_kt_tree_head_key, hash_token_to_string, HASH_ALG};
 use server_dashboard_common::app::{
+	AppAccessOptions,
+	AppConfig,
+	AppConfigCreateInput,
 	AppDetails,
 	AppFileOptionsInput,
 	AppJwtData,
 	AppJwtRegisterOutput,
+	AppJwtRotationOptions,
+	AppKeyEndpointOptionsInput,
 	AppOptions,
+	AppRateLimitOptions,
+	AppRateLimitThrottled,
 	AppRegisterInput,
 	AppRegisterOutput,
+	AppStats,
+	AppToken,
+	AppTokenCreateInput,
+	AppTokenCreateOutput,
 	AppTokenRenewOutput,
 	AppUpdateInput,
+	FileExternalDeleteFailure,
+	FileUsage,
+	APP_STATS_PERIOD_DAY,
+	APP_STATS_PERIOD_MONTH,
 };
 
+use crate::audit::{audit_service, AuditAction, AUDIT_TARGET_APP, AUDIT_TARGET_GROUP};
 use crate::customer::{customer_model, customer_util};
-use crate::customer_app::app_entities::AppCustomerAccess;
-use crate::customer_app::app_service::{check_file_options, check_group_options};
+use crate::customer_app::app_entities::{AppCustomerAccess, CUSTOMER_OWNER_TYPE_GROUP, CUSTOMER_OWNER_TYPE_USER};
+use crate::customer_app::app_service::{check_file_options, check_group_options, check_key_endpoint_options};
 use crate::customer_app::{app_model, app_service, generate_tokens};
 use crate::ApiErrorCodes;
 
@@ -40,11 +60,15 @@ pub async fn get_app_details(req: Request) -> JRes<AppDetails>
 {
 	let app_general_data = get_app_general_data(&req)?;
 
-	let (details, options, file_options, group_options) = tokio::try_join!(
+	let (details, options, file_options, group_options, key_endpoint_options, access_options, rate_limit_options, jwt_rotation_options) = tokio::try_join!(
 		app_model::get_app_view(&app_general_data.app_id, app_general_data.owner_type),
 		server_api_common::customer_app::get_app_options(&app_general_data.app_id),
 		app_model::get_app_file_options(&app_general_data.app_id),
-		app_model::get_app_group_options(&app_general_data.app_id)
+		app_model::get_app_group_options(&app_general_data.app_id),
+		app_model::get_app_key_endpoint_options(&app_general_data.app_id),
+		app_model::get_app_access_options(&app_general_data.app_id),
+		server_api_common::customer_app::get_rate_limit_options(&app_general_data.app_id),
+		app_model::get_app_jwt_rotation_options(&app_general_data.app_id)
 	)?;
 
 	echo(AppDetails {
@@ -52,9 +76,94 @@ pub async fn get_app_details(req: Request) -> JRes<AppDetails>
 		file_options,
 		details,
 		group_options,
+		key_endpoint_options,
+		access_options,
+		rate_limit_options,
+		jwt_rotation_options,
 	})
 }
 
+/**
+The used storage and the number of files of the whole app
+ */
+pub async fn get_file_usage(req: Request) -> JRes<FileUsage>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	let usage = server_api_common::file::get_file_usage(
+		&app_general_data.app_id,
+		FILE_USAGE_TYPE_APP,
+		&app_general_data.app_id,
+	)
+	.await?;
+
+	echo(usage)
+}
+
+/**
+The parts of the external storage which couldn't be deleted yet, the oldest first.
+ */
+pub async fn get_file_external_delete_failures(req: Request) -> JRes<Vec<FileExternalDeleteFailure>>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	let params = get_params(&req)?;
+	let last_part_id = get_name_param_from_params(params, "last_part_id")?;
+	let last_fetched_time = get_name_param_from_params(params, "last_fetched_time")?;
+	let last_fetched_time = get_time_from_url_param(last_fetched_time)?;
+
+	let list = server_api_common::file::get_external_delete_failures(&app_general_data.app_id, last_fetched_time, last_part_id).await?;
+
+	echo(list)
+}
+
+/**
+The daily or monthly usage of the app between from and to, computed by the app stats worker.
+ */
+pub async fn get_stats(req: Request) -> JRes<Vec<AppStats>>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	let params = get_params(&req)?;
+
+	let period = get_name_param_from_params(params, "period")?;
+
+	let period_type = if period == "day" {
+		APP_STATS_PERIOD_DAY
+	} else if period == "month" {
+		APP_STATS_PERIOD_MONTH
+	} else {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong stats period, please choose day or month",
+		));
+	};
+
+	let from = get_time_from_url_param(get_name_param_from_params(params, "from")?)?;
+	let to = get_time_from_url_param(get_name_param_from_params(params, "to")?)?;
+
+	let stats = app_model::get_stats(&app_general_data.app_id, period_type, from, to).await?;
+
+	echo(stats)
+}
+
+/**
+The requests which were rejected by the rate limits of the app, per day and endpoint group
+ */
+pub async fn get_rate_limit_throttled(req: Request) -> JRes<Vec<AppRateLimitThrottled>>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	let params = get_params(&req)?;
+	let from = get_time_from_url_param(get_name_param_from_params(params, "from")?)?;
+	let to = get_time_from_url_param(get_name_param_from_params(params, "to")?)?;
+
+	let list = app_model::get_rate_limit_throttled(&app_general_data.app_id, from, to).await?;
+
+	echo(list)
+}
+
 pub fn create_app_user(req: Request) -> impl Future<Output = JRes<AppRegisterOutput>>
 {
 	create_app(req, false)
@@ -65,19 +174,84 @@ pub fn create_app_group(req: Request) -> impl Future<Output = JRes<AppRegisterOu
 	create_app(req, true)
 }
 
+pub fn create_app_user_from_config(req: Request) -> impl Future<Output = JRes<AppRegisterOutput>>
+{
+	create_app_from_config(req, false)
+}
+
+pub fn create_app_group_from_config(req: Request) -> impl Future<Output = JRes<AppRegisterOutput>>
+{
+	create_app_from_config(req, true)
+}
+
 async fn create_app(mut req: Request, group: bool) -> JRes<AppRegisterOutput>
 {
 	let body = get_raw_body(&mut req).await?;
 	let input: AppRegisterInput = bytes_to_json(&body)?;
 
-	let customer = get_jwt_data_from_param(&req)?;
+	let (customer_id, group_id) = get_new_app_owner(&req, group).await?;
+
+	let after = json!({
+		"identifier": &input.identifier,
+		"group_id": group_id,
+	});
+
+	let customer_app_data = app_service::create_app(input, &customer_id, group_id).await?;
+
+	audit_service::save(
+		AUDIT_TARGET_APP,
+		&customer_app_data.app_id,
+		&customer_id,
+		AuditAction::AppCreate,
+		None,
+		Some(after),
+	)
+	.await?;
+
+	echo(customer_app_data)
+}
+
+async fn create_app_from_config(mut req: Request, group: bool) -> JRes<AppRegisterOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+	let input: AppConfigCreateInput = bytes_to_json(&body)?;
+
+	let (customer_id, group_id) = get_new_app_owner(&req, group).await?;
+
+	let after = json!({
+		"identifier": &input.identifier,
+		"group_id": group_id,
+		"config_version": input.config.version,
+	});
+
+	let customer_app_data = app_service::create_app_from_config(input, &customer_id, group_id).await?;
+
+	audit_service::save(
+		AUDIT_TARGET_APP,
+		&customer_app_data.app_id,
+		&customer_id,
+		AuditAction::AppCreate,
+		None,
+		Some(after),
+	)
+	.await?;
+
+	echo(customer_app_data)
+}
+
+/**
+Check if the customer is allowed to create an app, either for the own account or in the customer group of the url
+ */
+async fn get_new_app_owner(req: &Request, group: bool) -> AppRes<(CustomerId, Option<&str>)>
+{
+	let customer = get_jwt_data_from_param(req)?;
 	let customer_id = &customer.id;
 
 	//only create apps when validate the e-mail
 	customer_util::check_customer_valid(customer_id).await?;
 
 	let group_id = if group {
-		let group_id = get_name_param_from_req(&req, "group_id")?;
+		let group_id = get_name_param_from_req(req, "group_id")?;
 
 		let group_data = customer_model::get_customer_group(group_id, customer_id)
 			.await?
@@ -96,9 +270,50 @@ async fn create_app(mut req: Request, group: bool) -> JRes<AppRegisterOutput>
 		None
 	};
 
-	let customer_app_data = app_service::create_app(input, customer_id, group_id).await?;
+	Ok((customer_id.to_string(), group_id))
+}
 
-	echo(customer_app_data)
+/**
+Export the options of the app as config document, without tokens, keys and auth tokens
+ */
+pub async fn export_config(req: Request) -> JRes<AppConfig>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	let config = app_service::export_config(&app_general_data.app_id).await?;
+
+	echo(config)
+}
+
+/**
+Overwrite all options of the app with the options of the config document
+ */
+pub async fn import_config(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app_general_data = get_app_general_data(&req)?;
+
+	if app_general_data.rank > 2 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"No rights to do this action",
+		));
+	}
+
+	let input: AppConfig = bytes_to_json(&body)?;
+
+	let before = app_service::export_config(&app_general_data.app_id).await?;
+
+	app_service::import_config(app_general_data, input).await?;
+
+	//use the exported config for the log, so no auth token is saved
+	let after = app_service::export_config(&app_general_data.app_id).await?;
+
+	audit_change(&req, app_general_data, AuditAction::AppConfigImport, &before, &after).await?;
+
+	echo_success()
 }
 
 pub async fn renew_tokens(req: Request) -> JRes<AppTokenRenewOutput>
@@ -130,10 +345,9 @@ pub async fn renew_tokens(req: Request) -> JRes<AppTokenRenewOutput>
 	.await?;
 
 	//delete the cache
-	let old_hashed_secret = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_secret_token;
-	let old_hashed_public_token = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_public_token;
+	app_service::delete_app_cache(app_general_data, vec![]).await?;
 
-	cache::delete_multiple(&[&old_hashed_secret, &old_hashed_public_token]).await?;
+	audit_action(&req, app_general_data, AuditAction::AppTokenRenew, None, None).await?;
 
 	let out = AppTokenRenewOutput {
 		secret_token: base64::encode(secret_token),
@@ -143,6 +357,80 @@ pub async fn renew_tokens(req: Request) -> JRes<AppTokenRenewOutput>
 	echo(out)
 }
 
+pub async fn create_token(mut req: Request) -> JRes<AppTokenCreateOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app_general_data = get_app_general_data(&req)?;
+
+	if app_general_data.rank > 2 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"No rights to do this action",
+		));
+	}
+
+	let input: AppTokenCreateInput = bytes_to_json(&body)?;
+
+	let mut after = json!({
+		"name": &input.name,
+		"token_type": input.token_type,
+		"expires_at": input.expires_at,
+		"scope": &input.scope,
+		"access_options": &input.access_options,
+	});
+
+	let out = app_service::create_token(&app_general_data.app_id, input).await?;
+
+	after["token_id"] = Value::from(out.token_id.as_str());
+
+	audit_action(&req, app_general_data, AuditAction::AppTokenCreate, None, Some(after)).await?;
+
+	echo(out)
+}
+
+pub async fn get_tokens(req: Request) -> JRes<Vec<AppToken>>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	let params = get_params(&req)?;
+	let last_id = get_name_param_from_params(params, "last_id")?;
+	let last_fetched_time = get_time_from_url_param(get_name_param_from_params(params, "last_fetched_time")?)?;
+
+	let list = app_service::get_tokens(&app_general_data.app_id, last_fetched_time, last_id).await?;
+
+	echo(list)
+}
+
+pub async fn revoke_token(req: Request) -> JRes<ServerSuccessOutput>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	if app_general_data.rank > 2 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"No rights to do this action",
+		));
+	}
+
+	let token_id = get_name_param_from_req(&req, "token_id")?;
+
+	app_service::revoke_token(&app_general_data.app_id, token_id).await?;
+
+	audit_action(
+		&req,
+		app_general_data,
+		AuditAction::AppTokenRevoke,
+		Some(json!({"token_id": token_id})),
+		None,
+	)
+	.await?;
+
+	echo_success()
+}
+
 pub async fn add_jwt_keys(req: Request) -> JRes<AppJwtRegisterOutput>
 {
 	let app_general_data = get_app_general_data(&req)?;
@@ -168,11 +456,21 @@ pub async fn add_jwt_keys(req: Request) -> JRes<AppJwtRegisterOutput>
 	//delete the cache of the app because it can happened that this id was used before
 	let verify_key_cache_key = get_app_jwt_verify_key(&jwt_id);
 	let sign_key_cache_key = get_app_jwt_sign_key(&jwt_id);
-	//delete the app data cache
-	let old_hashed_secret = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_secret_token;
-	let old_hashed_public_token = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_public_token;
+	//delete the app data cache and the key transparency tree head which is signed with the first jwt key
+	app_service::delete_app_cache(
+		app_general_data,
+		vec![verify_key_cache_key, sign_key_cache_key, get_kt_tree_head_key(&app_general_data.app_id)],
+	)
+	.await?;
 
-	cache::delete_multiple(&[&verify_key_cache_key, &sign_key_cache_key, &old_hashed_secret, &old_hashed_public_token]).await?;
+	audit_action(
+		&req,
+		app_general_data,
+		AuditAction::AppJwtKeysAdd,
+		None,
+		Some(json!({"jwt_id": &jwt_id, "jwt_alg": alg})),
+	)
+	.await?;
 
 	echo(AppJwtRegisterOutput {
 		app_id: app_general_data.app_id.to_string(),
@@ -200,14 +498,24 @@ pub async fn delete_jwt_keys(req: Request) -> JRes<ServerSuccessOutput>
 
 	app_model::delete_jwt_keys(&app_general_data.app_id, jwt_id).await?;
 
-	//delete the app data cache
-
-	let old_hashed_secret = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_secret_token;
-	let old_hashed_public_token = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_public_token;
+	//delete the app data cache and the key transparency tree head which is signed with the first jwt key
 	let verify_key_cache_key = get_app_jwt_verify_key(jwt_id);
 	let sign_key_cache_key = get_app_jwt_sign_key(jwt_id);
 
-	cache::delete_multiple(&[&old_hashed_secret, &old_hashed_public_token, &verify_key_cache_key, &sign_key_cache_key]).await?;
+	app_service::delete_app_cache(
+		app_general_data,
+		vec![verify_key_cache_key, sign_key_cache_key, get_kt_tree_head_key(&app_general_data.app_id)],
+	)
+	.await?;
+
+	audit_action(
+		&req,
+		app_general_data,
+		AuditAction::AppJwtKeysDelete,
+		Some(json!({"jwt_id": jwt_id})),
+		None,
+	)
+	.await?;
 
 	echo_success()
 }
@@ -224,13 +532,138 @@ pub async fn delete(req: Request) -> JRes<ServerSuccessOutput>
 		));
 	}
 
+	let app = app_model::get_app_view(&app_general_data.app_id, app_general_data.owner_type).await?;
+
 	app_model::delete(&app_general_data.app_id).await?;
 
 	server_api_common::file::delete_file_for_app(&app_general_data.app_id).await?;
 
+	//the log of the app is deleted with the app, only the log of the customer group is kept
+	if app_general_data.owner_type == CUSTOMER_OWNER_TYPE_GROUP {
+		let customer = get_jwt_data_from_param(&req)?;
+
+		audit_service::save(
+			AUDIT_TARGET_GROUP,
+			&app_general_data.owner_id,
+			&customer.id,
+			AuditAction::AppDelete,
+			Some(json!({"app_id": app.id, "identifier": app.identifier})),
+			None,
+		)
+		.await?;
+	}
+
+	echo_success()
+}
+
+/**
+Move the app into the customer group of the url.
+
+The customer must be an admin of the app and of the new group.
+ */
+pub async fn transfer_to_group(req: Request) -> JRes<ServerSuccessOutput>
+{
+	let app_general_data = get_app_general_data(&req)?;
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	if app_general_data.rank > 1 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"No rights to do this action",
+		));
+	}
+
+	if group_data.user_data.rank > 1 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::GroupUserRank,
+			"No rights to do this action",
+		));
+	}
+
+	let group_id = &group_data.group_data.id;
+
+	app_service::transfer(app_general_data, group_id, CUSTOMER_OWNER_TYPE_GROUP).await?;
+
+	audit_transfer(&req, app_general_data, group_id, CUSTOMER_OWNER_TYPE_GROUP).await?;
+
 	echo_success()
 }
 
+/**
+Move the app of a customer group to the customer who sends the request
+ */
+pub async fn transfer_to_customer(req: Request) -> JRes<ServerSuccessOutput>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	if app_general_data.rank > 1 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"No rights to do this action",
+		));
+	}
+
+	let customer = get_jwt_data_from_param(&req)?;
+
+	customer_util::check_customer_valid(&customer.id).await?;
+
+	app_service::transfer(app_general_data, &customer.id, CUSTOMER_OWNER_TYPE_USER).await?;
+
+	audit_transfer(&req, app_general_data, &customer.id, CUSTOMER_OWNER_TYPE_USER).await?;
+
+	echo_success()
+}
+
+/**
+Save the transfer in the log of the app and in the logs of the old and the new customer group
+ */
+async fn audit_transfer(req: &Request, app: &AppCustomerAccess, new_owner_id: &str, new_owner_type: i32) -> AppRes<()>
+{
+	let customer = get_jwt_data_from_param(req)?;
+
+	let before = json!({"app_id": &app.app_id, "owner_id": &app.owner_id, "owner_type": app.owner_type});
+	let after = json!({"app_id": &app.app_id, "owner_id": new_owner_id, "owner_type": new_owner_type});
+
+	audit_service::save(
+		AUDIT_TARGET_APP,
+		&app.app_id,
+		&customer.id,
+		AuditAction::AppTransfer,
+		Some(before.clone()),
+		Some(after.clone()),
+	)
+	.await?;
+
+	if app.owner_type == CUSTOMER_OWNER_TYPE_GROUP {
+		audit_service::save(
+			AUDIT_TARGET_GROUP,
+			&app.owner_id,
+			&customer.id,
+			AuditAction::AppTransfer,
+			Some(before.clone()),
+			Some(after.clone()),
+		)
+		.await?;
+	}
+
+	if new_owner_type == CUSTOMER_OWNER_TYPE_GROUP {
+		audit_service::save(
+			AUDIT_TARGET_GROUP,
+			new_owner_id,
+			&customer.id,
+			AuditAction::AppTransfer,
+			Some(before),
+			Some(after),
+		)
+		.await?;
+	}
+
+	Ok(())
+}
+
 pub async fn reset(req: Request) -> JRes<ServerSuccessOutput>
 {
 	let app_general_data = get_app_general_data(&req)?;
@@ -245,6 +678,8 @@ pub async fn reset(req: Request) -> JRes<ServerSuccessOutput>
 
 	app_service::reset(&app_general_data.app_id).await?;
 
+	audit_action(&req, app_general_data, AuditAction::AppReset, None, None).await?;
+
 	echo_success()
 }
 
@@ -264,8 +699,20 @@ pub async fn update(mut req: Request) -> JRes<ServerSuccessOutput>
 
 	let input: AppUpdateInput = bytes_to_json(&body)?;
 
+	let before = app_model::get_app_view(&app_general_data.app_id, app_general_data.owner_type).await?;
+	let after = json!({"identifier": &input.identifier});
+
 	app_model::update(&app_general_data.app_id, input.identifier).await?;
 
+	audit_change(
+		&req,
+		app_general_data,
+		AuditAction::AppUpdate,
+		&json!({"identifier": before.identifier}),
+		&after,
+	)
+	.await?;
+
 	echo_success()
 }
 
@@ -285,14 +732,15 @@ pub async fn update_options(mut req: Request) -> JRes<ServerSuccessOutput>
 
 	let input: AppOptions = bytes_to_json(&body)?;
 
-	app_model::update_options(&app_general_data.app_id, input).await?;
+	let before = server_api_common::customer_app::get_app_options(&app_general_data.app_id).await?;
+	let after = audit_service::to_audit_value(&input)?;
 
-	//get the public and secret token and delete the app cache because in the cache there are still the old options
+	app_model::update_options(&app_general_data.app_id, input).await?;
 
-	let old_hashed_secret = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_secret_token;
-	let old_hashed_public_token = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_public_token;
+	//delete the app cache of all tokens because in the cache there are still the old options
+	app_service::delete_app_cache(app_general_data, vec![]).await?;
 
-	cache::delete_multiple(&[&old_hashed_secret, &old_hashed_public_token]).await?;
+	audit_change(&req, app_general_data, AuditAction::AppOptionsUpdate, &before, &after).await?;
 
 	echo_success()
 }
@@ -315,14 +763,22 @@ pub async fn update_file_options(mut req: Request) -> JRes<ServerSuccessOutput>
 
 	check_file_options(&input)?;
 
-	app_model::update_file_options(&app_general_data.app_id, input).await?;
+	let before = app_model::get_app_file_options(&app_general_data.app_id).await?;
+	let after = audit_service::to_audit_value(&input)?;
 
-	//get the public and secret token and delete the app cache because in the cache there are still the old options
+	app_model::update_file_options(&app_general_data.app_id, input).await?;
 
-	let old_hashed_secret = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_secret_token;
-	let old_hashed_public_token = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_public_token;
+	//delete the app cache of all tokens because in the cache there are still the old options
+	app_service::delete_app_cache(app_general_data, vec![]).await?;
 
-	cache::delete_multiple(&[&old_hashed_secret, &old_hashed_public_token]).await?;
+	audit_change(
+		&req,
+		app_general_data,
+		AuditAction::AppFileOptionsUpdate,
+		&before,
+		&after,
+	)
+	.await?;
 
 	echo_success()
 }
@@ -345,17 +801,193 @@ pub async fn update_group_options(mut req: Request) -> JRes<ServerSuccessOutput>
 
 	check_group_options(&input)?;
 
+	let before = app_model::get_app_group_options(&app_general_data.app_id).await?;
+	let after = audit_service::to_audit_value(&input)?;
+
 	app_model::update_group_options(&app_general_data.app_id, input).await?;
 
-	let old_hashed_secret = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_secret_token;
-	let old_hashed_public_token = APP_TOKEN_CACHE.to_string() + &app_general_data.hashed_public_token;
+	app_service::delete_app_cache(app_general_data, vec![]).await?;
 
-	cache::delete_multiple(&[&old_hashed_secret, &old_hashed_public_token]).await?;
+	audit_change(
+		&req,
+		app_general_data,
+		AuditAction::AppGroupOptionsUpdate,
+		&before,
+		&after,
+	)
+	.await?;
 
 	echo_success()
 }
 
-fn get_app_general_data(req: &Request) -> AppRes<&AppCustomerAccess>
+pub async fn update_key_endpoint_options(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app_general_data = get_app_general_data(&req)?;
+
+	if app_general_data.rank > 2 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"No rights to do this action",
+		));
+	}
+
+	let input: AppKeyEndpointOptionsInput = bytes_to_json(&body)?;
+
+	check_key_endpoint_options(&input)?;
+
+	let before = app_model::get_app_key_endpoint_options(&app_general_data.app_id).await?;
+	let after = audit_service::to_audit_value(&input)?;
+
+	app_model::update_key_endpoint_options(&app_general_data.app_id, input).await?;
+
+	app_service::delete_app_cache(app_general_data, vec![]).await?;
+
+	audit_change(
+		&req,
+		app_general_data,
+		AuditAction::AppKeyEndpointOptionsUpdate,
+		&before,
+		&after,
+	)
+	.await?;
+
+	echo_success()
+}
+
+pub async fn update_rate_limit_options(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app_general_data = get_app_general_data(&req)?;
+
+	if app_general_data.rank > 2 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"No rights to do this action",
+		));
+	}
+
+	let input: AppRateLimitOptions = bytes_to_json(&body)?;
+
+	app_service::check_rate_limit_options(&input)?;
+
+	let before = server_api_common::customer_app::get_rate_limit_options(&app_general_data.app_id).await?;
+
+	app_model::update_rate_limit_options(&app_general_data.app_id, &input).await?;
+
+	app_service::delete_app_cache(app_general_data, vec![]).await?;
+
+	audit_change(
+		&req,
+		app_general_data,
+		AuditAction::AppRateLimitOptionsUpdate,
+		&before,
+		&input,
+	)
+	.await?;
+
+	echo_success()
+}
+
+/**
+Set the allowed origins and ips for all tokens of the app. Empty lists allow every origin and ip.
+ */
+pub async fn update_access_options(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app_general_data = get_app_general_data(&req)?;
+
+	if app_general_data.rank > 2 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"No rights to do this action",
+		));
+	}
+
+	let input: AppAccessOptions = bytes_to_json(&body)?;
+
+	let input = app_service::check_access_options(&input)?;
+
+	let before = app_model::get_app_access_options(&app_general_data.app_id).await?;
+
+	app_model::update_access_options(&app_general_data.app_id, &input).await?;
+
+	app_service::delete_app_cache(app_general_data, vec![]).await?;
+
+	audit_change(
+		&req,
+		app_general_data,
+		AuditAction::AppAccessOptionsUpdate,
+		&before,
+		&input,
+	)
+	.await?;
+
+	echo_success()
+}
+
+/**
+Create a new jwt key every n days, see the jwt rotation worker. The rotation period is counted from the newest key.
+ */
+pub async fn update_jwt_rotation_options(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app_general_data = get_app_general_data(&req)?;
+
+	if app_general_data.rank > 2 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"No rights to do this action",
+		));
+	}
+
+	let input: AppJwtRotationOptions = bytes_to_json(&body)?;
+
+	app_service::check_jwt_rotation_options(&input)?;
+
+	let before = app_model::get_app_jwt_rotation_options(&app_general_data.app_id).await?;
+
+	//no cache to delete, the options are only used by the worker
+	app_model::update_jwt_rotation_options(&app_general_data.app_id, &input).await?;
+
+	audit_change(
+		&req,
+		app_general_data,
+		AuditAction::AppJwtRotationOptionsUpdate,
+		&before,
+		&input,
+	)
+	.await?;
+
+	echo_success()
+}
+
+/**
+Save the changed options in the audit log of the app
+ */
+async fn audit_change<B: Serialize, A: Serialize>(req: &Request, app: &AppCustomerAccess, action: AuditAction, before: &B, after: &A) -> AppRes<()>
+{
+	let customer = get_jwt_data_from_param(req)?;
+
+	audit_service::save_change(AUDIT_TARGET_APP, &app.app_id, &customer.id, action, before, after).await
+}
+
+async fn audit_action(req: &Request, app: &AppCustomerAccess, action: AuditAction, before: Option<Value>, after: Option<Value>) -> AppRes<()>
+{
+	let customer = get_jwt_data_from_param(req)?;
+
+	audit_service::save(AUDIT_TARGET_APP, &app.app_id, &customer.id, action, before, after).await
+}
+
+pub(crate) fn get_app_general_data(req: &Request) -> AppRes<&AppCustomerAccess>
 {
 	req.extensions()
 		.get::<AppCustomerAccess>()
@@ -1,5 +1,6 @@
 use rustgram_server_util::DB;
-use sentc_crypto_common::{AppId, CustomerId, GroupId};
+use sentc_crypto_common::{AppId, CustomerId, GroupId, JwtKeyId};
+use server_dashboard_common::app::{AppAccessOptions, AppJwtRotationOptions, AppKeyEndpointOptionsInput, AppRateLimitOptions};
 
 pub const CUSTOMER_OWNER_TYPE_USER: i32 = 0;
 pub const CUSTOMER_OWNER_TYPE_GROUP: i32 = 1;
@@ -18,3 +19,113 @@ pub struct AppCustomerAccess
 	pub group_id: GroupId,
 	pub rank: i32,
 }
+
+//__________________________________________________________________________________________________
+
+/**
+A named token of the app, the scope is saved as json of the app options and the access lists comma separated
+ */
+#[derive(DB)]
+pub struct AppTokenItem
+{
+	pub token_id: String,
+	pub name: String,
+	pub token_type: i32,
+	pub time: u128,
+	pub expires_at: u128,
+	pub last_used: u128,
+	pub scope: Option<String>,
+	pub allowed_origins: Option<String>,
+	pub allowed_ips: Option<String>,
+}
+
+//__________________________________________________________________________________________________
+
+/**
+The counted actions of the users of an app in a period from the user action log
+ */
+#[derive(DB)]
+pub struct AppStatsActions
+{
+	pub app_id: AppId,
+	pub active_users: i32,
+	pub logins: i32,
+	pub refreshes: i32,
+	pub key_rotations: i32,
+}
+
+#[derive(DB)]
+pub struct AppStatsCount
+{
+	pub app_id: AppId,
+	pub count: i32,
+}
+
+#[derive(DB)]
+pub struct AppStatsUsage
+{
+	pub app_id: AppId,
+	pub used_storage: i64,
+	pub file_count: i32,
+}
+
+#[derive(Default)]
+pub struct AppStatsActionCount
+{
+	pub active_users: i32,
+	pub logins: i32,
+	pub refreshes: i32,
+	pub registrations: i32,
+	pub key_rotations: i32,
+}
+
+/**
+The current values of an app at the time of the rollup
+ */
+#[derive(Default)]
+pub struct AppStatsSnapshot
+{
+	pub used_storage: i64,
+	pub file_count: i32,
+	pub part_count: i32,
+	pub group_count: i32,
+	pub member_count: i32,
+}
+
+//__________________________________________________________________________________________________
+
+/**
+An app with automatic jwt key rotation and the creation time of the newest key
+ */
+#[derive(DB)]
+pub struct JwtRotationApp
+{
+	pub app_id: AppId,
+	pub rotation_days: i32,
+	pub grace_hours: i32,
+	pub last_key_time: u128,
+}
+
+/**
+A jwt key after the grace period of the rotation
+ */
+#[derive(DB)]
+pub struct RetiredJwtKey
+{
+	pub jwt_key_id: JwtKeyId,
+	pub app_id: AppId,
+}
+
+//__________________________________________________________________________________________________
+
+/**
+The options of an app config which are not part of the app register input.
+They are saved in the same transaction as the new app.
+ */
+pub struct AppConfigOptions
+{
+	pub key_endpoint_options: AppKeyEndpointOptionsInput,
+	pub rate_limit_options: AppRateLimitOptions,
+	pub access_options: AppAccessOptions,
+	pub jwt_rotation_options: AppJwtRotationOptions,
+}
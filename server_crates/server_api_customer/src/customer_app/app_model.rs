@@ -1,13 +1,48 @@
 use rustgram_server_util::db::id_handling::create_id;
-use rustgram_server_util::db::{exec, exec_transaction, query, query_first, query_string, I32Entity, Params, TransactionData};
+use rustgram_server_util::db::{exec, exec_transaction, query, query_first, query_string, I32Entity, Params, StringEntity, TransactionData};
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
 use rustgram_server_util::res::AppRes;
 use rustgram_server_util::{get_time, set_params};
 use sentc_crypto_common::{AppId, CustomerId, GroupId, JwtKeyId, UserId};
-use server_dashboard_common::app::{AppFileOptionsInput, AppGroupOption, AppJwtData, AppOptions, AppRegisterInput};
+use server_api::sentc_user_service::UserAction;
+use server_api_common::customer_app::app_access::{list_from_db, list_to_db};
+use server_api_common::customer_app::app_entities::AppAccessOptionsEntity;
+use server_api_common::file::{FILE_STATUS_AVAILABLE, FILE_USAGE_TYPE_APP};
+use server_api_common::group::GROUP_TYPE_NORMAL;
+use server_dashboard_common::app::{
+	AppAccessOptions,
+	AppConfig,
+	AppFileOptionsInput,
+	AppGroupOption,
+	AppJwtData,
+	AppJwtRotationOptions,
+	AppKeyEndpointOptionsInput,
+	AppOptions,
+	AppRateLimitOptions,
+	AppRateLimitThrottled,
+	AppRegisterInput,
+	AppStats,
+	RATE_LIMIT_GROUP_AUTH,
+	RATE_LIMIT_GROUP_FILE,
+	RATE_LIMIT_GROUP_GROUP,
+	RATE_LIMIT_GROUP_KEY,
+};
 use server_dashboard_common::customer::CustomerAppList;
 
-use crate::customer_app::app_entities::{AppCustomerAccess, CUSTOMER_OWNER_TYPE_GROUP, CUSTOMER_OWNER_TYPE_USER};
+use crate::customer_app::app_entities::{
+	AppConfigOptions,
+	AppCustomerAccess,
+	AppStatsActionCount,
+	AppStatsActions,
+	AppStatsCount,
+	AppStatsSnapshot,
+	AppStatsUsage,
+	AppTokenItem,
+	JwtRotationApp,
+	RetiredJwtKey,
+	CUSTOMER_OWNER_TYPE_GROUP,
+	CUSTOMER_OWNER_TYPE_USER,
+};
 use crate::ApiErrorCodes;
 
 /**
@@ -47,6 +82,22 @@ WHERE
 		.ok_or_else(|| ServerCoreError::new_msg(401, ApiErrorCodes::AppTokenNotFound, "App token not found"))
 }
 
+/**
+Get the app data without the customer check, only for the server admin tools
+ */
+pub(super) async fn get_app_general_internal(app_id: impl Into<AppId>) -> AppRes<AppCustomerAccess>
+{
+	//language=SQL
+	let sql = r"
+SELECT id as app_id, owner_id, owner_type, hashed_secret_token, hashed_public_token, hash_alg, 'none', 0
+FROM sentc_app
+WHERE id = ?";
+
+	query_first(sql, set_params!(app_id.into()))
+		.await?
+		.ok_or_else(|| ServerCoreError::new_msg(400, ApiErrorCodes::AppNotFound, "App not found"))
+}
+
 /**
 Get jwt data like internal get app data
 
@@ -56,7 +107,7 @@ pub(super) async fn get_jwt_data(app_id: impl Into<AppId>) -> AppRes<Vec<AppJwtD
 {
 	//language=SQL
 	let sql = r"
-SELECT ak.id, alg, ak.time, sign_key, verify_key 
+SELECT ak.id, alg, ak.time, sign_key, verify_key, retire_at 
 FROM sentc_app a, sentc_app_jwt_keys ak 
 WHERE 
     app_id = ? AND 
@@ -186,7 +237,24 @@ WHERE
 pub(super) async fn get_app_file_options(app_id: impl Into<AppId>) -> AppRes<AppFileOptionsInput>
 {
 	//language=SQL
-	let sql = "SELECT file_storage,storage_url,auth_token FROM sentc_file_options WHERE app_id = ?";
+	let sql = r"
+SELECT 
+    file_storage,
+    storage_url,
+    auth_token,
+    max_storage_app,
+    max_storage_user,
+    max_storage_group,
+    max_files_app,
+    max_files_user,
+    max_files_group,
+    session_lifetime,
+    default_ttl,
+    max_ttl,
+    version_keep_last,
+    version_keep_days 
+FROM sentc_file_options 
+WHERE app_id = ?";
 
 	query_first(sql, set_params!(app_id.into()))
 		.await?
@@ -196,13 +264,57 @@ pub(super) async fn get_app_file_options(app_id: impl Into<AppId>) -> AppRes<App
 pub(super) async fn get_app_group_options(app_id: impl Into<AppId>) -> AppRes<AppGroupOption>
 {
 	//language=SQL
-	let sql = "SELECT max_key_rotation_month,min_rank_key_rotation FROM sentc_app_group_options WHERE app_id = ?";
+	let sql = "SELECT max_key_rotation_month,min_rank_key_rotation,require_signed_keys FROM sentc_app_group_options WHERE app_id = ?";
 
 	query_first(sql, set_params!(app_id.into()))
 		.await?
 		.ok_or_else(|| ServerCoreError::new_msg(400, ApiErrorCodes::AppNotFound, "App not found"))
 }
 
+/**
+Get the key endpoint options. When the customer never set an endpoint, the default options are returned.
+ */
+pub(super) async fn get_app_key_endpoint_options(app_id: impl Into<AppId>) -> AppRes<AppKeyEndpointOptionsInput>
+{
+	//language=SQL
+	let sql = "SELECT key_endpoint_url,auth_token,timeout,fallback FROM sentc_app_key_endpoint_options WHERE app_id = ?";
+
+	let options: Option<AppKeyEndpointOptionsInput> = query_first(sql, set_params!(app_id.into())).await?;
+
+	Ok(options.unwrap_or_default())
+}
+
+pub(super) async fn get_app_jwt_rotation_options(app_id: impl Into<AppId>) -> AppRes<AppJwtRotationOptions>
+{
+	//language=SQL
+	let sql = "SELECT rotation_days, grace_hours FROM sentc_app_jwt_rotation_options WHERE app_id = ?";
+
+	let options: Option<AppJwtRotationOptions> = query_first(sql, set_params!(app_id.into())).await?;
+
+	Ok(options.unwrap_or_default())
+}
+
+/**
+Get the access options. When the customer never restricted the app tokens, empty lists are returned.
+ */
+pub(super) async fn get_app_access_options(app_id: impl Into<AppId>) -> AppRes<AppAccessOptions>
+{
+	//language=SQL
+	let sql = "SELECT allowed_origins, allowed_ips FROM sentc_app_access_options WHERE app_id = ?";
+
+	let options: Option<AppAccessOptionsEntity> = query_first(sql, set_params!(app_id.into())).await?;
+
+	Ok(match options {
+		Some(o) => {
+			AppAccessOptions {
+				allowed_origins: list_from_db(&o.allowed_origins),
+				allowed_ips: list_from_db(&o.allowed_ips),
+			}
+		},
+		None => AppAccessOptions::default(),
+	})
+}
+
 pub(super) async fn check_app_exists(app_id: impl Into<AppId>, customer_id: impl Into<CustomerId>) -> AppRes<bool>
 {
 	//language=SQL
@@ -227,6 +339,7 @@ pub(super) async fn create_app_with_id(
 	first_jwt_verify_key: impl Into<String>,
 	first_jwt_alg: impl Into<String>,
 	group_id: Option<impl Into<GroupId>>,
+	config_options: Option<AppConfigOptions>,
 ) -> AppRes<JwtKeyId>
 {
 	//in another fn to also create the sentc root app with a specific id
@@ -285,23 +398,53 @@ VALUES (?,?,?,?,?,?,?,?)";
 	let (sql_options, params_options) = prepare_options_insert(app_id.clone(), input.options);
 
 	//language=SQL
-	let sql_file_options = "INSERT INTO sentc_file_options (app_id, file_storage, storage_url, auth_token) VALUES (?,?,?,?)";
+	let sql_file_options = r"
+INSERT INTO sentc_file_options 
+    (app_id, 
+     file_storage, 
+     storage_url, 
+     auth_token, 
+     max_storage_app, 
+     max_storage_user, 
+     max_storage_group, 
+     max_files_app, 
+     max_files_user, 
+     max_files_group, 
+     session_lifetime, 
+     default_ttl, 
+     max_ttl, 
+     version_keep_last, 
+     version_keep_days) 
+VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)";
 	let params_file_options = set_params!(
 		app_id.clone(),
 		input.file_options.file_storage,
 		input.file_options.storage_url,
-		input.file_options.auth_token
+		input.file_options.auth_token,
+		input.file_options.max_storage_app,
+		input.file_options.max_storage_user,
+		input.file_options.max_storage_group,
+		input.file_options.max_files_app,
+		input.file_options.max_files_user,
+		input.file_options.max_files_group,
+		input.file_options.session_lifetime,
+		input.file_options.default_ttl,
+		input.file_options.max_ttl,
+		input.file_options.version_keep_last,
+		input.file_options.version_keep_days
 	);
 
 	//language=SQL
-	let sql_group_options = "INSERT INTO sentc_app_group_options (app_id, max_key_rotation_month, min_rank_key_rotation) VALUES (?,?,?)";
+	let sql_group_options =
+		"INSERT INTO sentc_app_group_options (app_id, max_key_rotation_month, min_rank_key_rotation, require_signed_keys) VALUES (?,?,?,?)";
 	let params_group_options = set_params!(
-		app_id,
+		app_id.clone(),
 		input.group_options.max_key_rotation_month,
-		input.group_options.min_rank_key_rotation
+		input.group_options.min_rank_key_rotation,
+		input.group_options.require_signed_keys
 	);
 
-	exec_transaction(vec![
+	let mut data = vec![
 		TransactionData {
 			sql: sql_app,
 			params: params_app,
@@ -322,8 +465,22 @@ VALUES (?,?,?,?,?,?,?,?)";
 			sql: sql_group_options,
 			params: params_group_options,
 		},
-	])
-	.await?;
+	];
+
+	if let Some(options) = config_options {
+		data.extend(
+			prepare_config_options_update(app_id, options)
+				.into_iter()
+				.map(|(sql, params)| {
+					TransactionData {
+						sql,
+						params,
+					}
+				}),
+		);
+	}
+
+	exec_transaction(data).await?;
 
 	Ok(jwt_key_id)
 }
@@ -339,6 +496,7 @@ pub(super) async fn create_app(
 	first_jwt_verify_key: impl Into<String>,
 	first_jwt_alg: impl Into<String>,
 	group_id: Option<impl Into<GroupId>>,
+	config_options: Option<AppConfigOptions>,
 ) -> AppRes<(AppId, JwtKeyId)>
 {
 	let app_id = create_id();
@@ -354,6 +512,7 @@ pub(super) async fn create_app(
 		first_jwt_verify_key,
 		first_jwt_alg,
 		group_id,
+		config_options,
 	)
 	.await?;
 
@@ -432,34 +591,125 @@ pub(super) async fn update(app_id: impl Into<AppId>, identifier: Option<String>)
 	Ok(())
 }
 
+pub(super) async fn transfer(app_id: impl Into<AppId>, new_owner_id: impl Into<CustomerId>, new_owner_type: i32) -> AppRes<()>
+{
+	//language=SQL
+	let sql = "UPDATE sentc_app SET owner_id = ?, owner_type = ? WHERE id = ?";
+
+	exec(sql, set_params!(new_owner_id.into(), new_owner_type, app_id.into())).await?;
+
+	Ok(())
+}
+
 pub(super) async fn update_options(app_id: impl Into<AppId>, app_options: AppOptions) -> AppRes<()>
 {
-	let app_id = app_id.into();
+	exec_transaction(
+		prepare_options_update(app_id.into(), app_options)
+			.into_iter()
+			.map(|(sql, params)| {
+				TransactionData {
+					sql,
+					params,
+				}
+			})
+			.collect(),
+	)
+	.await?;
 
-	//delete the old options
+	Ok(())
+}
 
-	//language=SQL
-	let sql = "DELETE FROM sentc_app_options WHERE app_id = ?";
+pub(super) async fn update_file_options(app_id: impl Into<AppId>, options: AppFileOptionsInput) -> AppRes<()>
+{
+	let (sql, params) = prepare_file_options_update(app_id.into(), options);
 
-	exec(sql, set_params!(app_id.clone())).await?;
+	exec(sql, params).await?;
 
-	let (sql_options, params_options) = prepare_options_insert(app_id, app_options);
+	Ok(())
+}
 
-	exec(sql_options, params_options).await?;
+pub(super) async fn update_key_endpoint_options(app_id: impl Into<AppId>, options: AppKeyEndpointOptionsInput) -> AppRes<()>
+{
+	exec_transaction(
+		prepare_key_endpoint_options_update(app_id.into(), options)
+			.into_iter()
+			.map(|(sql, params)| {
+				TransactionData {
+					sql,
+					params,
+				}
+			})
+			.collect(),
+	)
+	.await?;
 
 	Ok(())
 }
 
-pub(super) async fn update_file_options(app_id: impl Into<AppId>, options: AppFileOptionsInput) -> AppRes<()>
+pub(super) async fn update_rate_limit_options(app_id: impl Into<AppId>, options: &AppRateLimitOptions) -> AppRes<()>
 {
-	let app_id = app_id.into();
+	exec_transaction(
+		prepare_rate_limit_options_update(app_id.into(), options)
+			.into_iter()
+			.map(|(sql, params)| {
+				TransactionData {
+					sql,
+					params,
+				}
+			})
+			.collect(),
+	)
+	.await?;
 
+	Ok(())
+}
+
+pub(super) async fn get_rate_limit_throttled(app_id: impl Into<AppId>, from: u128, to: u128) -> AppRes<Vec<AppRateLimitThrottled>>
+{
 	//language=SQL
-	let sql = "UPDATE sentc_file_options SET storage_url = ?, file_storage = ?, auth_token = ? WHERE app_id = ?";
+	let sql = r"
+SELECT endpoint_group, day, count 
+FROM sentc_app_rate_limit_throttled 
+WHERE 
+    app_id = ? AND 
+    day >= ? AND 
+    day <= ? 
+ORDER BY day, endpoint_group 
+LIMIT 1600";
 
-	exec(
-		sql,
-		set_params!(options.storage_url, options.file_storage, options.auth_token, app_id),
+	query(sql, set_params!(app_id.into(), from.to_string(), to.to_string())).await
+}
+
+pub(super) async fn update_access_options(app_id: impl Into<AppId>, options: &AppAccessOptions) -> AppRes<()>
+{
+	exec_transaction(
+		prepare_access_options_update(app_id.into(), options)
+			.into_iter()
+			.map(|(sql, params)| {
+				TransactionData {
+					sql,
+					params,
+				}
+			})
+			.collect(),
+	)
+	.await?;
+
+	Ok(())
+}
+
+pub(super) async fn update_jwt_rotation_options(app_id: impl Into<AppId>, options: &AppJwtRotationOptions) -> AppRes<()>
+{
+	exec_transaction(
+		prepare_jwt_rotation_options_update(app_id.into(), options)
+			.into_iter()
+			.map(|(sql, params)| {
+				TransactionData {
+					sql,
+					params,
+				}
+			})
+			.collect(),
 	)
 	.await?;
 
@@ -468,16 +718,42 @@ pub(super) async fn update_file_options(app_id: impl Into<AppId>, options: AppFi
 
 pub(super) async fn update_group_options(app_id: impl Into<AppId>, options: AppGroupOption) -> AppRes<()>
 {
-	//language=SQL
-	let sql = "UPDATE sentc_app_group_options SET max_key_rotation_month = ?, min_rank_key_rotation = ? WHERE app_id = ?";
+	let (sql, params) = prepare_group_options_update(app_id.into(), options);
 
-	exec(
-		sql,
-		set_params!(
-			options.max_key_rotation_month,
-			options.min_rank_key_rotation,
-			app_id.into()
-		),
+	exec(sql, params).await?;
+
+	Ok(())
+}
+
+/**
+Overwrite all options of the app with the config in one transaction. The access lists must be normalized.
+ */
+pub(super) async fn import_config(app_id: impl Into<AppId>, config: AppConfig) -> AppRes<()>
+{
+	let app_id = app_id.into();
+
+	let mut data = prepare_options_update(app_id.clone(), config.options);
+	data.push(prepare_file_options_update(app_id.clone(), config.file_options));
+	data.push(prepare_group_options_update(app_id.clone(), config.group_options));
+	data.extend(prepare_config_options_update(
+		app_id,
+		AppConfigOptions {
+			key_endpoint_options: config.key_endpoint_options,
+			rate_limit_options: config.rate_limit_options,
+			access_options: config.access_options,
+			jwt_rotation_options: config.jwt_rotation_options,
+		},
+	));
+
+	exec_transaction(
+		data.into_iter()
+			.map(|(sql, params)| {
+				TransactionData {
+					sql,
+					params,
+				}
+			})
+			.collect(),
 	)
 	.await?;
 
@@ -550,6 +826,384 @@ pub(super) async fn reset(app_id: impl Into<AppId>) -> AppRes<()>
 	Ok(())
 }
 
+//__________________________________________________________________________________________________
+// named tokens
+
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn create_token(
+	app_id: impl Into<AppId>,
+	name: String,
+	token_type: i32,
+	hashed_token: String,
+	alg: impl Into<String>,
+	expires_at: u128,
+	scope: Option<String>,
+	allowed_origins: Option<String>,
+	allowed_ips: Option<String>,
+) -> AppRes<String>
+{
+	let token_id = create_id();
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = r"
+INSERT INTO sentc_app_token 
+    (id, app_id, name, token_type, hashed_token, hash_alg, time, expires_at, last_used, scope, allowed_origins, allowed_ips) 
+VALUES (?,?,?,?,?,?,?,?,?,?,?,?)";
+
+	exec(
+		sql,
+		set_params!(
+			token_id.clone(),
+			app_id.into(),
+			name,
+			token_type,
+			hashed_token,
+			alg.into(),
+			time.to_string(),
+			expires_at.to_string(),
+			"0".to_string(),
+			scope,
+			allowed_origins,
+			allowed_ips
+		),
+	)
+	.await?;
+
+	Ok(token_id)
+}
+
+pub(super) async fn get_tokens(app_id: impl Into<AppId>, last_fetched_time: u128, last_id: impl Into<String>) -> AppRes<Vec<AppTokenItem>>
+{
+	//language=SQL
+	let sql = r"
+SELECT id, name, token_type, time, expires_at, last_used, scope, allowed_origins, allowed_ips 
+FROM sentc_app_token 
+WHERE app_id = ?"
+		.to_string();
+
+	let (sql, params) = if last_fetched_time > 0 {
+		let sql = sql + " AND time >= ? AND (time > ? OR (time = ? AND id > ?)) ORDER BY time, id LIMIT 20";
+		(
+			sql,
+			set_params!(
+				app_id.into(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_id.into()
+			),
+		)
+	} else {
+		let sql = sql + " ORDER BY time, id LIMIT 20";
+		(sql, set_params!(app_id.into()))
+	};
+
+	query_string(sql, params).await
+}
+
+pub(super) async fn get_token_hash(app_id: impl Into<AppId>, token_id: impl Into<String>) -> AppRes<Option<StringEntity>>
+{
+	//language=SQL
+	let sql = "SELECT hashed_token FROM sentc_app_token WHERE id = ? AND app_id = ?";
+
+	query_first(sql, set_params!(token_id.into(), app_id.into())).await
+}
+
+/**
+The hashed tokens of all named tokens, to delete the cached app data
+ */
+pub(super) async fn get_token_hashes(app_id: impl Into<AppId>) -> AppRes<Vec<StringEntity>>
+{
+	//language=SQL
+	let sql = "SELECT hashed_token FROM sentc_app_token WHERE app_id = ?";
+
+	query(sql, set_params!(app_id.into())).await
+}
+
+pub(super) async fn delete_token(app_id: impl Into<AppId>, token_id: impl Into<String>) -> AppRes<()>
+{
+	//language=SQL
+	let sql = "DELETE FROM sentc_app_token WHERE id = ? AND app_id = ?";
+
+	exec(sql, set_params!(token_id.into(), app_id.into())).await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+
+pub(super) async fn get_stats(app_id: impl Into<AppId>, period_type: i32, from: u128, to: u128) -> AppRes<Vec<AppStats>>
+{
+	//language=SQL
+	let sql = r"
+SELECT 
+    period_start, 
+    active_users, 
+    logins, 
+    refreshes, 
+    registrations, 
+    key_rotations, 
+    used_storage, 
+    file_count, 
+    part_count, 
+    group_count, 
+    member_count 
+FROM sentc_app_stats 
+WHERE 
+    app_id = ? AND 
+    period_type = ? AND 
+    period_start >= ? AND 
+    period_start <= ? 
+ORDER BY period_start 
+LIMIT 400";
+
+	query(
+		sql,
+		set_params!(app_id.into(), period_type, from.to_string(), to.to_string()),
+	)
+	.await
+}
+
+/**
+Count the actions of all apps in the period.
+
+Key rotations are saved with the group id as user id, so they don't count as active users.
+ */
+pub(super) async fn get_stats_actions(start: u128, end: u128) -> AppRes<Vec<AppStatsActions>>
+{
+	//language=SQL
+	let sql = r"
+SELECT 
+    app_id, 
+    COUNT(DISTINCT CASE WHEN action_id IN (?,?,?) THEN user_id END), 
+    COUNT(CASE WHEN action_id = ? THEN 1 END), 
+    COUNT(CASE WHEN action_id = ? THEN 1 END), 
+    COUNT(CASE WHEN action_id = ? THEN 1 END) 
+FROM sentc_user_action_log 
+WHERE 
+    time >= ? AND 
+    time < ? 
+GROUP BY app_id";
+
+	query(
+		sql,
+		set_params!(
+			UserAction::Login.get_int_code(),
+			UserAction::Refresh.get_int_code(),
+			UserAction::Init.get_int_code(),
+			UserAction::Login.get_int_code(),
+			UserAction::Refresh.get_int_code(),
+			UserAction::KeyRotation.get_int_code(),
+			start.to_string(),
+			end.to_string()
+		),
+	)
+	.await
+}
+
+pub(super) async fn get_stats_registrations(start: u128, end: u128) -> AppRes<Vec<AppStatsCount>>
+{
+	//language=SQL
+	let sql = "SELECT app_id, COUNT(id) FROM sentc_user WHERE time >= ? AND time < ? GROUP BY app_id";
+
+	query(sql, set_params!(start.to_string(), end.to_string())).await
+}
+
+pub(super) async fn get_stats_usage() -> AppRes<Vec<AppStatsUsage>>
+{
+	//language=SQL
+	let sql = "SELECT app_id, used_storage, file_count FROM sentc_file_usage WHERE usage_type = ? AND id = app_id";
+
+	query(sql, set_params!(FILE_USAGE_TYPE_APP)).await
+}
+
+pub(super) async fn get_stats_parts() -> AppRes<Vec<AppStatsCount>>
+{
+	//language=SQL
+	let sql = r"
+SELECT fp.app_id, COUNT(fp.id) 
+FROM 
+    sentc_file_part fp, 
+    sentc_file f 
+WHERE 
+    fp.file_id = f.id AND 
+    f.status = ? 
+GROUP BY fp.app_id";
+
+	query(sql, set_params!(FILE_STATUS_AVAILABLE)).await
+}
+
+pub(super) async fn get_stats_groups() -> AppRes<Vec<AppStatsCount>>
+{
+	//only normal groups, user groups are counted as users
+
+	//language=SQL
+	let sql = "SELECT app_id, COUNT(id) FROM sentc_group WHERE type = ? GROUP BY app_id";
+
+	query(sql, set_params!(GROUP_TYPE_NORMAL)).await
+}
+
+pub(super) async fn get_stats_members() -> AppRes<Vec<AppStatsCount>>
+{
+	//language=SQL
+	let sql = r"
+SELECT g.app_id, COUNT(gu.user_id) 
+FROM 
+    sentc_group g, 
+    sentc_group_user gu 
+WHERE 
+    gu.group_id = g.id AND 
+    g.type = ? 
+GROUP BY g.app_id";
+
+	query(sql, set_params!(GROUP_TYPE_NORMAL)).await
+}
+
+fn prepare_stats_init(app_id: &str, period_type: i32, period_start: u128) -> (&'static str, Params)
+{
+	#[cfg(feature = "mysql")]
+	//language=SQL
+	let sql = "INSERT IGNORE INTO sentc_app_stats (app_id, period_type, period_start) VALUES (?,?,?)";
+
+	#[cfg(feature = "sqlite")]
+	let sql = "INSERT OR IGNORE INTO sentc_app_stats (app_id, period_type, period_start) VALUES (?,?,?)";
+
+	(
+		sql,
+		set_params!(app_id.to_string(), period_type, period_start.to_string()),
+	)
+}
+
+pub(super) async fn save_stats_actions(app_id: &str, period_type: i32, period_start: u128, stats: AppStatsActionCount) -> AppRes<()>
+{
+	let (sql_init, params_init) = prepare_stats_init(app_id, period_type, period_start);
+
+	//language=SQL
+	let sql = r"
+UPDATE sentc_app_stats 
+SET 
+    active_users = ?, 
+    logins = ?, 
+    refreshes = ?, 
+    registrations = ?, 
+    key_rotations = ? 
+WHERE 
+    app_id = ? AND 
+    period_type = ? AND 
+    period_start = ?";
+
+	exec_transaction(vec![
+		TransactionData {
+			sql: sql_init,
+			params: params_init,
+		},
+		TransactionData {
+			sql,
+			params: set_params!(
+				stats.active_users,
+				stats.logins,
+				stats.refreshes,
+				stats.registrations,
+				stats.key_rotations,
+				app_id.to_string(),
+				period_type,
+				period_start.to_string()
+			),
+		},
+	])
+	.await?;
+
+	Ok(())
+}
+
+pub(super) async fn save_stats_snapshot(app_id: &str, period_type: i32, period_start: u128, stats: &AppStatsSnapshot) -> AppRes<()>
+{
+	let (sql_init, params_init) = prepare_stats_init(app_id, period_type, period_start);
+
+	//language=SQL
+	let sql = r"
+UPDATE sentc_app_stats 
+SET 
+    used_storage = ?, 
+    file_count = ?, 
+    part_count = ?, 
+    group_count = ?, 
+    member_count = ? 
+WHERE 
+    app_id = ? AND 
+    period_type = ? AND 
+    period_start = ?";
+
+	exec_transaction(vec![
+		TransactionData {
+			sql: sql_init,
+			params: params_init,
+		},
+		TransactionData {
+			sql,
+			params: set_params!(
+				stats.used_storage,
+				stats.file_count,
+				stats.part_count,
+				stats.group_count,
+				stats.member_count,
+				app_id.to_string(),
+				period_type,
+				period_start.to_string()
+			),
+		},
+	])
+	.await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+//jwt key rotation
+
+/**
+All apps with an automatic rotation and the time of the newest jwt key
+ */
+pub(super) async fn get_jwt_rotation_apps() -> AppRes<Vec<JwtRotationApp>>
+{
+	//language=SQL
+	let sql = r"
+SELECT r.app_id, rotation_days, grace_hours, MAX(k.time) 
+FROM sentc_app_jwt_rotation_options r, sentc_app_jwt_keys k 
+WHERE 
+    rotation_days > ? AND 
+    k.app_id = r.app_id 
+GROUP BY r.app_id, rotation_days, grace_hours";
+
+	query(sql, set_params!(0)).await
+}
+
+/**
+Set the retire time for all active keys of the app except the new key
+ */
+pub(super) async fn retire_jwt_keys(app_id: impl Into<AppId>, new_jwt_key_id: impl Into<JwtKeyId>, retire_at: u128) -> AppRes<()>
+{
+	//language=SQL
+	let sql = "UPDATE sentc_app_jwt_keys SET retire_at = ? WHERE app_id = ? AND id <> ? AND retire_at = 0";
+
+	exec(
+		sql,
+		set_params!(retire_at.to_string(), app_id.into(), new_jwt_key_id.into()),
+	)
+	.await?;
+
+	Ok(())
+}
+
+pub(super) async fn get_retired_jwt_keys(time: u128) -> AppRes<Vec<RetiredJwtKey>>
+{
+	//language=SQL
+	let sql = "SELECT id, app_id FROM sentc_app_jwt_keys WHERE retire_at > 0 AND retire_at <= ? LIMIT 1000";
+
+	query(sql, set_params!(time.to_string())).await
+}
+
 //__________________________________________________________________________________________________
 
 fn prepare_options_insert(app_id: impl Into<AppId>, app_options: AppOptions) -> (&'static str, Params)
@@ -665,3 +1319,195 @@ INSERT INTO sentc_app_options
 
 	(sql, params_options)
 }
+
+fn prepare_options_update(app_id: AppId, app_options: AppOptions) -> Vec<(&'static str, Params)>
+{
+	//delete the old options
+
+	//language=SQL
+	let sql = "DELETE FROM sentc_app_options WHERE app_id = ?";
+
+	vec![(sql, set_params!(app_id.clone())), prepare_options_insert(app_id, app_options)]
+}
+
+fn prepare_file_options_update(app_id: AppId, options: AppFileOptionsInput) -> (&'static str, Params)
+{
+	//language=SQL
+	let sql = r"
+UPDATE sentc_file_options 
+SET 
+    storage_url = ?, 
+    file_storage = ?, 
+    auth_token = ?, 
+    max_storage_app = ?, 
+    max_storage_user = ?, 
+    max_storage_group = ?, 
+    max_files_app = ?, 
+    max_files_user = ?, 
+    max_files_group = ?, 
+    session_lifetime = ?, 
+    default_ttl = ?, 
+    max_ttl = ?, 
+    version_keep_last = ?, 
+    version_keep_days = ? 
+WHERE app_id = ?";
+
+	let params = set_params!(
+		options.storage_url,
+		options.file_storage,
+		options.auth_token,
+		options.max_storage_app,
+		options.max_storage_user,
+		options.max_storage_group,
+		options.max_files_app,
+		options.max_files_user,
+		options.max_files_group,
+		options.session_lifetime,
+		options.default_ttl,
+		options.max_ttl,
+		options.version_keep_last,
+		options.version_keep_days,
+		app_id
+	);
+
+	(sql, params)
+}
+
+fn prepare_group_options_update(app_id: AppId, options: AppGroupOption) -> (&'static str, Params)
+{
+	//language=SQL
+	let sql = "UPDATE sentc_app_group_options SET max_key_rotation_month = ?, min_rank_key_rotation = ?, require_signed_keys = ? WHERE app_id = ?";
+
+	let params = set_params!(
+		options.max_key_rotation_month,
+		options.min_rank_key_rotation,
+		options.require_signed_keys,
+		app_id
+	);
+
+	(sql, params)
+}
+
+fn prepare_key_endpoint_options_update(app_id: AppId, options: AppKeyEndpointOptionsInput) -> Vec<(&'static str, Params)>
+{
+	//the options are only stored when the customer sets them, so delete the old and insert the new.
+
+	//language=SQL
+	let sql_delete = "DELETE FROM sentc_app_key_endpoint_options WHERE app_id = ?";
+
+	//language=SQL
+	let sql = r"
+INSERT INTO sentc_app_key_endpoint_options 
+    (app_id, key_endpoint_url, auth_token, timeout, fallback) 
+VALUES (?,?,?,?,?)";
+
+	vec![
+		(sql_delete, set_params!(app_id.clone())),
+		(
+			sql,
+			set_params!(
+				app_id,
+				options.key_endpoint_url,
+				options.auth_token,
+				options.timeout,
+				options.fallback
+			),
+		),
+	]
+}
+
+/**
+Only the endpoint groups with a limit are saved
+ */
+fn prepare_rate_limit_options_update(app_id: AppId, options: &AppRateLimitOptions) -> Vec<(&'static str, Params)>
+{
+	//language=SQL
+	let sql = "DELETE FROM sentc_app_rate_limit_options WHERE app_id = ?";
+
+	let mut data = vec![(sql, set_params!(app_id.clone()))];
+
+	for endpoint_group in [RATE_LIMIT_GROUP_AUTH, RATE_LIMIT_GROUP_GROUP, RATE_LIMIT_GROUP_FILE, RATE_LIMIT_GROUP_KEY] {
+		let limit = options.get(endpoint_group);
+
+		if limit.capacity <= 0 {
+			continue;
+		}
+
+		//language=SQL
+		let sql = r"
+INSERT INTO sentc_app_rate_limit_options 
+    (app_id, endpoint_group, capacity, refill_per_minute, limit_by) 
+VALUES (?,?,?,?,?)";
+
+		data.push((
+			sql,
+			set_params!(
+				app_id.clone(),
+				endpoint_group,
+				limit.capacity,
+				limit.refill_per_minute,
+				limit.limit_by
+			),
+		));
+	}
+
+	data
+}
+
+fn prepare_access_options_update(app_id: AppId, options: &AppAccessOptions) -> Vec<(&'static str, Params)>
+{
+	//like the key endpoint options, the options are only stored when the customer restricts the tokens
+
+	//language=SQL
+	let sql = "DELETE FROM sentc_app_access_options WHERE app_id = ?";
+
+	let mut data = vec![(sql, set_params!(app_id.clone()))];
+
+	if options.allowed_origins.is_empty() && options.allowed_ips.is_empty() {
+		return data;
+	}
+
+	//language=SQL
+	let sql = "INSERT INTO sentc_app_access_options (app_id, allowed_origins, allowed_ips) VALUES (?,?,?)";
+
+	data.push((
+		sql,
+		set_params!(
+			app_id,
+			list_to_db(&options.allowed_origins),
+			list_to_db(&options.allowed_ips)
+		),
+	));
+
+	data
+}
+
+fn prepare_jwt_rotation_options_update(app_id: AppId, options: &AppJwtRotationOptions) -> Vec<(&'static str, Params)>
+{
+	//language=SQL
+	let sql_delete = "DELETE FROM sentc_app_jwt_rotation_options WHERE app_id = ?";
+
+	//language=SQL
+	let sql = "INSERT INTO sentc_app_jwt_rotation_options (app_id, rotation_days, grace_hours) VALUES (?,?,?)";
+
+	vec![
+		(sql_delete, set_params!(app_id.clone())),
+		(sql, set_params!(app_id, options.rotation_days, options.grace_hours)),
+	]
+}
+
+fn prepare_config_options_update(app_id: AppId, options: AppConfigOptions) -> Vec<(&'static str, Params)>
+{
+	let mut data = prepare_key_endpoint_options_update(app_id.clone(), options.key_endpoint_options);
+	data.extend(prepare_rate_limit_options_update(
+		app_id.clone(),
+		&options.rate_limit_options,
+	));
+	data.extend(prepare_access_options_update(app_id.clone(), &options.access_options));
+	data.extend(prepare_jwt_rotation_options_update(
+		app_id,
+		&options.jwt_rotation_options,
+	));
+
+	data
+}
@@ -2,26 +2,55 @@ use std::env;
 use std::future::Future;
 
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::input_helper::{bytes_to_json, json_to_string};
 use rustgram_server_util::res::AppRes;
+use rustgram_server_util::{cache, get_time};
 use sentc_crypto_common::{AppId, CustomerId, GroupId};
+use server_api_common::customer_app::app_access::{list_from_db, list_to_db, normalize_origin, parse_ip_range};
 use server_api_common::user::jwt::create_jwt_keys;
-use server_api_common::util::{hash_token_to_string, HASH_ALG};
+use server_api_common::util::{hash_token_to_string, APP_TOKEN_CACHE, HASH_ALG};
 use server_api_common::SENTC_ROOT_APP;
 use server_dashboard_common::app::{
+	AppAccessOptions,
+	AppConfig,
+	AppConfigCreateInput,
 	AppFileOptionsInput,
 	AppGroupOption,
 	AppJwtRegisterOutput,
+	AppJwtRotationOptions,
+	AppKeyEndpointOptionsInput,
 	AppOptions,
+	AppRateLimitOptions,
 	AppRegisterInput,
 	AppRegisterOutput,
+	AppToken,
+	AppTokenCreateInput,
+	AppTokenCreateOutput,
+	APP_CONFIG_VERSION,
+	APP_TOKEN_TYPE_PUBLIC,
+	APP_TOKEN_TYPE_SECRET,
+	FILE_SESSION_LIFETIME_MAX,
+	FILE_SESSION_LIFETIME_MIN,
 	FILE_STORAGE_OWN,
 	FILE_STORAGE_SENTC,
+	JWT_ROTATION_GRACE_HOURS_MAX,
+	JWT_ROTATION_MAX_DAYS,
+	KEY_ENDPOINT_FALLBACK_LOCAL,
+	KEY_ENDPOINT_FALLBACK_NONE,
+	KEY_ENDPOINT_MAX_TIMEOUT,
+	RATE_LIMIT_BY_IP,
+	RATE_LIMIT_BY_TOKEN,
+	RATE_LIMIT_BY_USER,
+	RATE_LIMIT_MAX_CAPACITY,
 };
 use server_dashboard_common::customer::CustomerAppList;
 
+use crate::customer_app::app_entities::{AppConfigOptions, AppCustomerAccess};
 use crate::customer_app::{app_model, generate_tokens};
 use crate::ApiErrorCodes;
 
+const ACCESS_LIST_MAX: usize = 100;
+
 /*
    (
 	   secret_token,
@@ -82,6 +111,7 @@ pub async fn create_sentc_root_app() -> AppRes<()>
 		&jwt_verify_key,
 		alg,
 		None::<String>,
+		None,
 	)
 	.await?;
 
@@ -93,6 +123,16 @@ pub async fn create_app(
 	customer_id: impl Into<CustomerId>,
 	group_id: Option<impl Into<GroupId>>,
 ) -> AppRes<AppRegisterOutput>
+{
+	create_app_with_config_options(input, customer_id, group_id, None).await
+}
+
+async fn create_app_with_config_options(
+	input: AppRegisterInput,
+	customer_id: impl Into<CustomerId>,
+	group_id: Option<impl Into<GroupId>>,
+	config_options: Option<AppConfigOptions>,
+) -> AppRes<AppRegisterOutput>
 {
 	let (secret_token, public_token, hashed_secret_token, hashed_public_token, jwt_sign_key, jwt_verify_key, alg) = prepare_app_create(&input)?;
 
@@ -108,6 +148,7 @@ pub async fn create_app(
 		&jwt_verify_key,
 		alg,
 		group_id,
+		config_options,
 	)
 	.await?;
 
@@ -138,6 +179,175 @@ pub async fn reset(app_id: impl Into<AppId>) -> AppRes<()>
 	Ok(())
 }
 
+//__________________________________________________________________________________________________
+// named tokens
+
+pub async fn create_token(app_id: impl Into<AppId>, input: AppTokenCreateInput) -> AppRes<AppTokenCreateOutput>
+{
+	if input.name.is_empty() || input.name.len() > 100 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"The token name must be between 1 and 100 characters",
+		));
+	}
+
+	if input.token_type != APP_TOKEN_TYPE_PUBLIC && input.token_type != APP_TOKEN_TYPE_SECRET {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong token type, please choose public or secret",
+		));
+	}
+
+	if input.expires_at > 0 && input.expires_at <= get_time()? {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"The token expiry must be in the future. Use 0 for no expiry.",
+		));
+	}
+
+	let scope = match &input.scope {
+		Some(s) => Some(json_to_string(s)?),
+		None => None,
+	};
+
+	//the token uses the lists of the app when no own lists are set
+	let (allowed_origins, allowed_ips) = match &input.access_options {
+		Some(o) => {
+			let o = check_access_options(o)?;
+
+			(Some(list_to_db(&o.allowed_origins)), Some(list_to_db(&o.allowed_ips)))
+		},
+		None => (None, None),
+	};
+
+	let (secret_token, public_token) = generate_tokens()?;
+
+	//use the same length as the default tokens
+	let (token, hashed_token) = if input.token_type == APP_TOKEN_TYPE_SECRET {
+		(base64::encode(secret_token), hash_token_to_string(&secret_token)?)
+	} else {
+		(base64::encode(public_token), hash_token_to_string(&public_token)?)
+	};
+
+	let token_id = app_model::create_token(
+		app_id,
+		input.name,
+		input.token_type,
+		hashed_token,
+		HASH_ALG,
+		input.expires_at,
+		scope,
+		allowed_origins,
+		allowed_ips,
+	)
+	.await?;
+
+	Ok(AppTokenCreateOutput {
+		token_id,
+		token,
+	})
+}
+
+pub async fn get_tokens(app_id: impl Into<AppId>, last_fetched_time: u128, last_id: impl Into<String>) -> AppRes<Vec<AppToken>>
+{
+	let list = app_model::get_tokens(app_id, last_fetched_time, last_id).await?;
+
+	let mut out = Vec::with_capacity(list.len());
+
+	for item in list {
+		let scope: Option<AppOptions> = match item.scope {
+			Some(s) => Some(bytes_to_json(s.as_bytes())?),
+			None => None,
+		};
+
+		let access_options = if item.allowed_origins.is_none() && item.allowed_ips.is_none() {
+			None
+		} else {
+			Some(AppAccessOptions {
+				allowed_origins: list_from_db(item.allowed_origins.as_deref().unwrap_or("")),
+				allowed_ips: list_from_db(item.allowed_ips.as_deref().unwrap_or("")),
+			})
+		};
+
+		out.push(AppToken {
+			token_id: item.token_id,
+			name: item.name,
+			token_type: item.token_type,
+			time: item.time,
+			expires_at: item.expires_at,
+			last_used: item.last_used,
+			scope,
+			access_options,
+		});
+	}
+
+	Ok(out)
+}
+
+pub async fn revoke_token(app_id: impl Into<AppId>, token_id: impl Into<String>) -> AppRes<()>
+{
+	let app_id = app_id.into();
+	let token_id = token_id.into();
+
+	let hashed_token = app_model::get_token_hash(&app_id, &token_id)
+		.await?
+		.ok_or_else(|| ServerCoreError::new_msg(400, ApiErrorCodes::AppTokenNotFound, "App token not found"))?;
+
+	app_model::delete_token(app_id, token_id).await?;
+
+	cache::delete(&(APP_TOKEN_CACHE.to_string() + &hashed_token.0)).await?;
+
+	Ok(())
+}
+
+/**
+Delete the cached app data of the default tokens and of all named tokens.
+
+The app data is cached per token, so every token still has the old options in the cache.
+ */
+pub(super) async fn delete_app_cache(app: &AppCustomerAccess, other_keys: Vec<String>) -> AppRes<()>
+{
+	let mut keys = other_keys;
+
+	keys.push(APP_TOKEN_CACHE.to_string() + &app.hashed_secret_token);
+	keys.push(APP_TOKEN_CACHE.to_string() + &app.hashed_public_token);
+
+	for token in app_model::get_token_hashes(&app.app_id).await? {
+		keys.push(APP_TOKEN_CACHE.to_string() + &token.0);
+	}
+
+	let keys: Vec<&str> = keys.iter().map(|k| k.as_str()).collect();
+
+	cache::delete_multiple(&keys).await
+}
+
+/**
+Move the app to another customer or customer group.
+
+The owner is part of the cached app data of every token, so the cache of all tokens is deleted.
+ */
+pub async fn transfer(app: &AppCustomerAccess, new_owner_id: impl Into<CustomerId>, new_owner_type: i32) -> AppRes<()>
+{
+	let new_owner_id = new_owner_id.into();
+
+	if app.owner_id == new_owner_id && app.owner_type == new_owner_type {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"The app is already owned by this customer or group",
+		));
+	}
+
+	app_model::transfer(&app.app_id, new_owner_id, new_owner_type).await?;
+
+	delete_app_cache(app, vec![]).await
+}
+
+//__________________________________________________________________________________________________
+
 pub(super) fn check_file_options(input: &AppFileOptionsInput) -> AppRes<()>
 {
 	//check the file option if the right storage is used
@@ -180,6 +390,52 @@ pub(super) fn check_file_options(input: &AppFileOptionsInput) -> AppRes<()>
 		}
 	}
 
+	if input.max_storage_app < 0 ||
+		input.max_storage_user < 0 ||
+		input.max_storage_group < 0 ||
+		input.max_files_app < 0 ||
+		input.max_files_user < 0 ||
+		input.max_files_group < 0
+	{
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong value for the file quotas. Use 0 for no limit.",
+		));
+	}
+
+	if input.session_lifetime < FILE_SESSION_LIFETIME_MIN || input.session_lifetime > FILE_SESSION_LIFETIME_MAX {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong value for the upload session lifetime. Use a value between 60 seconds and 7 days.",
+		));
+	}
+
+	if input.default_ttl < 0 || input.max_ttl < 0 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"The file ttl can't be negative",
+		));
+	}
+
+	if input.max_ttl > 0 && input.default_ttl > input.max_ttl {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"The default file ttl can't be greater than the max file ttl",
+		));
+	}
+
+	if input.version_keep_last < 0 || input.version_keep_days < 0 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"The file version retention can't be negative",
+		));
+	}
+
 	Ok(())
 }
 
@@ -201,9 +457,303 @@ pub(super) fn check_group_options(input: &AppGroupOption) -> AppRes<()>
 		));
 	}
 
+	if input.require_signed_keys != 0 && input.require_signed_keys != 1 {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong value for signed keys. Use 1 to require signed keys and 0 to not require them.",
+		));
+	}
+
+	Ok(())
+}
+
+pub(super) fn check_key_endpoint_options(input: &AppKeyEndpointOptionsInput) -> AppRes<()>
+{
+	if input.fallback != KEY_ENDPOINT_FALLBACK_NONE && input.fallback != KEY_ENDPOINT_FALLBACK_LOCAL {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong fallback for the key endpoint selected",
+		));
+	}
+
+	if input.timeout <= 0 || input.timeout > KEY_ENDPOINT_MAX_TIMEOUT {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong timeout for the key endpoint. Max timeout is 30000 ms",
+		));
+	}
+
+	if let Some(url) = &input.key_endpoint_url {
+		//only allow plain http when the server admin allows it, e.g. for local testing
+		let allow_http = env::var("KEY_ENDPOINT_ALLOW_HTTP").unwrap_or_else(|_| "0".into());
+
+		if !url.starts_with("https://") && !(allow_http == "1" && url.starts_with("http://")) {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::AppAction,
+				"The key endpoint must be a https url",
+			));
+		}
+
+		if input.auth_token.is_none() {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::AppAction,
+				"No auth token for the key endpoint set. The token is needed to sign the requests.",
+			));
+		}
+	}
+
+	if let Some(at) = &input.auth_token {
+		if at.len() < 16 || at.len() > 100 {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::AppAction,
+				"Auth token for the key endpoint must be between 16 and 100 characters",
+			));
+		}
+	}
+
+	Ok(())
+}
+
+pub(super) fn check_rate_limit_options(input: &AppRateLimitOptions) -> AppRes<()>
+{
+	for limit in [&input.auth, &input.group, &input.file, &input.key] {
+		if limit.capacity <= 0 {
+			//no limit for this endpoint group
+			continue;
+		}
+
+		if limit.capacity > RATE_LIMIT_MAX_CAPACITY || limit.refill_per_minute <= 0 || limit.refill_per_minute > RATE_LIMIT_MAX_CAPACITY {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::AppAction,
+				"Wrong rate limit. The capacity and the refill per minute must be between 1 and 100000",
+			));
+		}
+
+		if limit.limit_by != RATE_LIMIT_BY_TOKEN && limit.limit_by != RATE_LIMIT_BY_USER && limit.limit_by != RATE_LIMIT_BY_IP {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::AppAction,
+				"Wrong rate limit, please choose to limit by app token, user or ip",
+			));
+		}
+	}
+
+	Ok(())
+}
+
+pub(super) fn check_jwt_rotation_options(input: &AppJwtRotationOptions) -> AppRes<()>
+{
+	if input.rotation_days < 0 || input.rotation_days > JWT_ROTATION_MAX_DAYS {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong rotation period. Use 0 to disable the rotation or a value up to 365 days",
+		));
+	}
+
+	if input.grace_hours < 1 || input.grace_hours > JWT_ROTATION_GRACE_HOURS_MAX {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong grace period. The grace period must be between 1 hour and 30 days",
+		));
+	}
+
+	if input.rotation_days > 0 && input.grace_hours > input.rotation_days * 24 {
+		//otherwise there would be more and more keys which are still valid
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"The grace period can't be longer than the rotation period",
+		));
+	}
+
 	Ok(())
 }
 
+/**
+Validate the origin and ip allowlists and return them normalized like the middleware compares them.
+ */
+pub(super) fn check_access_options(input: &AppAccessOptions) -> AppRes<AppAccessOptions>
+{
+	if input.allowed_origins.len() > ACCESS_LIST_MAX || input.allowed_ips.len() > ACCESS_LIST_MAX {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Too many entries in the access list. Max is 100 origins and 100 ips.",
+		));
+	}
+
+	let mut allowed_origins = Vec::with_capacity(input.allowed_origins.len());
+
+	for origin in &input.allowed_origins {
+		let origin = normalize_origin(origin);
+
+		if (!origin.starts_with("https://") && !origin.starts_with("http://")) || origin.contains(',') {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::AppAction,
+				"Wrong origin in the access list. An origin must start with http:// or https://",
+			));
+		}
+
+		allowed_origins.push(origin);
+	}
+
+	let mut allowed_ips = Vec::with_capacity(input.allowed_ips.len());
+
+	for ip in &input.allowed_ips {
+		let ip = ip.trim();
+
+		if parse_ip_range(ip).is_none() {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::AppAction,
+				"Wrong ip in the access list. Use a single ip or a cidr range like 10.0.0.0/8",
+			));
+		}
+
+		allowed_ips.push(ip.to_string());
+	}
+
+	Ok(AppAccessOptions {
+		allowed_origins,
+		allowed_ips,
+	})
+}
+
+//__________________________________________________________________________________________________
+
+/**
+Export the configuration of the app without any secrets
+ */
+pub async fn export_config(app_id: impl Into<AppId>) -> AppRes<AppConfig>
+{
+	let app_id = app_id.into();
+
+	let (options, mut file_options, group_options, mut key_endpoint_options, access_options, rate_limit_options, jwt_rotation_options) = tokio::try_join!(
+		server_api_common::customer_app::get_app_options(&app_id),
+		app_model::get_app_file_options(&app_id),
+		app_model::get_app_group_options(&app_id),
+		app_model::get_app_key_endpoint_options(&app_id),
+		app_model::get_app_access_options(&app_id),
+		server_api_common::customer_app::get_rate_limit_options(&app_id),
+		app_model::get_app_jwt_rotation_options(&app_id)
+	)?;
+
+	file_options.auth_token = None;
+	key_endpoint_options.auth_token = None;
+
+	Ok(AppConfig {
+		version: APP_CONFIG_VERSION,
+		options,
+		file_options,
+		group_options,
+		key_endpoint_options,
+		access_options,
+		rate_limit_options,
+		jwt_rotation_options,
+	})
+}
+
+/**
+Validate the whole document before anything is saved. Returns the normalized access lists.
+ */
+fn check_config(config: &AppConfig) -> AppRes<AppAccessOptions>
+{
+	if config.version < 1 || config.version > APP_CONFIG_VERSION {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Unknown version of the app config document",
+		));
+	}
+
+	check_file_options(&config.file_options)?;
+	check_group_options(&config.group_options)?;
+	check_key_endpoint_options(&config.key_endpoint_options)?;
+	check_rate_limit_options(&config.rate_limit_options)?;
+	check_jwt_rotation_options(&config.jwt_rotation_options)?;
+
+	check_access_options(&config.access_options)
+}
+
+/**
+Overwrite the configuration of an existing app.
+
+The auth tokens of the app are kept when the document contains none and the url is not changed.
+ */
+pub async fn import_config(app: &AppCustomerAccess, mut config: AppConfig) -> AppRes<()>
+{
+	let (file_options, key_endpoint_options) = tokio::try_join!(
+		app_model::get_app_file_options(&app.app_id),
+		app_model::get_app_key_endpoint_options(&app.app_id)
+	)?;
+
+	if config.file_options.auth_token.is_none() && config.file_options.storage_url == file_options.storage_url {
+		config.file_options.auth_token = file_options.auth_token;
+	}
+
+	if config.key_endpoint_options.auth_token.is_none() && config.key_endpoint_options.key_endpoint_url == key_endpoint_options.key_endpoint_url {
+		config.key_endpoint_options.auth_token = key_endpoint_options.auth_token;
+	}
+
+	config.access_options = check_config(&config)?;
+
+	app_model::import_config(&app.app_id, config).await?;
+
+	//delete the app cache of all tokens because in the cache there are still the old options
+	delete_app_cache(app, vec![]).await
+}
+
+/**
+Create a new app with the options of the document
+ */
+pub async fn create_app_from_config(
+	input: AppConfigCreateInput,
+	customer_id: impl Into<CustomerId>,
+	group_id: Option<impl Into<GroupId>>,
+) -> AppRes<AppRegisterOutput>
+{
+	let access_options = check_config(&input.config)?;
+
+	let config = input.config;
+
+	create_app_with_config_options(
+		AppRegisterInput {
+			identifier: input.identifier,
+			options: config.options,
+			file_options: config.file_options,
+			group_options: config.group_options,
+		},
+		customer_id,
+		group_id,
+		//the other options are not part of the register input
+		Some(AppConfigOptions {
+			key_endpoint_options: config.key_endpoint_options,
+			rate_limit_options: config.rate_limit_options,
+			access_options,
+			jwt_rotation_options: config.jwt_rotation_options,
+		}),
+	)
+	.await
+}
+
+/**
+Get the app without the customer check, e.g. to import a config from the command line
+ */
+pub fn get_app_general_internal<'a>(app_id: impl Into<AppId> + 'a) -> impl Future<Output = AppRes<AppCustomerAccess>> + 'a
+{
+	app_model::get_app_general_internal(app_id)
+}
+
 pub fn get_all_apps<'a>(
 	customer_id: impl Into<CustomerId> + 'a,
 	last_fetched_time: u128,
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use rustgram_server_util::get_time;
+use rustgram_server_util::res::AppRes;
+use sentc_crypto_common::AppId;
+use server_api_common::metering::{begin_of_day, METERING_DAY};
+use server_dashboard_common::app::{APP_STATS_PERIOD_DAY, APP_STATS_PERIOD_MONTH};
+
+use crate::customer_app::app_entities::{AppStatsActionCount, AppStatsSnapshot};
+use crate::customer_app::app_model;
+use crate::metering::begin_of_month;
+
+/**
+Roll up the usage of all apps into the stats of the current day and month.
+
+The previous day and month are computed again to count the actions until the end of the period.
+ */
+pub async fn start() -> AppRes<()>
+{
+	let now = get_time()?;
+
+	let day_start = begin_of_day(now);
+	let month_start = begin_of_month(now);
+
+	let periods = [
+		(APP_STATS_PERIOD_DAY, day_start - METERING_DAY, day_start),
+		(APP_STATS_PERIOD_DAY, day_start, day_start + METERING_DAY),
+		(APP_STATS_PERIOD_MONTH, begin_of_month(month_start - 1), month_start),
+		(
+			APP_STATS_PERIOD_MONTH,
+			month_start,
+			begin_of_month(month_start + 32 * METERING_DAY),
+		),
+	];
+
+	for (period_type, start, end) in periods {
+		rollup_actions(period_type, start, end).await?;
+	}
+
+	//the current values only belong to the current periods
+	rollup_snapshot(&[(APP_STATS_PERIOD_DAY, day_start), (APP_STATS_PERIOD_MONTH, month_start)]).await
+}
+
+async fn rollup_actions(period_type: i32, start: u128, end: u128) -> AppRes<()>
+{
+	let mut map: HashMap<AppId, AppStatsActionCount> = HashMap::new();
+
+	for actions in app_model::get_stats_actions(start, end).await? {
+		let stats = map.entry(actions.app_id).or_default();
+
+		stats.active_users = actions.active_users;
+		stats.logins = actions.logins;
+		stats.refreshes = actions.refreshes;
+		stats.key_rotations = actions.key_rotations;
+	}
+
+	for registrations in app_model::get_stats_registrations(start, end).await? {
+		map.entry(registrations.app_id).or_default().registrations = registrations.count;
+	}
+
+	for (app_id, stats) in map {
+		app_model::save_stats_actions(&app_id, period_type, start, stats).await?;
+	}
+
+	Ok(())
+}
+
+async fn rollup_snapshot(periods: &[(i32, u128)]) -> AppRes<()>
+{
+	let mut map: HashMap<AppId, AppStatsSnapshot> = HashMap::new();
+
+	for usage in app_model::get_stats_usage().await? {
+		let stats = map.entry(usage.app_id).or_default();
+
+		stats.used_storage = usage.used_storage;
+		stats.file_count = usage.file_count;
+	}
+
+	for parts in app_model::get_stats_parts().await? {
+		map.entry(parts.app_id).or_default().part_count = parts.count;
+	}
+
+	for groups in app_model::get_stats_groups().await? {
+		map.entry(groups.app_id).or_default().group_count = groups.count;
+	}
+
+	for members in app_model::get_stats_members().await? {
+		map.entry(members.app_id).or_default().member_count = members.count;
+	}
+
+	for (app_id, stats) in map {
+		for (period_type, period_start) in periods {
+			app_model::save_stats_snapshot(&app_id, *period_type, *period_start, &stats).await?;
+		}
+	}
+
+	Ok(())
+}
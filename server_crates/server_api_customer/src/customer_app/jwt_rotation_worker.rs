@@ -0,0 +1,84 @@
+use rustgram_server_util::get_time;
+use rustgram_server_util::res::AppRes;
+use server_api_common::user::jwt::create_jwt_keys;
+use server_api_common::util::{get_app_jwt_sign_key, get_app_jwt_verify_key, get_kt_tree_head_key};
+
+use crate::customer_app::app_entities::RetiredJwtKey;
+use crate::customer_app::{app_model, app_service};
+
+const HOUR_MS: u128 = 60 * 60 * 1000;
+const DAY_MS: u128 = 24 * HOUR_MS;
+
+/**
+Create a new jwt key for every app where the newest key is older than the rotation period
+and delete the old keys after the grace period.
+
+The newest key is always used to sign the jwt, so the signing switches to the new key.
+The old keys can still verify the jwt until they are deleted.
+An error of an app is logged and the worker continues with the next app.
+ */
+pub async fn start() -> AppRes<()>
+{
+	let time = get_time()?;
+
+	for app in app_model::get_jwt_rotation_apps().await? {
+		if app.last_key_time + app.rotation_days as u128 * DAY_MS > time {
+			continue;
+		}
+
+		//one app must not stop the rotation of the other apps
+		if let Err(e) = rotate(&app.app_id, time + app.grace_hours as u128 * HOUR_MS).await {
+			eprintln!("jwt rotation failed for app: {}, {}", app.app_id, e.msg);
+		}
+	}
+
+	for key in app_model::get_retired_jwt_keys(time).await? {
+		let app_id = key.app_id.clone();
+
+		if let Err(e) = retire(key).await {
+			eprintln!("jwt key retirement failed for app: {}, {}", app_id, e.msg);
+		}
+	}
+
+	Ok(())
+}
+
+async fn rotate(app_id: &str, retire_at: u128) -> AppRes<()>
+{
+	let app = app_model::get_app_general_internal(app_id).await?;
+
+	//same as adding the keys manually
+	let (jwt_sign_key, jwt_verify_key, alg) = create_jwt_keys()?;
+
+	let jwt_id = app_model::add_jwt_keys(app_id, jwt_sign_key, jwt_verify_key, alg).await?;
+
+	app_model::retire_jwt_keys(app_id, &jwt_id, retire_at).await?;
+
+	//the jwt keys are cached with the app data of every token, the key transparency tree head is signed with the newest key
+	app_service::delete_app_cache(
+		&app,
+		vec![
+			get_app_jwt_verify_key(&jwt_id),
+			get_app_jwt_sign_key(&jwt_id),
+			get_kt_tree_head_key(app_id),
+		],
+	)
+	.await
+}
+
+async fn retire(key: RetiredJwtKey) -> AppRes<()>
+{
+	let app = app_model::get_app_general_internal(&key.app_id).await?;
+
+	app_model::delete_jwt_keys(&key.app_id, &key.jwt_key_id).await?;
+
+	app_service::delete_app_cache(
+		&app,
+		vec![
+			get_app_jwt_verify_key(&key.jwt_key_id),
+			get_app_jwt_sign_key(&key.jwt_key_id),
+			get_kt_tree_head_key(&key.app_id),
+		],
+	)
+	.await
+}
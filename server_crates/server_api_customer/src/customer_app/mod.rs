@@ -2,6 +2,8 @@ pub mod app_controller;
 pub mod app_entities;
 pub(crate) mod app_model;
 pub mod app_service;
+pub mod app_stats_worker;
+pub mod jwt_rotation_worker;
 
 use rand::RngCore;
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
@@ -0,0 +1,62 @@
+use std::env;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use rustgram_server_util::db::id_handling::create_id;
+use rustgram_server_util::get_time;
+use rustgram_server_util::res::AppRes;
+
+use crate::email::send_mail::{build_message, send_err, EmailMessage, EmailTransport};
+
+/**
+Writes every email into a maildir instead of sending it, for development and tests.
+
+The mails can be read with every mail client which supports maildir or directly from the new dir.
+ */
+pub(super) struct MaildirTransport
+{
+	path: PathBuf,
+	from: Mailbox,
+}
+
+impl MaildirTransport
+{
+	pub fn new(from: Mailbox) -> Self
+	{
+		let path = PathBuf::from(env::var("EMAIL_FILE_PATH").unwrap_or_else(|_| "storage_mail".to_string()));
+
+		for dir in ["tmp", "new", "cur"] {
+			std::fs::create_dir_all(path.join(dir)).unwrap();
+		}
+
+		Self {
+			path,
+			from,
+		}
+	}
+}
+
+#[async_trait]
+impl EmailTransport for MaildirTransport
+{
+	async fn send(&self, message: &EmailMessage) -> AppRes<()>
+	{
+		let email = build_message(&self.from, message)?;
+
+		let file_name = format!("{}.{}.sentc", get_time()?, create_id());
+
+		//write it into tmp first, so a reader never sees a half written mail in new
+		let tmp_path = self.path.join("tmp").join(&file_name);
+
+		tokio::fs::write(&tmp_path, email.formatted())
+			.await
+			.map_err(send_err)?;
+
+		tokio::fs::rename(tmp_path, self.path.join("new").join(file_name))
+			.await
+			.map_err(send_err)?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,84 @@
+use std::env;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use rustgram_server_util::res::AppRes;
+use serde::Serialize;
+
+use crate::email::send_mail::{send_err, EmailMessage, EmailTransport};
+
+const HTTP_TRANSPORT_TIMEOUT_SEC: u64 = 10;
+
+#[derive(Serialize)]
+struct HttpEmailBody<'a>
+{
+	from: String,
+	to: &'a str,
+	subject: &'a str,
+	text: &'a str,
+	html: &'a str,
+}
+
+/**
+Send the email as json to an api of an email provider or an own service.
+
+The auth token is sent as bearer token when it is set.
+ */
+pub(super) struct HttpTransport
+{
+	url: String,
+	auth_token: Option<String>,
+	from: Mailbox,
+}
+
+impl HttpTransport
+{
+	pub fn new(from: Mailbox) -> Self
+	{
+		//This is executed at the server start, so unwrap is ok here
+		let url = env::var("EMAIL_HTTP_URL").unwrap();
+		let auth_token = env::var("EMAIL_HTTP_TOKEN").ok();
+
+		Self {
+			url,
+			auth_token,
+			from,
+		}
+	}
+}
+
+#[async_trait]
+impl EmailTransport for HttpTransport
+{
+	async fn send(&self, message: &EmailMessage) -> AppRes<()>
+	{
+		let body = serde_json::to_string(&HttpEmailBody {
+			from: self.from.to_string(),
+			to: &message.to,
+			subject: &message.subject,
+			text: &message.body_txt,
+			html: &message.body_html,
+		})
+		.map_err(send_err)?;
+
+		let client = reqwest::Client::new();
+
+		let mut req = client
+			.post(self.url.as_str())
+			.timeout(Duration::from_secs(HTTP_TRANSPORT_TIMEOUT_SEC))
+			.header("content-type", "application/json");
+
+		if let Some(token) = &self.auth_token {
+			req = req.bearer_auth(token);
+		}
+
+		let res = req.body(body).send().await.map_err(send_err)?;
+
+		if !res.status().is_success() {
+			return Err(send_err(format!("email api returned status {}", res.status())));
+		}
+
+		Ok(())
+	}
+}
@@ -1,5 +1,13 @@
 #[cfg(feature = "send_mail")]
+mod file_transport;
+#[cfg(feature = "send_mail")]
+mod http_transport;
+#[cfg(feature = "send_mail")]
 pub mod send_mail;
+#[cfg(feature = "send_mail")]
+mod smtp_transport;
+#[cfg(feature = "send_mail")]
+pub mod template;
 
 use regex::Regex;
 use tokio::sync::OnceCell;
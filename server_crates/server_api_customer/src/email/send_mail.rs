@@ -1,111 +1,103 @@
 use std::env;
-use std::future::Future;
 
+use async_trait::async_trait;
 use lettre::message::{header, Mailbox, MultiPart, SinglePart};
-use lettre::transport::smtp::authentication::Credentials;
-use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use lettre::Message;
 use rustgram_server_util::error::{CoreErrorCodes, ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::res::AppRes;
 use tokio::sync::OnceCell;
 
-static EMAIL_SENDER_REGISTER: OnceCell<Email> = OnceCell::const_new();
+use crate::email::file_transport::MaildirTransport;
+use crate::email::http_transport::HttpTransport;
+use crate::email::smtp_transport::SmtpTransport;
+use crate::email::template;
+
+pub struct EmailMessage
+{
+	pub to: String,
+	pub subject: String,
+	pub body_txt: String,
+	pub body_html: String,
+}
+
+#[async_trait]
+pub trait EmailTransport: Send + Sync
+{
+	async fn send(&self, message: &EmailMessage) -> AppRes<()>;
+}
+
+static EMAIL_TRANSPORT: OnceCell<Box<dyn EmailTransport>> = OnceCell::const_new();
 
 pub async fn init_email_register()
 {
-	let from = format!("Sentc <{}>", env::var("EMAIL_ADDRESS").unwrap());
+	template::init_templates().await;
+
+	let from: Mailbox = format!("Sentc <{}>", env::var("EMAIL_ADDRESS").unwrap())
+		.parse()
+		.unwrap();
+
+	let transport = env::var("EMAIL_TRANSPORT").unwrap_or_else(|_| "0".to_string());
 
-	EMAIL_SENDER_REGISTER
+	//This is executed at the server start, so unwrap is ok here
+	EMAIL_TRANSPORT
 		.get_or_init(move || {
-			async {
-				//init email with env vars
-				Email::new(from)
+			async move {
+				let transport: Box<dyn EmailTransport> = match transport.as_str() {
+					"1" => Box::new(MaildirTransport::new(from)),
+					"2" => Box::new(HttpTransport::new(from)),
+					_ => Box::new(SmtpTransport::new(from)),
+				};
+
+				transport
 			}
 		})
 		.await;
 }
 
-pub fn send_mail_registration<'a>(
-	to: &'a str,
-	subject: &'a str,
-	body_txt: String,
-	body_html: String,
-) -> impl Future<Output = Result<(), ServerCoreError>> + 'a
+pub async fn send_email(message: &EmailMessage) -> AppRes<()>
 {
-	let email = EMAIL_SENDER_REGISTER.get().unwrap();
+	let transport = EMAIL_TRANSPORT.get().unwrap();
 
-	email.send_email_smpt(to, subject, body_txt, body_html)
+	transport.send(message).await
 }
 
-struct Email
+/**
+Build the mime message with the plain text fallback for the smtp and the maildir transport
+ */
+pub(super) fn build_message(from: &Mailbox, message: &EmailMessage) -> AppRes<Message>
 {
-	user: String,
-	pw: String,
-	server: String,
-	port: u16,
-	from: Mailbox,
+	let to = message
+		.to
+		.parse()
+		.map_err(|_e| ServerCoreError::new_msg(400, CoreErrorCodes::EmailMessage, "Error in email message"))?;
+
+	Message::builder()
+		.from(from.clone())
+		.to(to)
+		.subject(message.subject.as_str())
+		.multipart(
+			MultiPart::alternative()
+				.singlepart(
+					//plain text fallback
+					SinglePart::builder()
+						.header(header::ContentType::TEXT_PLAIN)
+						.body(message.body_txt.clone()),
+				)
+				.singlepart(
+					SinglePart::builder()
+						.header(header::ContentType::TEXT_HTML)
+						.body(message.body_html.clone()),
+				),
+		)
+		.map_err(|_e| ServerCoreError::new_msg(400, CoreErrorCodes::EmailMessage, "Error in email message"))
 }
 
-impl Email
+pub(super) fn send_err(e: impl ToString) -> ServerCoreError
 {
-	pub fn new(from: String) -> Self
-	{
-		//This is executed at the server start, so unwrap is ok here
-		let user = env::var("EMAIL_USER").unwrap();
-		let pw = env::var("EMAIL_PW").unwrap();
-		let server = env::var("EMAIL_SERVER").unwrap();
-		let port = env::var("EMAIL_PORT").unwrap().parse().unwrap();
-		let from = from.parse().unwrap();
-
-		Self {
-			user,
-			pw,
-			server,
-			port,
-			from,
-		}
-	}
-
-	pub async fn send_email_smpt(&self, to: &str, subject: &str, body_txt: String, body_html: String) -> Result<(), ServerCoreError>
-	{
-		let smtp_credentials = Credentials::new(self.user.to_string(), self.pw.to_string());
-		let mailer = AsyncSmtpTransport::<Tokio1Executor>::relay(self.server.as_str())
-			.unwrap()
-			.credentials(smtp_credentials)
-			.port(self.port)
-			.build();
-
-		let to = to
-			.parse()
-			.map_err(|_e| ServerCoreError::new_msg(400, CoreErrorCodes::EmailMessage, "Error in email message"))?;
-
-		let email = Message::builder()
-			.from(self.from.clone())
-			.to(to)
-			.subject(subject)
-			.multipart(
-				MultiPart::alternative()
-					.singlepart(
-						//plain text fallback
-						SinglePart::builder()
-							.header(header::ContentType::TEXT_PLAIN)
-							.body(body_txt),
-					)
-					.singlepart(
-						SinglePart::builder()
-							.header(header::ContentType::TEXT_HTML)
-							.body(body_html),
-					),
-			)
-			.map_err(|_e| ServerCoreError::new_msg(400, CoreErrorCodes::EmailMessage, "Error in email message"))?;
-
-		mailer.send(email).await.map_err(|e| {
-			ServerCoreError::new_msg_owned(
-				400,
-				CoreErrorCodes::EmailSend,
-				format!("Error in email send: {}", e),
-				None,
-			)
-		})?;
-
-		Ok(())
-	}
+	ServerCoreError::new_msg_owned(
+		400,
+		CoreErrorCodes::EmailSend,
+		format!("Error in email send: {}", e.to_string()),
+		None,
+	)
 }
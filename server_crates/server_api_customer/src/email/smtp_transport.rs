@@ -0,0 +1,58 @@
+use std::env;
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use rustgram_server_util::res::AppRes;
+
+use crate::email::send_mail::{build_message, send_err, EmailMessage, EmailTransport};
+
+pub(super) struct SmtpTransport
+{
+	user: String,
+	pw: String,
+	server: String,
+	port: u16,
+	from: Mailbox,
+}
+
+impl SmtpTransport
+{
+	pub fn new(from: Mailbox) -> Self
+	{
+		//This is executed at the server start, so unwrap is ok here
+		let user = env::var("EMAIL_USER").unwrap();
+		let pw = env::var("EMAIL_PW").unwrap();
+		let server = env::var("EMAIL_SERVER").unwrap();
+		let port = env::var("EMAIL_PORT").unwrap().parse().unwrap();
+
+		Self {
+			user,
+			pw,
+			server,
+			port,
+			from,
+		}
+	}
+}
+
+#[async_trait]
+impl EmailTransport for SmtpTransport
+{
+	async fn send(&self, message: &EmailMessage) -> AppRes<()>
+	{
+		let smtp_credentials = Credentials::new(self.user.to_string(), self.pw.to_string());
+		let mailer = AsyncSmtpTransport::<Tokio1Executor>::relay(self.server.as_str())
+			.unwrap()
+			.credentials(smtp_credentials)
+			.port(self.port)
+			.build();
+
+		let email = build_message(&self.from, message)?;
+
+		mailer.send(email).await.map_err(send_err)?;
+
+		Ok(())
+	}
+}
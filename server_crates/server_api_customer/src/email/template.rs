@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::env;
+
+use tokio::sync::OnceCell;
+
+static EMAIL_TEMPLATES: OnceCell<EmailTemplates> = OnceCell::const_new();
+
+/**
+The templates which are used when there is no own template for the locale
+ */
+const DEFAULT_TEMPLATES: [(&str, &str, &str); 3] = [
+	(
+		"register",
+		"Sentc Email validation for registration",
+		"Thanks for registration at sentc. Please verify your Email.",
+	),
+	(
+		"pw_reset",
+		"Sentc Password reset",
+		"Your forgot your password at sentc? Please verify your Email before resetting the password.",
+	),
+	(
+		"email_update",
+		"Sentc Email update",
+		"You updated your Email address for sentc. Please verify your new Email address.",
+	),
+];
+
+pub struct RenderedEmail
+{
+	pub subject: String,
+	pub body_txt: String,
+	pub body_html: String,
+}
+
+/**
+Load the templates from the template dir.
+
+Every locale has its own dir with the files for each email:
+- `<dir>/<locale>/<name>.subject.txt`
+- `<dir>/<locale>/<name>.txt`
+- `<dir>/<locale>/<name>.html`
+
+A file which is missing for a locale falls back to the language without the region (de-at -> de),
+then to the default locale and then to the default template.
+ */
+pub(super) async fn init_templates()
+{
+	EMAIL_TEMPLATES
+		.get_or_init(move || {
+			async {
+				//This is executed at the server start, so unwrap is ok here
+				EmailTemplates::new()
+			}
+		})
+		.await;
+}
+
+/**
+Render the subject and the bodies. The vars are used as {{name}} in the templates, the subject is also available as {{subject}}.
+ */
+pub fn render(name: &str, locale: Option<&str>, vars: &[(&str, &str)]) -> RenderedEmail
+{
+	let templates = EMAIL_TEMPLATES.get().unwrap();
+
+	let locales = templates.get_locales(locale);
+
+	let subject = replace_vars(templates.get(&locales, &format!("{name}.subject.txt")), vars, false);
+
+	let mut vars = vars.to_vec();
+	vars.push(("subject", subject.as_str()));
+
+	RenderedEmail {
+		body_txt: replace_vars(templates.get(&locales, &format!("{name}.txt")), &vars, false),
+		body_html: replace_vars(templates.get(&locales, &format!("{name}.html")), &vars, true),
+		subject,
+	}
+}
+
+/**
+Normalize the locale from the accept language header, e.g. de-DE,de;q=0.9,en;q=0.8 -> de-de
+ */
+pub fn parse_locale(accept_language: &str) -> Option<String>
+{
+	let locale = accept_language
+		.split(',')
+		.next()?
+		.split(';')
+		.next()?
+		.trim()
+		.to_lowercase()
+		.replace('_', "-");
+
+	if locale.is_empty() ||
+		locale.len() > 35 ||
+		!locale
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || c == '-')
+	{
+		return None;
+	}
+
+	Some(locale)
+}
+
+/**
+Replace the vars in one pass, so a value which contains a {{name}} is not replaced again.
+Unknown vars stay in the text.
+ */
+fn replace_vars(template: &str, vars: &[(&str, &str)], html: bool) -> String
+{
+	let mut out = String::with_capacity(template.len());
+	let mut rest = template;
+
+	while let Some(start) = rest.find("{{") {
+		out.push_str(&rest[..start]);
+		rest = &rest[start..];
+
+		let value = rest.find("}}").and_then(|end| {
+			let name = &rest[2..end];
+
+			vars.iter()
+				.find(|(n, _)| *n == name)
+				.map(|(_, value)| (end + 2, value))
+		});
+
+		match value {
+			Some((len, value)) => {
+				if html {
+					out.push_str(&escape_html(value));
+				} else {
+					out.push_str(value);
+				}
+
+				rest = &rest[len..];
+			},
+			None => {
+				out.push_str("{{");
+				rest = &rest[2..];
+			},
+		}
+	}
+
+	out.push_str(rest);
+
+	out
+}
+
+fn escape_html(value: &str) -> String
+{
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&#39;")
+}
+
+struct EmailTemplates
+{
+	//locale/file name -> template. The default templates have no locale
+	templates: HashMap<String, String>,
+	default_locale: String,
+}
+
+impl EmailTemplates
+{
+	fn new() -> Self
+	{
+		let mut templates = HashMap::new();
+
+		for (name, subject, text) in DEFAULT_TEMPLATES {
+			templates.insert(format!("/{name}.subject.txt"), subject.to_string());
+			templates.insert(format!("/{name}.txt"), default_text(text));
+			templates.insert(format!("/{name}.html"), default_html(text));
+		}
+
+		if let Ok(dir) = env::var("EMAIL_TEMPLATE_DIR") {
+			for locale_dir in std::fs::read_dir(dir).unwrap() {
+				let locale_dir = locale_dir.unwrap();
+
+				if !locale_dir.file_type().unwrap().is_dir() {
+					continue;
+				}
+
+				let locale = locale_dir.file_name().to_string_lossy().to_lowercase();
+
+				for file in std::fs::read_dir(locale_dir.path()).unwrap() {
+					let file = file.unwrap();
+
+					if !file.file_type().unwrap().is_file() {
+						continue;
+					}
+
+					let content = std::fs::read_to_string(file.path()).unwrap();
+
+					templates.insert(
+						format!("{}/{}", locale, file.file_name().to_string_lossy()),
+						content.trim_end().to_string(),
+					);
+				}
+			}
+		}
+
+		Self {
+			templates,
+			default_locale: env::var("EMAIL_TEMPLATE_DEFAULT_LOCALE")
+				.unwrap_or_else(|_| "en".to_string())
+				.to_lowercase(),
+		}
+	}
+
+	fn get_locales(&self, locale: Option<&str>) -> Vec<String>
+	{
+		let mut locales = Vec::with_capacity(4);
+
+		if let Some(locale) = locale {
+			locales.push(locale.to_string());
+
+			if let Some((lang, _)) = locale.split_once('-') {
+				locales.push(lang.to_string());
+			}
+		}
+
+		locales.push(self.default_locale.clone());
+		locales.push(String::new());
+
+		locales
+	}
+
+	fn get(&self, locales: &[String], file_name: &str) -> &str
+	{
+		for locale in locales {
+			if let Some(template) = self.templates.get(&format!("{locale}/{file_name}")) {
+				return template;
+			}
+		}
+
+		""
+	}
+}
+
+fn default_text(text: &str) -> String
+{
+	format!(
+		r"{}
+Go to {{{{link}}}} or enter your token: {{{{token}}}}
+	",
+		text
+	)
+}
+
+fn default_html(text: &str) -> String
+{
+	//language=HTML
+	format!(
+		r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+	<meta charset="UTF-8">
+	<meta name="viewport" content="width=device-width, initial-scale=1.0">
+	<title>Sentc {{{{subject}}}}</title>
+</head>
+<body>
+	<div style="display: flex; flex-direction: column; align-items: center">
+		<div style="text-align: left;">
+			<h1>{{{{subject}}}}</h1>
+
+			<p>
+				{}
+			</p>
+
+			<p>
+				<a href="{{{{link}}}}">Click here</a>
+				<br>
+				<br>
+				Or enter your token: <br>
+				{{{{token}}}}
+			</p>
+		</div>
+	</div>
+</body>
+</html>"#,
+		text
+	)
+}
+
+#[cfg(test)]
+mod test
+{
+	use super::*;
+
+	fn templates(files: &[(&str, &str)]) -> EmailTemplates
+	{
+		let mut templates: HashMap<String, String> = files
+			.iter()
+			.map(|(k, v)| (k.to_string(), v.to_string()))
+			.collect();
+
+		templates.insert("/register.txt".to_string(), "built-in".to_string());
+
+		EmailTemplates {
+			templates,
+			default_locale: "en".to_string(),
+		}
+	}
+
+	fn get(templates: &EmailTemplates, locale: Option<&str>) -> String
+	{
+		templates
+			.get(&templates.get_locales(locale), "register.txt")
+			.to_string()
+	}
+
+	#[test]
+	fn test_locale_fallback()
+	{
+		let all = templates(&[("de-at/register.txt", "de-at"), ("de/register.txt", "de"), ("en/register.txt", "en")]);
+
+		assert_eq!(get(&all, Some("de-at")), "de-at");
+		assert_eq!(get(&all, Some("de-de")), "de");
+		assert_eq!(get(&all, Some("de")), "de");
+		assert_eq!(get(&all, Some("fr-fr")), "en");
+		assert_eq!(get(&all, None), "en");
+
+		let without_default = templates(&[("de/register.txt", "de")]);
+
+		assert_eq!(get(&without_default, Some("de-at")), "de");
+		assert_eq!(get(&without_default, Some("fr")), "built-in");
+		assert_eq!(get(&without_default, None), "built-in");
+	}
+
+	#[test]
+	fn test_parse_locale()
+	{
+		assert_eq!(parse_locale("de-DE,de;q=0.9,en;q=0.8"), Some("de-de".to_string()));
+		assert_eq!(parse_locale("en;q=0.8"), Some("en".to_string()));
+		assert_eq!(parse_locale(" de_AT "), Some("de-at".to_string()));
+		assert_eq!(parse_locale(""), None);
+		assert_eq!(parse_locale("../de"), None);
+		assert_eq!(parse_locale(&"a".repeat(36)), None);
+	}
+
+	#[test]
+	fn test_replace_vars()
+	{
+		let vars = [("token", "<b>&'\"</b>"), ("link", "{{token}}")];
+
+		assert_eq!(
+			replace_vars("{{token}} {{link}} {{other}} {{", &vars, false),
+			"<b>&'\"</b> {{token}} {{other}} {{"
+		);
+
+		assert_eq!(
+			replace_vars("<p>{{token}}</p>", &vars, true),
+			"<p>&lt;b&gt;&amp;&#39;&quot;&lt;/b&gt;</p>"
+		);
+	}
+}
@@ -3,10 +3,12 @@
 use rustgram::Router;
 use rustgram_server_util::error::ServerErrorCodes;
 
+pub mod audit;
 mod captcha;
 pub mod customer;
 pub mod customer_app;
 mod email;
+pub mod metering;
 pub mod mw;
 mod routes;
 
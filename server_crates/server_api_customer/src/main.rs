@@ -19,5 +19,5 @@ pub async fn main()
 	.parse()
 	.unwrap();
 
-	rustgram::start(router, addr).await;
+	server_api_common::start_server(router, addr).await;
 }
@@ -0,0 +1,71 @@
+use hyper::Body;
+use rustgram::service::IntoResponse;
+use rustgram::{Request, Response};
+use rustgram_server_util::res::AppRes;
+use rustgram_server_util::url_helper::{get_name_param_from_params, get_params, get_time_from_url_param};
+use server_api_common::group::get_group_user_data_from_req;
+
+use crate::customer_app::app_controller::get_app_general_data;
+use crate::metering::metering_entities::MeteringFormat;
+use crate::metering::metering_service;
+
+pub async fn export_app(req: Request) -> Response
+{
+	export_app_internally(req)
+		.await
+		.unwrap_or_else(|e| e.into_response())
+}
+
+pub async fn export_group(req: Request) -> Response
+{
+	export_group_internally(req)
+		.await
+		.unwrap_or_else(|e| e.into_response())
+}
+
+async fn export_app_internally(req: Request) -> AppRes<Response>
+{
+	let app_general_data = get_app_general_data(&req)?;
+
+	let (from, to, format) = get_export_params(&req)?;
+
+	let out = metering_service::export_app(&app_general_data.app_id, from, to, &format).await?;
+
+	Ok(export_response(out, &format))
+}
+
+async fn export_group_internally(req: Request) -> AppRes<Response>
+{
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	let (from, to, format) = get_export_params(&req)?;
+
+	let out = metering_service::export_apps_of_group(&group_data.group_data.id, from, to, &format).await?;
+
+	Ok(export_response(out, &format))
+}
+
+fn get_export_params(req: &Request) -> AppRes<(u128, u128, MeteringFormat)>
+{
+	let params = get_params(req)?;
+
+	let format = metering_service::get_format(get_name_param_from_params(params, "format")?)?;
+	let from = get_time_from_url_param(get_name_param_from_params(params, "from")?)?;
+	let to = get_time_from_url_param(get_name_param_from_params(params, "to")?)?;
+
+	Ok((from, to, format))
+}
+
+fn export_response(out: String, format: &MeteringFormat) -> Response
+{
+	let content_type = match format {
+		MeteringFormat::Csv => "text/csv",
+		MeteringFormat::Json => "application/json",
+	};
+
+	hyper::Response::builder()
+		.header("Content-Type", content_type)
+		.header("Access-Control-Allow-Origin", "*")
+		.body(Body::from(out))
+		.unwrap()
+}
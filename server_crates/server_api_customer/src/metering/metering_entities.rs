@@ -0,0 +1,18 @@
+use rustgram_server_util::DB;
+use sentc_crypto_common::AppId;
+
+/**
+A billable unit of an app at a day
+ */
+#[derive(DB)]
+pub struct MeteringCount
+{
+	pub app_id: AppId,
+	pub count: i64,
+}
+
+pub enum MeteringFormat
+{
+	Csv,
+	Json,
+}
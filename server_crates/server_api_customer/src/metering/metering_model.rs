@@ -0,0 +1,239 @@
+use rustgram_server_util::db::{exec_transaction, query, Params, TransactionData};
+use rustgram_server_util::res::AppRes;
+use rustgram_server_util::set_params;
+use sentc_crypto_common::{AppId, CustomerId};
+use server_api::sentc_user_service::UserAction;
+use server_api_common::file::FILE_USAGE_TYPE_APP;
+use server_dashboard_common::app::{AppMetering, APP_STATS_PERIOD_MONTH};
+
+use crate::customer_app::app_entities::CUSTOMER_OWNER_TYPE_GROUP;
+use crate::metering::metering_entities::MeteringCount;
+
+/**
+The active users of the month are already counted by the app stats worker
+ */
+pub(super) async fn get_monthly_active_users(month_start: u128) -> AppRes<Vec<MeteringCount>>
+{
+	//language=SQL
+	let sql = "SELECT app_id, active_users FROM sentc_app_stats WHERE period_type = ? AND period_start = ?";
+
+	query(sql, set_params!(APP_STATS_PERIOD_MONTH, month_start.to_string())).await
+}
+
+/**
+The amount of a key rotation action is the number of the group members
+ */
+pub(super) async fn get_key_rotation_members(start: u128, end: u128) -> AppRes<Vec<MeteringCount>>
+{
+	//language=SQL
+	let sql = r"
+SELECT app_id, COALESCE(SUM(amount), 0) 
+FROM sentc_user_action_log 
+WHERE 
+    action_id = ? AND 
+    time >= ? AND 
+    time < ? 
+GROUP BY app_id";
+
+	query(
+		sql,
+		set_params!(
+			UserAction::KeyRotation.get_int_code(),
+			start.to_string(),
+			end.to_string()
+		),
+	)
+	.await
+}
+
+pub(super) async fn get_stored_bytes() -> AppRes<Vec<MeteringCount>>
+{
+	//language=SQL
+	let sql = "SELECT app_id, used_storage FROM sentc_file_usage WHERE usage_type = ? AND id = app_id";
+
+	query(sql, set_params!(FILE_USAGE_TYPE_APP)).await
+}
+
+fn prepare_metering_init(app_id: &str, day: u128) -> (&'static str, Params)
+{
+	#[cfg(feature = "mysql")]
+	//language=SQL
+	let sql = "INSERT IGNORE INTO sentc_app_metering (app_id, day) VALUES (?,?)";
+
+	#[cfg(feature = "sqlite")]
+	let sql = "INSERT OR IGNORE INTO sentc_app_metering (app_id, day) VALUES (?,?)";
+
+	(sql, set_params!(app_id.to_string(), day.to_string()))
+}
+
+pub(super) async fn save_actions(app_id: &str, day: u128, monthly_active_users: i64, key_rotation_members: i64) -> AppRes<()>
+{
+	let (sql_init, params_init) = prepare_metering_init(app_id, day);
+
+	//language=SQL
+	let sql = "UPDATE sentc_app_metering SET monthly_active_users = ?, key_rotation_members = ? WHERE app_id = ? AND day = ?";
+
+	exec_transaction(vec![
+		TransactionData {
+			sql: sql_init,
+			params: params_init,
+		},
+		TransactionData {
+			sql,
+			params: set_params!(
+				monthly_active_users,
+				key_rotation_members,
+				app_id.to_string(),
+				day.to_string()
+			),
+		},
+	])
+	.await?;
+
+	Ok(())
+}
+
+pub(super) async fn save_stored_bytes(app_id: &str, day: u128, stored_bytes: i64) -> AppRes<()>
+{
+	let (sql_init, params_init) = prepare_metering_init(app_id, day);
+
+	//language=SQL
+	let sql = "UPDATE sentc_app_metering SET stored_bytes = ? WHERE app_id = ? AND day = ?";
+
+	exec_transaction(vec![
+		TransactionData {
+			sql: sql_init,
+			params: params_init,
+		},
+		TransactionData {
+			sql,
+			params: set_params!(stored_bytes, app_id.to_string(), day.to_string()),
+		},
+	])
+	.await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+// export
+
+pub(super) async fn get_app(app_id: impl Into<AppId>, from: u128, to: u128) -> AppRes<Vec<AppMetering>>
+{
+	//language=SQL
+	let sql = r"
+SELECT 
+    app_id, 
+    MAX(monthly_active_users), 
+    MAX(stored_bytes), 
+    SUM(transferred_bytes), 
+    SUM(key_rotation_members) 
+FROM sentc_app_metering 
+WHERE 
+    app_id = ? AND 
+    day >= ? AND 
+    day <= ? 
+GROUP BY app_id";
+
+	query(sql, set_params!(app_id.into(), from.to_string(), to.to_string())).await
+}
+
+/**
+All apps of a customer group, deleted apps are not in the group anymore
+ */
+pub(super) async fn get_apps_of_group(group_id: impl Into<CustomerId>, from: u128, to: u128) -> AppRes<Vec<AppMetering>>
+{
+	//language=SQL
+	let sql = r"
+SELECT 
+    m.app_id, 
+    MAX(monthly_active_users), 
+    MAX(stored_bytes), 
+    SUM(transferred_bytes), 
+    SUM(key_rotation_members) 
+FROM 
+    sentc_app_metering m, 
+    sentc_app a 
+WHERE 
+    a.id = m.app_id AND 
+    a.owner_id = ? AND 
+    a.owner_type = ? AND 
+    day >= ? AND 
+    day <= ? 
+GROUP BY m.app_id 
+ORDER BY m.app_id";
+
+	query(
+		sql,
+		set_params!(
+			group_id.into(),
+			CUSTOMER_OWNER_TYPE_GROUP,
+			from.to_string(),
+			to.to_string()
+		),
+	)
+	.await
+}
+
+/**
+All apps of the server, also the deleted apps
+ */
+pub(super) async fn get_all_apps(from: u128, to: u128) -> AppRes<Vec<AppMetering>>
+{
+	//language=SQL
+	let sql = r"
+SELECT 
+    app_id, 
+    MAX(monthly_active_users), 
+    MAX(stored_bytes), 
+    SUM(transferred_bytes), 
+    SUM(key_rotation_members) 
+FROM sentc_app_metering 
+WHERE 
+    day >= ? AND 
+    day <= ? 
+GROUP BY app_id 
+ORDER BY app_id";
+
+	query(sql, set_params!(from.to_string(), to.to_string())).await
+}
+
+/**
+The sum of the apps of each customer group
+ */
+pub(super) async fn get_all_groups(from: u128, to: u128) -> AppRes<Vec<AppMetering>>
+{
+	//language=SQL
+	let sql = r"
+SELECT 
+    owner_id, 
+    SUM(monthly_active_users), 
+    SUM(stored_bytes), 
+    SUM(transferred_bytes), 
+    SUM(key_rotation_members) 
+FROM (
+    SELECT 
+        a.owner_id, 
+        MAX(monthly_active_users) as monthly_active_users, 
+        MAX(stored_bytes) as stored_bytes, 
+        SUM(transferred_bytes) as transferred_bytes, 
+        SUM(key_rotation_members) as key_rotation_members 
+    FROM 
+        sentc_app_metering m, 
+        sentc_app a 
+    WHERE 
+        a.id = m.app_id AND 
+        a.owner_type = ? AND 
+        day >= ? AND 
+        day <= ? 
+    GROUP BY a.owner_id, m.app_id
+) as apps 
+GROUP BY owner_id 
+ORDER BY owner_id";
+
+	query(
+		sql,
+		set_params!(CUSTOMER_OWNER_TYPE_GROUP, from.to_string(), to.to_string()),
+	)
+	.await
+}
@@ -0,0 +1,81 @@
+use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::res::AppRes;
+use sentc_crypto_common::{AppId, CustomerId};
+use server_dashboard_common::app::AppMetering;
+
+use crate::metering::metering_entities::MeteringFormat;
+use crate::metering::metering_model;
+use crate::ApiErrorCodes;
+
+pub fn get_format(format: &str) -> AppRes<MeteringFormat>
+{
+	if format == "csv" {
+		Ok(MeteringFormat::Csv)
+	} else if format == "json" {
+		Ok(MeteringFormat::Json)
+	} else {
+		Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::AppAction,
+			"Wrong export format, please choose csv or json",
+		))
+	}
+}
+
+pub async fn export_app(app_id: impl Into<AppId>, from: u128, to: u128, format: &MeteringFormat) -> AppRes<String>
+{
+	let list = metering_model::get_app(app_id, from, to).await?;
+
+	export(&list, format)
+}
+
+pub async fn export_apps_of_group(group_id: impl Into<CustomerId>, from: u128, to: u128, format: &MeteringFormat) -> AppRes<String>
+{
+	let list = metering_model::get_apps_of_group(group_id, from, to).await?;
+
+	export(&list, format)
+}
+
+/**
+Export the metering of all apps of the server
+ */
+pub async fn export_all_apps(from: u128, to: u128, format: &MeteringFormat) -> AppRes<String>
+{
+	let list = metering_model::get_all_apps(from, to).await?;
+
+	export(&list, format)
+}
+
+/**
+Export the metering of all customer groups of the server
+ */
+pub async fn export_all_groups(from: u128, to: u128, format: &MeteringFormat) -> AppRes<String>
+{
+	let list = metering_model::get_all_groups(from, to).await?;
+
+	export(&list, format)
+}
+
+fn export(list: &[AppMetering], format: &MeteringFormat) -> AppRes<String>
+{
+	match format {
+		MeteringFormat::Csv => Ok(to_csv(list)),
+		MeteringFormat::Json => {
+			serde_json::to_string(list).map_err(|_e| ServerCoreError::new_msg(400, ApiErrorCodes::AppAction, "Can't export the metering"))
+		},
+	}
+}
+
+fn to_csv(list: &[AppMetering]) -> String
+{
+	let mut out = String::from("id,monthly_active_users,stored_bytes,transferred_bytes,key_rotation_members\n");
+
+	for m in list {
+		out.push_str(&format!(
+			"{},{},{},{},{}\n",
+			m.id, m.monthly_active_users, m.stored_bytes, m.transferred_bytes, m.key_rotation_members
+		));
+	}
+
+	out
+}
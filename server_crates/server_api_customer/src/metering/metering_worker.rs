@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use rustgram_server_util::get_time;
+use rustgram_server_util::res::AppRes;
+use sentc_crypto_common::AppId;
+use server_api_common::metering::{begin_of_day, METERING_DAY};
+
+use crate::metering::{begin_of_month, metering_model};
+
+/**
+Save the billable units of all apps for the current day.
+
+The previous day is computed again to count the actions until the end of the day.
+The monthly active users are taken from the month stats, so they are as current as the last run of the app stats worker.
+The transferred bytes are saved by the metering worker of the file server.
+ */
+pub async fn start() -> AppRes<()>
+{
+	let today = begin_of_day(get_time()?);
+
+	for day in [today - METERING_DAY, today] {
+		rollup_actions(day).await?;
+	}
+
+	//the stored bytes are only known for the current day
+	for stored in metering_model::get_stored_bytes().await? {
+		metering_model::save_stored_bytes(&stored.app_id, today, stored.count).await?;
+	}
+
+	Ok(())
+}
+
+async fn rollup_actions(day: u128) -> AppRes<()>
+{
+	let end = day + METERING_DAY;
+
+	//(monthly active users, key rotation members)
+	let mut map: HashMap<AppId, (i64, i64)> = HashMap::new();
+
+	for users in metering_model::get_monthly_active_users(begin_of_month(day)).await? {
+		map.entry(users.app_id).or_default().0 = users.count;
+	}
+
+	for members in metering_model::get_key_rotation_members(day, end).await? {
+		map.entry(members.app_id).or_default().1 = members.count;
+	}
+
+	for (app_id, (monthly_active_users, key_rotation_members)) in map {
+		metering_model::save_actions(&app_id, day, monthly_active_users, key_rotation_members).await?;
+	}
+
+	Ok(())
+}
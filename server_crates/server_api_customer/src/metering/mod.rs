@@ -0,0 +1,19 @@
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+
+pub mod metering_controller;
+pub mod metering_entities;
+pub(crate) mod metering_model;
+pub mod metering_service;
+pub mod metering_worker;
+
+/**
+The begin of the month in utc of the time, the metering and the app stats use it for the monthly values.
+ */
+pub(crate) fn begin_of_month(time: u128) -> u128
+{
+	let date: DateTime<Utc> = Utc.timestamp_millis_opt(time as i64).unwrap();
+
+	Utc.with_ymd_and_hms(date.year(), date.month(), 1, 0, 0, 0)
+		.unwrap()
+		.timestamp_millis() as u128
+}
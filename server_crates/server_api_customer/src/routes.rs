@@ -140,6 +140,18 @@ pub(crate) fn routes(router: &mut Router)
 			.add(server_api_common::middleware::group::group_app_transform)
 			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
 	);
+	router.get(
+		"/api/v1/customer/group/:group_id/metering/:from/:to/:format",
+		r(crate::metering::metering_controller::export_group)
+			.add(server_api_common::middleware::group::group_app_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.get(
+		"/api/v1/customer/group/:group_id/audit/:last_fetched_time/:last_id",
+		r(crate::audit::audit_controller::get_group_logs)
+			.add(server_api_common::middleware::group::group_app_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
 	router.delete(
 		"/api/v1/customer/group/:group_id",
 		r(crate::customer::customer_controller::delete_customer_group)
@@ -158,10 +170,43 @@ pub(crate) fn routes(router: &mut Router)
 			.add(server_api_common::middleware::group::group_app_transform)
 			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
 	);
+	router.put(
+		"/api/v1/customer/group/:group_id/app/:app_id/transfer",
+		r(crate::customer_app::app_controller::transfer_to_group)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::group::group_app_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
 	router.get(
 		"/api/v1/customer/apps/:last_fetched_time/:last_app_id",
 		r(crate::customer::customer_controller::get_all_apps).add(server_api_common::middleware::jwt::jwt_customer_app_transform),
 	);
+	router.post(
+		"/api/v1/customer/app_config",
+		r(crate::customer_app::app_controller::create_app_user_from_config).add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.post(
+		"/api/v1/customer/app_config/:group_id",
+		r(crate::customer_app::app_controller::create_app_group_from_config).add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.post(
+		"/api/v1/customer/app_token/:app_id",
+		r(crate::customer_app::app_controller::create_token)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.get(
+		"/api/v1/customer/app_token/:app_id/all/:last_fetched_time/:last_id",
+		r(crate::customer_app::app_controller::get_tokens)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.delete(
+		"/api/v1/customer/app_token/:app_id/:token_id",
+		r(crate::customer_app::app_controller::revoke_token)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
 	router.post(
 		"/api/v1/customer/app",
 		r(crate::customer_app::app_controller::create_app_user).add(server_api_common::middleware::jwt::jwt_customer_app_transform),
@@ -194,12 +239,84 @@ pub(crate) fn routes(router: &mut Router)
 			.add(crate::mw::app_access_transform)
 			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
 	);
+	router.get(
+		"/api/v1/customer/app/:app_id/file_usage",
+		r(crate::customer_app::app_controller::get_file_usage)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.get(
+		"/api/v1/customer/app/:app_id/file_external_delete/:last_fetched_time/:last_part_id",
+		r(crate::customer_app::app_controller::get_file_external_delete_failures)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.get(
+		"/api/v1/customer/app/:app_id/stats/:period/:from/:to",
+		r(crate::customer_app::app_controller::get_stats)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.get(
+		"/api/v1/customer/app/:app_id/metering/:from/:to/:format",
+		r(crate::metering::metering_controller::export_app)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
 	router.put(
 		"/api/v1/customer/app/:app_id/group_options",
 		r(crate::customer_app::app_controller::update_group_options)
 			.add(crate::mw::app_access_transform)
 			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
 	);
+	router.put(
+		"/api/v1/customer/app/:app_id/key_endpoint_options",
+		r(crate::customer_app::app_controller::update_key_endpoint_options)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.put(
+		"/api/v1/customer/app/:app_id/access_options",
+		r(crate::customer_app::app_controller::update_access_options)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.put(
+		"/api/v1/customer/app/:app_id/rate_limit_options",
+		r(crate::customer_app::app_controller::update_rate_limit_options)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.put(
+		"/api/v1/customer/app/:app_id/jwt_rotation_options",
+		r(crate::customer_app::app_controller::update_jwt_rotation_options)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.get(
+		"/api/v1/customer/app/:app_id/rate_limit/throttled/:from/:to",
+		r(crate::customer_app::app_controller::get_rate_limit_throttled)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.get(
+		"/api/v1/customer/app/:app_id/config",
+		r(crate::customer_app::app_controller::export_config)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.put(
+		"/api/v1/customer/app/:app_id/config",
+		r(crate::customer_app::app_controller::import_config)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
+	router.get(
+		"/api/v1/customer/app/:app_id/audit/:last_fetched_time/:last_id",
+		r(crate::audit::audit_controller::get_app_logs)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
 	router.delete(
 		"/api/v1/customer/app/:app_id",
 		r(crate::customer_app::app_controller::delete)
@@ -212,6 +329,12 @@ pub(crate) fn routes(router: &mut Router)
 			.add(crate::mw::app_access_transform)
 			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
 	);
+	router.put(
+		"/api/v1/customer/app/:app_id/transfer_to_customer",
+		r(crate::customer_app::app_controller::transfer_to_customer)
+			.add(crate::mw::app_access_transform)
+			.add(server_api_common::middleware::jwt::jwt_customer_app_transform),
+	);
 	router.patch(
 		"/api/v1/customer/app/:app_id/token_renew",
 		r(crate::customer_app::app_controller::renew_tokens)
@@ -2,17 +2,30 @@ use rustgram::service::IntoResponse;
 use rustgram::{Request, Response};
 use rustgram_server_util::db::id_handling::create_id;
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::get_time;
 use rustgram_server_util::input_helper::{bytes_to_json, get_raw_body};
 use rustgram_server_util::res::{echo, echo_success, AppRes, JRes, ServerSuccessOutput};
-use rustgram_server_util::url_helper::{get_name_param_from_params, get_name_param_from_req, get_params};
+use rustgram_server_util::url_helper::{get_name_param_from_params, get_name_param_from_req, get_params, get_time_from_url_param};
 use sentc_crypto_common::file::{FileNameUpdate, FilePartRegisterOutput, FileRegisterInput, FileRegisterOutput};
-use sentc_crypto_common::FileId;
 use server_api_common::customer_app::{check_endpoint_with_app_options, check_endpoint_with_req, get_app_data_from_req, Endpoint};
+use server_api_common::file::{FILE_BELONGS_TO_TYPE_GROUP, FILE_USAGE_TYPE_GROUP, FILE_USAGE_TYPE_USER};
 use server_api_common::group::get_group_user_data_from_req;
 use server_api_common::user::get_jwt_data_from_param;
 use server_dashboard_common::app::{FILE_STORAGE_OWN, FILE_STORAGE_SENTC};
 
-use crate::file_entities::{FileMetaData, FilePartListItem};
+use crate::file_entities::{
+	FileMetaData,
+	FileMoveInput,
+	FilePartListItem,
+	FileRegisterExpiry,
+	FileSessionCheck,
+	FileSessionStatus,
+	FileShare,
+	FileShareInput,
+	FileUsageOutput,
+	FileVersion,
+};
+use crate::file_service::FileQuota;
 use crate::{file_model, file_service, ApiErrorCodes};
 
 pub async fn register_file(mut req: Request) -> JRes<FileRegisterOutput>
@@ -25,8 +38,20 @@ pub async fn register_file(mut req: Request) -> JRes<FileRegisterOutput>
 	let user = get_jwt_data_from_param(&req)?;
 
 	let input: FileRegisterInput = bytes_to_json(&body)?;
+	let expiry: FileRegisterExpiry = bytes_to_json(&body)?;
 
-	let out = file_service::register_file(input, &app.app_data.app_id, &user.id, None).await?;
+	let file_options = &app.file_options;
+	let expires_at = file_service::get_expires_at(expiry.expires_at, file_options.default_ttl, file_options.max_ttl)?;
+
+	let out = file_service::register_file(
+		input,
+		expires_at,
+		file_options.into(),
+		&app.app_data.app_id,
+		&user.id,
+		None,
+	)
+	.await?;
 
 	echo(out)
 }
@@ -35,15 +60,22 @@ pub async fn register_file_in_group(mut req: Request) -> JRes<FileRegisterOutput
 {
 	let body = get_raw_body(&mut req).await?;
 
-	check_endpoint_with_req(&req, Endpoint::FileRegister)?;
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileRegister)?;
 
 	let group_data = get_group_user_data_from_req(&req)?;
 	let user = get_jwt_data_from_param(&req)?;
 
 	let input: FileRegisterInput = bytes_to_json(&body)?;
+	let expiry: FileRegisterExpiry = bytes_to_json(&body)?;
+
+	let file_options = &app.file_options;
+	let expires_at = file_service::get_expires_at(expiry.expires_at, file_options.default_ttl, file_options.max_ttl)?;
 
 	let out = file_service::register_file(
 		input,
+		expires_at,
+		file_options.into(),
 		&group_data.group_data.app_id,
 		&user.id,
 		Some(group_data.group_data.id.clone()),
@@ -98,11 +130,41 @@ pub async fn register_file_part(req: Request) -> JRes<FilePartRegisterOutput>
 
 	let app_id = &app.app_data.app_id;
 
-	let (file_id, _chunk_size, sequence, end) = check_session(&req, app_id, user_id).await?;
+	let (session, sequence, end) = check_session(&req, app_id, user_id, file_options.session_lifetime).await?;
+
+	if let Some(part) = file_model::get_part_by_sequence(app_id, &session.file_id, session.version, sequence).await? {
+		//the part was already registered, e.g. the client retries after a lost response
+		if end {
+			file_model::finish_session(app_id, session.file_id, session.version).await?;
+		}
+
+		return echo(FilePartRegisterOutput {
+			part_id: part.part_id,
+		});
+	}
+
+	//the part is not uploaded to our backend, so the checksum is taken from the client header without checking it
+	let checksum = file_service::get_part_checksum_from_req(&req)?;
+
+	let group_id = session_group_id(&session);
 
 	let part_id = create_id();
 
-	file_model::save_part(app_id, file_id, part_id.clone(), 0, sequence, end, true).await?;
+	file_model::save_part(
+		app_id,
+		user_id,
+		group_id.as_deref(),
+		session.file_id,
+		session.version,
+		part_id.clone(),
+		0,
+		sequence,
+		end,
+		true,
+		checksum,
+		None,
+	)
+	.await?;
 
 	echo(FilePartRegisterOutput {
 		part_id,
@@ -118,6 +180,8 @@ pub async fn upload_part(req: Request) -> JRes<ServerSuccessOutput>
 
 	let user = get_jwt_data_from_param(&req)?;
 	let app_id = app.app_data.app_id.clone(); //must be owned because req is dropped before save part with app id
+	let user_id = user.id.clone();
+	let quota: FileQuota = file_options.into();
 
 	if file_options.file_storage != FILE_STORAGE_SENTC {
 		return Err(ServerCoreError::new_msg(
@@ -127,19 +191,62 @@ pub async fn upload_part(req: Request) -> JRes<ServerSuccessOutput>
 		));
 	}
 
-	let (file_id, chunk_size, sequence, end) = check_session(&req, &app_id, &user.id).await?;
+	let (session, sequence, end) = check_session(&req, &app_id, &user_id, file_options.session_lifetime).await?;
+
+	if file_model::get_part_by_sequence(&app_id, &session.file_id, session.version, sequence)
+		.await?
+		.is_some()
+	{
+		//the part was already stored, e.g. the client resumes the upload. Don't store the same sequence twice.
+		if end {
+			file_model::finish_session(&app_id, session.file_id, session.version).await?;
+		}
+
+		return echo_success();
+	}
+
+	let group_id = session_group_id(&session);
+
+	//at least one byte must be free before the upload
+	file_service::check_quota(quota, &app_id, &user_id, group_id.as_deref(), 1, 0).await?;
+
+	//verify the part before it is stored
+	let checksum = file_service::get_part_checksum_from_req(&req)?;
+	let (req, checksum) = file_service::read_and_check_part(req, session.max_chunk_size, checksum.as_deref()).await?;
 
 	//create the id here to upload the right file
 	let part_id = create_id();
 
-	let size = rustgram_server_util::file::upload_part(req, &part_id, chunk_size).await?;
+	let size = rustgram_server_util::file::upload_part(req, &part_id, session.max_chunk_size).await?;
+
+	//the part and the usage are only saved when the storage quota is not reached after the upload
+	let res = file_model::save_part(
+		&app_id,
+		&user_id,
+		group_id.as_deref(),
+		session.file_id,
+		session.version,
+		part_id.clone(),
+		size,
+		sequence,
+		end,
+		false,
+		Some(checksum),
+		Some(quota),
+	)
+	.await;
+
+	if let Err(e) = res {
+		//remove the uploaded part again, e.g. when the storage quota is reached
+		rustgram_server_util::file::delete_parts(&[part_id]).await?;
 
-	file_model::save_part(&app_id, file_id, part_id, size, sequence, end, false).await?;
+		return Err(e);
+	}
 
 	echo_success()
 }
 
-async fn check_session(req: &Request, app_id: &str, user_id: &str) -> AppRes<(FileId, usize, i32, bool)>
+async fn check_session(req: &Request, app_id: &str, user_id: &str, session_lifetime: i32) -> AppRes<(FileSessionCheck, i32, bool)>
 {
 	let params = get_params(req)?;
 	let session_id = get_name_param_from_params(params, "session_id")?;
@@ -156,9 +263,45 @@ async fn check_session(req: &Request, app_id: &str, user_id: &str) -> AppRes<(Fi
 		.parse()
 		.map_err(|_e| ServerCoreError::new_msg(400, ApiErrorCodes::UnexpectedTime, "Parameter end has a wrong format"))?;
 
-	let (file_id, chunk_size) = file_model::check_session(app_id, session_id, user_id).await?;
+	let session = file_model::check_session(app_id, session_id, user_id, session_lifetime).await?;
 
-	Ok((file_id, chunk_size, sequence, end))
+	Ok((session, sequence, end))
+}
+
+/**
+Get the already stored parts of an upload session.
+
+The session belongs to the user and not to the device, so the upload can be resumed from another device of the user.
+ */
+pub async fn get_session_status(req: Request) -> JRes<FileSessionStatus>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FilePartUpload)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+	let app_id = &app.app_data.app_id;
+
+	let session_id = get_name_param_from_req(&req, "session_id")?;
+
+	let session = file_model::check_session(app_id, session_id, &user.id, app.file_options.session_lifetime).await?;
+
+	let parts = file_model::get_session_parts(app_id, &session.file_id, session.version).await?;
+
+	echo(FileSessionStatus {
+		file_id: session.file_id,
+		max_chunk_size: session.max_chunk_size,
+		parts,
+	})
+}
+
+fn session_group_id(session: &FileSessionCheck) -> Option<String>
+{
+	//only files in a group count for the group
+	if session.belongs_to_type == FILE_BELONGS_TO_TYPE_GROUP {
+		session.belongs_to.clone()
+	} else {
+		None
+	}
 }
 
 //__________________________________________________________________________________________________
@@ -219,6 +362,48 @@ pub async fn get_parts(req: Request) -> JRes<Vec<FilePartListItem>>
 	echo(parts)
 }
 
+/**
+Get all files of the user without the part list. Use get file to fetch the parts.
+ */
+pub async fn get_my_files(req: Request) -> JRes<Vec<FileMetaData>>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileGet)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+
+	let params = get_params(&req)?;
+	let last_id = get_name_param_from_params(params, "last_id")?;
+	let last_fetched_time = get_name_param_from_params(params, "last_fetched_time")?;
+	let last_fetched_time = get_time_from_url_param(last_fetched_time)?;
+
+	let files = file_model::get_files_for_user(&app.app_data.app_id, &user.id, last_fetched_time, last_id).await?;
+
+	echo(files)
+}
+
+pub async fn get_files_in_group(req: Request) -> JRes<Vec<FileMetaData>>
+{
+	check_endpoint_with_req(&req, Endpoint::FileGet)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	let params = get_params(&req)?;
+	let last_id = get_name_param_from_params(params, "last_id")?;
+	let last_fetched_time = get_name_param_from_params(params, "last_fetched_time")?;
+	let last_fetched_time = get_time_from_url_param(last_fetched_time)?;
+
+	let files = file_model::get_files_for_group(
+		&group_data.group_data.app_id,
+		&group_data.group_data.id,
+		last_fetched_time,
+		last_id,
+	)
+	.await?;
+
+	echo(files)
+}
+
 pub async fn download_part(req: Request) -> Response
 {
 	download_part_internally(req)
@@ -228,11 +413,33 @@ pub async fn download_part(req: Request) -> Response
 
 pub async fn download_part_internally(req: Request) -> AppRes<Response>
 {
-	check_endpoint_with_req(&req, Endpoint::FilePartDownload)?;
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FilePartDownload)?;
 
 	let part_id = get_name_param_from_req(&req, "part_id")?;
 
-	rustgram_server_util::file::get_part(part_id).await
+	let part = file_model::get_part_for_download(&app.app_data.app_id, part_id).await?;
+
+	let time = get_time()?;
+
+	if let Some(p) = &part {
+		if p.expires_at > 0 && p.expires_at < time {
+			return Err(ServerCoreError::new_msg(
+				410,
+				ApiErrorCodes::FileExpired,
+				"The file is expired",
+			));
+		}
+	}
+
+	let res = rustgram_server_util::file::get_part(part_id).await?;
+
+	if let Some(p) = part {
+		//count the downloaded bytes for the metering
+		server_api_common::metering::add_transferred_bytes(&app.app_data.app_id, time, p.size).await;
+	}
+
+	Ok(res)
 }
 
 pub async fn update_file_name(mut req: Request) -> JRes<ServerSuccessOutput>
@@ -252,6 +459,341 @@ pub async fn update_file_name(mut req: Request) -> JRes<ServerSuccessOutput>
 	echo_success()
 }
 
+//__________________________________________________________________________________________________
+// share
+
+pub async fn share_file(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileRegister)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+	let file_id = get_name_param_from_req(&req, "file_id")?;
+
+	let input: FileShareInput = bytes_to_json(&body)?;
+
+	file_service::share_file(&app.app_data.app_id, &user.id, file_id, input).await?;
+
+	echo_success()
+}
+
+pub async fn get_shares(req: Request) -> JRes<Vec<FileShare>>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileGet)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+
+	let params = get_params(&req)?;
+	let file_id = get_name_param_from_params(params, "file_id")?;
+	let last_id = get_name_param_from_params(params, "last_id")?;
+	let last_fetched_time = get_name_param_from_params(params, "last_fetched_time")?;
+	let last_fetched_time = get_time_from_url_param(last_fetched_time)?;
+
+	let shares = file_service::get_shares(&app.app_data.app_id, &user.id, file_id, last_fetched_time, last_id).await?;
+
+	echo(shares)
+}
+
+pub async fn delete_share(req: Request) -> JRes<ServerSuccessOutput>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileRegister)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+
+	let params = get_params(&req)?;
+	let file_id = get_name_param_from_params(params, "file_id")?;
+	let share_to = get_name_param_from_params(params, "share_to")?;
+
+	file_service::delete_share(&app.app_data.app_id, &user.id, file_id, share_to).await?;
+
+	echo_success()
+}
+
+//__________________________________________________________________________________________________
+// move
+
+pub async fn move_file(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileRegister)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+	let file_id = get_name_param_from_req(&req, "file_id")?;
+
+	let input: FileMoveInput = bytes_to_json(&body)?;
+
+	file_service::move_file(
+		&app.app_data.app_id,
+		&user.id,
+		file_id,
+		None,
+		input,
+		(&app.file_options).into(),
+	)
+	.await?;
+
+	echo_success()
+}
+
+pub async fn move_file_in_group(mut req: Request) -> JRes<ServerSuccessOutput>
+{
+	let body = get_raw_body(&mut req).await?;
+
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileRegister)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+	let user = get_jwt_data_from_param(&req)?;
+	let file_id = get_name_param_from_req(&req, "file_id")?;
+
+	let input: FileMoveInput = bytes_to_json(&body)?;
+
+	file_service::move_file(
+		&group_data.group_data.app_id,
+		&user.id,
+		file_id,
+		Some(group_data),
+		input,
+		(&app.file_options).into(),
+	)
+	.await?;
+
+	echo_success()
+}
+
+//__________________________________________________________________________________________________
+// versions
+
+pub async fn register_file_version(req: Request) -> JRes<FileRegisterOutput>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileRegister)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+	let file_id = get_name_param_from_req(&req, "file_id")?;
+
+	let out = file_service::register_version(&app.app_data.app_id, &user.id, file_id).await?;
+
+	echo(out)
+}
+
+pub async fn get_versions(req: Request) -> JRes<Vec<FileVersion>>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileGet)?;
+
+	//use optional user id like get file
+	let user_id = match get_jwt_data_from_param(&req) {
+		Err(_e) => None,
+		Ok(jwt) => Some(jwt.id.as_str()),
+	};
+
+	let params = get_params(&req)?;
+	let file_id = get_name_param_from_params(params, "file_id")?;
+	let last_version = parse_version(get_name_param_from_params(params, "last_version")?)?;
+
+	let versions = file_service::get_versions(&app.app_data.app_id, user_id, file_id, None, last_version).await?;
+
+	echo(versions)
+}
+
+pub async fn get_versions_in_group(req: Request) -> JRes<Vec<FileVersion>>
+{
+	check_endpoint_with_req(&req, Endpoint::FileGet)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	let params = get_params(&req)?;
+	let file_id = get_name_param_from_params(params, "file_id")?;
+	let last_version = parse_version(get_name_param_from_params(params, "last_version")?)?;
+
+	let versions = file_service::get_versions(
+		&group_data.group_data.app_id,
+		Some(&group_data.user_data.user_id),
+		file_id,
+		Some(&group_data.group_data.id),
+		last_version,
+	)
+	.await?;
+
+	echo(versions)
+}
+
+pub async fn get_file_version(req: Request) -> JRes<FileMetaData>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileGet)?;
+
+	let user_id = match get_jwt_data_from_param(&req) {
+		Err(_e) => None,
+		Ok(jwt) => Some(jwt.id.as_str()),
+	};
+
+	let params = get_params(&req)?;
+	let file_id = get_name_param_from_params(params, "file_id")?;
+	let version = parse_version(get_name_param_from_params(params, "version")?)?;
+
+	let file = file_service::get_file_version(&app.app_data.app_id, user_id, file_id, None, version).await?;
+
+	echo(file)
+}
+
+pub async fn get_file_version_in_group(req: Request) -> JRes<FileMetaData>
+{
+	check_endpoint_with_req(&req, Endpoint::FileGet)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	let params = get_params(&req)?;
+	let file_id = get_name_param_from_params(params, "file_id")?;
+	let version = parse_version(get_name_param_from_params(params, "version")?)?;
+
+	let file = file_service::get_file_version(
+		&group_data.group_data.app_id,
+		Some(&group_data.user_data.user_id),
+		file_id,
+		Some(&group_data.group_data.id),
+		version,
+	)
+	.await?;
+
+	echo(file)
+}
+
+pub async fn get_version_parts(req: Request) -> JRes<Vec<FilePartListItem>>
+{
+	let app_data = get_app_data_from_req(&req)?;
+
+	check_endpoint_with_app_options(app_data, Endpoint::FileGet)?;
+
+	let user_id = match get_jwt_data_from_param(&req) {
+		Err(_e) => None,
+		Ok(jwt) => Some(jwt.id.as_str()),
+	};
+
+	let (file_id, version, last_sequence) = get_version_parts_params(&req)?;
+
+	let parts = file_service::get_version_parts(
+		&app_data.app_data.app_id,
+		user_id,
+		file_id,
+		None,
+		version,
+		last_sequence,
+	)
+	.await?;
+
+	echo(parts)
+}
+
+pub async fn get_version_parts_in_group(req: Request) -> JRes<Vec<FilePartListItem>>
+{
+	check_endpoint_with_req(&req, Endpoint::FileGet)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	let (file_id, version, last_sequence) = get_version_parts_params(&req)?;
+
+	let parts = file_service::get_version_parts(
+		&group_data.group_data.app_id,
+		Some(&group_data.user_data.user_id),
+		file_id,
+		Some(&group_data.group_data.id),
+		version,
+		last_sequence,
+	)
+	.await?;
+
+	echo(parts)
+}
+
+fn get_version_parts_params(req: &Request) -> AppRes<(&str, i32, i32)>
+{
+	let params = get_params(req)?;
+	let file_id = get_name_param_from_params(params, "file_id")?;
+	let version = parse_version(get_name_param_from_params(params, "version")?)?;
+	let last_sequence = get_name_param_from_params(params, "last_sequence")?;
+	let last_sequence: i32 = last_sequence
+		.parse()
+		.map_err(|_e| ServerCoreError::new_msg(400, ApiErrorCodes::UnexpectedTime, "last fetched sequence is wrong"))?;
+
+	Ok((file_id, version, last_sequence))
+}
+
+pub async fn restore_version(req: Request) -> JRes<ServerSuccessOutput>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileRegister)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+
+	let params = get_params(&req)?;
+	let file_id = get_name_param_from_params(params, "file_id")?;
+	let version = parse_version(get_name_param_from_params(params, "version")?)?;
+
+	file_service::restore_version(&app.app_data.app_id, &user.id, file_id, version).await?;
+
+	echo_success()
+}
+
+fn parse_version(version: &str) -> AppRes<i32>
+{
+	version.parse().map_err(|_e| {
+		ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::UnexpectedTime,
+			"Parameter version has a wrong format",
+		)
+	})
+}
+
+//__________________________________________________________________________________________________
+
+pub async fn get_usage(req: Request) -> JRes<FileUsageOutput>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileGet)?;
+
+	let user = get_jwt_data_from_param(&req)?;
+
+	let usage = server_api_common::file::get_file_usage(&app.app_data.app_id, FILE_USAGE_TYPE_USER, &user.id).await?;
+
+	echo(FileUsageOutput {
+		used_storage: usage.used_storage,
+		file_count: usage.file_count,
+		max_storage: app.file_options.max_storage_user,
+		max_files: app.file_options.max_files_user,
+	})
+}
+
+pub async fn get_usage_in_group(req: Request) -> JRes<FileUsageOutput>
+{
+	let app = get_app_data_from_req(&req)?;
+	check_endpoint_with_app_options(app, Endpoint::FileGet)?;
+
+	let group_data = get_group_user_data_from_req(&req)?;
+
+	let usage = server_api_common::file::get_file_usage(
+		&group_data.group_data.app_id,
+		FILE_USAGE_TYPE_GROUP,
+		&group_data.group_data.id,
+	)
+	.await?;
+
+	echo(FileUsageOutput {
+		used_storage: usage.used_storage,
+		file_count: usage.file_count,
+		max_storage: app.file_options.max_storage_group,
+		max_files: app.file_options.max_files_group,
+	})
+}
+
 //__________________________________________________________________________________________________
 
 pub async fn delete_file(req: Request) -> JRes<ServerSuccessOutput>
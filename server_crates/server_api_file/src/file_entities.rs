@@ -1,7 +1,7 @@
 use rustgram_server_util::{take_or_err, DB};
 use sentc_crypto_common::file::BelongsToType;
 use sentc_crypto_common::{AppId, FileId, PartId, UserId};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(DB)]
 pub struct FileSessionCheck
@@ -9,10 +9,55 @@ pub struct FileSessionCheck
 	pub file_id: FileId,
 	pub created_at: u128,
 	pub max_chunk_size: usize,
+	pub belongs_to: Option<String>,
+	pub belongs_to_type: i32,
+	pub version: i32,
+}
+
+#[derive(DB)]
+pub struct FileSessionOld
+{
+	pub session_id: String,
+	pub file_id: FileId,
+	pub version: i32,
+}
+
+#[derive(DB)]
+pub struct FileStoredPart
+{
+	pub part_id: PartId,
+	pub size: i64,
+}
+
+#[derive(DB, Serialize)]
+pub struct FileSessionPart
+{
+	pub sequence: i32,
+	pub size: i64,
+}
+
+/**
+The state of an upload session. The client can resume the upload after the last stored sequence.
+ */
+#[derive(Serialize)]
+pub struct FileSessionStatus
+{
+	pub file_id: FileId,
+	pub max_chunk_size: usize,
+	pub parts: Vec<FileSessionPart>,
 }
 
 //__________________________________________________________________________________________________
 
+/**
+The optional expiry time of a new file in ms. It is sent with the file register input.
+ */
+#[derive(Deserialize)]
+pub struct FileRegisterExpiry
+{
+	pub expires_at: Option<u128>,
+}
+
 #[derive(Serialize)]
 pub struct FileMetaData
 {
@@ -26,6 +71,8 @@ pub struct FileMetaData
 	pub time: u128,
 	pub encrypted_file_name: Option<String>,
 	pub part_list: Vec<FilePartListItem>,
+	pub expires_at: u128,
+	pub version: i32,
 }
 
 impl Into<sentc_crypto_common::file::FileData> for FileMetaData
@@ -80,6 +127,8 @@ impl rustgram_server_util::db::mysql_async_export::prelude::FromRow for FileMeta
 			part_list: Vec::new(),
 			encrypted_file_name: rustgram_server_util::take_or_err_opt!(row, 7, String),
 			master_key_id: take_or_err!(row, 8, String),
+			expires_at: take_or_err!(row, 9, u128),
+			version: take_or_err!(row, 10, i32),
 		})
 	}
 }
@@ -110,18 +159,44 @@ impl rustgram_server_util::db::FromSqliteRow for FileMetaData
 			encrypted_file_name: take_or_err!(row, 7),
 			part_list: Vec::new(),
 			master_key_id: take_or_err!(row, 8),
+			expires_at: rustgram_server_util::take_or_err_u128!(row, 9),
+			version: take_or_err!(row, 10),
 		})
 	}
 }
 
 //__________________________________________________________________________________________________
 
+/**
+An uploaded version of a file. The parts of a version are encrypted with the same file key.
+ */
+#[derive(Serialize, DB)]
+pub struct FileVersion
+{
+	pub version: i32,
+	pub owner: UserId,
+	pub time: u128,
+}
+
+#[derive(DB)]
+pub struct FileVersionRetention
+{
+	pub file_id: FileId,
+	pub version: i32,
+	pub time: u128,
+	pub version_keep_last: i32,
+	pub version_keep_days: i32,
+}
+
+//__________________________________________________________________________________________________
+
 #[derive(Serialize, DB)]
 pub struct FilePartListItem
 {
 	pub part_id: PartId,
 	pub sequence: i32,
 	pub extern_storage: bool,
+	pub checksum: Option<String>,
 }
 
 impl Into<sentc_crypto_common::file::FilePartListItem> for FilePartListItem
@@ -147,6 +222,20 @@ pub struct FilePartListItemDelete
 	pub app_id: AppId,
 }
 
+#[derive(DB)]
+pub struct FilePartDownload
+{
+	pub size: i64,
+	pub expires_at: u128,
+}
+
+#[derive(DB)]
+pub struct FilePartCheck
+{
+	pub part_id: PartId,
+	pub checksum: String,
+}
+
 //__________________________________________________________________________________________________
 
 #[derive(DB)]
@@ -157,4 +246,97 @@ pub struct FileExternalStorageUrl
 	pub auth_token: Option<String>,
 }
 
+/**
+A part which is not confirmed as deleted by the external storage with the current storage url of the app.
+ */
+#[derive(DB)]
+pub struct FileExternalDelete
+{
+	pub part_id: PartId,
+	pub app_id: AppId,
+	pub attempts: i32,
+	pub storage_url: String,
+	pub auth_token: Option<String>,
+}
+
+//__________________________________________________________________________________________________
+
+#[derive(DB)]
+pub struct FileUsageItem
+{
+	pub usage_type: i32,
+	pub used_storage: i64,
+	pub file_count: i32,
+}
+
+#[derive(DB)]
+pub struct FileDeleteUsage
+{
+	pub file_id: FileId,
+	pub version: i32,
+	pub app_id: AppId,
+	pub owner: UserId,
+	pub belongs_to: Option<String>,
+	pub belongs_to_type: i32,
+	pub file_size: i64,
+}
+
+/**
+The usage of a user or a group with the limits of the app. 0 = no limit
+ */
+#[derive(Serialize)]
+pub struct FileUsageOutput
+{
+	pub used_storage: i64,
+	pub file_count: i32,
+	pub max_storage: i64,
+	pub max_files: i32,
+}
+
+//__________________________________________________________________________________________________
+
+/**
+The new group or user of the file with the file key encrypted by a key of the new group or user
+ */
+#[derive(Deserialize)]
+pub struct FileMoveInput
+{
+	pub belongs_to_id: Option<String>,
+	pub belongs_to_type: BelongsToType,
+	pub encrypted_key: String,
+	pub encrypted_key_alg: String,
+	pub master_key_id: String,
+}
+
 //__________________________________________________________________________________________________
+
+#[derive(Deserialize)]
+pub struct FileShareInput
+{
+	pub share_to: String,
+	pub share_to_type: BelongsToType,
+	pub encrypted_key: String,
+	pub encrypted_key_alg: String,
+	pub master_key_id: String,
+}
+
+/**
+A share of a file. share_to_type: 1 = group, 2 = user
+ */
+#[derive(Serialize, DB)]
+pub struct FileShare
+{
+	pub share_to: String,
+	pub share_to_type: i32,
+	pub shared_by: UserId,
+	pub master_key_id: String,
+	pub time: u128,
+}
+
+#[derive(DB)]
+pub struct FileShareKey
+{
+	pub encrypted_key: String,
+	pub encrypted_key_alg: String,
+	pub master_key_id: String,
+}
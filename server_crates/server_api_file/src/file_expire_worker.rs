@@ -0,0 +1,9 @@
+use rustgram_server_util::res::AppRes;
+
+use crate::file_model;
+
+pub async fn start() -> AppRes<()>
+{
+	//mark the expired files as to delete, the parts are deleted by the file worker
+	file_model::delete_expired_files().await
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+use rustgram_server_util::get_time;
+use rustgram_server_util::res::AppRes;
+use sentc_crypto_common::{AppId, PartId};
+use server_dashboard_common::app::FileExternalDeleteOutput;
+
+use crate::file_entities::FileExternalDelete;
+use crate::file_model;
+
+/**
+The max time between two delete requests for the same part, 24 h
+ */
+const MAX_BACKOFF: u128 = 24 * 60 * 60 * 1000;
+
+/**
+Send the deleted parts of the external storage to the storage url of the app.
+
+Parts are only removed when the storage confirmed the delete in the response.
+Every other part is tried again later with an exponential backoff.
+ */
+pub async fn start() -> AppRes<()>
+{
+	send_deletes(None).await
+}
+
+/**
+Like start but only for the deleted parts of one app.
+ */
+pub async fn start_for_app(app_id: &str) -> AppRes<()>
+{
+	send_deletes(Some(app_id)).await
+}
+
+async fn send_deletes(app_id: Option<&str>) -> AppRes<()>
+{
+	let backoff_sec: u128 = env::var("FILE_EXTERNAL_DELETE_BACKOFF_SEC")
+		.unwrap_or_else(|_| "60".into())
+		.parse()
+		.unwrap_or(60);
+
+	let start_time = get_time()?;
+
+	let mut last_id = None;
+
+	loop {
+		let parts = file_model::get_external_deletes(last_id, start_time, app_id).await?;
+		let part_len = parts.len();
+
+		match parts.last() {
+			Some(p) => last_id = Some(p.part_id.to_string()),
+			None => {
+				break;
+			},
+		}
+
+		let mut app_map: HashMap<AppId, Vec<FileExternalDelete>> = HashMap::new();
+
+		for part in parts {
+			app_map
+				.entry(part.app_id.to_string())
+				.or_default()
+				.push(part);
+		}
+
+		for (_, parts) in app_map {
+			let (confirmed, failed, error) = match send_delete(&parts).await {
+				Ok(deleted) => {
+					let (confirmed, failed): (Vec<_>, Vec<_>) = parts
+						.into_iter()
+						.partition(|p| deleted.contains(&p.part_id));
+
+					(
+						confirmed,
+						failed,
+						"part not confirmed as deleted by the storage".to_string(),
+					)
+				},
+				Err(e) => (Vec::new(), parts, e),
+			};
+
+			file_model::delete_external_deletes(confirmed.into_iter().map(|p| p.part_id).collect()).await?;
+
+			//the next try depends on the failed attempts of the part
+			let mut attempt_map: HashMap<i32, Vec<PartId>> = HashMap::new();
+
+			for part in failed {
+				attempt_map
+					.entry(part.attempts)
+					.or_default()
+					.push(part.part_id);
+			}
+
+			for (attempts, part_ids) in attempt_map {
+				let backoff = (backoff_sec * 1000 * 2u128.pow(attempts.clamp(0, 10) as u32)).min(MAX_BACKOFF);
+
+				file_model::set_external_deletes_failed(part_ids, get_time()? + backoff, error.clone()).await?;
+			}
+		}
+
+		if part_len < 500 {
+			break;
+		}
+	}
+
+	Ok(())
+}
+
+/**
+Post the part ids as json array to the storage url.
+
+The storage must respond with a success status and the deleted part ids, see FileExternalDeleteOutput.
+ */
+async fn send_delete(parts: &[FileExternalDelete]) -> Result<Vec<PartId>, String>
+{
+	//all parts are from the same app
+	let (url, auth_token) = match parts.first() {
+		Some(p) => (&p.storage_url, &p.auth_token),
+		None => return Ok(Vec::new()),
+	};
+
+	let part_ids: Vec<&PartId> = parts.iter().map(|p| &p.part_id).collect();
+
+	let body = serde_json::to_string(&part_ids).map_err(|e| format!("Can't create the request body: {}", e))?;
+
+	let client = reqwest::Client::new();
+
+	let req = client.post(url).body(body).timeout(Duration::from_secs(10));
+
+	let req = match auth_token {
+		Some(at) => req.header("x-sentc-app-token", at),
+		None => req,
+	};
+
+	let res = req
+		.send()
+		.await
+		.map_err(|e| format!("Request to the storage failed: {}", e))?;
+
+	let status = res.status();
+
+	if !status.is_success() {
+		return Err(format!("Storage responded with status {}", status));
+	}
+
+	let body = res
+		.text()
+		.await
+		.map_err(|e| format!("Can't read the response of the storage: {}", e))?;
+
+	let out: FileExternalDeleteOutput = serde_json::from_str(&body).map_err(|e| format!("Wrong response of the storage: {}", e))?;
+
+	Ok(out.deleted)
+}
@@ -1,12 +1,54 @@
 use rustgram_server_util::db::id_handling::create_id;
-use rustgram_server_util::db::{exec, exec_transaction, get_in, query_first, query_string, TransactionData};
+use rustgram_server_util::db::{
+	bulk_insert,
+	exec,
+	exec_string,
+	exec_transaction,
+	get_in,
+	query,
+	query_first,
+	query_string,
+	I32Entity,
+	I64Entity,
+	Params,
+	TransactionData,
+	TupleEntity,
+};
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
 use rustgram_server_util::res::AppRes;
-use rustgram_server_util::{get_time, set_params, set_params_vec_outer};
-use sentc_crypto_common::{AppId, FileId, FileSessionId, PartId, UserId};
-use server_api_common::file::{FILE_STATUS_AVAILABLE, FILE_STATUS_TO_DELETE, MAX_CHUNK_SIZE, MAX_SESSION_ALIVE_TIME};
+use rustgram_server_util::{get_time, set_params, set_params_vec, set_params_vec_outer};
+use sentc_crypto_common::{AppId, FileId, FileSessionId, GroupId, PartId, UserId};
+use server_api_common::file::{
+	FILE_BELONGS_TO_TYPE_GROUP,
+	FILE_BELONGS_TO_TYPE_USER,
+	FILE_STATUS_AVAILABLE,
+	FILE_STATUS_TO_DELETE,
+	FILE_USAGE_TYPE_APP,
+	FILE_USAGE_TYPE_GROUP,
+	FILE_USAGE_TYPE_USER,
+	MAX_CHUNK_SIZE,
+};
 
-use crate::file_entities::{FileExternalStorageUrl, FileMetaData, FilePartListItem, FilePartListItemDelete, FileSessionCheck};
+use crate::file_entities::{
+	FileDeleteUsage,
+	FileExternalDelete,
+	FileExternalStorageUrl,
+	FileMetaData,
+	FilePartCheck,
+	FilePartDownload,
+	FilePartListItem,
+	FilePartListItemDelete,
+	FileSessionCheck,
+	FileSessionOld,
+	FileSessionPart,
+	FileShare,
+	FileShareKey,
+	FileStoredPart,
+	FileUsageItem,
+	FileVersion,
+	FileVersionRetention,
+};
+use crate::file_service::FileQuota;
 use crate::ApiErrorCodes;
 
 #[allow(clippy::too_many_arguments)]
@@ -17,11 +59,13 @@ pub(super) async fn register_file(
 	file_name: Option<String>,
 	belongs_to_id: Option<String>,
 	belongs_to_type: i32,
+	expires_at: u128,
 	app_id: impl Into<AppId>,
 	user_id: impl Into<UserId>,
 ) -> AppRes<(String, String)>
 {
 	let app_id = app_id.into();
+	let user_id = user_id.into();
 
 	let file_id = create_id();
 	let session_id = create_id();
@@ -29,11 +73,11 @@ pub(super) async fn register_file(
 	let time = get_time()?;
 
 	//language=SQL
-	let sql = "INSERT INTO sentc_file (id, owner, belongs_to, belongs_to_type, app_id, encrypted_key, encrypted_key_alg, time, status, delete_at, encrypted_file_name, master_key_id) VALUES (?,?,?,?,?,?,?,?,?,?,?,?)";
+	let sql = "INSERT INTO sentc_file (id, owner, belongs_to, belongs_to_type, app_id, encrypted_key, encrypted_key_alg, time, status, delete_at, encrypted_file_name, master_key_id, expires_at) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?)";
 	let params = set_params!(
 		file_id.clone(),
-		user_id.into(),
-		belongs_to_id,
+		user_id.clone(),
+		belongs_to_id.clone(),
 		belongs_to_type,
 		app_id.clone(),
 		encrypted_key,
@@ -42,7 +86,8 @@ pub(super) async fn register_file(
 		FILE_STATUS_AVAILABLE,
 		0,
 		file_name,
-		master_key_id
+		master_key_id,
+		expires_at.to_string()
 	);
 
 	//language=SQL
@@ -56,7 +101,25 @@ pub(super) async fn register_file(
 		MAX_CHUNK_SIZE.to_string()
 	);
 
-	exec_transaction(vec![
+	//language=SQL
+	let sql_version = "INSERT INTO sentc_file_version (file_id, version, app_id, owner, time, status, delete_at) VALUES (?,?,?,?,?,?,?)";
+	let params_version = set_params!(
+		file_id.clone(),
+		1,
+		app_id.clone(),
+		user_id.clone(),
+		time.to_string(),
+		FILE_STATUS_AVAILABLE,
+		0
+	);
+
+	let group_id = if belongs_to_type == FILE_BELONGS_TO_TYPE_GROUP {
+		belongs_to_id.as_deref()
+	} else {
+		None
+	};
+
+	let mut data = vec![
 		TransactionData {
 			sql,
 			params,
@@ -65,8 +128,20 @@ pub(super) async fn register_file(
 			sql: sql_session,
 			params: params_session,
 		},
-	])
-	.await?;
+		TransactionData {
+			sql: sql_version,
+			params: params_version,
+		},
+	];
+
+	for (sql, params) in prepare_usage_update(&app_id, &user_id, group_id, 0, 1) {
+		data.push(TransactionData {
+			sql,
+			params,
+		});
+	}
+
+	exec_transaction(data).await?;
 
 	Ok((file_id, session_id))
 }
@@ -75,14 +150,15 @@ pub(super) async fn check_session(
 	app_id: impl Into<AppId>,
 	session_id: impl Into<FileSessionId>,
 	user_id: impl Into<UserId>,
-) -> AppRes<(FileId, usize)>
+	session_lifetime: i32,
+) -> AppRes<FileSessionCheck>
 {
 	let app_id = app_id.into();
 	let session_id = session_id.into();
 
 	//language=SQL
 	let sql = r"
-SELECT file_id, created_at, max_chunk_size 
+SELECT file_id, created_at, max_chunk_size, belongs_to, belongs_to_type, s.version 
 FROM 
     sentc_file f, 
     sentc_file_session s 
@@ -90,9 +166,19 @@ WHERE
     f.id = file_id AND 
     s.id = ? AND 
     owner = ? AND 
-    f.app_id = ?";
+    f.app_id = ? AND 
+    status = ?";
 
-	let check: Option<FileSessionCheck> = query_first(sql, set_params!(session_id.clone(), user_id.into(), app_id.clone())).await?;
+	let check: Option<FileSessionCheck> = query_first(
+		sql,
+		set_params!(
+			session_id.clone(),
+			user_id.into(),
+			app_id.clone(),
+			FILE_STATUS_AVAILABLE
+		),
+	)
+	.await?;
 
 	let check = match check {
 		Some(o) => o,
@@ -108,9 +194,16 @@ WHERE
 	//check the exp date
 	let time = get_time()?;
 
-	if check.created_at + MAX_SESSION_ALIVE_TIME < time {
-		//session exp
-		delete_session(session_id, app_id).await?;
+	if check.created_at + session_lifetime as u128 * 1000 < time {
+		//session exp, the file can't be finished anymore
+		delete_session(session_id, &app_id).await?;
+
+		if check.version > 1 {
+			//only delete the new version and not the whole file
+			delete_version(app_id, &check.file_id, check.version).await?;
+		} else {
+			delete_file(app_id, &check.file_id).await?;
+		}
 
 		return Err(ServerCoreError::new_msg(
 			400,
@@ -119,48 +212,226 @@ WHERE
 		));
 	}
 
-	Ok((check.file_id, check.max_chunk_size))
+	Ok(check)
 }
 
+/**
+Save the part and increase the usage.
+
+With a quota the part is only inserted when the storage of the app, the user and the group is not over the limit after the usage update.
+The usage rows are locked by the update, so parallel uploads can't pass the limit together.
+When the part was not inserted the usage update is reverted in the same transaction and the quota error is returned.
+ */
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn save_part(
 	app_id: impl Into<AppId>,
+	user_id: &str,
+	group_id: Option<&str>,
 	file_id: FileId,
+	version: i32,
 	part_id: String,
 	size: usize,
 	sequence: i32,
 	end: bool,
 	extern_storage: bool,
+	checksum: Option<String>,
+	quota: Option<FileQuota>,
 ) -> AppRes<()>
 {
 	//part and file id are owned because they are fetched
 
 	let app_id = app_id.into();
 
-	//language=SQL
-	let sql = "INSERT INTO sentc_file_part (id, file_id, app_id, size, sequence, extern) VALUES (?,?,?,?,?,?)";
+	//only check the quota when the part got a size
+	let quota = quota.filter(|_| size > 0);
 
-	exec(
-		sql,
-		set_params!(
-			part_id,
-			file_id.clone(),
-			app_id.clone(),
-			size.to_string(),
-			sequence,
-			extern_storage
-		),
-	)
-	.await?;
+	let mut data = Vec::new();
+
+	if size > 0 {
+		for (sql, params) in prepare_usage_update(&app_id, user_id, group_id, size as i64, 0) {
+			data.push(TransactionData {
+				sql,
+				params,
+			});
+		}
+	}
+
+	match quota {
+		Some(quota) => {
+			//no limit for a type when the max storage is 0, no group row when the group id is null
+			#[cfg(feature = "mysql")]
+			//language=SQL
+			let sql = r"
+INSERT INTO sentc_file_part (id, file_id, app_id, size, sequence, extern, checksum, version) 
+SELECT ?,?,?,?,?,?,?,? 
+FROM DUAL 
+WHERE NOT EXISTS(
+    SELECT 1 
+    FROM sentc_file_usage 
+    WHERE 
+        app_id = ? AND 
+        (
+            (usage_type = ? AND id = ? AND ? > 0 AND used_storage > ?) OR 
+            (usage_type = ? AND id = ? AND ? > 0 AND used_storage > ?) OR 
+            (usage_type = ? AND id = ? AND ? > 0 AND used_storage > ?)
+        )
+)";
+
+			#[cfg(feature = "sqlite")]
+			let sql = r"
+INSERT INTO sentc_file_part (id, file_id, app_id, size, sequence, extern, checksum, version) 
+SELECT ?,?,?,?,?,?,?,? 
+WHERE NOT EXISTS(
+    SELECT 1 
+    FROM sentc_file_usage 
+    WHERE 
+        app_id = ? AND 
+        (
+            (usage_type = ? AND id = ? AND ? > 0 AND used_storage > ?) OR 
+            (usage_type = ? AND id = ? AND ? > 0 AND used_storage > ?) OR 
+            (usage_type = ? AND id = ? AND ? > 0 AND used_storage > ?)
+        )
+)";
+
+			data.push(TransactionData {
+				sql,
+				params: set_params!(
+					part_id.clone(),
+					file_id.clone(),
+					app_id.clone(),
+					size.to_string(),
+					sequence,
+					extern_storage,
+					checksum,
+					version,
+					app_id.clone(),
+					FILE_USAGE_TYPE_APP,
+					app_id.clone(),
+					quota.max_storage_app,
+					quota.max_storage_app,
+					FILE_USAGE_TYPE_USER,
+					user_id.to_string(),
+					quota.max_storage_user,
+					quota.max_storage_user,
+					FILE_USAGE_TYPE_GROUP,
+					group_id.map(|g| g.to_string()),
+					quota.max_storage_group,
+					quota.max_storage_group
+				),
+			});
+
+			for (sql, params) in prepare_usage_revert(&app_id, user_id, group_id, &part_id, size as i64) {
+				data.push(TransactionData {
+					sql,
+					params,
+				});
+			}
+		},
+		None => {
+			//language=SQL
+			let sql = "INSERT INTO sentc_file_part (id, file_id, app_id, size, sequence, extern, checksum, version) VALUES (?,?,?,?,?,?,?,?)";
+
+			data.push(TransactionData {
+				sql,
+				params: set_params!(
+					part_id.clone(),
+					file_id.clone(),
+					app_id.clone(),
+					size.to_string(),
+					sequence,
+					extern_storage,
+					checksum,
+					version
+				),
+			});
+		},
+	}
+
+	exec_transaction(data).await?;
+
+	if quota.is_some() && get_stored_part(&app_id, &part_id).await?.is_none() {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::FileQuotaStorage,
+			"The storage quota is reached",
+		));
+	}
 
 	if end {
-		//language=SQL
-		let sql = "DELETE FROM sentc_file_session WHERE app_id = ? AND file_id = ?";
-		exec(sql, set_params!(app_id, file_id)).await?;
+		finish_session(app_id, file_id, version).await?;
 	}
 
 	Ok(())
 }
 
+/**
+Delete the session after the last part and use the uploaded version as the current version of the file.
+ */
+pub(super) async fn finish_session(app_id: impl Into<AppId>, file_id: impl Into<FileId>, version: i32) -> AppRes<()>
+{
+	let app_id = app_id.into();
+	let file_id = file_id.into();
+
+	//language=SQL
+	let sql = "DELETE FROM sentc_file_session WHERE app_id = ? AND file_id = ?";
+
+	//language=SQL
+	let sql_version = "UPDATE sentc_file SET version = ? WHERE app_id = ? AND id = ?";
+
+	exec_transaction(vec![
+		TransactionData {
+			sql,
+			params: set_params!(app_id.clone(), file_id.clone()),
+		},
+		TransactionData {
+			sql: sql_version,
+			params: set_params!(version, app_id, file_id),
+		},
+	])
+	.await?;
+
+	Ok(())
+}
+
+/**
+Get the already stored part of a sequence to make the upload of a part idempotent.
+ */
+pub(super) async fn get_part_by_sequence(
+	app_id: impl Into<AppId>,
+	file_id: impl Into<FileId>,
+	version: i32,
+	sequence: i32,
+) -> AppRes<Option<FileStoredPart>>
+{
+	//language=SQL
+	let sql = "SELECT id, size FROM sentc_file_part WHERE app_id = ? AND file_id = ? AND version = ? AND sequence = ? LIMIT 1";
+
+	let part: Option<FileStoredPart> = query_first(sql, set_params!(app_id.into(), file_id.into(), version, sequence)).await?;
+
+	Ok(part)
+}
+
+async fn get_stored_part(app_id: &str, part_id: &str) -> AppRes<Option<FileStoredPart>>
+{
+	//language=SQL
+	let sql = "SELECT id, size FROM sentc_file_part WHERE app_id = ? AND id = ?";
+
+	query_first(sql, set_params!(app_id.to_string(), part_id.to_string())).await
+}
+
+/**
+All parts which are already stored for the file of an upload session, so the client can resume the upload.
+ */
+pub(super) async fn get_session_parts(app_id: impl Into<AppId>, file_id: impl Into<FileId>, version: i32) -> AppRes<Vec<FileSessionPart>>
+{
+	//language=SQL
+	let sql = "SELECT sequence, size FROM sentc_file_part WHERE app_id = ? AND file_id = ? AND version = ? ORDER BY sequence";
+
+	let parts: Vec<FileSessionPart> = query(sql, set_params!(app_id.into(), file_id.into(), version)).await?;
+
+	Ok(parts)
+}
+
 pub(super) async fn delete_file_part(app_id: impl Into<AppId>, part_id: impl Into<PartId>) -> AppRes<()>
 {
 	//language=SQL
@@ -194,7 +465,9 @@ SELECT
     encrypted_key_alg,
     time, 
     encrypted_file_name,
-    master_key_id
+    master_key_id,
+    expires_at,
+    version
 FROM sentc_file 
 WHERE 
     app_id = ? AND 
@@ -215,17 +488,173 @@ WHERE
 	}
 }
 
+/**
+All finished files of the user. Files which belong to a group are only listed in the group.
+
+Files which belong to the user but were uploaded by another user are listed too.
+ */
+pub(super) async fn get_files_for_user(
+	app_id: impl Into<AppId>,
+	user_id: impl Into<UserId>,
+	last_fetched_time: u128,
+	last_id: impl Into<FileId>,
+) -> AppRes<Vec<FileMetaData>>
+{
+	let user_id = user_id.into();
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = r"
+SELECT 
+    id, 
+    owner, 
+    belongs_to, 
+    belongs_to_type, 
+    encrypted_key, 
+    encrypted_key_alg,
+    time, 
+    encrypted_file_name,
+    master_key_id,
+    expires_at,
+    version
+FROM sentc_file f
+WHERE 
+    app_id = ? AND 
+    status = ? AND 
+    (
+        (owner = ? AND belongs_to_type != ?) OR 
+        (belongs_to_type = ? AND belongs_to = ?)
+    ) AND 
+    (expires_at = 0 OR expires_at > ?) AND 
+    NOT EXISTS(SELECT 1 FROM sentc_file_session s WHERE s.file_id = f.id AND s.version = 1)"
+		.to_string();
+
+	let (sql, params) = if last_fetched_time > 0 {
+		let sql = sql + " AND time <= ? AND (time < ? OR (time = ? AND id > ?)) ORDER BY time DESC, id LIMIT 50";
+
+		(
+			sql,
+			set_params!(
+				app_id.into(),
+				FILE_STATUS_AVAILABLE,
+				user_id.clone(),
+				FILE_BELONGS_TO_TYPE_GROUP,
+				FILE_BELONGS_TO_TYPE_USER,
+				user_id,
+				time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_id.into()
+			),
+		)
+	} else {
+		let sql = sql + " ORDER BY time DESC, id LIMIT 50";
+
+		(
+			sql,
+			set_params!(
+				app_id.into(),
+				FILE_STATUS_AVAILABLE,
+				user_id.clone(),
+				FILE_BELONGS_TO_TYPE_GROUP,
+				FILE_BELONGS_TO_TYPE_USER,
+				user_id,
+				time.to_string()
+			),
+		)
+	};
+
+	let files: Vec<FileMetaData> = query_string(sql, params).await?;
+
+	Ok(files)
+}
+
+/**
+All finished files which belong to the group
+ */
+pub(super) async fn get_files_for_group(
+	app_id: impl Into<AppId>,
+	group_id: impl Into<GroupId>,
+	last_fetched_time: u128,
+	last_id: impl Into<FileId>,
+) -> AppRes<Vec<FileMetaData>>
+{
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = r"
+SELECT 
+    id, 
+    owner, 
+    belongs_to, 
+    belongs_to_type, 
+    encrypted_key, 
+    encrypted_key_alg,
+    time, 
+    encrypted_file_name,
+    master_key_id,
+    expires_at,
+    version
+FROM sentc_file f
+WHERE 
+    app_id = ? AND 
+    status = ? AND 
+    belongs_to_type = ? AND 
+    belongs_to = ? AND 
+    (expires_at = 0 OR expires_at > ?) AND 
+    NOT EXISTS(SELECT 1 FROM sentc_file_session s WHERE s.file_id = f.id AND s.version = 1)"
+		.to_string();
+
+	let (sql, params) = if last_fetched_time > 0 {
+		let sql = sql + " AND time <= ? AND (time < ? OR (time = ? AND id > ?)) ORDER BY time DESC, id LIMIT 50";
+
+		(
+			sql,
+			set_params!(
+				app_id.into(),
+				FILE_STATUS_AVAILABLE,
+				FILE_BELONGS_TO_TYPE_GROUP,
+				group_id.into(),
+				time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_id.into()
+			),
+		)
+	} else {
+		let sql = sql + " ORDER BY time DESC, id LIMIT 50";
+
+		(
+			sql,
+			set_params!(
+				app_id.into(),
+				FILE_STATUS_AVAILABLE,
+				FILE_BELONGS_TO_TYPE_GROUP,
+				group_id.into(),
+				time.to_string()
+			),
+		)
+	};
+
+	let files: Vec<FileMetaData> = query_string(sql, params).await?;
+
+	Ok(files)
+}
+
 pub(super) async fn get_file_parts(app_id: impl Into<AppId>, file_id: impl Into<FileId>, last_sequence: i32) -> AppRes<Vec<FilePartListItem>>
 {
-	//get the file parts
+	//get the file parts of the current version
 	//language=SQL
 	let sql = r"
-SELECT id,sequence,extern 
+SELECT id,sequence,extern,checksum 
 FROM 
-    sentc_file_part 
+    sentc_file_part p 
 WHERE 
     app_id = ? AND 
-    file_id = ?"
+    file_id = ? AND 
+    version = (SELECT f.version FROM sentc_file f WHERE f.id = p.file_id)"
 		.to_string();
 
 	let (sql, params) = if last_sequence > 0 {
@@ -260,78 +689,1187 @@ pub(super) async fn update_file_name(file_name: Option<String>, app_id: impl Int
 }
 
 //__________________________________________________________________________________________________
+// share
 
-pub(super) async fn delete_file(app_id: impl Into<AppId>, file_id: impl Into<FileId>) -> AppRes<()>
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn share_file(
+	app_id: impl Into<AppId>,
+	file_id: impl Into<FileId>,
+	user_id: impl Into<UserId>,
+	share_to: String,
+	share_to_type: i32,
+	encrypted_key: String,
+	encrypted_key_alg: String,
+	master_key_id: String,
+) -> AppRes<()>
 {
-	//mark the file as to delete
+	let app_id = app_id.into();
+	let file_id = file_id.into();
+
 	let time = get_time()?;
 
+	//delete the old share first to replace the key
+
 	//language=SQL
-	let sql = "UPDATE sentc_file SET status = ?, delete_at = ? WHERE id = ? AND app_id = ?";
+	let sql_delete = "DELETE FROM sentc_file_share WHERE app_id = ? AND file_id = ? AND share_to = ?";
 
-	exec(
-		sql,
-		set_params!(FILE_STATUS_TO_DELETE, time.to_string(), file_id.into(), app_id.into()),
-	)
+	//language=SQL
+	let sql = r"
+INSERT INTO sentc_file_share 
+    (file_id, app_id, share_to, share_to_type, shared_by, encrypted_key, encrypted_key_alg, master_key_id, time) 
+VALUES (?,?,?,?,?,?,?,?,?)";
+
+	exec_transaction(vec![
+		TransactionData {
+			sql: sql_delete,
+			params: set_params!(app_id.clone(), file_id.clone(), share_to.clone()),
+		},
+		TransactionData {
+			sql,
+			params: set_params!(
+				file_id,
+				app_id,
+				share_to,
+				share_to_type,
+				user_id.into(),
+				encrypted_key,
+				encrypted_key_alg,
+				master_key_id,
+				time.to_string()
+			),
+		},
+	])
 	.await?;
 
 	Ok(())
 }
 
-//__________________________________________________________________________________________________
-
-pub(super) async fn get_external_app_file_delete_info(app_ids: Vec<AppId>) -> AppRes<Vec<FileExternalStorageUrl>>
+pub(super) async fn get_shares(
+	app_id: impl Into<AppId>,
+	file_id: impl Into<FileId>,
+	last_fetched_time: u128,
+	last_id: impl Into<String>,
+) -> AppRes<Vec<FileShare>>
 {
-	let ins = get_in(&app_ids);
+	//language=SQL
+	let sql = r"
+SELECT share_to, share_to_type, shared_by, master_key_id, time 
+FROM sentc_file_share 
+WHERE app_id = ? AND file_id = ?"
+		.to_string();
 
-	//language=SQLx
-	let sql = format!(
-		"SELECT storage_url,app_id,auth_token FROM sentc_file_options WHERE app_id IN ({})",
-		ins
-	);
+	let (sql, params) = if last_fetched_time > 0 {
+		let sql = sql + " AND time <= ? AND (time < ? OR (time = ? AND share_to > ?)) ORDER BY time DESC, share_to LIMIT 50";
 
-	let res: Vec<FileExternalStorageUrl> = query_string(sql, set_params_vec_outer!(app_ids)).await?;
+		(
+			sql,
+			set_params!(
+				app_id.into(),
+				file_id.into(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_fetched_time.to_string(),
+				last_id.into()
+			),
+		)
+	} else {
+		let sql = sql + " ORDER BY time DESC, share_to LIMIT 50";
 
-	Ok(res)
+		(sql, set_params!(app_id.into(), file_id.into()))
+	};
+
+	let shares: Vec<FileShare> = query_string(sql, params).await?;
+
+	Ok(shares)
 }
 
-pub(super) async fn get_all_files_marked_to_delete(last_part_id: Option<String>, start_time: u128) -> AppRes<Vec<FilePartListItemDelete>>
+/**
+Get the file key which was encrypted for the user or the group
+ */
+pub(super) async fn get_share_key(
+	app_id: impl Into<AppId>,
+	file_id: impl Into<FileId>,
+	share_to: impl Into<String>,
+	share_to_type: i32,
+) -> AppRes<Option<FileShareKey>>
 {
-	//owned last part id because of the file worker
-
 	//language=SQL
 	let sql = r"
-SELECT fp.id as file_id_part_id, sequence, extern, fp.app_id as part_app_id 
-FROM 
-    sentc_file_part fp, sentc_file f 
+SELECT encrypted_key, encrypted_key_alg, master_key_id 
+FROM sentc_file_share 
 WHERE 
-    status = ? AND 
-    file_id = f.id AND 
-    delete_at < ?"
-		.to_string();
+    app_id = ? AND 
+    file_id = ? AND 
+    share_to = ? AND 
+    share_to_type = ?";
 
-	let (sql, params) = match last_part_id {
-		None => {
-			let sql = sql + " ORDER BY fp.id LIMIT 500";
-			(sql, set_params!(FILE_STATUS_TO_DELETE, start_time.to_string()))
-		},
-		Some(last) => {
-			let sql = sql + " AND fp.id > ? ORDER BY fp.id LIMIT 500";
-			(sql, set_params!(FILE_STATUS_TO_DELETE, start_time.to_string(), last))
-		},
-	};
+	let key: Option<FileShareKey> = query_first(
+		sql,
+		set_params!(app_id.into(), file_id.into(), share_to.into(), share_to_type),
+	)
+	.await?;
 
-	let file_parts: Vec<FilePartListItemDelete> = query_string(sql, params).await?;
+	Ok(key)
+}
 
-	Ok(file_parts)
+pub(super) async fn delete_share(app_id: impl Into<AppId>, file_id: impl Into<FileId>, share_to: impl Into<String>) -> AppRes<()>
+{
+	//language=SQL
+	let sql = "DELETE FROM sentc_file_share WHERE app_id = ? AND file_id = ? AND share_to = ?";
+
+	exec(sql, set_params!(app_id.into(), file_id.into(), share_to.into())).await?;
+
+	Ok(())
 }
 
-pub(super) async fn delete_file_complete(start_time: u128) -> AppRes<()>
+//__________________________________________________________________________________________________
+// move
+
+/**
+The storage of all versions of the file
+ */
+pub(super) async fn get_file_size(app_id: impl Into<AppId>, file_id: impl Into<FileId>) -> AppRes<i64>
 {
 	//language=SQL
-	let sql = "DELETE FROM sentc_file WHERE delete_at < ? AND status = ?";
+	let sql = "SELECT COALESCE(SUM(size), 0) FROM sentc_file_part WHERE app_id = ? AND file_id = ?";
+
+	let size: Option<I64Entity> = query_first(sql, set_params!(app_id.into(), file_id.into())).await?;
+
+	Ok(size.map(|s| s.0).unwrap_or(0))
+}
+
+/**
+Set the new owner group or user of the file with the file key encrypted by the new owner.
+
+The parts are not changed. The storage and the file are moved from the usage of the old group to the new group.
+ */
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn move_file(
+	app_id: impl Into<AppId>,
+	file_id: impl Into<FileId>,
+	belongs_to_id: Option<String>,
+	belongs_to_type: i32,
+	encrypted_key: String,
+	encrypted_key_alg: String,
+	master_key_id: String,
+	old_group_id: Option<&str>,
+	new_group_id: Option<&str>,
+	size: i64,
+) -> AppRes<()>
+{
+	let app_id = app_id.into();
+
+	//language=SQL
+	let sql = r"
+UPDATE sentc_file 
+SET 
+    belongs_to = ?, 
+    belongs_to_type = ?, 
+    encrypted_key = ?, 
+    encrypted_key_alg = ?, 
+    master_key_id = ? 
+WHERE 
+    app_id = ? AND 
+    id = ?";
+
+	let mut data = vec![TransactionData {
+		sql,
+		params: set_params!(
+			belongs_to_id,
+			belongs_to_type,
+			encrypted_key,
+			encrypted_key_alg,
+			master_key_id,
+			app_id.clone(),
+			file_id.into()
+		),
+	}];
+
+	if old_group_id != new_group_id {
+		if let Some(g) = old_group_id {
+			for (sql, params) in prepare_usage_update_for_targets(&app_id, vec![(FILE_USAGE_TYPE_GROUP, g)], -size, -1) {
+				data.push(TransactionData {
+					sql,
+					params,
+				});
+			}
+		}
+
+		if let Some(g) = new_group_id {
+			for (sql, params) in prepare_usage_update_for_targets(&app_id, vec![(FILE_USAGE_TYPE_GROUP, g)], size, 1) {
+				data.push(TransactionData {
+					sql,
+					params,
+				});
+			}
+		}
+	}
+
+	exec_transaction(data).await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+// versions
+
+/**
+Check if the file is still uploading a version.
+ */
+pub(super) async fn has_open_session(app_id: impl Into<AppId>, file_id: impl Into<FileId>) -> AppRes<bool>
+{
+	//language=SQL
+	let sql = "SELECT 1 FROM sentc_file_session WHERE app_id = ? AND file_id = ? LIMIT 1";
+
+	let session: Option<I64Entity> = query_first(sql, set_params!(app_id.into(), file_id.into())).await?;
+
+	Ok(session.is_some())
+}
+
+/**
+Create the next version of the file with a new upload session.
+
+The version is the current version of the file after the last part was uploaded.
+ */
+pub(super) async fn register_version(app_id: impl Into<AppId>, file_id: impl Into<FileId>, user_id: impl Into<UserId>) -> AppRes<(i32, String)>
+{
+	let app_id = app_id.into();
+	let file_id = file_id.into();
+
+	//language=SQL
+	let sql = "SELECT version FROM sentc_file_version WHERE file_id = ? ORDER BY version DESC LIMIT 1";
+
+	let last: Option<I32Entity> = query_first(sql, set_params!(file_id.clone())).await?;
+
+	let version = match last {
+		Some(v) => v.0 + 1,
+		None => 2,
+	};
+
+	let session_id = create_id();
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = "INSERT INTO sentc_file_version (file_id, version, app_id, owner, time, status, delete_at) VALUES (?,?,?,?,?,?,?)";
+	let params = set_params!(
+		file_id.clone(),
+		version,
+		app_id.clone(),
+		user_id.into(),
+		time.to_string(),
+		FILE_STATUS_AVAILABLE,
+		0
+	);
+
+	//language=SQL
+	let sql_session =
+		"INSERT INTO sentc_file_session (id, file_id, app_id, created_at, expected_size, max_chunk_size, version) VALUES (?,?,?,?,?,?,?)";
+	let params_session = set_params!(
+		session_id.clone(),
+		file_id,
+		app_id,
+		time.to_string(),
+		0,
+		MAX_CHUNK_SIZE.to_string(),
+		version
+	);
+
+	exec_transaction(vec![
+		TransactionData {
+			sql,
+			params,
+		},
+		TransactionData {
+			sql: sql_session,
+			params: params_session,
+		},
+	])
+	.await?;
+
+	Ok((version, session_id))
+}
+
+/**
+All uploaded versions of the file, the newest first. Versions which are still uploading are not listed.
+ */
+pub(super) async fn get_versions(app_id: impl Into<AppId>, file_id: impl Into<FileId>, last_version: i32) -> AppRes<Vec<FileVersion>>
+{
+	//language=SQL
+	let sql = r"
+SELECT version, owner, time 
+FROM sentc_file_version v 
+WHERE 
+    app_id = ? AND 
+    file_id = ? AND 
+    status = ? AND 
+    NOT EXISTS(SELECT 1 FROM sentc_file_session s WHERE s.file_id = v.file_id AND s.version = v.version)"
+		.to_string();
+
+	let (sql, params) = if last_version > 0 {
+		let sql = sql + " AND version < ? ORDER BY version DESC LIMIT 50";
+		(
+			sql,
+			set_params!(app_id.into(), file_id.into(), FILE_STATUS_AVAILABLE, last_version),
+		)
+	} else {
+		let sql = sql + " ORDER BY version DESC LIMIT 50";
+		(sql, set_params!(app_id.into(), file_id.into(), FILE_STATUS_AVAILABLE))
+	};
+
+	let versions: Vec<FileVersion> = query_string(sql, params).await?;
+
+	Ok(versions)
+}
+
+pub(super) async fn get_version(app_id: impl Into<AppId>, file_id: impl Into<FileId>, version: i32) -> AppRes<FileVersion>
+{
+	//language=SQL
+	let sql = r"
+SELECT version, owner, time 
+FROM sentc_file_version v 
+WHERE 
+    app_id = ? AND 
+    file_id = ? AND 
+    version = ? AND 
+    status = ? AND 
+    NOT EXISTS(SELECT 1 FROM sentc_file_session s WHERE s.file_id = v.file_id AND s.version = v.version)";
+
+	let version: Option<FileVersion> = query_first(
+		sql,
+		set_params!(app_id.into(), file_id.into(), version, FILE_STATUS_AVAILABLE),
+	)
+	.await?;
+
+	match version {
+		Some(v) => Ok(v),
+		None => {
+			Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::FileVersionNotFound,
+				"File version not found",
+			))
+		},
+	}
+}
+
+pub(super) async fn get_version_parts(
+	app_id: impl Into<AppId>,
+	file_id: impl Into<FileId>,
+	version: i32,
+	last_sequence: i32,
+) -> AppRes<Vec<FilePartListItem>>
+{
+	//language=SQL
+	let sql = r"
+SELECT id,sequence,extern,checksum 
+FROM 
+    sentc_file_part 
+WHERE 
+    app_id = ? AND 
+    file_id = ? AND 
+    version = ?"
+		.to_string();
+
+	let (sql, params) = if last_sequence > 0 {
+		let sql = sql + " AND sequence > ? ORDER BY sequence LIMIT 500";
+		(
+			sql,
+			set_params!(app_id.into(), file_id.into(), version, last_sequence),
+		)
+	} else {
+		let sql = sql + " ORDER BY sequence LIMIT 500";
+		(sql, set_params!(app_id.into(), file_id.into(), version))
+	};
+
+	let file_parts: Vec<FilePartListItem> = query_string(sql, params).await?;
+
+	if file_parts.is_empty() {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::FileVersionNotFound,
+			"File version not found",
+		));
+	}
+
+	Ok(file_parts)
+}
+
+/**
+Use an older version as the current version of the file
+ */
+pub(super) async fn set_current_version(app_id: impl Into<AppId>, file_id: impl Into<FileId>, version: i32) -> AppRes<()>
+{
+	//language=SQL
+	let sql = "UPDATE sentc_file SET version = ? WHERE app_id = ? AND id = ?";
+
+	exec(sql, set_params!(version, app_id.into(), file_id.into())).await?;
+
+	Ok(())
+}
+
+pub(super) async fn delete_version(app_id: impl Into<AppId>, file_id: impl Into<FileId>, version: i32) -> AppRes<()>
+{
+	//mark the version as to delete, the file worker deletes the parts
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = "UPDATE sentc_file_version SET status = ?, delete_at = ? WHERE app_id = ? AND file_id = ? AND version = ?";
+
+	exec(
+		sql,
+		set_params!(
+			FILE_STATUS_TO_DELETE,
+			time.to_string(),
+			app_id.into(),
+			file_id.into(),
+			version
+		),
+	)
+	.await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+
+pub(super) async fn delete_file(app_id: impl Into<AppId>, file_id: impl Into<FileId>) -> AppRes<()>
+{
+	//mark the file as to delete
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = "UPDATE sentc_file SET status = ?, delete_at = ? WHERE id = ? AND app_id = ?";
+
+	exec(
+		sql,
+		set_params!(FILE_STATUS_TO_DELETE, time.to_string(), file_id.into(), app_id.into()),
+	)
+	.await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+// usage
+
+/**
+Get the usage of the app, the user and the group (if set) at once
+ */
+pub(super) async fn get_usage(app_id: impl Into<AppId>, user_id: impl Into<UserId>, group_id: Option<&str>) -> AppRes<Vec<FileUsageItem>>
+{
+	let app_id = app_id.into();
+
+	//language=SQL
+	let sql = r"
+SELECT usage_type, used_storage, file_count 
+FROM sentc_file_usage 
+WHERE 
+    app_id = ? AND 
+    (
+        (usage_type = ? AND id = ?) OR 
+        (usage_type = ? AND id = ?) OR 
+        (usage_type = ? AND id = ?)
+    )";
+
+	query(
+		sql,
+		set_params!(
+			app_id.clone(),
+			FILE_USAGE_TYPE_APP,
+			app_id,
+			FILE_USAGE_TYPE_USER,
+			user_id.into(),
+			FILE_USAGE_TYPE_GROUP,
+			group_id.map(|g| g.to_string())
+		),
+	)
+	.await
+}
+
+/**
+Change the usage counter of the app, the user and the group.
+
+Creates the counter row if it not exists yet.
+ */
+fn prepare_usage_update(app_id: &str, user_id: &str, group_id: Option<&str>, storage: i64, files: i32) -> Vec<(&'static str, Params)>
+{
+	let mut targets = vec![(FILE_USAGE_TYPE_APP, app_id), (FILE_USAGE_TYPE_USER, user_id)];
+
+	if let Some(g) = group_id {
+		targets.push((FILE_USAGE_TYPE_GROUP, g));
+	}
+
+	prepare_usage_update_for_targets(app_id, targets, storage, files)
+}
+
+fn prepare_usage_update_for_targets(app_id: &str, targets: Vec<(i32, &str)>, storage: i64, files: i32) -> Vec<(&'static str, Params)>
+{
+	#[cfg(feature = "mysql")]
+	//language=SQL
+	let sql_init = "INSERT IGNORE INTO sentc_file_usage (app_id, usage_type, id, used_storage, file_count) VALUES (?,?,?,0,0)";
+
+	#[cfg(feature = "sqlite")]
+	let sql_init = "INSERT OR IGNORE INTO sentc_file_usage (app_id, usage_type, id, used_storage, file_count) VALUES (?,?,?,0,0)";
+
+	//language=SQL
+	let sql_update = r"
+UPDATE sentc_file_usage 
+SET 
+    used_storage = used_storage + ?, 
+    file_count = file_count + ? 
+WHERE 
+    app_id = ? AND 
+    usage_type = ? AND 
+    id = ?";
+
+	let mut out = Vec::with_capacity(targets.len() * 2);
+
+	for (usage_type, id) in targets {
+		out.push((sql_init, set_params!(app_id.to_string(), usage_type, id.to_string())));
+		out.push((
+			sql_update,
+			set_params!(storage, files, app_id.to_string(), usage_type, id.to_string()),
+		));
+	}
+
+	out
+}
+
+/**
+Remove the size of the part from the usage again when the part was not inserted because of the storage quota.
+ */
+fn prepare_usage_revert(app_id: &str, user_id: &str, group_id: Option<&str>, part_id: &str, storage: i64) -> Vec<(&'static str, Params)>
+{
+	//language=SQL
+	let sql = r"
+UPDATE sentc_file_usage 
+SET used_storage = used_storage - ? 
+WHERE 
+    app_id = ? AND 
+    usage_type = ? AND 
+    id = ? AND 
+    NOT EXISTS(SELECT 1 FROM sentc_file_part WHERE id = ?)";
+
+	let mut targets = vec![(FILE_USAGE_TYPE_APP, app_id), (FILE_USAGE_TYPE_USER, user_id)];
+
+	if let Some(g) = group_id {
+		targets.push((FILE_USAGE_TYPE_GROUP, g));
+	}
+
+	targets
+		.into_iter()
+		.map(|(usage_type, id)| {
+			(
+				sql,
+				set_params!(
+					storage,
+					app_id.to_string(),
+					usage_type,
+					id.to_string(),
+					part_id.to_string()
+				),
+			)
+		})
+		.collect()
+}
+
+//__________________________________________________________________________________________________
+// expiry
+
+pub(super) async fn get_part_for_download(app_id: impl Into<AppId>, part_id: impl Into<PartId>) -> AppRes<Option<FilePartDownload>>
+{
+	//language=SQL
+	let sql = r"
+SELECT p.size, f.expires_at 
+FROM sentc_file f, sentc_file_part p 
+WHERE 
+    p.id = ? AND 
+    p.app_id = ? AND 
+    f.id = p.file_id";
+
+	query_first(sql, set_params!(part_id.into(), app_id.into())).await
+}
+
+/**
+Mark all expired files as to delete, so the file worker can delete the parts.
+ */
+pub(super) async fn delete_expired_files() -> AppRes<()>
+{
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = "UPDATE sentc_file SET status = ?, delete_at = ? WHERE status = ? AND expires_at > 0 AND expires_at < ?";
+
+	exec(
+		sql,
+		set_params!(
+			FILE_STATUS_TO_DELETE,
+			time.to_string(),
+			FILE_STATUS_AVAILABLE,
+			time.to_string()
+		),
+	)
+	.await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+// abandoned uploads
+
+/**
+Get all sessions which are older than the session lifetime of their app.
+ */
+pub(super) async fn get_old_sessions(last_session_id: Option<String>, time: u128) -> AppRes<Vec<FileSessionOld>>
+{
+	//language=SQL
+	let sql = r"
+SELECT s.id, file_id, version 
+FROM 
+    sentc_file_session s, 
+    sentc_file_options o 
+WHERE 
+    o.app_id = s.app_id AND 
+    s.created_at + (o.session_lifetime * 1000) < ?"
+		.to_string();
+
+	let (sql, params) = match last_session_id {
+		None => {
+			let sql = sql + " ORDER BY s.id LIMIT 500";
+			(sql, set_params!(time.to_string()))
+		},
+		Some(last) => {
+			let sql = sql + " AND s.id > ? ORDER BY s.id LIMIT 500";
+			(sql, set_params!(time.to_string(), last))
+		},
+	};
+
+	let sessions: Vec<FileSessionOld> = query_string(sql, params).await?;
+
+	Ok(sessions)
+}
+
+/**
+Mark the not finished files as to delete, so the file worker can delete the uploaded parts.
+ */
+pub(super) async fn delete_abandoned_files(file_ids: Vec<FileId>) -> AppRes<()>
+{
+	if file_ids.is_empty() {
+		return Ok(());
+	}
+
+	let time = get_time()?;
+
+	let ins = get_in(&file_ids);
+
+	//language=SQLx
+	let sql = format!(
+		"UPDATE sentc_file SET status = ?, delete_at = ? WHERE id IN ({})",
+		ins
+	);
+
+	let mut exec_vec = Vec::with_capacity(file_ids.len() + 2);
+
+	exec_vec.push(TupleEntity(FILE_STATUS_TO_DELETE.to_string()));
+	exec_vec.push(TupleEntity(time.to_string()));
+
+	for id in &file_ids {
+		exec_vec.push(TupleEntity(id.to_string()));
+	}
+
+	exec_string(sql, set_params_vec!(exec_vec)).await?;
+
+	//language=SQLx
+	let sql = format!("DELETE FROM sentc_file_session WHERE file_id IN ({})", ins);
+
+	exec_string(sql, set_params_vec_outer!(file_ids)).await?;
+
+	Ok(())
+}
+
+/**
+Mark the not finished versions as to delete. The file and the other versions are still available.
+ */
+pub(super) async fn delete_abandoned_versions(versions: Vec<(FileId, i32)>) -> AppRes<()>
+{
+	if versions.is_empty() {
+		return Ok(());
+	}
+
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = "UPDATE sentc_file_version SET status = ?, delete_at = ? WHERE file_id = ? AND version = ?";
+
+	//language=SQL
+	let sql_session = "DELETE FROM sentc_file_session WHERE file_id = ? AND version = ?";
+
+	let mut data = Vec::with_capacity(versions.len() * 2);
+
+	for (file_id, version) in versions {
+		data.push(TransactionData {
+			sql,
+			params: set_params!(FILE_STATUS_TO_DELETE, time.to_string(), file_id.clone(), version),
+		});
+		data.push(TransactionData {
+			sql: sql_session,
+			params: set_params!(file_id, version),
+		});
+	}
+
+	exec_transaction(data).await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+// part check
+
+/**
+Get the parts of our storage with a checksum to check if the stored data was changed.
+ */
+pub(super) async fn get_parts_to_check(last_part_id: Option<String>) -> AppRes<Vec<FilePartCheck>>
+{
+	//language=SQL
+	let sql = r"
+SELECT id, checksum 
+FROM sentc_file_part 
+WHERE 
+    extern = ? AND 
+    corrupted = ? AND 
+    checksum IS NOT NULL"
+		.to_string();
+
+	let (sql, params) = match last_part_id {
+		None => {
+			let sql = sql + " ORDER BY id LIMIT 500";
+			(sql, set_params!(false, false))
+		},
+		Some(last) => {
+			let sql = sql + " AND id > ? ORDER BY id LIMIT 500";
+			(sql, set_params!(false, false, last))
+		},
+	};
+
+	let parts: Vec<FilePartCheck> = query_string(sql, params).await?;
+
+	Ok(parts)
+}
+
+pub(super) async fn set_parts_corrupted(part_ids: Vec<PartId>) -> AppRes<()>
+{
+	if part_ids.is_empty() {
+		return Ok(());
+	}
+
+	let ins = get_in(&part_ids);
+
+	//language=SQLx
+	let sql = format!("UPDATE sentc_file_part SET corrupted = 1 WHERE id IN ({})", ins);
+
+	exec_string(sql, set_params_vec_outer!(part_ids)).await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+
+pub(super) async fn get_external_app_file_delete_info(app_ids: Vec<AppId>) -> AppRes<Vec<FileExternalStorageUrl>>
+{
+	let ins = get_in(&app_ids);
+
+	//language=SQLx
+	let sql = format!(
+		"SELECT storage_url,app_id,auth_token FROM sentc_file_options WHERE app_id IN ({})",
+		ins
+	);
+
+	let res: Vec<FileExternalStorageUrl> = query_string(sql, set_params_vec_outer!(app_ids)).await?;
+
+	Ok(res)
+}
+
+/**
+Save the deleted parts of the external storage until the storage confirmed the delete.
+ */
+pub(super) async fn add_external_deletes(app_id: AppId, part_ids: Vec<PartId>) -> AppRes<()>
+{
+	let time = get_time()?;
+
+	//ignore parts which are already saved, e.g. when the file worker stopped before the parts were deleted
+	bulk_insert(
+		true,
+		"sentc_file_external_delete",
+		&["part_id", "app_id", "time", "attempts", "next_try"],
+		part_ids,
+		move |id| set_params!(id, app_id.clone(), time.to_string(), 0, time.to_string()),
+	)
+	.await?;
+
+	Ok(())
+}
+
+/**
+Only the parts of one app when the app id is set
+ */
+pub(super) async fn get_external_deletes(last_part_id: Option<String>, start_time: u128, app_id: Option<&str>) -> AppRes<Vec<FileExternalDelete>>
+{
+	//only the apps which still got an external storage
+
+	//language=SQL
+	let mut sql = r"
+SELECT part_id, d.app_id, attempts, storage_url, auth_token 
+FROM 
+    sentc_file_external_delete d, 
+    sentc_file_options o 
+WHERE 
+    o.app_id = d.app_id AND 
+    storage_url IS NOT NULL AND 
+    next_try <= ?"
+		.to_string();
+
+	let mut params = vec![TupleEntity(start_time.to_string())];
+
+	if let Some(id) = app_id {
+		sql += " AND d.app_id = ?";
+		params.push(TupleEntity(id.to_string()));
+	}
+
+	if let Some(id) = last_part_id {
+		sql += " AND part_id > ?";
+		params.push(TupleEntity(id));
+	}
+
+	sql += " ORDER BY part_id LIMIT 500";
+
+	let res: Vec<FileExternalDelete> = query_string(sql, set_params_vec!(params)).await?;
+
+	Ok(res)
+}
+
+pub(super) async fn delete_external_deletes(part_ids: Vec<PartId>) -> AppRes<()>
+{
+	if part_ids.is_empty() {
+		return Ok(());
+	}
+
+	let ins = get_in(&part_ids);
+
+	//language=SQLx
+	let sql = format!("DELETE FROM sentc_file_external_delete WHERE part_id IN ({})", ins);
+
+	exec_string(sql, set_params_vec_outer!(part_ids)).await?;
+
+	Ok(())
+}
+
+pub(super) async fn set_external_deletes_failed(part_ids: Vec<PartId>, next_try: u128, error: String) -> AppRes<()>
+{
+	if part_ids.is_empty() {
+		return Ok(());
+	}
+
+	let ins = get_in(&part_ids);
+
+	//language=SQLx
+	let sql = format!(
+		"UPDATE sentc_file_external_delete SET attempts = attempts + 1, next_try = ?, last_error = ? WHERE part_id IN ({})",
+		ins
+	);
+
+	let mut params = vec![TupleEntity(next_try.to_string()), TupleEntity(error)];
+
+	for id in part_ids {
+		params.push(TupleEntity(id));
+	}
+
+	exec_string(sql, set_params_vec!(params)).await?;
+
+	Ok(())
+}
+
+pub(super) async fn get_all_files_marked_to_delete(last_part_id: Option<String>, start_time: u128) -> AppRes<Vec<FilePartListItemDelete>>
+{
+	//owned last part id because of the file worker
+
+	//language=SQL
+	let sql = r"
+SELECT fp.id as file_id_part_id, sequence, extern, fp.app_id as part_app_id 
+FROM 
+    sentc_file_part fp, sentc_file f 
+WHERE 
+    status = ? AND 
+    file_id = f.id AND 
+    delete_at < ?"
+		.to_string();
+
+	let (sql, params) = match last_part_id {
+		None => {
+			let sql = sql + " ORDER BY fp.id LIMIT 500";
+			(sql, set_params!(FILE_STATUS_TO_DELETE, start_time.to_string()))
+		},
+		Some(last) => {
+			let sql = sql + " AND fp.id > ? ORDER BY fp.id LIMIT 500";
+			(sql, set_params!(FILE_STATUS_TO_DELETE, start_time.to_string(), last))
+		},
+	};
+
+	let file_parts: Vec<FilePartListItemDelete> = query_string(sql, params).await?;
+
+	Ok(file_parts)
+}
+
+/**
+Delete the files which are marked as to delete and remove their usage from the counters.
+
+Both happens in one transaction, so the usage of a file is only removed once even when the worker stops in between.
+ */
+pub(super) async fn delete_file_complete(start_time: u128) -> AppRes<()>
+{
+	//language=SQL
+	let sql = r"
+SELECT 
+    f.id, 
+    f.version, 
+    f.app_id, 
+    owner, 
+    belongs_to, 
+    belongs_to_type, 
+    (SELECT COALESCE(SUM(size), 0) FROM sentc_file_part fp WHERE fp.file_id = f.id) as file_size 
+FROM sentc_file f 
+WHERE 
+    delete_at < ? AND 
+    status = ?";
+
+	let files: Vec<FileDeleteUsage> = query(sql, set_params!(start_time.to_string(), FILE_STATUS_TO_DELETE)).await?;
+
+	if files.is_empty() {
+		return Ok(());
+	}
+
+	//language=SQL
+	let sql_delete = "DELETE FROM sentc_file WHERE id = ? AND status = ?";
+
+	let mut data = Vec::with_capacity(files.len() * 7);
+
+	for file in files {
+		let group_id = if file.belongs_to_type == FILE_BELONGS_TO_TYPE_GROUP {
+			file.belongs_to.as_deref()
+		} else {
+			None
+		};
+
+		for (sql, params) in prepare_usage_update(&file.app_id, &file.owner, group_id, -file.file_size, -1) {
+			data.push(TransactionData {
+				sql,
+				params,
+			});
+		}
+
+		data.push(TransactionData {
+			sql: sql_delete,
+			params: set_params!(file.file_id, FILE_STATUS_TO_DELETE),
+		});
+	}
+
+	exec_transaction(data).await?;
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+// version retention
+
+/**
+Get the old versions of the files of apps with a retention policy, ordered by file and the newest version first.
+
+The current version and the versions which are still uploading are never deleted.
+ */
+pub(super) async fn get_versions_for_retention(last: Option<(FileId, i32)>) -> AppRes<Vec<FileVersionRetention>>
+{
+	//language=SQL
+	let sql = r"
+SELECT v.file_id, v.version, v.time, version_keep_last, version_keep_days 
+FROM 
+    sentc_file_version v, 
+    sentc_file f, 
+    sentc_file_options o 
+WHERE 
+    f.id = v.file_id AND 
+    o.app_id = v.app_id AND 
+    v.status = ? AND 
+    f.status = ? AND 
+    v.version != f.version AND 
+    (version_keep_last > 0 OR version_keep_days > 0) AND 
+    NOT EXISTS(SELECT 1 FROM sentc_file_session s WHERE s.file_id = v.file_id AND s.version = v.version)"
+		.to_string();
+
+	let (sql, params) = match last {
+		None => {
+			let sql = sql + " ORDER BY v.file_id, v.version DESC LIMIT 500";
+			(sql, set_params!(FILE_STATUS_AVAILABLE, FILE_STATUS_AVAILABLE))
+		},
+		Some((last_file_id, last_version)) => {
+			let sql = sql + " AND (v.file_id > ? OR (v.file_id = ? AND v.version < ?)) ORDER BY v.file_id, v.version DESC LIMIT 500";
+			(
+				sql,
+				set_params!(
+					FILE_STATUS_AVAILABLE,
+					FILE_STATUS_AVAILABLE,
+					last_file_id.clone(),
+					last_file_id,
+					last_version
+				),
+			)
+		},
+	};
+
+	let versions: Vec<FileVersionRetention> = query_string(sql, params).await?;
+
+	Ok(versions)
+}
+
+pub(super) async fn delete_versions(versions: Vec<(FileId, i32)>) -> AppRes<()>
+{
+	if versions.is_empty() {
+		return Ok(());
+	}
+
+	let time = get_time()?;
+
+	//language=SQL
+	let sql = "UPDATE sentc_file_version SET status = ?, delete_at = ? WHERE file_id = ? AND version = ?";
+
+	let data = versions
+		.into_iter()
+		.map(|(file_id, version)| {
+			TransactionData {
+				sql,
+				params: set_params!(FILE_STATUS_TO_DELETE, time.to_string(), file_id, version),
+			}
+		})
+		.collect();
+
+	exec_transaction(data).await?;
+
+	Ok(())
+}
+
+/**
+The parts of the deleted versions. Parts of deleted files are fetched with the files.
+ */
+pub(super) async fn get_all_versions_marked_to_delete(last_part_id: Option<String>, start_time: u128) -> AppRes<Vec<FilePartListItemDelete>>
+{
+	//language=SQL
+	let sql = r"
+SELECT fp.id as file_id_part_id, sequence, extern, fp.app_id as part_app_id 
+FROM 
+    sentc_file_part fp, 
+    sentc_file_version v, 
+    sentc_file f 
+WHERE 
+    v.file_id = fp.file_id AND 
+    v.version = fp.version AND 
+    f.id = fp.file_id AND 
+    f.status = ? AND 
+    v.status = ? AND 
+    v.delete_at < ?"
+		.to_string();
+
+	let (sql, params) = match last_part_id {
+		None => {
+			let sql = sql + " ORDER BY fp.id LIMIT 500";
+			(
+				sql,
+				set_params!(FILE_STATUS_AVAILABLE, FILE_STATUS_TO_DELETE, start_time.to_string()),
+			)
+		},
+		Some(last) => {
+			let sql = sql + " AND fp.id > ? ORDER BY fp.id LIMIT 500";
+			(
+				sql,
+				set_params!(
+					FILE_STATUS_AVAILABLE,
+					FILE_STATUS_TO_DELETE,
+					start_time.to_string(),
+					last
+				),
+			)
+		},
+	};
+
+	let file_parts: Vec<FilePartListItemDelete> = query_string(sql, params).await?;
+
+	Ok(file_parts)
+}
+
+/**
+Delete the versions which are marked as to delete and remove their storage from the counters. The file count is not changed.
+
+The versions of deleted files are deleted with the file.
+ */
+pub(super) async fn delete_versions_complete(start_time: u128) -> AppRes<()>
+{
+	//language=SQL
+	let sql = r"
+SELECT 
+    v.file_id, 
+    v.version, 
+    f.app_id, 
+    f.owner, 
+    belongs_to, 
+    belongs_to_type, 
+    (SELECT COALESCE(SUM(size), 0) FROM sentc_file_part fp WHERE fp.file_id = v.file_id AND fp.version = v.version) as file_size 
+FROM sentc_file_version v, sentc_file f 
+WHERE 
+    f.id = v.file_id AND 
+    f.status = ? AND 
+    v.status = ? AND 
+    v.delete_at < ?";
+
+	let versions: Vec<FileDeleteUsage> = query(
+		sql,
+		set_params!(FILE_STATUS_AVAILABLE, FILE_STATUS_TO_DELETE, start_time.to_string()),
+	)
+	.await?;
+
+	if versions.is_empty() {
+		return Ok(());
+	}
+
+	//language=SQL
+	let sql_parts = "DELETE FROM sentc_file_part WHERE file_id = ? AND version = ?";
+
+	//language=SQL
+	let sql_version = "DELETE FROM sentc_file_version WHERE file_id = ? AND version = ? AND status = ?";
+
+	let mut data = Vec::with_capacity(versions.len() * 8);
+
+	for version in versions {
+		let group_id = if version.belongs_to_type == FILE_BELONGS_TO_TYPE_GROUP {
+			version.belongs_to.as_deref()
+		} else {
+			None
+		};
+
+		for (sql, params) in prepare_usage_update(&version.app_id, &version.owner, group_id, -version.file_size, 0) {
+			data.push(TransactionData {
+				sql,
+				params,
+			});
+		}
+
+		data.push(TransactionData {
+			sql: sql_parts,
+			params: set_params!(version.file_id.clone(), version.version),
+		});
+		data.push(TransactionData {
+			sql: sql_version,
+			params: set_params!(version.file_id, version.version, FILE_STATUS_TO_DELETE),
+		});
+	}
 
-	exec(sql, set_params!(start_time.to_string(), FILE_STATUS_TO_DELETE)).await?;
+	exec_transaction(data).await?;
 
 	Ok(())
 }
@@ -0,0 +1,64 @@
+use hyper::StatusCode;
+use rustgram_server_util::res::AppRes;
+
+use crate::file_entities::FilePartCheck;
+use crate::{file_model, file_service};
+
+/**
+Re-hash all parts of our storage and mark the parts as corrupted where the stored data doesn't match the checksum from the upload.
+
+Parts from the external storage are not checked, the customer must check their own storage.
+ */
+pub async fn start() -> AppRes<()>
+{
+	let mut last_id = None;
+
+	loop {
+		let parts = file_model::get_parts_to_check(last_id).await?;
+		let part_len = parts.len();
+
+		match parts.last() {
+			Some(p) => last_id = Some(p.part_id.to_string()),
+			None => {
+				//parts are empty
+				break;
+			},
+		}
+
+		let mut corrupted = Vec::new();
+
+		for part in parts {
+			if !check_part(&part).await {
+				corrupted.push(part.part_id);
+			}
+		}
+
+		file_model::set_parts_corrupted(corrupted).await?;
+
+		if part_len < 500 {
+			break;
+		}
+	}
+
+	Ok(())
+}
+
+async fn check_part(part: &FilePartCheck) -> bool
+{
+	//a part which can't be loaded counts as corrupted too
+	let res = match rustgram_server_util::file::get_part(part.part_id.as_str()).await {
+		Ok(r) => r,
+		Err(_e) => return false,
+	};
+
+	if res.status() != StatusCode::OK {
+		return false;
+	}
+
+	let data = match hyper::body::to_bytes(res.into_body()).await {
+		Ok(d) => d,
+		Err(_e) => return false,
+	};
+
+	file_service::part_checksum(&data) == part.checksum
+}
@@ -1,10 +1,17 @@
+use hyper::body::HttpBody;
+use hyper::Body;
+use ring::digest::{digest, SHA256};
+use rustgram::Request;
 use rustgram_server_util::error::{ServerCoreError, ServerErrorConstructor};
+use rustgram_server_util::get_time;
 use rustgram_server_util::res::AppRes;
 use sentc_crypto_common::file::{BelongsToType, FileRegisterInput, FileRegisterOutput};
 use sentc_crypto_common::{AppId, FileId, GroupId};
+use server_api_common::customer_app::app_entities::AppFileOptions;
+use server_api_common::file::{FILE_USAGE_TYPE_APP, FILE_USAGE_TYPE_GROUP, FILE_USAGE_TYPE_USER};
 use server_api_common::group::group_entities::InternalGroupDataComplete;
 
-use crate::file_entities::FileMetaData;
+use crate::file_entities::{FileMetaData, FileMoveInput, FilePartListItem, FileShare, FileShareInput, FileVersion};
 use crate::{file_model, ApiErrorCodes};
 
 //same values as in file entity
@@ -12,7 +19,250 @@ pub(super) const FILE_BELONGS_TO_TYPE_NONE: i32 = 0;
 pub(super) const FILE_BELONGS_TO_TYPE_GROUP: i32 = 1;
 pub(super) const FILE_BELONGS_TO_TYPE_USER: i32 = 2;
 
-pub async fn register_file(input: FileRegisterInput, app_id: &str, user_id: &str, group_id: Option<GroupId>) -> AppRes<FileRegisterOutput>
+pub const PART_CHECKSUM_HEADER: &str = "x-sentc-part-checksum";
+
+/**
+The file quotas of the app. 0 = no limit
+
+Copied from the app options because the request is consumed by the part upload.
+ */
+#[derive(Clone, Copy)]
+pub struct FileQuota
+{
+	pub max_storage_app: i64,
+	pub max_storage_user: i64,
+	pub max_storage_group: i64,
+	pub max_files_app: i32,
+	pub max_files_user: i32,
+	pub max_files_group: i32,
+}
+
+impl From<&AppFileOptions> for FileQuota
+{
+	fn from(options: &AppFileOptions) -> Self
+	{
+		Self {
+			max_storage_app: options.max_storage_app,
+			max_storage_user: options.max_storage_user,
+			max_storage_group: options.max_storage_group,
+			max_files_app: options.max_files_app,
+			max_files_user: options.max_files_user,
+			max_files_group: options.max_files_group,
+		}
+	}
+}
+
+impl FileQuota
+{
+	/**
+	Only the limits of the group, e.g. when a file is moved into a group. The usage of the app and the user is not changed.
+	 */
+	fn group_only(self) -> Self
+	{
+		Self {
+			max_storage_app: 0,
+			max_storage_user: 0,
+			max_files_app: 0,
+			max_files_user: 0,
+			..self
+		}
+	}
+
+	fn is_unlimited(&self, group: bool) -> bool
+	{
+		self.max_storage_app == 0 &&
+			self.max_storage_user == 0 &&
+			self.max_files_app == 0 &&
+			self.max_files_user == 0 &&
+			(!group || (self.max_storage_group == 0 && self.max_files_group == 0))
+	}
+}
+
+/**
+Check if the app, the user and the group got space for the new storage and the new files.
+ */
+pub async fn check_quota(quota: FileQuota, app_id: &str, user_id: &str, group_id: Option<&str>, new_storage: i64, new_files: i32) -> AppRes<()>
+{
+	if quota.is_unlimited(group_id.is_some()) {
+		return Ok(());
+	}
+
+	let usage = file_model::get_usage(app_id, user_id, group_id).await?;
+
+	let mut limits = vec![
+		(FILE_USAGE_TYPE_APP, quota.max_storage_app, quota.max_files_app),
+		(FILE_USAGE_TYPE_USER, quota.max_storage_user, quota.max_files_user),
+	];
+
+	if group_id.is_some() {
+		limits.push((FILE_USAGE_TYPE_GROUP, quota.max_storage_group, quota.max_files_group));
+	}
+
+	for (usage_type, max_storage, max_files) in limits {
+		//no row when nothing was uploaded yet
+		let (used_storage, file_count) = usage
+			.iter()
+			.find(|u| u.usage_type == usage_type)
+			.map(|u| (u.used_storage, u.file_count))
+			.unwrap_or((0, 0));
+
+		if new_storage > 0 && max_storage > 0 && used_storage + new_storage > max_storage {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::FileQuotaStorage,
+				"The storage quota is reached",
+			));
+		}
+
+		if new_files > 0 && max_files > 0 && file_count + new_files > max_files {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::FileQuotaFiles,
+				"The file quota is reached",
+			));
+		}
+	}
+
+	Ok(())
+}
+
+//__________________________________________________________________________________________________
+// part checksum
+
+/**
+The sha256 of an encrypted part as lowercase hex
+ */
+pub fn part_checksum(data: &[u8]) -> String
+{
+	digest(&SHA256, data)
+		.as_ref()
+		.iter()
+		.map(|b| format!("{:02x}", b))
+		.collect()
+}
+
+/**
+Get the checksum which was sent by the client for a part. The checksum is optional for older clients.
+ */
+pub fn get_part_checksum_from_req(req: &Request) -> AppRes<Option<String>>
+{
+	let checksum = match req.headers().get(PART_CHECKSUM_HEADER) {
+		Some(c) => c,
+		None => return Ok(None),
+	};
+
+	let checksum = checksum.to_str().map_err(|_e| {
+		ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::FilePartChecksum,
+			"Part checksum has a wrong format",
+		)
+	})?;
+
+	if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::FilePartChecksum,
+			"Part checksum has a wrong format",
+		));
+	}
+
+	Ok(Some(checksum.to_lowercase()))
+}
+
+/**
+Read the part into memory to hash it before it is stored.
+
+The request is rebuilt with the read body, so it can be passed to the storage upload.
+If the client sent a checksum, the hash must match it.
+ */
+pub async fn read_and_check_part(req: Request, max_chunk_size: usize, checksum: Option<&str>) -> AppRes<(Request, String)>
+{
+	let (parts, mut body) = req.into_parts();
+
+	let mut data: Vec<u8> = Vec::new();
+
+	while let Some(chunk) = body.data().await {
+		let chunk = chunk.map_err(|_e| ServerCoreError::new_msg(400, ApiErrorCodes::FilePartRead, "Can't read the part"))?;
+
+		if data.len() + chunk.len() > max_chunk_size {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::FilePartRead,
+				"The part is too large",
+			));
+		}
+
+		data.extend_from_slice(&chunk);
+	}
+
+	let hash = part_checksum(&data);
+
+	if let Some(c) = checksum {
+		if c != hash {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::FilePartChecksum,
+				"The part checksum does not match. The part was changed during the upload.",
+			));
+		}
+	}
+
+	Ok((Request::from_parts(parts, Body::from(data)), hash))
+}
+
+/**
+Get the expiry time of a new file.
+
+The expiry time is a timestamp in ms, the ttl options of the app are in seconds.
+Without an expiry time from the client the default ttl is used or the max ttl if there is no default.
+ */
+pub fn get_expires_at(expires_at: Option<u128>, default_ttl: i64, max_ttl: i64) -> AppRes<u128>
+{
+	let time = get_time()?;
+
+	let max = if max_ttl > 0 { Some(time + max_ttl as u128 * 1000) } else { None };
+
+	match expires_at {
+		None | Some(0) => {
+			if default_ttl > 0 {
+				Ok(time + default_ttl as u128 * 1000)
+			} else {
+				Ok(max.unwrap_or(0))
+			}
+		},
+		Some(expires_at) => {
+			if expires_at <= time {
+				return Err(ServerCoreError::new_msg(
+					400,
+					ApiErrorCodes::FileExpiresAt,
+					"The expiry time of the file must be in the future",
+				));
+			}
+
+			if let Some(max) = max {
+				if expires_at > max {
+					return Err(ServerCoreError::new_msg(
+						400,
+						ApiErrorCodes::FileExpiresAt,
+						"The expiry time of the file is greater than the max ttl of the app",
+					));
+				}
+			}
+
+			Ok(expires_at)
+		},
+	}
+}
+
+pub async fn register_file(
+	input: FileRegisterInput,
+	expires_at: u128,
+	quota: FileQuota,
+	app_id: &str,
+	user_id: &str,
+	group_id: Option<GroupId>,
+) -> AppRes<FileRegisterOutput>
 {
 	//check first if belongs to is set
 
@@ -46,6 +296,15 @@ pub async fn register_file(input: FileRegisterInput, app_id: &str, user_id: &str
 		},
 	};
 
+	//only files in a group count for the group
+	let quota_group_id = if belongs_to_type == FILE_BELONGS_TO_TYPE_GROUP {
+		belongs_to.as_deref()
+	} else {
+		None
+	};
+
+	check_quota(quota, app_id, user_id, quota_group_id, 0, 1).await?;
+
 	let (file_id, session_id) = file_model::register_file(
 		input.encrypted_key,
 		input.encrypted_key_alg,
@@ -53,6 +312,7 @@ pub async fn register_file(input: FileRegisterInput, app_id: &str, user_id: &str
 		input.encrypted_file_name,
 		belongs_to,
 		belongs_to_type,
+		expires_at,
 		app_id,
 		user_id,
 	)
@@ -65,37 +325,81 @@ pub async fn register_file(input: FileRegisterInput, app_id: &str, user_id: &str
 }
 
 pub async fn get_file(app_id: &str, user_id: Option<&str>, file_id: &str, group_id: Option<&str>) -> AppRes<FileMetaData>
+{
+	let mut file = get_file_with_access(app_id, user_id, file_id, group_id).await?;
+
+	//first page of the part list
+	let file_parts = file_model::get_file_parts(app_id, file_id, 0).await?;
+
+	file.part_list = file_parts;
+
+	Ok(file)
+}
+
+/**
+Get the file without the parts if the user or the group got access to it
+ */
+async fn get_file_with_access(app_id: &str, user_id: Option<&str>, file_id: &str, group_id: Option<&str>) -> AppRes<FileMetaData>
 {
 	let mut file = file_model::get_file(app_id, file_id).await?;
 
+	if !check_file_access(&file, user_id, group_id) {
+		//check if the file was shared with the user or with the group of the group routes
+		let share_to = match (group_id, user_id) {
+			(Some(g_id), _) => Some((g_id, FILE_BELONGS_TO_TYPE_GROUP)),
+			(None, Some(u_id)) => Some((u_id, FILE_BELONGS_TO_TYPE_USER)),
+			(None, None) => None,
+		};
+
+		let share = match share_to {
+			Some((id, share_to_type)) => file_model::get_share_key(app_id, file_id, id, share_to_type).await?,
+			None => None,
+		};
+
+		match share {
+			Some(share) => {
+				//use the file key which is encrypted for the user or group
+				file.encrypted_key = share.encrypted_key;
+				file.encrypted_key_alg = share.encrypted_key_alg;
+				file.master_key_id = share.master_key_id;
+			},
+			None => {
+				return Err(ServerCoreError::new_msg(
+					400,
+					ApiErrorCodes::FileAccess,
+					"No access to this file",
+				));
+			},
+		}
+	}
+
+	if file.expires_at > 0 && file.expires_at < get_time()? {
+		return Err(ServerCoreError::new_msg(
+			410,
+			ApiErrorCodes::FileExpired,
+			"The file is expired",
+		));
+	}
+
+	Ok(file)
+}
+
+fn check_file_access(file: &FileMetaData, user_id: Option<&str>, group_id: Option<&str>) -> bool
+{
 	match &file.belongs_to_type {
-		BelongsToType::None => {},
+		BelongsToType::None => true,
 		BelongsToType::Group => {
 			//check if the user got access to this group
 			match &file.belongs_to {
 				//no group id set for register file
-				None => {},
+				None => true,
 				//check group access
 				Some(id) => {
 					match group_id {
-						None => {
-							//user tries to access the file outside of the group routes
-							return Err(ServerCoreError::new_msg(
-								400,
-								ApiErrorCodes::FileAccess,
-								"No access to this file",
-							));
-						},
-						Some(g_id) => {
-							//user tires to access the file from another group (where he got access in this group)
-							if g_id != id {
-								return Err(ServerCoreError::new_msg(
-									400,
-									ApiErrorCodes::FileAccess,
-									"No access to this file",
-								));
-							}
-						},
+						//user tries to access the file outside of the group routes
+						None => false,
+						//user tires to access the file from another group (where he got access in this group)
+						Some(g_id) => g_id == id.as_str(),
 					}
 				},
 			}
@@ -103,40 +407,19 @@ pub async fn get_file(app_id: &str, user_id: Option<&str>, file_id: &str, group_
 		BelongsToType::User => {
 			//check if this user is the actual user
 			match &file.belongs_to {
-				None => {},
+				None => true,
 				//user id was set in the file for belongs to
 				Some(id) => {
 					match user_id {
 						//no valid jwt to get the user id
-						None => {
-							return Err(ServerCoreError::new_msg(
-								400,
-								ApiErrorCodes::FileAccess,
-								"No access to this file",
-							));
-						},
-						Some(user_id) => {
-							//valid jwt but user got no access
-							if *user_id != *id && user_id != file.owner {
-								return Err(ServerCoreError::new_msg(
-									400,
-									ApiErrorCodes::FileAccess,
-									"No access to this file",
-								));
-							}
-						},
+						None => false,
+						//valid jwt but user got no access
+						Some(user_id) => user_id == id.as_str() || user_id == file.owner,
 					}
 				},
-			};
+			}
 		},
 	}
-
-	//first page of the part list
-	let file_parts = file_model::get_file_parts(app_id, file_id, 0).await?;
-
-	file.part_list = file_parts;
-
-	Ok(file)
 }
 
 pub async fn update_file_name(app_id: &str, user_id: &str, file_id: &str, file_name: Option<String>) -> AppRes<()>
@@ -158,6 +441,330 @@ pub async fn update_file_name(app_id: &str, user_id: &str, file_id: &str, file_n
 	Ok(())
 }
 
+//__________________________________________________________________________________________________
+// share
+
+async fn check_file_owner(app_id: &str, user_id: &str, file_id: &str) -> AppRes<()>
+{
+	let file = file_model::get_file(app_id, file_id).await?;
+
+	//only the owner can share the file
+	if user_id != file.owner {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::FileAccess,
+			"No access to this file",
+		));
+	}
+
+	Ok(())
+}
+
+/**
+Share the file with another user or group.
+
+The file key is encrypted by the owner with a key of the user or group, so the file is accessible without being in the group of the file.
+Sharing it again with the same user or group replaces the key.
+ */
+pub async fn share_file(app_id: &str, user_id: &str, file_id: &str, input: FileShareInput) -> AppRes<()>
+{
+	check_file_owner(app_id, user_id, file_id).await?;
+
+	let share_to_type = match input.share_to_type {
+		BelongsToType::None => {
+			return Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::FileShareTarget,
+				"The file can only be shared with a user or a group",
+			));
+		},
+		BelongsToType::Group => {
+			//err if the group is not in the app
+			server_api_common::group::get_internal_group_data(app_id, &input.share_to).await?;
+
+			FILE_BELONGS_TO_TYPE_GROUP
+		},
+		BelongsToType::User => {
+			let check = server_api_common::user::check_user_in_app_by_user_id(app_id, &input.share_to).await?;
+
+			if !check {
+				return Err(ServerCoreError::new_msg(
+					400,
+					ApiErrorCodes::FileShareTarget,
+					"User not found",
+				));
+			}
+
+			FILE_BELONGS_TO_TYPE_USER
+		},
+	};
+
+	file_model::share_file(
+		app_id,
+		file_id,
+		user_id,
+		input.share_to,
+		share_to_type,
+		input.encrypted_key,
+		input.encrypted_key_alg,
+		input.master_key_id,
+	)
+	.await
+}
+
+pub async fn get_shares(app_id: &str, user_id: &str, file_id: &str, last_fetched_time: u128, last_id: &str) -> AppRes<Vec<FileShare>>
+{
+	check_file_owner(app_id, user_id, file_id).await?;
+
+	file_model::get_shares(app_id, file_id, last_fetched_time, last_id).await
+}
+
+pub async fn delete_share(app_id: &str, user_id: &str, file_id: &str, share_to: &str) -> AppRes<()>
+{
+	check_file_owner(app_id, user_id, file_id).await?;
+
+	file_model::delete_share(app_id, file_id, share_to).await
+}
+
+//__________________________________________________________________________________________________
+// move
+
+/**
+Move the file to another group, to a user or out of a group.
+
+The client encrypts the file key with a key of the new group or user. The parts are not changed, so the file is not uploaded again.
+In a group the user needs the rank to delete files in the old and in the new group.
+ */
+pub async fn move_file(
+	app_id: &str,
+	user_id: &str,
+	file_id: &str,
+	group: Option<&InternalGroupDataComplete>,
+	input: FileMoveInput,
+	quota: FileQuota,
+) -> AppRes<()>
+{
+	let file = file_model::get_file(app_id, file_id).await?;
+
+	let old_group_id = match (&file.belongs_to_type, &file.belongs_to) {
+		(BelongsToType::Group, Some(id)) => {
+			//user tries to move the file outside of the group routes or from another group
+			match group {
+				Some(g) if g.group_data.id == *id && g.user_data.rank <= 3 => Some(id.as_str()),
+				_ => {
+					return Err(ServerCoreError::new_msg(
+						400,
+						ApiErrorCodes::FileAccess,
+						"No access to this file",
+					));
+				},
+			}
+		},
+		_ => {
+			if file.owner != user_id {
+				return Err(ServerCoreError::new_msg(
+					400,
+					ApiErrorCodes::FileAccess,
+					"No access to this file",
+				));
+			}
+
+			None
+		},
+	};
+
+	let (belongs_to_type, belongs_to) = match input.belongs_to_type {
+		BelongsToType::None => (FILE_BELONGS_TO_TYPE_NONE, None),
+		BelongsToType::Group => {
+			let id = get_move_target(input.belongs_to_id)?;
+
+			check_group_write_access(app_id, &id, user_id).await?;
+
+			(FILE_BELONGS_TO_TYPE_GROUP, Some(id))
+		},
+		BelongsToType::User => {
+			let id = get_move_target(input.belongs_to_id)?;
+
+			let check = server_api_common::user::check_user_in_app_by_user_id(app_id, &id).await?;
+
+			if !check {
+				return Err(ServerCoreError::new_msg(
+					400,
+					ApiErrorCodes::FileMoveTarget,
+					"User not found",
+				));
+			}
+
+			(FILE_BELONGS_TO_TYPE_USER, Some(id))
+		},
+	};
+
+	let new_group_id = if belongs_to_type == FILE_BELONGS_TO_TYPE_GROUP {
+		belongs_to.as_deref()
+	} else {
+		None
+	};
+
+	let size = if old_group_id != new_group_id {
+		let size = file_model::get_file_size(app_id, file_id).await?;
+
+		if new_group_id.is_some() {
+			check_quota(quota.group_only(), app_id, user_id, new_group_id, size, 1).await?;
+		}
+
+		size
+	} else {
+		0
+	};
+
+	file_model::move_file(
+		app_id,
+		file_id,
+		belongs_to.clone(),
+		belongs_to_type,
+		input.encrypted_key,
+		input.encrypted_key_alg,
+		input.master_key_id,
+		old_group_id,
+		new_group_id,
+		size,
+	)
+	.await
+}
+
+fn get_move_target(belongs_to_id: Option<String>) -> AppRes<String>
+{
+	belongs_to_id.ok_or_else(|| {
+		ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::FileMoveTarget,
+			"No group or user set to move the file to",
+		)
+	})
+}
+
+/**
+Check the rank of the user in the group where the file is moved to. The user can also be a member of a parent group.
+ */
+async fn check_group_write_access(app_id: &str, group_id: &str, user_id: &str) -> AppRes<()>
+{
+	//err if the group is not in the app
+	server_api_common::group::get_internal_group_data(app_id, group_id).await?;
+
+	let rank = match server_api_common::group::get_internal_group_user_data(group_id, user_id).await? {
+		Some(u) => Some(u.rank),
+		None => {
+			server_api_common::group::get_user_from_parent_groups(group_id, user_id)
+				.await?
+				.map(|u| u.rank)
+		},
+	};
+
+	match rank {
+		Some(r) if r <= 3 => Ok(()),
+		_ => {
+			Err(ServerCoreError::new_msg(
+				400,
+				ApiErrorCodes::FileAccess,
+				"No access to the group where the file should be moved to",
+			))
+		},
+	}
+}
+
+//__________________________________________________________________________________________________
+// versions
+
+/**
+Register a new version of the file. The parts of the new version must be encrypted with the same file key.
+
+Only the owner can upload a new version. The file keeps the old version until the last part of the new version is uploaded.
+ */
+pub async fn register_version(app_id: &str, user_id: &str, file_id: &str) -> AppRes<FileRegisterOutput>
+{
+	let file = file_model::get_file(app_id, file_id).await?;
+
+	if user_id != file.owner {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::FileAccess,
+			"No access to this file",
+		));
+	}
+
+	if file.expires_at > 0 && file.expires_at < get_time()? {
+		return Err(ServerCoreError::new_msg(
+			410,
+			ApiErrorCodes::FileExpired,
+			"The file is expired",
+		));
+	}
+
+	if file_model::has_open_session(app_id, file_id).await? {
+		return Err(ServerCoreError::new_msg(
+			400,
+			ApiErrorCodes::FileVersionUpload,
+			"The file is still uploading. Finish the upload before uploading a new version.",
+		));
+	}
+
+	let (_version, session_id) = file_model::register_version(app_id, file_id, user_id).await?;
+
+	Ok(FileRegisterOutput {
+		file_id: file_id.to_string(),
+		session_id,
+	})
+}
+
+pub async fn get_versions(app_id: &str, user_id: Option<&str>, file_id: &str, group_id: Option<&str>, last_version: i32) -> AppRes<Vec<FileVersion>>
+{
+	get_file_with_access(app_id, user_id, file_id, group_id).await?;
+
+	file_model::get_versions(app_id, file_id, last_version).await
+}
+
+/**
+Get the file with the first page of the parts of an older version
+ */
+pub async fn get_file_version(app_id: &str, user_id: Option<&str>, file_id: &str, group_id: Option<&str>, version: i32) -> AppRes<FileMetaData>
+{
+	let mut file = get_file_with_access(app_id, user_id, file_id, group_id).await?;
+
+	let version = file_model::get_version(app_id, file_id, version).await?;
+
+	file.part_list = file_model::get_version_parts(app_id, file_id, version.version, 0).await?;
+	file.version = version.version;
+
+	Ok(file)
+}
+
+/**
+Get the next page of the parts of an older version, with the same access and expiry check as the file
+ */
+pub async fn get_version_parts(
+	app_id: &str,
+	user_id: Option<&str>,
+	file_id: &str,
+	group_id: Option<&str>,
+	version: i32,
+	last_sequence: i32,
+) -> AppRes<Vec<FilePartListItem>>
+{
+	get_file_with_access(app_id, user_id, file_id, group_id).await?;
+
+	file_model::get_version_parts(app_id, file_id, version, last_sequence).await
+}
+
+pub async fn restore_version(app_id: &str, user_id: &str, file_id: &str, version: i32) -> AppRes<()>
+{
+	check_file_owner(app_id, user_id, file_id).await?;
+
+	//err if the version was deleted or is still uploading
+	file_model::get_version(app_id, file_id, version).await?;
+
+	file_model::set_current_version(app_id, file_id, version).await
+}
+
 //__________________________________________________________________________________________________
 
 pub async fn delete_file(file_id: impl Into<FileId>, app_id: impl Into<AppId>, user_id: &str, group: Option<&InternalGroupDataComplete>)
@@ -0,0 +1,50 @@
+use rustgram_server_util::get_time;
+use rustgram_server_util::res::AppRes;
+use sentc_crypto_common::FileId;
+
+use crate::file_model;
+
+/**
+Delete the files and versions of the upload sessions which are older than the session lifetime of the app.
+
+The files are only marked as to delete, the uploaded parts are deleted by the file worker.
+ */
+pub async fn start() -> AppRes<()>
+{
+	let start_time = get_time()?;
+
+	let mut last_id = None;
+
+	loop {
+		let sessions = file_model::get_old_sessions(last_id, start_time).await?;
+		let session_len = sessions.len();
+
+		match sessions.last() {
+			Some(s) => last_id = Some(s.session_id.to_string()),
+			None => {
+				break;
+			},
+		}
+
+		let mut file_ids: Vec<FileId> = Vec::new();
+		let mut versions: Vec<(FileId, i32)> = Vec::new();
+
+		for session in sessions {
+			//a new version which is not finished is deleted without the file
+			if session.version > 1 {
+				versions.push((session.file_id, session.version));
+			} else {
+				file_ids.push(session.file_id);
+			}
+		}
+
+		file_model::delete_abandoned_files(file_ids).await?;
+		file_model::delete_abandoned_versions(versions).await?;
+
+		if session_len < 500 {
+			break;
+		}
+	}
+
+	Ok(())
+}
@@ -1,18 +1,43 @@
 use std::collections::HashMap;
-use std::time::Duration;
 
 use rustgram_server_util::get_time;
 use rustgram_server_util::res::AppRes;
 use sentc_crypto_common::{AppId, PartId};
 
-use crate::file_entities::{FileExternalStorageUrl, FilePartListItemDelete};
+use crate::file_entities::FilePartListItemDelete;
 use crate::file_model;
 
 pub async fn start() -> AppRes<()>
 {
-	//get all files which are marked as to delete
+	//mark the old versions of the apps with a retention policy as to delete
+	delete_old_versions(get_time()?).await?;
+
 	let start_time = get_time()?;
 
+	//delete the parts of the deleted versions before the files
+	let mut last_id = None;
+
+	loop {
+		let parts = file_model::get_all_versions_marked_to_delete(last_id, start_time).await?;
+		let part_len = parts.len();
+
+		match parts.last() {
+			Some(p) => last_id = Some(p.part_id.to_string()),
+			None => {
+				break;
+			},
+		}
+
+		delete_parts(parts).await?;
+
+		if part_len < 500 {
+			break;
+		}
+	}
+
+	file_model::delete_versions_complete(start_time).await?;
+
+	//get all files which are marked as to delete
 	let mut last_id = None;
 
 	loop {
@@ -35,9 +60,62 @@ pub async fn start() -> AppRes<()>
 		}
 	}
 
-	//now delete all files which got a smaller deleted_at time as the start time
+	//now delete all files which got a smaller deleted_at time as the start time and free the quota of the owners
 	file_model::delete_file_complete(start_time).await?;
 
+	//send the queued external deletes of the parts to the storage of the apps
+	crate::file_external_delete_worker::start().await
+}
+
+/**
+Apply the version retention of the apps.
+
+An old version is deleted when there are more newer old versions than the app keeps or when it is older than the days to keep.
+ */
+async fn delete_old_versions(start_time: u128) -> AppRes<()>
+{
+	let mut last = None;
+
+	//the kept versions of the current file, the versions are ordered by file
+	let mut current_file = String::new();
+	let mut kept = 0;
+
+	loop {
+		let versions = file_model::get_versions_for_retention(last).await?;
+		let version_len = versions.len();
+
+		match versions.last() {
+			Some(v) => last = Some((v.file_id.to_string(), v.version)),
+			None => {
+				break;
+			},
+		}
+
+		let mut to_delete = Vec::new();
+
+		for version in versions {
+			if version.file_id != current_file {
+				current_file = version.file_id.clone();
+				kept = 0;
+			}
+
+			let too_many = version.version_keep_last > 0 && kept >= version.version_keep_last;
+			let too_old = version.version_keep_days > 0 && version.time + (version.version_keep_days as u128 * 24 * 60 * 60 * 1000) < start_time;
+
+			if too_many || too_old {
+				to_delete.push((version.file_id, version.version));
+			} else {
+				kept += 1;
+			}
+		}
+
+		file_model::delete_versions(to_delete).await?;
+
+		if version_len < 500 {
+			break;
+		}
+	}
+
 	Ok(())
 }
 
@@ -73,49 +151,19 @@ async fn delete_parts(parts: Vec<FilePartListItemDelete>) -> AppRes<()>
 
 async fn delete_external(map: HashMap<AppId, Vec<PartId>>) -> AppRes<()>
 {
-	//get the app info
+	//only the apps which still got an external storage
 	let app_ids = map.keys().cloned().collect::<Vec<AppId>>();
 
 	let app_info = file_model::get_external_app_file_delete_info(app_ids).await?;
 
-	//iterate over each app which has files to delete from their external storage
-	for (app_id, file_data) in map {
-		let (url, auth_token) = match find_app_info(app_id, &app_info) {
-			Some(u) => (u.0, u.1),
-			None => continue,
-		};
-
-		//make req to the external storage url delete endpoint
-		// with the part ids in body as json
-		let body = match serde_json::to_string(&file_data) {
-			Err(_e) => continue,
-			Ok(f) => f,
-		};
-
-		let client = reqwest::Client::new();
-
-		// header token
-		let req = client.post(url).body(body).timeout(Duration::from_secs(10));
-
-		let req = match auth_token {
-			Some(at) => req.header("x-sentc-app-token", at),
-			None => req,
-		};
+	for (app_id, part_ids) in map {
+		if !app_info.iter().any(|info| info.app_id == app_id) {
+			continue;
+		}
 
-		//don't wait for the res
-		tokio::task::spawn(req.send());
+		//the external delete worker sends the parts until the storage confirmed the delete
+		file_model::add_external_deletes(app_id, part_ids).await?;
 	}
 
 	Ok(())
 }
-
-fn find_app_info(app_id: AppId, app_info: &Vec<FileExternalStorageUrl>) -> Option<(&str, &Option<String>)>
-{
-	for info in app_info {
-		if info.app_id == app_id {
-			return Some((&info.storage_url, &info.auth_token));
-		}
-	}
-
-	None
-}
@@ -4,8 +4,12 @@ use rustgram_server_util::error::ServerErrorCodes;
 
 pub mod file_controller;
 pub mod file_entities;
+pub mod file_expire_worker;
+pub mod file_external_delete_worker;
 mod file_model;
+pub mod file_part_check_worker;
 pub mod file_service;
+pub mod file_session_worker;
 pub mod file_worker;
 mod routes;
 
@@ -24,8 +28,18 @@ pub enum ApiErrorCodes
 	FileSessionNotFound,
 	FileSessionExpired,
 	FileNotFound,
+	FileExpired,
+	FileExpiresAt,
 	FileUploadAllowed,
 	FileAccess,
+	FileQuotaStorage,
+	FileQuotaFiles,
+	FilePartChecksum,
+	FilePartRead,
+	FileShareTarget,
+	FileVersionNotFound,
+	FileVersionUpload,
+	FileMoveTarget,
 }
 
 impl ServerErrorCodes for ApiErrorCodes
@@ -37,8 +51,18 @@ impl ServerErrorCodes for ApiErrorCodes
 			ApiErrorCodes::FileSessionNotFound => 510,
 			ApiErrorCodes::FileSessionExpired => 511,
 			ApiErrorCodes::FileNotFound => 512,
+			ApiErrorCodes::FileExpired => 513,
+			ApiErrorCodes::FileExpiresAt => 514,
 			ApiErrorCodes::FileUploadAllowed => 520,
 			ApiErrorCodes::FileAccess => 521,
+			ApiErrorCodes::FileQuotaStorage => 530,
+			ApiErrorCodes::FileQuotaFiles => 531,
+			ApiErrorCodes::FilePartChecksum => 532,
+			ApiErrorCodes::FilePartRead => 533,
+			ApiErrorCodes::FileShareTarget => 540,
+			ApiErrorCodes::FileVersionNotFound => 550,
+			ApiErrorCodes::FileVersionUpload => 551,
+			ApiErrorCodes::FileMoveTarget => 560,
 		}
 	}
 }
@@ -6,6 +6,8 @@ use server_api_file::file_routes;
 pub async fn main()
 {
 	server_api_common::start().await;
+	server_api_common::rate_limit::start_worker();
+	server_api_common::metering::start_worker();
 
 	let mut router = server_api_common::rest_routes();
 
@@ -19,5 +21,5 @@ pub async fn main()
 	.parse()
 	.unwrap();
 
-	rustgram::start(router, addr).await;
+	server_api_common::start_server(router, addr).await;
 }
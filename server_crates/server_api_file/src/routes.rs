@@ -12,52 +12,166 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/file",
 		r(crate::file_controller::register_file)
 			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.put(
 		"/api/v1/file/:file_id",
 		r(crate::file_controller::update_file_name)
 			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/file/:file_id",
 		r(crate::file_controller::delete_file)
 			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/file/usage",
+		r(crate::file_controller::get_usage)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/file/session/:session_id/status",
+		r(crate::file_controller::get_session_status)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/file/mine/:last_fetched_time/:last_id",
+		r(crate::file_controller::get_my_files)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.post(
+		"/api/v1/file/:file_id/share",
+		r(crate::file_controller::share_file)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/file/:file_id/share/:last_fetched_time/:last_id",
+		r(crate::file_controller::get_shares)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.delete(
+		"/api/v1/file/:file_id/share/:share_to",
+		r(crate::file_controller::delete_share)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.post(
+		"/api/v1/file/:file_id/version",
+		r(crate::file_controller::register_file_version)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.put(
+		"/api/v1/file/:file_id/version/:version/restore",
+		r(crate::file_controller::restore_version)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.put(
+		"/api/v1/file/:file_id/move",
+		r(crate::file_controller::move_file)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/file/:file_id",
 		r(crate::file_controller::get_file)
 			.add(server_api_common::middleware::jwt::jwt_optional_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/file/:file_id/part_fetch/:last_sequence",
-		r(crate::file_controller::get_parts).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::file_controller::get_parts)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/file/:file_id/version/all/:last_version",
+		r(crate::file_controller::get_versions)
+			.add(server_api_common::middleware::jwt::jwt_optional_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/file/:file_id/version/:version",
+		r(crate::file_controller::get_file_version)
+			.add(server_api_common::middleware::jwt::jwt_optional_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/file/:file_id/version/:version/part_fetch/:last_sequence",
+		r(crate::file_controller::get_version_parts)
+			.add(server_api_common::middleware::jwt::jwt_optional_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.get(
 		"/api/v1/file/part/:part_id",
-		r(crate::file_controller::download_part).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::file_controller::download_part)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.delete(
 		"/api/v1/file/part/:part_id",
-		r(crate::file_controller::delete_registered_file_part).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::file_controller::delete_registered_file_part)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/file/part/:session_id/:seq/:end",
 		r(crate::file_controller::upload_part)
 			.add(server_api_common::middleware::jwt::jwt_expire_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.patch(
 		"/api/v1/file/part/:session_id/:seq/:end/:user_id",
-		r(crate::file_controller::register_file_part).add(server_api_common::middleware::app_token::app_token_transform),
+		r(crate::file_controller::register_file_part)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
 	router.post(
 		"/api/v1/group/:group_id/file",
 		r(crate::file_controller::register_file_in_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/group/:group_id/file/usage",
+		r(crate::file_controller::get_usage_in_group)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/group/:group_id/file/all/:last_fetched_time/:last_id",
+		r(crate::file_controller::get_files_in_group)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -65,6 +179,7 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/file/:file_id",
 		r(crate::file_controller::get_file_in_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -72,6 +187,39 @@ pub(crate) fn routes(router: &mut Router)
 		"/api/v1/group/:group_id/file/:file_id",
 		r(crate::file_controller::delete_file_in_group)
 			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.put(
+		"/api/v1/group/:group_id/file/:file_id/move",
+		r(crate::file_controller::move_file_in_group)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/group/:group_id/file/:file_id/version/all/:last_version",
+		r(crate::file_controller::get_versions_in_group)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/group/:group_id/file/:file_id/version/:version",
+		r(crate::file_controller::get_file_version_in_group)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
+			.add(server_api_common::middleware::jwt::jwt_transform)
+			.add(server_api_common::middleware::app_token::app_token_transform),
+	);
+	router.get(
+		"/api/v1/group/:group_id/file/:file_id/version/:version/part_fetch/:last_sequence",
+		r(crate::file_controller::get_version_parts_in_group)
+			.add(server_api_common::middleware::group::group_transform)
+			.add(server_api_common::middleware::rate_limit::rate_limit_transform)
 			.add(server_api_common::middleware::jwt::jwt_transform)
 			.add(server_api_common::middleware::app_token::app_token_transform),
 	);
@@ -1005,6 +1005,61 @@ pub async fn user_key_rotation(
 
 //__________________________________________________________________________________________________
 
+/**
+Register a file for the user or in a group, returns the response body
+ */
+pub async fn register_file(jwt: &str, token: &str, file_input: String, group_id: Option<&str>) -> String
+{
+	let url = match group_id {
+		Some(id) => get_url(format!("api/v1/group/{id}/file")),
+		None => get_url("api/v1/file".to_string()),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", token)
+		.body(file_input)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+/**
+Upload a part to the sentc storage, returns the response body
+ */
+pub async fn upload_part(jwt: &str, token: &str, session_id: &str, seq: i32, end: bool, part: Vec<u8>) -> String
+{
+	upload_part_req(jwt, token, session_id, seq, end, part, None).await
+}
+
+pub async fn upload_part_with_checksum(jwt: &str, token: &str, session_id: &str, seq: i32, end: bool, part: Vec<u8>, checksum: &str) -> String
+{
+	upload_part_req(jwt, token, session_id, seq, end, part, Some(checksum)).await
+}
+
+async fn upload_part_req(jwt: &str, token: &str, session_id: &str, seq: i32, end: bool, part: Vec<u8>, checksum: Option<&str>) -> String
+{
+	let url = get_url(format!("api/v1/file/part/{session_id}/{seq}/{end}"));
+
+	let client = reqwest::Client::new();
+	let mut req = client
+		.post(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", token);
+
+	if let Some(c) = checksum {
+		req = req.header(server_api_file::file_service::PART_CHECKSUM_HEADER, c);
+	}
+
+	let res = req.body(part).send().await.unwrap();
+
+	res.text().await.unwrap()
+}
+
 pub async fn get_file(file_id: &str, jwt: &str, token: &str, group_id: Option<&str>) -> FileData
 {
 	//download the file info
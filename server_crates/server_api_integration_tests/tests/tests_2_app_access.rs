@@ -0,0 +1,208 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use sentc_crypto_common::user::UserIdentifierAvailableServerInput;
+use server_dashboard_common::app::{AppAccessOptions, AppDetails, AppTokenCreateInput, AppTokenCreateOutput, APP_TOKEN_TYPE_PUBLIC};
+
+use crate::test_fn::{auth_header, create_app, create_test_customer, customer_delete, get_server_error_from_normal_res, get_url};
+
+mod test_fn;
+
+async fn update_access_options(customer_jwt: &str, app_id: &str, input: &AppAccessOptions) -> String
+{
+	let url = get_url(format!("api/v1/customer/app/{app_id}/access_options"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.put(url)
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+/**
+Call an endpoint which only needs the app token, returns the cors header and the body
+
+The ip is set with the proxy header, the server must run with CLIENT_IP_HEADER=x-forwarded-for
+ */
+async fn user_exists(token: &str, origin: Option<&str>, ip: Option<&str>) -> (Option<String>, String)
+{
+	let input = UserIdentifierAvailableServerInput {
+		user_identifier: "hello_app_access".to_string(),
+	};
+
+	let client = reqwest::Client::new();
+	let mut req = client
+		.post(get_url("api/v1/exists".to_owned()))
+		.header("x-sentc-app-token", token)
+		.body(input.to_string().unwrap());
+
+	if let Some(o) = origin {
+		req = req.header("origin", o);
+	}
+
+	if let Some(i) = ip {
+		req = req.header("x-forwarded-for", i);
+	}
+
+	let res = req.send().await.unwrap();
+
+	let cors = res
+		.headers()
+		.get("access-control-allow-origin")
+		.map(|h| h.to_str().unwrap().to_string());
+
+	(cors, res.text().await.unwrap())
+}
+
+#[tokio::test]
+async fn app_access_options()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_app_access.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let app_id = app_data.app_id.as_str();
+	let token = app_data.public_token.as_str();
+
+	//no restriction by default
+	let (_, body) = user_exists(token, Some("https://other.com"), None).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+
+	//wrong inputs
+	let body = update_access_options(
+		customer_jwt,
+		app_id,
+		&AppAccessOptions {
+			allowed_origins: vec!["example.com".to_string()],
+			allowed_ips: vec![],
+		},
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	let body = update_access_options(
+		customer_jwt,
+		app_id,
+		&AppAccessOptions {
+			allowed_origins: vec![],
+			allowed_ips: vec!["10.0.0.0/33".to_string()],
+		},
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	let body = update_access_options(
+		customer_jwt,
+		app_id,
+		&AppAccessOptions {
+			allowed_origins: vec!["https://Example.com/".to_string()],
+			allowed_ips: vec!["10.0.0.0/8".to_string(), "::1".to_string()],
+		},
+	)
+	.await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/app/{app_id}")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	let details: AppDetails = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(details.access_options.allowed_origins, vec!["https://example.com"]);
+	assert_eq!(details.access_options.allowed_ips.len(), 2);
+
+	//allowed origin and ip, the origin is used in the cors header
+	let (cors, body) = user_exists(token, Some("https://example.com"), Some("1.2.3.4, 10.1.2.3")).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+	assert_eq!(cors.as_deref(), Some("https://example.com"));
+
+	//only the last entry of the proxy header is used
+	let (_, body) = user_exists(token, Some("https://example.com"), Some("10.1.2.3, 1.2.3.4")).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 206);
+
+	let (_, body) = user_exists(token, Some("https://other.com"), Some("10.1.2.3")).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 206);
+
+	let (_, body) = user_exists(token, None, Some("10.1.2.3")).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 206);
+
+	//without the proxy header the address of the connection is used
+	let (_, body) = user_exists(token, Some("https://example.com"), None).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 206);
+
+	//the backend with the secret token sends no origin, only the ip is checked
+	let secret_token = app_data.secret_token.as_str();
+
+	let (cors, body) = user_exists(secret_token, None, Some("10.1.2.3")).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+	assert_ne!(cors.as_deref(), Some("https://example.com"));
+
+	let (_, body) = user_exists(secret_token, Some("https://other.com"), Some("10.1.2.3")).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+
+	let (_, body) = user_exists(secret_token, None, Some("1.2.3.4")).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 206);
+
+	//the lists of a named token replace the lists of the app
+	let input = AppTokenCreateInput {
+		name: "mobile".to_string(),
+		token_type: APP_TOKEN_TYPE_PUBLIC,
+		expires_at: 0,
+		scope: None,
+		access_options: Some(AppAccessOptions {
+			allowed_origins: vec![],
+			allowed_ips: vec!["192.168.0.0/16".to_string()],
+		}),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url(format!("api/v1/customer/app_token/{app_id}")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	let named: AppTokenCreateOutput = handle_server_response(body.as_str()).unwrap();
+
+	let (cors, body) = user_exists(&named.token, None, Some("192.168.1.1")).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+	assert_ne!(cors.as_deref(), Some("https://example.com"));
+
+	let (_, body) = user_exists(&named.token, Some("https://example.com"), Some("10.1.2.3")).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 206);
+
+	//the address of the connection is allowed
+	let body = update_access_options(
+		customer_jwt,
+		app_id,
+		&AppAccessOptions {
+			allowed_origins: vec![],
+			allowed_ips: vec!["127.0.0.1".to_string()],
+		},
+	)
+	.await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let (_, body) = user_exists(token, None, None).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+
+	//remove the restriction of the app again
+	let body = update_access_options(customer_jwt, app_id, &AppAccessOptions::default()).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let (_, body) = user_exists(token, Some("https://other.com"), None).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+
+	customer_delete(customer_jwt).await;
+}
@@ -0,0 +1,221 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use sentc_crypto_common::group::{GroupChangeRankServerInput, GroupCreateOutput, GroupNewMemberLightInput};
+use serde_json::{json, to_string};
+use server_dashboard_common::app::{AppOptions, AppTokenRenewOutput, AppUpdateInput};
+use server_dashboard_common::customer::{CustomerAuditLogItem, CustomerGroupCreateInput};
+
+use crate::test_fn::{auth_header, create_app, create_test_customer, customer_delete, get_url};
+
+mod test_fn;
+
+async fn get_logs(customer_jwt: &str, url: String) -> Vec<CustomerAuditLogItem>
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(url))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+async fn send(customer_jwt: &str, method: reqwest::Method, url: String, body: String)
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.request(method, get_url(url))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(body)
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+}
+
+#[tokio::test]
+async fn app_audit_log()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (customer_id, customer_data) = create_test_customer("hello@test_app_audit.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let app_id = app_data.app_id.as_str();
+
+	let mut options = AppOptions::default();
+	options.group_create = 0;
+
+	send(
+		customer_jwt,
+		reqwest::Method::PUT,
+		format!("api/v1/customer/app/{app_id}/options"),
+		to_string(&options).unwrap(),
+	)
+	.await;
+
+	send(
+		customer_jwt,
+		reqwest::Method::PUT,
+		format!("api/v1/customer/app/{app_id}"),
+		to_string(&AppUpdateInput {
+			identifier: Some("audit_app".to_string()),
+		})
+		.unwrap(),
+	)
+	.await;
+
+	let client = reqwest::Client::new();
+	let res = client
+		.patch(get_url(format!("api/v1/customer/app/{app_id}/token_renew")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	handle_server_response::<AppTokenRenewOutput>(body.as_str()).unwrap();
+
+	let logs = get_logs(customer_jwt, format!("api/v1/customer/app/{app_id}/audit/0/none")).await;
+
+	let actions: Vec<&str> = logs.iter().map(|l| l.action.as_str()).collect();
+	assert_eq!(
+		actions,
+		vec!["app_token_renew", "app_update", "app_options_update", "app_create"]
+	);
+
+	for log in &logs {
+		assert_eq!(log.actor_id, customer_id);
+		assert_eq!(log.actor_email.as_deref(), Some("hello@test_app_audit.com"));
+	}
+
+	//only the changed values are saved
+	assert_eq!(logs[2].before, Some(json!({"group_create": 1})));
+	assert_eq!(logs[2].after, Some(json!({"group_create": 0})));
+
+	assert_eq!(logs[1].before, Some(json!({"identifier": ""})));
+	assert_eq!(logs[1].after, Some(json!({"identifier": "audit_app"})));
+
+	//the new tokens are never in the log
+	assert!(logs[0].before.is_none());
+	assert!(logs[0].after.is_none());
+
+	//pagination
+	let last = &logs[1];
+	let next = get_logs(
+		customer_jwt,
+		format!("api/v1/customer/app/{app_id}/audit/{}/{}", last.time, last.id),
+	)
+	.await;
+	assert_eq!(next.len(), 2);
+	assert_eq!(next[0].action, "app_options_update");
+
+	customer_delete(customer_jwt).await;
+}
+
+#[tokio::test]
+async fn customer_group_audit_log()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (creator_id, creator_data) = create_test_customer("hello@test_group_audit.com", "12345").await;
+	let creator_jwt = creator_data.verify.jwt.as_str();
+
+	let (member_id, member_data) = create_test_customer("hello1@test_group_audit.com", "12345").await;
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/customer/group".to_owned()))
+		.header(AUTHORIZATION, auth_header(creator_jwt))
+		.body(
+			to_string(&CustomerGroupCreateInput {
+				name: None,
+				des: None,
+			})
+			.unwrap(),
+		)
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	let group: GroupCreateOutput = handle_server_response(body.as_str()).unwrap();
+	let group_id = group.group_id.as_str();
+
+	send(
+		creator_jwt,
+		reqwest::Method::PUT,
+		format!("api/v1/customer/group/{group_id}/invite/{member_id}"),
+		to_string(&GroupNewMemberLightInput {
+			rank: None,
+		})
+		.unwrap(),
+	)
+	.await;
+
+	send(
+		creator_jwt,
+		reqwest::Method::PUT,
+		format!("api/v1/customer/group/{group_id}/change_rank"),
+		to_string(&GroupChangeRankServerInput {
+			changed_user_id: member_id.clone(),
+			new_rank: 2,
+		})
+		.unwrap(),
+	)
+	.await;
+
+	send(
+		creator_jwt,
+		reqwest::Method::DELETE,
+		format!("api/v1/customer/group/{group_id}/kick/{member_id}"),
+		String::new(),
+	)
+	.await;
+
+	let logs = get_logs(creator_jwt, format!("api/v1/customer/group/{group_id}/audit/0/none")).await;
+
+	let actions: Vec<&str> = logs.iter().map(|l| l.action.as_str()).collect();
+	assert_eq!(
+		actions,
+		vec!["group_member_kick", "group_member_rank_update", "group_member_invite"]
+	);
+
+	for log in &logs {
+		assert_eq!(log.actor_id, creator_id);
+	}
+
+	assert_eq!(logs[1].before.as_ref().unwrap()["rank"], json!(4));
+	assert_eq!(logs[1].after.as_ref().unwrap()["rank"], json!(2));
+	assert!(logs[0].after.is_none());
+
+	//the kicked member can't see the log anymore
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/group/{group_id}/audit/0/none")))
+		.header(AUTHORIZATION, auth_header(&member_data.verify.jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	assert!(handle_server_response::<Vec<CustomerAuditLogItem>>(body.as_str()).is_err());
+
+	customer_delete(&member_data.verify.jwt).await;
+
+	let client = reqwest::Client::new();
+	let res = client
+		.delete(get_url(format!("api/v1/customer/group/{group_id}")))
+		.header(AUTHORIZATION, auth_header(creator_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+
+	customer_delete(creator_jwt).await;
+}
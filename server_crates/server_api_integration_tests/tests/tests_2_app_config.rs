@@ -0,0 +1,153 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use server_dashboard_common::app::{
+	AppConfig,
+	AppConfigCreateInput,
+	AppKeyEndpointOptionsInput,
+	AppRateLimit,
+	AppRegisterOutput,
+	APP_CONFIG_VERSION,
+	KEY_ENDPOINT_FALLBACK_LOCAL,
+	RATE_LIMIT_BY_IP,
+};
+
+use crate::test_fn::{auth_header, create_app, create_test_customer, customer_delete, get_server_error_from_normal_res, get_url};
+
+mod test_fn;
+
+async fn export_config(customer_jwt: &str, app_id: &str) -> AppConfig
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/app/{app_id}/config")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+async fn import_config(customer_jwt: &str, app_id: &str, config: &AppConfig) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!("api/v1/customer/app/{app_id}/config")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(config.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn create_app_from_config(customer_jwt: &str, input: &AppConfigCreateInput) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/customer/app_config".to_owned()))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+#[tokio::test]
+async fn app_config_export_import()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_app_config.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let app_id = app_data.app_id.as_str();
+
+	let mut config = export_config(customer_jwt, app_id).await;
+	assert_eq!(config.version, APP_CONFIG_VERSION);
+	assert_eq!(config.options.group_create, 1);
+
+	//change the options of every part of the document
+	config.options.group_create = 0;
+	config.group_options.max_key_rotation_month = 10;
+	config.access_options.allowed_origins = vec!["https://Example.com/".to_string()];
+	config.rate_limit_options.auth = AppRateLimit {
+		capacity: 10,
+		refill_per_minute: 5,
+		limit_by: RATE_LIMIT_BY_IP,
+	};
+	config.key_endpoint_options = AppKeyEndpointOptionsInput {
+		key_endpoint_url: Some("https://keys.example.com".to_string()),
+		auth_token: Some("abcdefghijklmnopqrstuvwxyz".to_string()),
+		timeout: 1000,
+		fallback: KEY_ENDPOINT_FALLBACK_LOCAL,
+	};
+
+	let body = import_config(customer_jwt, app_id, &config).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let exported = export_config(customer_jwt, app_id).await;
+	assert_eq!(exported.options.group_create, 0);
+	assert_eq!(exported.group_options.max_key_rotation_month, 10);
+	assert_eq!(exported.access_options.allowed_origins, vec!["https://example.com"]);
+	assert_eq!(exported.rate_limit_options.auth.capacity, 10);
+	assert_eq!(
+		exported.key_endpoint_options.key_endpoint_url.as_deref(),
+		Some("https://keys.example.com")
+	);
+
+	//no secrets in the export
+	assert!(exported.key_endpoint_options.auth_token.is_none());
+	assert!(exported.file_options.auth_token.is_none());
+
+	//the auth token of the app is kept when importing the export again
+	let body = import_config(customer_jwt, app_id, &exported).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//a new app needs the auth token for the key endpoint
+	let body = create_app_from_config(
+		customer_jwt,
+		&AppConfigCreateInput {
+			identifier: None,
+			config: export_config(customer_jwt, app_id).await,
+		},
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	//unknown version
+	let mut config = export_config(customer_jwt, app_id).await;
+	config.version = APP_CONFIG_VERSION + 1;
+
+	let body = import_config(customer_jwt, app_id, &config).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	//create a new app with the same config
+	config.version = APP_CONFIG_VERSION;
+	config.key_endpoint_options.auth_token = Some("zyxwvutsrqponmlkjihgfedcba".to_string());
+
+	let body = create_app_from_config(
+		customer_jwt,
+		&AppConfigCreateInput {
+			identifier: Some("production".to_string()),
+			config,
+		},
+	)
+	.await;
+	let new_app: AppRegisterOutput = handle_server_response(body.as_str()).unwrap();
+	assert_ne!(new_app.app_id, app_id);
+
+	let new_config = export_config(customer_jwt, &new_app.app_id).await;
+	assert_eq!(
+		serde_json::to_value(&new_config).unwrap(),
+		serde_json::to_value(&exported).unwrap()
+	);
+
+	customer_delete(customer_jwt).await;
+}
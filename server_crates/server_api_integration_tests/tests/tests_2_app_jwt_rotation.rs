@@ -0,0 +1,112 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use server_dashboard_common::app::{AppDetails, AppJwtData, AppJwtRotationOptions, JWT_ROTATION_GRACE_HOURS_DEFAULT};
+use server_dashboard_common::customer::CustomerAuditLogItem;
+
+use crate::test_fn::{auth_header, create_app, create_test_customer, customer_delete, get_server_error_from_normal_res, get_url};
+
+mod test_fn;
+
+async fn update_jwt_rotation_options(customer_jwt: &str, app_id: &str, input: &AppJwtRotationOptions) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!("api/v1/customer/app/{app_id}/jwt_rotation_options")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_details(customer_jwt: &str, app_id: &str) -> AppDetails
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/app/{app_id}")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn app_jwt_rotation_options()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_app_jwt_rotation.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let app_id = app_data.app_id.as_str();
+
+	//no rotation by default
+	let details = get_details(customer_jwt, app_id).await;
+	assert_eq!(details.jwt_rotation_options.rotation_days, 0);
+	assert_eq!(
+		details.jwt_rotation_options.grace_hours,
+		JWT_ROTATION_GRACE_HOURS_DEFAULT
+	);
+
+	for (rotation_days, grace_hours) in [(-1, 24), (366, 24), (30, 0), (30, 30 * 24 + 1), (1, 25)] {
+		let body = update_jwt_rotation_options(
+			customer_jwt,
+			app_id,
+			&AppJwtRotationOptions {
+				rotation_days,
+				grace_hours,
+			},
+		)
+		.await;
+		assert_eq!(get_server_error_from_normal_res(&body), 203);
+	}
+
+	let body = update_jwt_rotation_options(
+		customer_jwt,
+		app_id,
+		&AppJwtRotationOptions {
+			rotation_days: 30,
+			grace_hours: 48,
+		},
+	)
+	.await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let details = get_details(customer_jwt, app_id).await;
+	assert_eq!(details.jwt_rotation_options.rotation_days, 30);
+	assert_eq!(details.jwt_rotation_options.grace_hours, 48);
+
+	//the key of the app is active until the worker rotates it
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/app/{app_id}/jwt")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	let keys: Vec<AppJwtData> = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(keys.len(), 1);
+	assert_eq!(keys[0].retire_at, 0);
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/app/{app_id}/audit/0/none")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	let logs: Vec<CustomerAuditLogItem> = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(logs[0].action, "app_jwt_rotation_options_update");
+	assert_eq!(logs[0].after.as_ref().unwrap()["rotation_days"], 30);
+
+	customer_delete(customer_jwt).await;
+}
@@ -0,0 +1,191 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::handle_general_server_response;
+use server_dashboard_common::app::AppMetering;
+
+use crate::test_fn::{
+	auth_header,
+	create_app,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_file,
+	get_group,
+	get_server_error_from_normal_res,
+	get_url,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+async fn export(customer_jwt: &str, app_id: &str, format: &str) -> String
+{
+	let to = rustgram_server_util::get_time().unwrap();
+
+	let url = get_url(format!("api/v1/customer/app/{app_id}/metering/0/{to}/{format}"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+/**
+The downloaded bytes are saved by the metering worker of the file server, wait until the bytes are saved
+ */
+async fn wait_for_transferred_bytes(customer_jwt: &str, app_id: &str, bytes: i64) -> Vec<AppMetering>
+{
+	let interval_sec: u64 = std::env::var("METERING_WORKER_INTERVAL_SEC")
+		.ok()
+		.and_then(|s| s.parse().ok())
+		.unwrap_or(60);
+
+	for _ in 0..interval_sec + 5 {
+		let body = export(customer_jwt, app_id, "json").await;
+		let metering: Vec<AppMetering> = serde_json::from_str(&body).unwrap();
+
+		if metering.iter().map(|m| m.transferred_bytes).sum::<i64>() >= bytes {
+			return metering;
+		}
+
+		tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+	}
+
+	let body = export(customer_jwt, app_id, "json").await;
+
+	serde_json::from_str(&body).unwrap()
+}
+
+#[tokio::test]
+async fn app_metering()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_app_metering.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+
+	let (_, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_app_metering",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+	let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+	let (file_input, _) = sentc_crypto::file::prepare_register_file(
+		encrypted_key.master_key_id,
+		&file_key,
+		encrypted_key_str,
+		None,
+		sentc_crypto::sdk_common::file::BelongsToType::None,
+		None,
+	)
+	.unwrap();
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/file".to_string()))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", &app_data.public_token)
+		.body(file_input)
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	let (file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url(format!("api/v1/file/part/{session_id}/1/true")))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", &app_data.public_token)
+		.body(vec![1, 2, 3, 4])
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let file = get_file(&file_id, jwt, &app_data.public_token, None).await;
+	let part_id = &file.part_list[0].part_id;
+
+	//download the part twice
+	for _ in 0..2 {
+		let client = reqwest::Client::new();
+		let res = client
+			.get(get_url(format!("api/v1/file/part/{part_id}")))
+			.header("x-sentc-app-token", &app_data.public_token)
+			.send()
+			.await
+			.unwrap();
+
+		assert_eq!(res.bytes().await.unwrap().len(), 4);
+	}
+
+	server_api_common::start().await;
+
+	//the monthly active users are counted by the app stats
+	server_api_customer::customer_app::app_stats_worker::start()
+		.await
+		.unwrap();
+
+	server_api_customer::metering::metering_worker::start()
+		.await
+		.unwrap();
+
+	let metering = wait_for_transferred_bytes(customer_jwt, &app_data.app_id, 8).await;
+
+	assert_eq!(metering.len(), 1);
+
+	let metering = &metering[0];
+
+	assert_eq!(metering.id, app_data.app_id);
+	assert_eq!(metering.monthly_active_users, 1);
+	assert_eq!(metering.stored_bytes, 4);
+	assert_eq!(metering.transferred_bytes, 8);
+	assert_eq!(metering.key_rotation_members, 0);
+
+	//running the worker again doesn't count the downloads twice
+	server_api_customer::metering::metering_worker::start()
+		.await
+		.unwrap();
+
+	let body = export(customer_jwt, &app_data.app_id, "csv").await;
+	let mut lines = body.lines();
+
+	assert_eq!(
+		lines.next(),
+		Some("id,monthly_active_users,stored_bytes,transferred_bytes,key_rotation_members")
+	);
+	assert_eq!(lines.next(), Some(format!("{},1,4,8,0", app_data.app_id).as_str()));
+	assert_eq!(lines.next(), None);
+
+	let body = export(customer_jwt, &app_data.app_id, "xml").await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	customer_delete(customer_jwt).await;
+}
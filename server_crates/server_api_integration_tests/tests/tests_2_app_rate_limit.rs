@@ -0,0 +1,252 @@
+use reqwest::header::AUTHORIZATION;
+use reqwest::StatusCode;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use sentc_crypto_common::user::UserIdentifierAvailableServerInput;
+use server_dashboard_common::app::{
+	AppDetails,
+	AppRateLimit,
+	AppRateLimitOptions,
+	AppRateLimitThrottled,
+	RATE_LIMIT_BY_IP,
+	RATE_LIMIT_BY_TOKEN,
+	RATE_LIMIT_GROUP_AUTH,
+};
+
+use crate::test_fn::{auth_header, create_app, create_test_customer, customer_delete, get_server_error_from_normal_res, get_url};
+
+mod test_fn;
+
+async fn update_rate_limit_options(customer_jwt: &str, app_id: &str, input: &AppRateLimitOptions) -> String
+{
+	let url = get_url(format!("api/v1/customer/app/{app_id}/rate_limit_options"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.put(url)
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_throttled(customer_jwt: &str, app_id: &str) -> Vec<AppRateLimitThrottled>
+{
+	let to = rustgram_server_util::get_time().unwrap();
+
+	let url = get_url(format!("api/v1/customer/app/{app_id}/rate_limit/throttled/0/{to}"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+/**
+The throttled requests are saved by the rate limit worker of the server, wait until the count is saved
+ */
+async fn wait_for_throttled(customer_jwt: &str, app_id: &str, count: i64) -> Vec<AppRateLimitThrottled>
+{
+	let interval_sec: u64 = std::env::var("RATE_LIMIT_WORKER_INTERVAL_SEC")
+		.ok()
+		.and_then(|s| s.parse().ok())
+		.unwrap_or(60);
+
+	for _ in 0..interval_sec + 5 {
+		let throttled = get_throttled(customer_jwt, app_id).await;
+
+		if throttled.iter().map(|t| t.count).sum::<i64>() >= count {
+			return throttled;
+		}
+
+		tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+	}
+
+	get_throttled(customer_jwt, app_id).await
+}
+
+/**
+Call an endpoint of the auth group, returns the status, the retry after header and the body
+
+The ip is set with the proxy header, the server must run with CLIENT_IP_HEADER=x-forwarded-for
+ */
+async fn user_exists(token: &str, ip: &str) -> (StatusCode, Option<String>, String)
+{
+	let input = UserIdentifierAvailableServerInput {
+		user_identifier: "hello_app_rate_limit".to_string(),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/exists".to_owned()))
+		.header("x-sentc-app-token", token)
+		.header("x-forwarded-for", ip)
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	let status = res.status();
+	let retry_after = res
+		.headers()
+		.get("retry-after")
+		.map(|h| h.to_str().unwrap().to_string());
+
+	(status, retry_after, res.text().await.unwrap())
+}
+
+#[tokio::test]
+async fn app_rate_limit()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_app_rate_limit.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let app_id = app_data.app_id.as_str();
+	let token = app_data.public_token.as_str();
+
+	//wrong inputs
+	let body = update_rate_limit_options(
+		customer_jwt,
+		app_id,
+		&AppRateLimitOptions {
+			auth: AppRateLimit {
+				capacity: 2,
+				refill_per_minute: 0,
+				limit_by: RATE_LIMIT_BY_TOKEN,
+			},
+			..Default::default()
+		},
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	let body = update_rate_limit_options(
+		customer_jwt,
+		app_id,
+		&AppRateLimitOptions {
+			auth: AppRateLimit {
+				capacity: 2,
+				refill_per_minute: 1,
+				limit_by: 5,
+			},
+			..Default::default()
+		},
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	//two requests per token, one new request every minute
+	let body = update_rate_limit_options(
+		customer_jwt,
+		app_id,
+		&AppRateLimitOptions {
+			auth: AppRateLimit {
+				capacity: 2,
+				refill_per_minute: 1,
+				limit_by: RATE_LIMIT_BY_TOKEN,
+			},
+			..Default::default()
+		},
+	)
+	.await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/app/{app_id}")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	let details: AppDetails = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(details.rate_limit_options.auth.capacity, 2);
+	assert_eq!(details.rate_limit_options.group.capacity, 0);
+
+	for _ in 0..2 {
+		let (status, _, body) = user_exists(token, "10.0.0.1").await;
+		assert_eq!(status, StatusCode::OK);
+		handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+	}
+
+	//the bucket is empty for every ip
+	let (status, retry_after, body) = user_exists(token, "10.0.0.2").await;
+	assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+	assert_eq!(get_server_error_from_normal_res(&body), 207);
+
+	let retry_after: u64 = retry_after.unwrap().parse().unwrap();
+	assert!(retry_after > 0 && retry_after <= 60);
+
+	let throttled = wait_for_throttled(customer_jwt, app_id, 1).await;
+	assert_eq!(throttled.len(), 1);
+	assert_eq!(throttled[0].endpoint_group, RATE_LIMIT_GROUP_AUTH);
+	assert_eq!(throttled[0].count, 1);
+
+	//limit per ip, the old buckets are not used anymore
+	let body = update_rate_limit_options(
+		customer_jwt,
+		app_id,
+		&AppRateLimitOptions {
+			auth: AppRateLimit {
+				capacity: 1,
+				refill_per_minute: 1,
+				limit_by: RATE_LIMIT_BY_IP,
+			},
+			..Default::default()
+		},
+	)
+	.await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let (status, _, _) = user_exists(token, "10.0.0.1").await;
+	assert_eq!(status, StatusCode::OK);
+
+	let (status, _, _) = user_exists(token, "10.0.0.1").await;
+	assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+	let (status, _, _) = user_exists(token, "10.0.0.2").await;
+	assert_eq!(status, StatusCode::OK);
+
+	let throttled = wait_for_throttled(customer_jwt, app_id, 2).await;
+	assert_eq!(throttled[0].count, 2);
+
+	//parallel requests can't take the same token
+	let mut requests = Vec::with_capacity(10);
+
+	for _ in 0..10 {
+		let token = token.to_string();
+
+		requests.push(tokio::spawn(async move { user_exists(&token, "10.0.0.3").await.0 }));
+	}
+
+	let mut allowed = 0;
+
+	for request in requests {
+		if request.await.unwrap() == StatusCode::OK {
+			allowed += 1;
+		}
+	}
+
+	assert_eq!(allowed, 1);
+
+	//remove the limits
+	let body = update_rate_limit_options(customer_jwt, app_id, &AppRateLimitOptions::default()).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let (status, _, _) = user_exists(token, "10.0.0.1").await;
+	assert_eq!(status, StatusCode::OK);
+
+	customer_delete(customer_jwt).await;
+}
@@ -0,0 +1,103 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::handle_server_response;
+use server_dashboard_common::app::AppStats;
+
+use crate::test_fn::{
+	auth_header,
+	create_app,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_server_error_from_normal_res,
+	get_url,
+};
+
+mod test_fn;
+
+async fn get_stats(customer_jwt: &str, app_id: &str, period: &str) -> String
+{
+	let to = rustgram_server_util::get_time().unwrap();
+
+	let url = get_url(format!("api/v1/customer/app/{app_id}/stats/{period}/0/{to}"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+#[tokio::test]
+async fn app_stats()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_app_stats.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+
+	let (_, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_app_stats",
+		"12345",
+	)
+	.await;
+
+	create_group(
+		&app_data.secret_token,
+		&user.user_keys[0].public_key,
+		None,
+		user.jwt.as_str(),
+	)
+	.await;
+
+	//nothing is computed before the worker run
+	let body = get_stats(customer_jwt, &app_data.app_id, "day").await;
+	let stats: Vec<AppStats> = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(stats.len(), 0);
+
+	server_api_common::start().await;
+
+	server_api_customer::customer_app::app_stats_worker::start()
+		.await
+		.unwrap();
+
+	for period in ["day", "month"] {
+		let body = get_stats(customer_jwt, &app_data.app_id, period).await;
+		let stats: Vec<AppStats> = handle_server_response(body.as_str()).unwrap();
+
+		assert_eq!(stats.len(), 1);
+
+		let stats = &stats[0];
+
+		assert_eq!(stats.active_users, 1);
+		assert_eq!(stats.logins, 1);
+		assert_eq!(stats.registrations, 1);
+		assert_eq!(stats.key_rotations, 0);
+		assert_eq!(stats.used_storage, 0);
+		assert_eq!(stats.group_count, 1);
+		assert_eq!(stats.member_count, 1);
+	}
+
+	//running the worker again doesn't count the actions twice
+	server_api_customer::customer_app::app_stats_worker::start()
+		.await
+		.unwrap();
+
+	let body = get_stats(customer_jwt, &app_data.app_id, "day").await;
+	let stats: Vec<AppStats> = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(stats.len(), 1);
+	assert_eq!(stats[0].logins, 1);
+
+	let body = get_stats(customer_jwt, &app_data.app_id, "year").await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	customer_delete(customer_jwt).await;
+}
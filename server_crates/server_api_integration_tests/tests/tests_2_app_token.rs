@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use sentc_crypto_common::user::UserIdentifierAvailableServerInput;
+use server_dashboard_common::app::{AppOptions, AppToken, AppTokenCreateInput, AppTokenCreateOutput, APP_TOKEN_TYPE_PUBLIC, APP_TOKEN_TYPE_SECRET};
+
+use crate::test_fn::{auth_header, create_app, create_test_customer, customer_delete, get_server_error_from_normal_res, get_url};
+
+mod test_fn;
+
+async fn create_token(customer_jwt: &str, app_id: &str, input: AppTokenCreateInput) -> String
+{
+	let url = get_url(format!("api/v1/customer/app_token/{app_id}"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(url)
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_tokens(customer_jwt: &str, app_id: &str) -> Vec<AppToken>
+{
+	let url = get_url(format!("api/v1/customer/app_token/{app_id}/all/0/none"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+/**
+Check if the user exists, the endpoint only needs the app token
+ */
+async fn user_exists(token: &str) -> String
+{
+	let input = UserIdentifierAvailableServerInput {
+		user_identifier: "hello_app_token".to_string(),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/exists".to_owned()))
+		.header("x-sentc-app-token", token)
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+#[tokio::test]
+async fn app_named_tokens()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_app_token.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let app_id = app_data.app_id.as_str();
+
+	//wrong inputs
+	let body = create_token(
+		customer_jwt,
+		app_id,
+		AppTokenCreateInput {
+			name: "wrong type".to_string(),
+			token_type: 5,
+			expires_at: 0,
+			scope: None,
+			access_options: None,
+		},
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	let body = create_token(
+		customer_jwt,
+		app_id,
+		AppTokenCreateInput {
+			name: "expired".to_string(),
+			token_type: APP_TOKEN_TYPE_PUBLIC,
+			expires_at: 1,
+			scope: None,
+			access_options: None,
+		},
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	//a token with all endpoints of the app options
+	let body = create_token(
+		customer_jwt,
+		app_id,
+		AppTokenCreateInput {
+			name: "web".to_string(),
+			token_type: APP_TOKEN_TYPE_PUBLIC,
+			expires_at: 0,
+			scope: None,
+			access_options: None,
+		},
+	)
+	.await;
+	let web: AppTokenCreateOutput = handle_server_response(body.as_str()).unwrap();
+
+	//a token without the user exists endpoint
+	let mut scope = AppOptions::default();
+	scope.user_exists = 0;
+
+	let body = create_token(
+		customer_jwt,
+		app_id,
+		AppTokenCreateInput {
+			name: "scoped".to_string(),
+			token_type: APP_TOKEN_TYPE_PUBLIC,
+			expires_at: 0,
+			scope: Some(scope),
+			access_options: None,
+		},
+	)
+	.await;
+	let scoped: AppTokenCreateOutput = handle_server_response(body.as_str()).unwrap();
+
+	let expires_at = rustgram_server_util::get_time().unwrap() + 2000;
+
+	let body = create_token(
+		customer_jwt,
+		app_id,
+		AppTokenCreateInput {
+			name: "backend".to_string(),
+			token_type: APP_TOKEN_TYPE_SECRET,
+			expires_at,
+			scope: None,
+			access_options: None,
+		},
+	)
+	.await;
+	let backend: AppTokenCreateOutput = handle_server_response(body.as_str()).unwrap();
+
+	let body = user_exists(&web.token).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+
+	let body = user_exists(&scoped.token).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	let body = user_exists(&backend.token).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+
+	let tokens = get_tokens(customer_jwt, app_id).await;
+	assert_eq!(tokens.len(), 3);
+
+	for token in &tokens {
+		if token.token_id == web.token_id {
+			assert_eq!(token.name, "web");
+			assert!(token.last_used > 0);
+			assert!(token.scope.is_none());
+		} else if token.token_id == scoped.token_id {
+			assert_eq!(token.scope.as_ref().unwrap().user_exists, 0);
+		} else {
+			assert_eq!(token.token_id, backend.token_id);
+			assert_eq!(token.token_type, APP_TOKEN_TYPE_SECRET);
+			assert_eq!(token.expires_at, expires_at);
+		}
+	}
+
+	//the token is expired even when the app data is cached
+	tokio::time::sleep(Duration::from_millis(2500)).await;
+
+	let body = user_exists(&backend.token).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 205);
+
+	//the options of the named tokens are not cached after an update
+	let mut options = AppOptions::default();
+	options.user_exists = 2;
+
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!("api/v1/customer/app/{app_id}/options")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(serde_json::to_string(&options).unwrap())
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let body = user_exists(&web.token).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	//revoke the token, the other tokens are still valid
+	let client = reqwest::Client::new();
+	let res = client
+		.delete(get_url(format!(
+			"api/v1/customer/app_token/{app_id}/{}",
+			web.token_id
+		)))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let body = user_exists(&web.token).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 200);
+
+	let body = user_exists(&app_data.secret_token).await;
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+
+	assert_eq!(get_tokens(customer_jwt, app_id).await.len(), 2);
+
+	customer_delete(customer_jwt).await;
+}
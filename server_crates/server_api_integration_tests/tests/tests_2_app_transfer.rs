@@ -0,0 +1,200 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use sentc_crypto_common::group::{GroupCreateOutput, GroupNewMemberLightInput};
+use sentc_crypto_common::user::UserIdentifierAvailableServerInput;
+use serde_json::to_string;
+use server_dashboard_common::customer::{CustomerAppList, CustomerAuditLogItem, CustomerGroupCreateInput};
+
+use crate::test_fn::{auth_header, create_app, create_test_customer, customer_delete, get_server_error_from_normal_res, get_url};
+
+mod test_fn;
+
+async fn transfer_to_group(customer_jwt: &str, group_id: &str, app_id: &str) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!(
+			"api/v1/customer/group/{group_id}/app/{app_id}/transfer"
+		)))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn transfer_to_customer(customer_jwt: &str, app_id: &str) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!("api/v1/customer/app/{app_id}/transfer_to_customer")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_group_apps(customer_jwt: &str, group_id: &str) -> Vec<CustomerAppList>
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/group/{group_id}/apps/0/none")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+/**
+The app token must still work after the transfer
+ */
+async fn user_exists(token: &str)
+{
+	let input = UserIdentifierAvailableServerInput {
+		user_identifier: "hello_app_transfer".to_string(),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/exists".to_owned()))
+		.header("x-sentc-app-token", token)
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	handle_server_response::<serde_json::Value>(body.as_str()).unwrap();
+}
+
+#[tokio::test]
+async fn app_transfer()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, creator_data) = create_test_customer("hello@test_app_transfer.com", "12345").await;
+	let creator_jwt = creator_data.verify.jwt.as_str();
+
+	let (member_id, member_data) = create_test_customer("hello1@test_app_transfer.com", "12345").await;
+	let member_jwt = member_data.verify.jwt.as_str();
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/customer/group".to_owned()))
+		.header(AUTHORIZATION, auth_header(creator_jwt))
+		.body(
+			to_string(&CustomerGroupCreateInput {
+				name: None,
+				des: None,
+			})
+			.unwrap(),
+		)
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	let group: GroupCreateOutput = handle_server_response(body.as_str()).unwrap();
+	let group_id = group.group_id.as_str();
+
+	//the member gets the default rank and is no admin of the group
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!(
+			"api/v1/customer/group/{group_id}/invite/{member_id}"
+		)))
+		.header(AUTHORIZATION, auth_header(creator_jwt))
+		.body(
+			to_string(&GroupNewMemberLightInput {
+				rank: None,
+			})
+			.unwrap(),
+		)
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let app_data = create_app(creator_jwt).await;
+	let app_id = app_data.app_id.as_str();
+	let token = app_data.public_token.as_str();
+
+	//load the app data into the cache
+	user_exists(token).await;
+
+	//the member has no access to the app yet
+	let body = transfer_to_group(member_jwt, group_id, app_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 200);
+
+	let body = transfer_to_group(creator_jwt, group_id, app_id).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let apps = get_group_apps(creator_jwt, group_id).await;
+	assert_eq!(apps.len(), 1);
+	assert_eq!(apps[0].id, app_id);
+
+	user_exists(token).await;
+
+	//already in this group
+	let body = transfer_to_group(creator_jwt, group_id, app_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	//the member can see the app now but is no admin
+	let body = transfer_to_customer(member_jwt, app_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 203);
+
+	//the group sees the transfer in the log
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/group/{group_id}/audit/0/none")))
+		.header(AUTHORIZATION, auth_header(creator_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	let logs: Vec<CustomerAuditLogItem> = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(logs[0].action, "app_transfer");
+	assert_eq!(logs[0].after.as_ref().unwrap()["owner_id"], group_id);
+
+	//move it back
+	let body = transfer_to_customer(creator_jwt, app_id).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let apps = get_group_apps(creator_jwt, group_id).await;
+	assert_eq!(apps.len(), 0);
+
+	user_exists(token).await;
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/app/{app_id}/audit/0/none")))
+		.header(AUTHORIZATION, auth_header(creator_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	let logs: Vec<CustomerAuditLogItem> = handle_server_response(body.as_str()).unwrap();
+	let transfers = logs.iter().filter(|l| l.action == "app_transfer").count();
+	assert_eq!(transfers, 2);
+
+	customer_delete(member_jwt).await;
+
+	let client = reqwest::Client::new();
+	let res = client
+		.delete(get_url(format!("api/v1/customer/group/{group_id}")))
+		.header(AUTHORIZATION, auth_header(creator_jwt))
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+
+	customer_delete(creator_jwt).await;
+}
@@ -963,6 +963,7 @@ async fn test_40_change_app_group_options()
 	let input = AppGroupOption {
 		max_key_rotation_month: 3, //set to 3 because we already did a rotation for the signed key rotation test
 		min_rank_key_rotation: 1,
+		..Default::default()
 	};
 
 	let client = reqwest::Client::new();
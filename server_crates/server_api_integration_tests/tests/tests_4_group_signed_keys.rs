@@ -0,0 +1,140 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use sentc_crypto_common::group::{CreateData, GroupCreateOutput};
+use sentc_crypto_common::user::RegisterData;
+use serde_json::to_string;
+use server_dashboard_common::app::AppGroupOption;
+
+use crate::test_fn::{
+	auth_header,
+	create_app,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_server_error_from_normal_res,
+	get_url,
+	TestGroup,
+	TestUser,
+};
+
+mod test_fn;
+
+/**
+The group data of a user group is signed by its own verify key
+ */
+fn signed_group_data(identifier: &str) -> CreateData
+{
+	let register_data = TestUser::register(identifier, "12345").unwrap();
+
+	RegisterData::from_string(register_data.as_str())
+		.unwrap()
+		.group
+}
+
+async fn create_group(secret_token: &str, jwt: &str, body: String) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/group".to_owned()))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", secret_token)
+		.body(body)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+#[tokio::test]
+async fn require_signed_keys()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_signed_keys.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let secret_token = app_data.secret_token.as_str();
+
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!(
+			"api/v1/customer/app/{}/group_options",
+			app_data.app_id
+		)))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(
+			to_string(&AppGroupOption {
+				max_key_rotation_month: 100,
+				min_rank_key_rotation: 4,
+				require_signed_keys: 1,
+			})
+			.unwrap(),
+		)
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//the user group of the register is signed
+	let (_, user) = create_test_user(
+		secret_token,
+		app_data.public_token.as_str(),
+		"hello_signed_keys",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	//signed key is accepted
+	let group_data = signed_group_data("signed_1");
+	let body = create_group(secret_token, jwt, to_string(&group_data).unwrap()).await;
+	handle_server_response::<GroupCreateOutput>(body.as_str()).unwrap();
+
+	//unsigned key without verify key is rejected
+	let group_input = TestGroup::prepare_create(&user.user_keys[0].public_key, None, Default::default()).unwrap();
+	let body = create_group(secret_token, jwt, group_input).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 322);
+
+	//verify key without sig is rejected
+	let mut group_data = signed_group_data("signed_2");
+	group_data.public_key_sig = None;
+	let body = create_group(secret_token, jwt, to_string(&group_data).unwrap()).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 322);
+
+	//the sig of another key is rejected
+	let mut group_data = signed_group_data("signed_3");
+	group_data.public_key_sig = signed_group_data("signed_4").public_key_sig;
+	let body = create_group(secret_token, jwt, to_string(&group_data).unwrap()).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 322);
+
+	//the old options without the signed keys option turn the requirement off
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!(
+			"api/v1/customer/app/{}/group_options",
+			app_data.app_id
+		)))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(r#"{"max_key_rotation_month":100,"min_rank_key_rotation":4}"#)
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//unsigned key is accepted
+	let group_input = TestGroup::prepare_create(&user.user_keys[0].public_key, None, Default::default()).unwrap();
+	let body = create_group(secret_token, jwt, group_input).await;
+	handle_server_response::<GroupCreateOutput>(body.as_str()).unwrap();
+
+	//a sent sig is still checked
+	let mut group_data = signed_group_data("signed_5");
+	group_data.public_key_sig = signed_group_data("signed_6").public_key_sig;
+	let body = create_group(secret_token, jwt, to_string(&group_data).unwrap()).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 322);
+
+	customer_delete(customer_jwt).await;
+}
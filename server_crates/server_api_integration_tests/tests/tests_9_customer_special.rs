@@ -1,4 +1,5 @@
 use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use sentc_crypto_common::user::{RegisterData, UserDeviceRegisterInput};
 use sentc_crypto_common::ServerOutput;
@@ -95,3 +96,64 @@ async fn test_10_1_not_register_when_register_is_disabled()
 	assert!(!out.status);
 	assert_eq!(out.err_code.unwrap(), 65);
 }
+
+#[ignore]
+#[tokio::test]
+async fn test_11_register_customer_with_maildir_transport()
+{
+	//only run with the send_mail feature and env EMAIL_TRANSPORT=1, the server and the test must use the same EMAIL_FILE_PATH
+
+	dotenv::from_filename("sentc.env").ok();
+
+	let url = get_url("api/v1/customer/register".to_string());
+
+	let time = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap()
+		.as_millis();
+	let email = format!("hello{}@test_maildir.com", time);
+
+	let register_data = TestUser::register(email.as_str(), "12345").unwrap();
+	let register_data = RegisterData::from_string(register_data.as_str()).unwrap();
+
+	let captcha_input = get_captcha().await;
+
+	let input = CustomerRegisterData {
+		customer_data: CustomerData {
+			name: "abc".to_string(),
+			first_name: "abc".to_string(),
+			company: None,
+		},
+		email: email.clone(),
+		register_data: register_data.device,
+		captcha_input,
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(url)
+		.header("accept-language", "de-DE,de;q=0.9,en;q=0.8")
+		.body(serde_json::to_string(&input).unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	let out = ServerOutput::<CustomerRegisterOutput>::from_string(body.as_str()).unwrap();
+	assert!(out.status);
+
+	//the mail is sent in the background
+	tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+	let path = env::var("EMAIL_FILE_PATH").unwrap_or_else(|_| "storage_mail".to_string());
+
+	let mail = std::fs::read_dir(format!("{}/new", path))
+		.unwrap()
+		.map(|f| std::fs::read_to_string(f.unwrap().path()).unwrap())
+		.find(|m| m.contains(email.as_str()))
+		.unwrap();
+
+	assert!(mail.contains("/dashboard/customer/validation/register/?token="));
+	assert!(mail.contains("text/html"));
+}
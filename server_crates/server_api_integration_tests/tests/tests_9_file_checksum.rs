@@ -0,0 +1,142 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use serde_json::Value;
+use server_api_file::file_service::part_checksum;
+
+use crate::test_fn::{
+	auth_header,
+	create_app,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_group,
+	get_server_error_from_normal_res,
+	get_url,
+	register_file,
+	upload_part_with_checksum,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+async fn get_parts(jwt: &str, public_token: &str, file_id: &str) -> Value
+{
+	let url = get_url(format!("api/v1/file/{file_id}/part_fetch/0"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn file_part_checksum()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_file_checksum.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+
+	let (_, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_checksum",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+	let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+	let (file_input, _) = sentc_crypto::file::prepare_register_file(
+		encrypted_key.master_key_id,
+		&file_key,
+		encrypted_key_str,
+		None,
+		sentc_crypto::sdk_common::file::BelongsToType::None,
+		None,
+	)
+	.unwrap();
+
+	let body = register_file(jwt, &app_data.public_token, file_input, None).await;
+	let (file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	let part = vec![1, 2, 3, 4];
+	let checksum = part_checksum(&part);
+
+	//the part was changed during the upload
+	let body = upload_part_with_checksum(
+		jwt,
+		&app_data.public_token,
+		&session_id,
+		1,
+		false,
+		vec![1, 2, 3, 5],
+		&checksum,
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 532);
+
+	//wrong format
+	let body = upload_part_with_checksum(
+		jwt,
+		&app_data.public_token,
+		&session_id,
+		1,
+		false,
+		part.clone(),
+		"abc",
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 532);
+
+	let body = upload_part_with_checksum(
+		jwt,
+		&app_data.public_token,
+		&session_id,
+		1,
+		true,
+		part,
+		&checksum.to_uppercase(),
+	)
+	.await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let parts = get_parts(jwt, &app_data.public_token, &file_id).await;
+	let parts = parts.as_array().unwrap();
+
+	assert_eq!(parts.len(), 1);
+	assert_eq!(parts[0]["checksum"].as_str().unwrap(), checksum);
+
+	//the stored part is not changed
+	server_api_common::start().await;
+
+	server_api_file::file_part_check_worker::start()
+		.await
+		.unwrap();
+
+	customer_delete(customer_jwt).await;
+}
@@ -0,0 +1,165 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use serde_json::Value;
+use server_dashboard_common::app::{AppFileOptionsInput, AppOptions, AppRegisterInput, AppRegisterOutput};
+
+use crate::test_fn::{
+	auth_header,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_file,
+	get_group,
+	get_server_error_from_normal_res,
+	get_url,
+	register_file,
+	upload_part,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+fn now() -> u128
+{
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap()
+		.as_millis()
+}
+
+fn with_expiry(file_input: &str, expires_at: u128) -> String
+{
+	let mut input: Value = serde_json::from_str(file_input).unwrap();
+	input["expires_at"] = Value::from(expires_at as u64);
+
+	input.to_string()
+}
+
+#[tokio::test]
+async fn file_expiry()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_file_expiry.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let input = AppRegisterInput {
+		identifier: None,
+		options: AppOptions::default(),
+		file_options: AppFileOptionsInput {
+			max_ttl: 60,
+			..Default::default()
+		},
+		group_options: Default::default(),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/customer/app".to_owned()))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	let app_data: AppRegisterOutput = handle_server_response(body.as_str()).unwrap();
+
+	let (_, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_expiry",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+	let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+	let (file_input, _) = sentc_crypto::file::prepare_register_file(
+		encrypted_key.master_key_id,
+		&file_key,
+		encrypted_key_str,
+		None,
+		sentc_crypto::sdk_common::file::BelongsToType::None,
+		None,
+	)
+	.unwrap();
+
+	//in the past
+	let body = register_file(
+		jwt,
+		&app_data.public_token,
+		with_expiry(&file_input, now() - 1000),
+		None,
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 514);
+
+	//greater than the max ttl of the app
+	let body = register_file(
+		jwt,
+		&app_data.public_token,
+		with_expiry(&file_input, now() + 120 * 1000),
+		None,
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 514);
+
+	//without an expiry time the max ttl is used
+	let body = register_file(jwt, &app_data.public_token, file_input.clone(), None).await;
+	sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	let body = register_file(
+		jwt,
+		&app_data.public_token,
+		with_expiry(&file_input, now() + 2000),
+		None,
+	)
+	.await;
+	let (file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 1, true, vec![1, 2, 3]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let file = get_file(&file_id, jwt, &app_data.public_token, None).await;
+	let part_id = file.part_list[0].part_id.clone();
+
+	tokio::time::sleep(Duration::from_millis(2500)).await;
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/file/{file_id}")))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", &app_data.public_token)
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	assert_eq!(get_server_error_from_normal_res(&body), 513);
+
+	let res = client
+		.get(get_url(format!("api/v1/file/part/{part_id}")))
+		.header("x-sentc-app-token", &app_data.public_token)
+		.send()
+		.await
+		.unwrap();
+	assert_eq!(res.status(), 410);
+
+	customer_delete(customer_jwt).await;
+}
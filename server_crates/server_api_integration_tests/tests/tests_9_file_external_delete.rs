@@ -0,0 +1,231 @@
+use reqwest::header::AUTHORIZATION;
+use rustgram_server_util::get_time;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use sentc_crypto_common::file::FilePartRegisterOutput;
+use server_dashboard_common::app::{
+	AppFileOptionsInput,
+	AppOptions,
+	AppRegisterInput,
+	AppRegisterOutput,
+	FileExternalDeleteFailure,
+	FILE_STORAGE_OWN,
+};
+
+use crate::test_fn::{
+	auth_header,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_group,
+	get_url,
+	register_file,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+/**
+The delete route of the dummy server, the mode sets how the storage responds: fail, not_confirmed or confirmed
+ */
+fn storage_url(mode: &str) -> String
+{
+	format!("http://127.0.0.1:3003/file_part/delete_retry/{mode}")
+}
+
+fn file_options(mode: &str) -> AppFileOptionsInput
+{
+	AppFileOptionsInput {
+		file_storage: FILE_STORAGE_OWN,
+		storage_url: Some(storage_url(mode)),
+		auth_token: Some("abc".to_string()),
+		..Default::default()
+	}
+}
+
+async fn set_storage_mode(customer_jwt: &str, app_id: &str, mode: &str)
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!("api/v1/customer/app/{app_id}/file_options")))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(serde_json::to_string(&file_options(mode)).unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	handle_general_server_response(body.as_str()).unwrap();
+}
+
+async fn get_failures(customer_jwt: &str, app_id: &str) -> Vec<FileExternalDeleteFailure>
+{
+	let url = get_url(format!("api/v1/customer/app/{app_id}/file_external_delete/0/none"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn file_external_delete_retry()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	//send the failed parts again with the next worker run
+	std::env::set_var("FILE_EXTERNAL_DELETE_BACKOFF_SEC", "0");
+
+	let (_, customer_data) = create_test_customer("hello@test_file_external_delete.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let input = AppRegisterInput {
+		identifier: None,
+		options: AppOptions::default(),
+		file_options: file_options("fail"),
+		group_options: Default::default(),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/customer/app".to_owned()))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	let app_data: AppRegisterOutput = handle_server_response(body.as_str()).unwrap();
+
+	let (user_id, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_external_delete",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+	let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+	let (file_input, _) = sentc_crypto::file::prepare_register_file(
+		encrypted_key.master_key_id,
+		&file_key,
+		encrypted_key_str,
+		None,
+		sentc_crypto::sdk_common::file::BelongsToType::None,
+		None,
+	)
+	.unwrap();
+
+	let body = register_file(jwt, &app_data.public_token, file_input, None).await;
+	let (_, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	//register the part like the external storage does it
+	let mut part_ids = Vec::new();
+
+	for (seq, end) in [(0, false), (1, true)] {
+		let client = reqwest::Client::new();
+		let res = client
+			.patch(get_url(format!(
+				"api/v1/file/part/{session_id}/{seq}/{end}/{user_id}"
+			)))
+			.header("x-sentc-app-token", &app_data.secret_token)
+			.send()
+			.await
+			.unwrap();
+
+		let body = res.text().await.unwrap();
+		let out: FilePartRegisterOutput = handle_server_response(body.as_str()).unwrap();
+
+		part_ids.push(out.part_id);
+	}
+
+	server_api_common::start().await;
+
+	//the file worker queues the parts of the deleted files,
+	//queue only the parts of this file, so the other tests and the file worker are not touched
+	//language=SQL
+	let sql = "INSERT INTO sentc_file_external_delete (part_id, app_id, time, attempts, next_try) VALUES (?,?,?,?,?)";
+
+	let time = get_time().unwrap();
+
+	for part_id in &part_ids {
+		rustgram_server_util::db::exec(
+			sql,
+			rustgram_server_util::set_params!(
+				part_id.clone(),
+				app_data.app_id.clone(),
+				time.to_string(),
+				0,
+				time.to_string()
+			),
+		)
+		.await
+		.unwrap();
+	}
+
+	//the storage is down
+	server_api_file::file_external_delete_worker::start_for_app(&app_data.app_id)
+		.await
+		.unwrap();
+
+	let failures = get_failures(customer_jwt, &app_data.app_id).await;
+	assert_eq!(failures.len(), 2);
+
+	for failure in &failures {
+		assert!(part_ids.contains(&failure.part_id));
+		assert_eq!(failure.attempts, 1);
+		assert!(failure.last_error.as_ref().unwrap().contains("500"));
+	}
+
+	//the storage responds but doesn't confirm the parts
+	set_storage_mode(customer_jwt, &app_data.app_id, "not_confirmed").await;
+
+	server_api_file::file_external_delete_worker::start_for_app(&app_data.app_id)
+		.await
+		.unwrap();
+
+	let failures = get_failures(customer_jwt, &app_data.app_id).await;
+	assert_eq!(failures.len(), 2);
+
+	for failure in &failures {
+		assert_eq!(failure.attempts, 2);
+		assert!(failure
+			.last_error
+			.as_ref()
+			.unwrap()
+			.contains("not confirmed"));
+	}
+
+	//the storage confirms the delete
+	set_storage_mode(customer_jwt, &app_data.app_id, "confirmed").await;
+
+	server_api_file::file_external_delete_worker::start_for_app(&app_data.app_id)
+		.await
+		.unwrap();
+
+	assert_eq!(get_failures(customer_jwt, &app_data.app_id).await.len(), 0);
+
+	customer_delete(customer_jwt).await;
+}
@@ -0,0 +1,161 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use serde_json::Value;
+
+use crate::test_fn::{
+	auth_header,
+	create_app,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_group,
+	get_url,
+	register_file,
+	upload_part,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+async fn get_files(jwt: &str, public_token: &str, url: String) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(url))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+#[tokio::test]
+async fn file_list()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_file_list.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+
+	let (_, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_list",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let (_, user_2) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_list_2",
+		"12345",
+	)
+	.await;
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let mut file_ids = Vec::new();
+
+	//one file for the user, one in the group and one not finished file
+	for (belongs_to, group, finish) in [
+		(sentc_crypto::sdk_common::file::BelongsToType::None, None, true),
+		(
+			sentc_crypto::sdk_common::file::BelongsToType::Group,
+			Some(group_id.as_str()),
+			true,
+		),
+		(sentc_crypto::sdk_common::file::BelongsToType::None, None, false),
+	] {
+		let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+		let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+		let (file_input, _) = sentc_crypto::file::prepare_register_file(
+			encrypted_key.master_key_id,
+			&file_key,
+			encrypted_key_str,
+			group.map(|g| g.to_string()),
+			belongs_to,
+			None,
+		)
+		.unwrap();
+
+		let body = register_file(jwt, &app_data.public_token, file_input, group).await;
+		let (file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+		if finish {
+			let body = upload_part(jwt, &app_data.public_token, &session_id, 1, true, vec![1, 2, 3]).await;
+			handle_general_server_response(body.as_str()).unwrap();
+		}
+
+		file_ids.push(file_id);
+	}
+
+	let body = get_files(jwt, &app_data.public_token, "api/v1/file/mine/0/none".to_string()).await;
+	let files: Vec<Value> = handle_server_response(body.as_str()).unwrap();
+
+	assert_eq!(files.len(), 1);
+	assert_eq!(files[0]["file_id"].as_str().unwrap(), file_ids[0]);
+	assert_eq!(files[0]["part_list"].as_array().unwrap().len(), 0);
+
+	//next page
+	let body = get_files(
+		jwt,
+		&app_data.public_token,
+		format!(
+			"api/v1/file/mine/{}/{}",
+			files[0]["time"].as_u64().unwrap(),
+			files[0]["file_id"].as_str().unwrap()
+		),
+	)
+	.await;
+	let files: Vec<Value> = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(files.len(), 0);
+
+	let body = get_files(
+		jwt,
+		&app_data.public_token,
+		format!("api/v1/group/{group_id}/file/all/0/none"),
+	)
+	.await;
+	let files: Vec<Value> = handle_server_response(body.as_str()).unwrap();
+
+	assert_eq!(files.len(), 1);
+	assert_eq!(files[0]["file_id"].as_str().unwrap(), file_ids[1]);
+
+	//the other user is not in the group
+	let body = get_files(
+		&user_2.jwt,
+		&app_data.public_token,
+		format!("api/v1/group/{group_id}/file/all/0/none"),
+	)
+	.await;
+	assert!(handle_server_response::<Vec<Value>>(body.as_str()).is_err());
+
+	//and got no files
+	let body = get_files(
+		&user_2.jwt,
+		&app_data.public_token,
+		"api/v1/file/mine/0/none".to_string(),
+	)
+	.await;
+	let files: Vec<Value> = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(files.len(), 0);
+
+	customer_delete(customer_jwt).await;
+}
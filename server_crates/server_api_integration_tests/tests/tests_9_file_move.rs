@@ -0,0 +1,229 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use serde_json::{json, Value};
+
+use crate::test_fn::{
+	auth_header,
+	create_app,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_file,
+	get_group,
+	get_server_error_from_normal_res,
+	get_url,
+	register_file,
+	upload_part,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+async fn move_file(jwt: &str, public_token: &str, file_id: &str, group_id: Option<&str>, input: Value) -> String
+{
+	let url = match group_id {
+		Some(id) => get_url(format!("api/v1/group/{id}/file/{file_id}/move")),
+		None => get_url(format!("api/v1/file/{file_id}/move")),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.put(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.body(input.to_string())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_group_usage(jwt: &str, public_token: &str, group_id: &str) -> Value
+{
+	let url = get_url(format!("api/v1/group/{group_id}/file/usage"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn file_move()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_file_move.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+
+	let (user_id, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_move",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let (_, user_2) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_move_2",
+		"12345",
+	)
+	.await;
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let group_id_2 = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys_2 = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id_2,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	//the user is not in this group
+	let group_id_3 = create_group(
+		&app_data.secret_token,
+		&user_2.user_keys[0].public_key,
+		None,
+		user_2.jwt.as_str(),
+	)
+	.await;
+
+	let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+	let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+	let (file_input, _) = sentc_crypto::file::prepare_register_file(
+		encrypted_key.master_key_id,
+		&file_key,
+		encrypted_key_str,
+		Some(group_id.clone()),
+		sentc_crypto::sdk_common::file::BelongsToType::Group,
+		None,
+	)
+	.unwrap();
+
+	let body = register_file(jwt, &app_data.public_token, file_input, Some(&group_id)).await;
+	let (file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 1, true, vec![1, 2, 3, 4]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let file = get_file(&file_id, jwt, &app_data.public_token, Some(&group_id)).await;
+	let part_id = file.part_list[0].part_id.clone();
+
+	//the file key encrypted by the key of the new group
+	let master_key_id_2 = group_keys_2[0].group_key.key_id.clone();
+
+	let input = json!({
+		"belongs_to_id": group_id_2,
+		"belongs_to_type": sentc_crypto::sdk_common::file::BelongsToType::Group,
+		"encrypted_key": "key_for_group_2",
+		"encrypted_key_alg": "alg",
+		"master_key_id": master_key_id_2,
+	});
+
+	//not from the user routes for a group file
+	let body = move_file(jwt, &app_data.public_token, &file_id, None, input.clone()).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 521);
+
+	//not into a group without access
+	let mut input_3 = input.clone();
+	input_3["belongs_to_id"] = Value::from(group_id_3.as_str());
+
+	let body = move_file(jwt, &app_data.public_token, &file_id, Some(&group_id), input_3).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 521);
+
+	let body = move_file(jwt, &app_data.public_token, &file_id, Some(&group_id), input).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//the file is only in the new group and the parts are the same
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/group/{group_id}/file/{file_id}")))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", &app_data.public_token)
+		.send()
+		.await
+		.unwrap();
+	let body = res.text().await.unwrap();
+	assert_eq!(get_server_error_from_normal_res(&body), 521);
+
+	let file = get_file(&file_id, jwt, &app_data.public_token, Some(&group_id_2)).await;
+	assert_eq!(file.belongs_to.as_deref(), Some(group_id_2.as_str()));
+	assert_eq!(file.encrypted_key, "key_for_group_2");
+	assert_eq!(file.master_key_id, master_key_id_2);
+	assert_eq!(file.part_list.len(), 1);
+	assert_eq!(file.part_list[0].part_id, part_id);
+
+	let usage = get_group_usage(jwt, &app_data.public_token, &group_id).await;
+	assert_eq!(usage["used_storage"].as_i64().unwrap(), 0);
+	assert_eq!(usage["file_count"].as_i64().unwrap(), 0);
+
+	let usage = get_group_usage(jwt, &app_data.public_token, &group_id_2).await;
+	assert_eq!(usage["used_storage"].as_i64().unwrap(), 4);
+	assert_eq!(usage["file_count"].as_i64().unwrap(), 1);
+
+	//a user must be set
+	let input = json!({
+		"belongs_to_id": null,
+		"belongs_to_type": sentc_crypto::sdk_common::file::BelongsToType::User,
+		"encrypted_key": "key_for_user",
+		"encrypted_key_alg": "alg",
+		"master_key_id": "user_key_id",
+	});
+
+	let body = move_file(
+		jwt,
+		&app_data.public_token,
+		&file_id,
+		Some(&group_id_2),
+		input.clone(),
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 560);
+
+	//move the file back to the user
+	let mut input = input;
+	input["belongs_to_id"] = Value::from(user_id.as_str());
+
+	let body = move_file(jwt, &app_data.public_token, &file_id, Some(&group_id_2), input).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let file = get_file(&file_id, jwt, &app_data.public_token, None).await;
+	assert_eq!(file.belongs_to.as_deref(), Some(user_id.as_str()));
+	assert_eq!(file.encrypted_key, "key_for_user");
+	assert_eq!(file.part_list[0].part_id, part_id);
+
+	let usage = get_group_usage(jwt, &app_data.public_token, &group_id_2).await;
+	assert_eq!(usage["used_storage"].as_i64().unwrap(), 0);
+	assert_eq!(usage["file_count"].as_i64().unwrap(), 0);
+
+	customer_delete(customer_jwt).await;
+}
@@ -0,0 +1,139 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use serde_json::Value;
+use server_dashboard_common::app::{AppFileOptionsInput, AppOptions, AppRegisterInput, AppRegisterOutput, FileUsage};
+
+use crate::test_fn::{
+	auth_header,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_group,
+	get_server_error_from_normal_res,
+	get_url,
+	register_file,
+	upload_part,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+async fn get_usage(jwt: &str, public_token: &str) -> Value
+{
+	let url = get_url("api/v1/file/usage".to_string());
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn file_quota()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_file_quota.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let input = AppRegisterInput {
+		identifier: None,
+		options: AppOptions::default(),
+		file_options: AppFileOptionsInput {
+			max_storage_user: 10,
+			max_files_user: 1,
+			..Default::default()
+		},
+		group_options: Default::default(),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/customer/app".to_owned()))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	let app_data: AppRegisterOutput = handle_server_response(body.as_str()).unwrap();
+
+	let (_, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_quota",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+	let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+	let (file_input, _) = sentc_crypto::file::prepare_register_file(
+		encrypted_key.master_key_id,
+		&file_key,
+		encrypted_key_str,
+		None,
+		sentc_crypto::sdk_common::file::BelongsToType::None,
+		None,
+	)
+	.unwrap();
+
+	let body = register_file(jwt, &app_data.public_token, file_input.clone(), None).await;
+	let (_file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 1, false, vec![1, 2, 3, 4]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//too big for the rest of the user storage
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 2, true, vec![0; 20]).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 530);
+
+	let usage = get_usage(jwt, &app_data.public_token).await;
+	assert_eq!(usage["used_storage"].as_i64().unwrap(), 4);
+	assert_eq!(usage["file_count"].as_i64().unwrap(), 1);
+	assert_eq!(usage["max_files"].as_i64().unwrap(), 1);
+
+	//only one file for each user
+	let body = register_file(jwt, &app_data.public_token, file_input, None).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 531);
+
+	//usage of the whole app for the customer
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/customer/app/{}/file_usage", app_data.app_id)))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	let app_usage: FileUsage = handle_server_response(body.as_str()).unwrap();
+
+	assert_eq!(app_usage.used_storage, 4);
+	assert_eq!(app_usage.file_count, 1);
+
+	customer_delete(customer_jwt).await;
+}
@@ -0,0 +1,258 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use serde_json::Value;
+
+use crate::test_fn::{
+	auth_header,
+	create_app,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_file,
+	get_group,
+	get_server_error_from_normal_res,
+	get_url,
+	login_user,
+	register_file,
+	upload_part,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+async fn get_session_status_res(jwt: &str, public_token: &str, session_id: &str) -> String
+{
+	let url = get_url(format!("api/v1/file/session/{session_id}/status"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_session_status(jwt: &str, public_token: &str, session_id: &str) -> Value
+{
+	let body = get_session_status_res(jwt, public_token, session_id).await;
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+async fn get_file_res(jwt: &str, public_token: &str, file_id: &str) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(format!("api/v1/file/{file_id}")))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_usage(jwt: &str, public_token: &str) -> Value
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url("api/v1/file/usage".to_string()))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn file_resume_upload()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_file_resume.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+
+	let (_, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_resume",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+	let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+	let (file_input, _) = sentc_crypto::file::prepare_register_file(
+		encrypted_key.master_key_id,
+		&file_key,
+		encrypted_key_str,
+		None,
+		sentc_crypto::sdk_common::file::BelongsToType::None,
+		None,
+	)
+	.unwrap();
+
+	let body = register_file(jwt, &app_data.public_token, file_input, None).await;
+	let (file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 1, false, vec![1, 2, 3, 4]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 2, false, vec![5, 6]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//the client crashed, resume with a new login of the same user
+	let user_2 = login_user(&app_data.public_token, "hello_file_resume", "12345").await;
+	let jwt_2 = user_2.jwt.as_str();
+
+	let status = get_session_status(jwt_2, &app_data.public_token, &session_id).await;
+	assert_eq!(status["file_id"].as_str().unwrap(), file_id);
+
+	let parts = status["parts"].as_array().unwrap();
+	assert_eq!(parts.len(), 2);
+	assert_eq!(parts[0]["sequence"].as_i64().unwrap(), 1);
+	assert_eq!(parts[0]["size"].as_i64().unwrap(), 4);
+	assert_eq!(parts[1]["sequence"].as_i64().unwrap(), 2);
+	assert_eq!(parts[1]["size"].as_i64().unwrap(), 2);
+
+	//upload the last stored part again, it is not stored twice
+	let body = upload_part(jwt_2, &app_data.public_token, &session_id, 2, false, vec![5, 6]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let status = get_session_status(jwt_2, &app_data.public_token, &session_id).await;
+	assert_eq!(status["parts"].as_array().unwrap().len(), 2);
+
+	let body = upload_part(jwt_2, &app_data.public_token, &session_id, 3, true, vec![7]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let file = get_file(&file_id, jwt_2, &app_data.public_token, None).await;
+	assert_eq!(file.part_list.len(), 3);
+
+	customer_delete(customer_jwt).await;
+}
+
+#[tokio::test]
+async fn file_abandoned_session_cleanup()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_file_abandoned.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let public_token = app_data.public_token.as_str();
+
+	let (_, user) = create_test_user(&app_data.secret_token, public_token, "hello_file_abandoned", "12345").await;
+	let jwt = user.jwt.as_str();
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let mut uploads = Vec::with_capacity(2);
+
+	for _ in 0..2 {
+		let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+		let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+		let (file_input, _) = sentc_crypto::file::prepare_register_file(
+			encrypted_key.master_key_id,
+			&file_key,
+			encrypted_key_str,
+			None,
+			sentc_crypto::sdk_common::file::BelongsToType::None,
+			None,
+		)
+		.unwrap();
+
+		let body = register_file(jwt, public_token, file_input, None).await;
+		let (file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+		let body = upload_part(jwt, public_token, &session_id, 1, false, vec![1, 2, 3, 4]).await;
+		handle_general_server_response(body.as_str()).unwrap();
+
+		uploads.push((file_id, session_id));
+	}
+
+	let (abandoned_file_id, abandoned_session_id) = &uploads[0];
+	let (file_id, session_id) = &uploads[1];
+
+	let usage = get_usage(jwt, public_token).await;
+	assert_eq!(usage["used_storage"].as_i64().unwrap(), 8);
+	assert_eq!(usage["file_count"].as_i64().unwrap(), 2);
+
+	//move the first session before the session lifetime of the app
+	server_api_common::start().await;
+
+	//language=SQL
+	let sql = "UPDATE sentc_file_session SET created_at = ? WHERE id = ?";
+
+	rustgram_server_util::db::exec(
+		sql,
+		rustgram_server_util::set_params!("0".to_string(), abandoned_session_id.to_string()),
+	)
+	.await
+	.unwrap();
+
+	server_api_file::file_session_worker::start().await.unwrap();
+
+	//the abandoned file is gone
+	let body = get_session_status_res(jwt, public_token, abandoned_session_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 510);
+
+	let body = get_file_res(jwt, public_token, abandoned_file_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 512);
+
+	//the other upload can still be resumed
+	let status = get_session_status(jwt, public_token, session_id).await;
+	assert_eq!(status["parts"].as_array().unwrap().len(), 1);
+
+	//the parts and the usage are removed by the file worker
+	tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+	server_api_file::file_worker::start().await.unwrap();
+
+	let usage = get_usage(jwt, public_token).await;
+	assert_eq!(usage["used_storage"].as_i64().unwrap(), 4);
+	assert_eq!(usage["file_count"].as_i64().unwrap(), 1);
+
+	let body = upload_part(jwt, public_token, session_id, 2, true, vec![5, 6]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let file = get_file(file_id, jwt, public_token, None).await;
+	assert_eq!(file.part_list.len(), 2);
+
+	customer_delete(customer_jwt).await;
+}
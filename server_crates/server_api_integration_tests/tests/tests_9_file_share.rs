@@ -0,0 +1,180 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use serde_json::{json, Value};
+
+use crate::test_fn::{
+	auth_header,
+	create_app,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_file,
+	get_group,
+	get_server_error_from_normal_res,
+	get_url,
+	register_file,
+	upload_part,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+async fn req(method: reqwest::Method, jwt: &str, public_token: &str, url: String, body: Option<String>) -> String
+{
+	let client = reqwest::Client::new();
+	let req = client
+		.request(method, get_url(url))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token);
+
+	let req = match body {
+		Some(b) => req.body(b),
+		None => req,
+	};
+
+	let res = req.send().await.unwrap();
+
+	res.text().await.unwrap()
+}
+
+#[tokio::test]
+async fn file_share()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_file_share.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+
+	let (_, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_share",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let (user_id_2, user_2) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_share_2",
+		"12345",
+	)
+	.await;
+	let jwt_2 = user_2.jwt.as_str();
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	//the file belongs to the group of user 1
+	let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+	let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+	let (file_input, _) = sentc_crypto::file::prepare_register_file(
+		encrypted_key.master_key_id,
+		&file_key,
+		encrypted_key_str,
+		Some(group_id.clone()),
+		sentc_crypto::sdk_common::file::BelongsToType::Group,
+		None,
+	)
+	.unwrap();
+
+	let body = register_file(jwt, &app_data.public_token, file_input, Some(&group_id)).await;
+	let (file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 1, true, vec![1, 2, 3]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//user 2 is not in the group
+	let body = req(
+		reqwest::Method::GET,
+		jwt_2,
+		&app_data.public_token,
+		format!("api/v1/file/{file_id}"),
+		None,
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 521);
+
+	let share_input = json!({
+		"share_to": user_id_2,
+		"share_to_type": sentc_crypto::sdk_common::file::BelongsToType::User,
+		"encrypted_key": "key_for_user_2",
+		"encrypted_key_alg": "alg",
+		"master_key_id": "user_2_key_id",
+	});
+
+	//only the owner can share
+	let body = req(
+		reqwest::Method::POST,
+		jwt_2,
+		&app_data.public_token,
+		format!("api/v1/file/{file_id}/share"),
+		Some(share_input.to_string()),
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 521);
+
+	let body = req(
+		reqwest::Method::POST,
+		jwt,
+		&app_data.public_token,
+		format!("api/v1/file/{file_id}/share"),
+		Some(share_input.to_string()),
+	)
+	.await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//user 2 gets the key which was encrypted for him
+	let file = get_file(&file_id, jwt_2, &app_data.public_token, None).await;
+	assert_eq!(file.encrypted_key, "key_for_user_2");
+	assert_eq!(file.master_key_id, "user_2_key_id");
+	assert_eq!(file.part_list.len(), 1);
+
+	let body = req(
+		reqwest::Method::GET,
+		jwt,
+		&app_data.public_token,
+		format!("api/v1/file/{file_id}/share/0/none"),
+		None,
+	)
+	.await;
+	let shares: Vec<Value> = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(shares.len(), 1);
+	assert_eq!(shares[0]["share_to"].as_str().unwrap(), user_id_2);
+
+	//revoke the share
+	let body = req(
+		reqwest::Method::DELETE,
+		jwt,
+		&app_data.public_token,
+		format!("api/v1/file/{file_id}/share/{user_id_2}"),
+		None,
+	)
+	.await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let body = req(
+		reqwest::Method::GET,
+		jwt_2,
+		&app_data.public_token,
+		format!("api/v1/file/{file_id}"),
+		None,
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 521);
+
+	customer_delete(customer_jwt).await;
+}
@@ -58,6 +58,7 @@ async fn init_app()
 			file_storage: FILE_STORAGE_OWN,
 			storage_url: Some(format!("http://127.0.0.1:{}/file_part/delete", 3003)),
 			auth_token: Some("abc".to_string()),
+			..Default::default()
 		},
 		group_options: Default::default(),
 	};
@@ -0,0 +1,253 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use serde_json::Value;
+use server_dashboard_common::app::{AppFileOptionsInput, AppOptions, AppRegisterInput, AppRegisterOutput};
+
+use crate::test_fn::{
+	auth_header,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_group,
+	get_server_error_from_normal_res,
+	get_url,
+	register_file,
+	upload_part,
+	TestKeyGenerator,
+};
+
+mod test_fn;
+
+async fn register_version(jwt: &str, public_token: &str, file_id: &str) -> String
+{
+	let url = get_url(format!("api/v1/file/{file_id}/version"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get(jwt: &str, public_token: &str, url: String) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.get(get_url(url))
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_versions(jwt: &str, public_token: &str, file_id: &str) -> Vec<i64>
+{
+	let body = get(jwt, public_token, format!("api/v1/file/{file_id}/version/all/0")).await;
+	let versions: Value = handle_server_response(body.as_str()).unwrap();
+
+	versions
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|v| v["version"].as_i64().unwrap())
+		.collect()
+}
+
+async fn restore_version(jwt: &str, public_token: &str, file_id: &str, version: i32) -> String
+{
+	let url = get_url(format!("api/v1/file/{file_id}/version/{version}/restore"));
+
+	let client = reqwest::Client::new();
+	let res = client
+		.put(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+#[tokio::test]
+async fn file_versions()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_file_version.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let input = AppRegisterInput {
+		identifier: None,
+		options: AppOptions::default(),
+		file_options: AppFileOptionsInput {
+			version_keep_last: 1,
+			..Default::default()
+		},
+		group_options: Default::default(),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(get_url("api/v1/customer/app".to_owned()))
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(input.to_string().unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+	let app_data: AppRegisterOutput = handle_server_response(body.as_str()).unwrap();
+
+	let (_, user) = create_test_user(
+		&app_data.secret_token,
+		&app_data.public_token,
+		"hello_file_version",
+		"12345",
+	)
+	.await;
+	let jwt = user.jwt.as_str();
+
+	let group_id = create_group(&app_data.secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(
+		&app_data.secret_token,
+		jwt,
+		&group_id,
+		&user.user_keys[0].private_key,
+		false,
+	)
+	.await
+	.1;
+
+	let (file_key, encrypted_key) = TestKeyGenerator::generate_non_register_sym_key(&group_keys[0].group_key).unwrap();
+	let encrypted_key_str = encrypted_key.to_string().unwrap();
+
+	let (file_input, _) = sentc_crypto::file::prepare_register_file(
+		encrypted_key.master_key_id,
+		&file_key,
+		encrypted_key_str,
+		None,
+		sentc_crypto::sdk_common::file::BelongsToType::None,
+		None,
+	)
+	.unwrap();
+
+	let body = register_file(jwt, &app_data.public_token, file_input, None).await;
+	let (file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	//no new version before the first upload is done
+	let body = register_version(jwt, &app_data.public_token, &file_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 551);
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 1, false, vec![1, 2, 3]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 2, true, vec![4]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//upload the second version under the same file id
+	let body = register_version(jwt, &app_data.public_token, &file_id).await;
+	let (version_file_id, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+	assert_eq!(version_file_id, file_id);
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 1, false, vec![5, 6]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	//the old version is still used while the new version is uploading
+	let body = get(jwt, &app_data.public_token, format!("api/v1/file/{file_id}")).await;
+	let file: Value = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(file["version"].as_i64().unwrap(), 1);
+	assert_eq!(file["part_list"].as_array().unwrap().len(), 2);
+
+	assert_eq!(get_versions(jwt, &app_data.public_token, &file_id).await, vec![1]);
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 2, true, vec![7]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let body = get(jwt, &app_data.public_token, format!("api/v1/file/{file_id}")).await;
+	let file: Value = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(file["version"].as_i64().unwrap(), 2);
+	assert_eq!(file["part_list"].as_array().unwrap().len(), 2);
+
+	assert_eq!(get_versions(jwt, &app_data.public_token, &file_id).await, vec![2, 1]);
+
+	//download the old version
+	let body = get(
+		jwt,
+		&app_data.public_token,
+		format!("api/v1/file/{file_id}/version/1"),
+	)
+	.await;
+	let file: Value = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(file["version"].as_i64().unwrap(), 1);
+
+	let part_list = file["part_list"].as_array().unwrap();
+	assert_eq!(part_list.len(), 2);
+
+	let last_sequence = part_list[0]["sequence"].as_i64().unwrap();
+	let body = get(
+		jwt,
+		&app_data.public_token,
+		format!("api/v1/file/{file_id}/version/1/part_fetch/{last_sequence}"),
+	)
+	.await;
+	let parts: Value = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(parts.as_array().unwrap().len(), 1);
+
+	//the parts are only listed for an accessible file
+	let body = get(
+		jwt,
+		&app_data.public_token,
+		"api/v1/file/not_a_file/version/1/part_fetch/0".to_string(),
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 512);
+
+	let body = get(
+		jwt,
+		&app_data.public_token,
+		format!("api/v1/file/{file_id}/version/5"),
+	)
+	.await;
+	assert_eq!(get_server_error_from_normal_res(&body), 550);
+
+	//restore the first version
+	let body = restore_version(jwt, &app_data.public_token, &file_id, 1).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	let body = get(jwt, &app_data.public_token, format!("api/v1/file/{file_id}")).await;
+	let file: Value = handle_server_response(body.as_str()).unwrap();
+	assert_eq!(file["version"].as_i64().unwrap(), 1);
+
+	//a third version, the app only keeps one old version
+	let body = register_version(jwt, &app_data.public_token, &file_id).await;
+	let (_, session_id) = sentc_crypto::file::done_register_file(body.as_str()).unwrap();
+
+	let body = upload_part(jwt, &app_data.public_token, &session_id, 1, true, vec![8]).await;
+	handle_general_server_response(body.as_str()).unwrap();
+
+	assert_eq!(
+		get_versions(jwt, &app_data.public_token, &file_id).await,
+		vec![3, 2, 1]
+	);
+
+	server_api_common::start().await;
+
+	server_api_file::file_worker::start().await.unwrap();
+
+	//version 1 is the oldest of the old versions
+	assert_eq!(get_versions(jwt, &app_data.public_token, &file_id).await, vec![3, 2]);
+
+	let body = restore_version(jwt, &app_data.public_token, &file_id, 1).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 550);
+
+	customer_delete(customer_jwt).await;
+}
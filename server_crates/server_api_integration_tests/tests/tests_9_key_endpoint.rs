@@ -0,0 +1,177 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::sdk_utils::handle_general_server_response;
+use sentc_crypto::util_req_full::user::PreLoginOut;
+use sentc_crypto_common::UserId;
+use server_dashboard_common::app::{AppKeyEndpointOptionsInput, AppRegisterOutput, KEY_ENDPOINT_FALLBACK_LOCAL, KEY_ENDPOINT_FALLBACK_NONE};
+use server_dashboard_common::customer::CustomerDoneLoginOutput;
+use tokio::sync::{OnceCell, RwLock};
+
+use crate::test_fn::{auth_header, create_app, create_test_customer, customer_delete, get_base_url, get_url, login_user, register_user, TestUser};
+
+mod test_fn;
+
+/**
+Must be the same token as in the dummy server
+ */
+const AUTH_TOKEN: &str = "sentc_test_key_endpoint_token";
+
+static TEST_STATE: OnceCell<RwLock<TestData>> = OnceCell::const_new();
+
+pub struct TestData
+{
+	pub user_id: UserId,
+	pub username: String,
+	pub user_pw: String,
+
+	pub app_data: AppRegisterOutput,
+	pub customer_data: CustomerDoneLoginOutput,
+}
+
+async fn set_key_endpoint(customer_jwt: &str, app_id: &str, url: &str, fallback: i32)
+{
+	let url_req = get_url("api/v1/customer/app/".to_owned() + app_id + "/key_endpoint_options");
+
+	let input = AppKeyEndpointOptionsInput {
+		key_endpoint_url: Some(url.to_string()),
+		auth_token: Some(AUTH_TOKEN.to_string()),
+		timeout: 1000,
+		fallback,
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.put(url_req)
+		.header(AUTHORIZATION, auth_header(customer_jwt))
+		.body(serde_json::to_string(&input).unwrap())
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_general_server_response(body.as_str()).unwrap();
+}
+
+async fn init_app()
+{
+	let (_, customer_data) = create_test_customer("hello@test_key_endpoint.com", "12345").await;
+
+	let app_data = create_app(customer_data.verify.jwt.as_str()).await;
+
+	//store the keys at the dummy server and keep a local copy
+	set_key_endpoint(
+		customer_data.verify.jwt.as_str(),
+		&app_data.app_id,
+		"http://127.0.0.1:3003/key_endpoint",
+		KEY_ENDPOINT_FALLBACK_LOCAL,
+	)
+	.await;
+
+	let user_pw = "12345";
+	let username = "hello_key_endpoint";
+
+	//the device keys are now stored at the key endpoint
+	let user_id = register_user(&app_data.secret_token, username, user_pw).await;
+
+	TEST_STATE
+		.get_or_init(|| {
+			async move {
+				RwLock::new(TestData {
+					user_id,
+					username: username.to_string(),
+					user_pw: user_pw.to_string(),
+					app_data,
+					customer_data,
+				})
+			}
+		})
+		.await;
+}
+
+async fn login_with_key_endpoint()
+{
+	let state = TEST_STATE.get().unwrap().read().await;
+
+	//the keys are fetched from the key endpoint
+	let key_data = login_user(&state.app_data.public_token, &state.username, &state.user_pw).await;
+
+	assert_eq!(key_data.user_id, state.user_id);
+}
+
+async fn login_without_fallback()
+{
+	let state = TEST_STATE.get().unwrap().read().await;
+
+	//no server is running at this port
+	set_key_endpoint(
+		state.customer_data.verify.jwt.as_str(),
+		&state.app_data.app_id,
+		"http://127.0.0.1:3009/key_endpoint",
+		KEY_ENDPOINT_FALLBACK_NONE,
+	)
+	.await;
+
+	let out = TestUser::login(
+		get_base_url(),
+		&state.app_data.public_token,
+		&state.username,
+		&state.user_pw,
+	)
+	.await;
+
+	assert!(out.is_err());
+}
+
+async fn login_with_local_fallback()
+{
+	let state = TEST_STATE.get().unwrap().read().await;
+
+	set_key_endpoint(
+		state.customer_data.verify.jwt.as_str(),
+		&state.app_data.app_id,
+		"http://127.0.0.1:3009/key_endpoint",
+		KEY_ENDPOINT_FALLBACK_LOCAL,
+	)
+	.await;
+
+	//use the local copy of the keys
+	let out = TestUser::login(
+		get_base_url(),
+		&state.app_data.public_token,
+		&state.username,
+		&state.user_pw,
+	)
+	.await
+	.unwrap();
+
+	match out {
+		PreLoginOut::Direct(d) => assert_eq!(d.user_id, state.user_id),
+		_ => panic!("No mfa excepted"),
+	}
+}
+
+async fn clean_up()
+{
+	let state = TEST_STATE.get().unwrap().read().await;
+	customer_delete(state.customer_data.verify.jwt.as_str()).await;
+}
+
+/**
+Needs the dummy server and KEY_ENDPOINT_ALLOW_HTTP=1 because the dummy server is not using https
+ */
+#[ignore]
+#[tokio::test]
+async fn key_endpoint()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	init_app().await;
+
+	login_with_key_endpoint().await;
+
+	login_without_fallback().await;
+
+	login_with_local_fallback().await;
+
+	clean_up().await;
+}
@@ -0,0 +1,74 @@
+use sentc_crypto::util::public::handle_server_response;
+use serde_json::Value;
+
+use crate::test_fn::{create_app, create_test_customer, customer_delete, get_url, login_user, register_user};
+
+mod test_fn;
+
+async fn get_kt(public_token: &str, path: String) -> Value
+{
+	let url = get_url("api/v1/kt/".to_owned() + &path);
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	let body = res.text().await.unwrap();
+
+	handle_server_response(body.as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn key_transparency()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_key_transparency.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+
+	register_user(&app_data.secret_token, "hello_kt", "12345").await;
+
+	let tree_head = get_kt(&app_data.public_token, "tree_head".to_string()).await;
+
+	//device public key, device verify key, user group public key and user group verify key
+	let first_size = tree_head["tree_size"].as_i64().unwrap();
+	assert_eq!(first_size, 4);
+	assert!(!tree_head["signature"].as_str().unwrap().is_empty());
+
+	let user = login_user(&app_data.public_token, "hello_kt", "12345").await;
+
+	//device public key
+	let proof = get_kt(
+		&app_data.public_token,
+		format!("inclusion/0/{}/{}", user.device_id, first_size),
+	)
+	.await;
+
+	assert_eq!(proof["leaf"]["owner_id"].as_str().unwrap(), user.user_id);
+	assert_eq!(proof["leaf"]["leaf_index"].as_i64().unwrap(), 0);
+	assert_eq!(proof["audit_path"].as_array().unwrap().len(), 2);
+
+	//the tree grows with the next user
+	register_user(&app_data.secret_token, "hello_kt_1", "12345").await;
+
+	let tree_head = get_kt(&app_data.public_token, "tree_head".to_string()).await;
+	let second_size = tree_head["tree_size"].as_i64().unwrap();
+	assert_eq!(second_size, 8);
+
+	let proof = get_kt(
+		&app_data.public_token,
+		format!("consistency/{first_size}/{second_size}"),
+	)
+	.await;
+
+	//the first tree is a complete subtree of the second
+	assert_eq!(proof["proof"].as_array().unwrap().len(), 1);
+
+	customer_delete(customer_jwt).await;
+}
@@ -0,0 +1,458 @@
+use reqwest::header::AUTHORIZATION;
+use sentc_crypto::util::public::{handle_general_server_response, handle_server_response};
+use sentc_crypto_common::crypto::GeneratedSymKeyHeadServerRegisterOutput;
+use sentc_crypto_common::group::KeyRotationStartServerOutput;
+use serde_json::{json, Value};
+
+use crate::test_fn::{
+	add_user_by_invite,
+	auth_header,
+	create_app,
+	create_group,
+	create_test_customer,
+	create_test_user,
+	customer_delete,
+	get_group,
+	get_server_error_from_normal_res,
+	get_url,
+	TestGroup,
+};
+
+mod test_fn;
+
+fn key_input(label: Option<String>) -> String
+{
+	json!({
+		"master_key_id": "master_key",
+		"encrypted_key_string": "encrypted_key",
+		"alg": "alg",
+		"label": label,
+		"not_after": null,
+	})
+	.to_string()
+}
+
+async fn register_key(jwt: &str, public_token: &str, group_id: Option<&str>, input: String) -> String
+{
+	let url = match group_id {
+		Some(id) => get_url(format!("api/v1/group/{id}/sym_key")),
+		None => get_url("api/v1/keys/sym_key".to_string()),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.body(input)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_key(jwt: &str, public_token: &str, group_id: Option<&str>, key_id: &str) -> String
+{
+	let url = match group_id {
+		Some(id) => get_url(format!("api/v1/group/{id}/sym_key/{key_id}")),
+		None => get_url(format!("api/v1/keys/sym_key/{key_id}")),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.get(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn get_keys_by_ids(jwt: &str, public_token: &str, group_id: Option<&str>, ids: Vec<String>) -> String
+{
+	let url = match group_id {
+		Some(id) => get_url(format!("api/v1/group/{id}/sym_key/bulk")),
+		None => get_url("api/v1/keys/sym_key/bulk".to_string()),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.post(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.body(json!(ids).to_string())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn deprecate_key(jwt: &str, public_token: &str, group_id: Option<&str>, key_id: &str, not_after: Option<u128>) -> String
+{
+	let url = match group_id {
+		Some(id) => get_url(format!("api/v1/group/{id}/key_rotation/{key_id}/deprecate")),
+		None => get_url(format!("api/v1/keys/sym_key/{key_id}/deprecate")),
+	};
+
+	let client = reqwest::Client::new();
+	let res = client
+		.put(url)
+		.header(AUTHORIZATION, auth_header(jwt))
+		.header("x-sentc-app-token", public_token)
+		.body(json!({ "not_after": not_after }).to_string())
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn sym_key_compromised_forced(secret_token: &str, key_id: &str) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!("api/v1/keys/sym_key/forced/{key_id}/compromised")))
+		.header("x-sentc-app-token", secret_token)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+async fn group_key_compromised_forced(secret_token: &str, user_id: &str, group_id: &str, key_id: &str, input: String) -> String
+{
+	let client = reqwest::Client::new();
+	let res = client
+		.put(get_url(format!(
+			"api/v1/group/forced/{user_id}/{group_id}/key/{key_id}/compromised"
+		)))
+		.header("x-sentc-app-token", secret_token)
+		.body(input)
+		.send()
+		.await
+		.unwrap();
+
+	res.text().await.unwrap()
+}
+
+#[tokio::test]
+async fn sym_key_scope_and_limits()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_sym_key.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let secret_token = app_data.secret_token.as_str();
+	let public_token = app_data.public_token.as_str();
+
+	let (_, user) = create_test_user(secret_token, public_token, "hello_sym_key", "12345").await;
+	let jwt = user.jwt.as_str();
+
+	let (user_id_2, user_2) = create_test_user(secret_token, public_token, "hello_sym_key_2", "12345").await;
+	let (_, user_3) = create_test_user(secret_token, public_token, "hello_sym_key_3", "12345").await;
+
+	let group_id = create_group(secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(secret_token, jwt, &group_id, &user.user_keys[0].private_key, false)
+		.await
+		.1;
+
+	let group_id_2 = create_group(secret_token, &user.user_keys[0].public_key, None, jwt).await;
+
+	//user 2 is a member with the default rank 4
+	add_user_by_invite(
+		secret_token,
+		jwt,
+		&group_id,
+		&group_keys,
+		&user_id_2,
+		&user_2.jwt,
+		&user_2.user_keys[0].exported_public_key,
+		&user_2.user_keys[0].private_key,
+	)
+	.await;
+
+	//______________________________________________________________________________________________
+	//register
+
+	let body = register_key(jwt, public_token, None, key_input(None)).await;
+	let user_key_id = handle_server_response::<GeneratedSymKeyHeadServerRegisterOutput>(&body)
+		.unwrap()
+		.key_id;
+
+	let body = register_key(
+		jwt,
+		public_token,
+		Some(&group_id),
+		key_input(Some("group".to_string())),
+	)
+	.await;
+	let group_key_id = handle_server_response::<GeneratedSymKeyHeadServerRegisterOutput>(&body)
+		.unwrap()
+		.key_id;
+
+	//a member with rank 4 can't register keys in the group
+	let body = register_key(&user_2.jwt, public_token, Some(&group_id), key_input(None)).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 301);
+
+	//not a member of the group
+	let body = register_key(&user_3.jwt, public_token, Some(&group_id), key_input(None)).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 310);
+
+	//______________________________________________________________________________________________
+	//access scope
+
+	let body = get_key(jwt, public_token, None, &user_key_id).await;
+	let key: Value = handle_server_response(&body).unwrap();
+	assert_eq!(key["key_id"], user_key_id);
+
+	//user keys are not available in the group
+	let body = get_key(jwt, public_token, Some(&group_id), &user_key_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 400);
+
+	//group keys are not available outside the group
+	let body = get_key(jwt, public_token, None, &group_key_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 400);
+
+	let body = get_keys_by_ids(jwt, public_token, None, vec![group_key_id.clone()]).await;
+	let keys: Vec<Value> = handle_server_response(&body).unwrap();
+	assert_eq!(keys.len(), 0);
+
+	//group keys are not available in another group of the same user
+	let body = get_key(jwt, public_token, Some(&group_id_2), &group_key_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 400);
+
+	//every member can read the keys of the group
+	let body = get_key(&user_2.jwt, public_token, Some(&group_id), &group_key_id).await;
+	let key: Value = handle_server_response(&body).unwrap();
+	assert_eq!(key["key_id"], group_key_id);
+	assert_eq!(key["label"], "group");
+
+	let body = get_keys_by_ids(&user_2.jwt, public_token, Some(&group_id), vec![group_key_id.clone()]).await;
+	let keys: Vec<Value> = handle_server_response(&body).unwrap();
+	assert_eq!(keys.len(), 1);
+
+	//but not the user who is not in the group
+	let body = get_key(&user_3.jwt, public_token, Some(&group_id), &group_key_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 310);
+
+	//______________________________________________________________________________________________
+	//bulk limits
+
+	let body = get_keys_by_ids(jwt, public_token, None, vec![]).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 402);
+
+	let too_many: Vec<String> = (0..101).map(|i| format!("key_{i}")).collect();
+
+	let body = get_keys_by_ids(jwt, public_token, None, too_many.clone()).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 402);
+
+	let body = get_keys_by_ids(jwt, public_token, Some(&group_id), too_many).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 402);
+
+	let max: Vec<String> = (0..100).map(|i| format!("key_{i}")).collect();
+
+	let body = get_keys_by_ids(jwt, public_token, None, max).await;
+	let keys: Vec<Value> = handle_server_response(&body).unwrap();
+	assert_eq!(keys.len(), 0);
+
+	//______________________________________________________________________________________________
+	//label length
+
+	let body = register_key(jwt, public_token, None, key_input(Some("a".repeat(201)))).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 401);
+
+	let body = register_key(jwt, public_token, Some(&group_id), key_input(Some("a".repeat(201)))).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 401);
+
+	let body = register_key(jwt, public_token, None, key_input(Some("a".repeat(200)))).await;
+	handle_server_response::<GeneratedSymKeyHeadServerRegisterOutput>(&body).unwrap();
+
+	customer_delete(customer_jwt).await;
+}
+
+#[tokio::test]
+async fn sym_key_deprecate_and_compromised()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_sym_key_status.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let secret_token = app_data.secret_token.as_str();
+	let public_token = app_data.public_token.as_str();
+
+	let (_, user) = create_test_user(secret_token, public_token, "hello_sym_key_status", "12345").await;
+	let jwt = user.jwt.as_str();
+
+	let (_, user_2) = create_test_user(secret_token, public_token, "hello_sym_key_status_2", "12345").await;
+
+	let body = register_key(jwt, public_token, None, key_input(None)).await;
+	let key_id = handle_server_response::<GeneratedSymKeyHeadServerRegisterOutput>(&body)
+		.unwrap()
+		.key_id;
+
+	let not_after = rustgram_server_util::get_time().unwrap() + 1000 * 60 * 60;
+
+	//______________________________________________________________________________________________
+	//deprecate
+
+	let body = deprecate_key(jwt, public_token, None, "not_a_key", Some(not_after)).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 400);
+
+	//only the creator can deprecate the key
+	let body = deprecate_key(&user_2.jwt, public_token, None, &key_id, Some(not_after)).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 400);
+
+	let body = deprecate_key(jwt, public_token, None, &key_id, Some(not_after)).await;
+	handle_general_server_response(&body).unwrap();
+
+	let body = get_key(jwt, public_token, None, &key_id).await;
+	let key: Value = handle_server_response(&body).unwrap();
+	assert_eq!(key["key_status"], 1);
+	assert_eq!(key["not_after"], json!(not_after));
+
+	//______________________________________________________________________________________________
+	//compromised
+
+	let body = sym_key_compromised_forced(secret_token, "not_a_key").await;
+	assert_eq!(get_server_error_from_normal_res(&body), 400);
+
+	//only with the secret token
+	let body = sym_key_compromised_forced(public_token, &key_id).await;
+	assert!(handle_general_server_response(&body).is_err());
+
+	let body = sym_key_compromised_forced(secret_token, &key_id).await;
+	handle_general_server_response(&body).unwrap();
+
+	//deprecate doesn't overwrite the compromised status
+	let body = deprecate_key(jwt, public_token, None, &key_id, Some(not_after)).await;
+	handle_general_server_response(&body).unwrap();
+
+	let body = get_key(jwt, public_token, None, &key_id).await;
+	let key: Value = handle_server_response(&body).unwrap();
+	assert_eq!(key["key_status"], 2);
+
+	//______________________________________________________________________________________________
+	//key worker deletes the keys after the grace period (30 days by default)
+
+	let body = register_key(jwt, public_token, None, key_input(None)).await;
+	let expired_key_id = handle_server_response::<GeneratedSymKeyHeadServerRegisterOutput>(&body)
+		.unwrap()
+		.key_id;
+
+	let body = register_key(jwt, public_token, None, key_input(None)).await;
+	let grace_key_id = handle_server_response::<GeneratedSymKeyHeadServerRegisterOutput>(&body)
+		.unwrap()
+		.key_id;
+
+	for id in [&expired_key_id, &grace_key_id] {
+		let body = deprecate_key(jwt, public_token, None, id, Some(not_after)).await;
+		handle_general_server_response(&body).unwrap();
+	}
+
+	server_api_common::start().await;
+
+	//the not after time must be in the future for the api, so move it back directly in the db
+	let day = 1000 * 60 * 60 * 24;
+	let time = rustgram_server_util::get_time().unwrap();
+
+	//language=SQL
+	let sql = "UPDATE sentc_sym_key_management SET not_after = ? WHERE id = ?";
+
+	rustgram_server_util::db::exec(
+		sql,
+		rustgram_server_util::set_params!((time - 31 * day).to_string(), expired_key_id.clone()),
+	)
+	.await
+	.unwrap();
+
+	rustgram_server_util::db::exec(
+		sql,
+		rustgram_server_util::set_params!((time - day).to_string(), grace_key_id.clone()),
+	)
+	.await
+	.unwrap();
+
+	server_api::sentc_key_worker::start().await.unwrap();
+
+	let body = get_key(jwt, public_token, None, &expired_key_id).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 400);
+
+	//still in the grace period
+	let body = get_key(jwt, public_token, None, &grace_key_id).await;
+	let key: Value = handle_server_response(&body).unwrap();
+	assert_eq!(key["key_id"], grace_key_id);
+
+	customer_delete(customer_jwt).await;
+}
+
+#[tokio::test]
+async fn group_key_deprecate_and_compromised()
+{
+	dotenv::from_filename("sentc.env").ok();
+
+	let (_, customer_data) = create_test_customer("hello@test_group_key_status.com", "12345").await;
+	let customer_jwt = customer_data.verify.jwt.as_str();
+
+	let app_data = create_app(customer_jwt).await;
+	let secret_token = app_data.secret_token.as_str();
+	let public_token = app_data.public_token.as_str();
+
+	let (user_id, user) = create_test_user(secret_token, public_token, "hello_group_key_status", "12345").await;
+	let jwt = user.jwt.as_str();
+
+	let group_id = create_group(secret_token, &user.user_keys[0].public_key, None, jwt).await;
+	let group_keys = get_group(secret_token, jwt, &group_id, &user.user_keys[0].private_key, false)
+		.await
+		.1;
+	let key_id = group_keys[0].group_key.key_id.as_str();
+
+	let group_id_2 = create_group(secret_token, &user.user_keys[0].public_key, None, jwt).await;
+
+	let not_after = rustgram_server_util::get_time().unwrap() + 1000 * 60 * 60;
+
+	//______________________________________________________________________________________________
+	//deprecate
+
+	let body = deprecate_key(jwt, public_token, Some(&group_id), "not_a_key", Some(not_after)).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 304);
+
+	//the key belongs to another group
+	let body = deprecate_key(jwt, public_token, Some(&group_id_2), key_id, Some(not_after)).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 304);
+
+	let body = deprecate_key(jwt, public_token, Some(&group_id), key_id, Some(not_after)).await;
+	handle_general_server_response(&body).unwrap();
+
+	//______________________________________________________________________________________________
+	//compromised
+
+	let input = TestGroup::key_rotation(
+		&group_keys[0].group_key,
+		&user.user_keys[0].public_key,
+		false,
+		None,
+		"test".to_string(),
+	)
+	.unwrap();
+
+	let body = group_key_compromised_forced(secret_token, &user_id, &group_id, "not_a_key", input.clone()).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 304);
+
+	let body = group_key_compromised_forced(secret_token, &user_id, &group_id_2, key_id, input.clone()).await;
+	assert_eq!(get_server_error_from_normal_res(&body), 304);
+
+	let body = group_key_compromised_forced(secret_token, &user_id, &group_id, key_id, input).await;
+	let out: KeyRotationStartServerOutput = handle_server_response(&body).unwrap();
+	assert_eq!(out.group_id, group_id);
+
+	customer_delete(customer_jwt).await;
+}
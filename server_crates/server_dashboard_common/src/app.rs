@@ -1,4 +1,4 @@
-use sentc_crypto_common::{AppId, JwtKeyId, SignKeyPairId};
+use sentc_crypto_common::{AppId, JwtKeyId, PartId, SignKeyPairId};
 use serde::{Deserialize, Serialize};
 use serde_json::to_string;
 
@@ -255,6 +255,10 @@ pub struct AppDetails
 	pub options: AppOptions,
 	pub file_options: AppFileOptionsInput,
 	pub group_options: AppGroupOption,
+	pub key_endpoint_options: AppKeyEndpointOptionsInput,
+	pub access_options: AppAccessOptions,
+	pub rate_limit_options: AppRateLimitOptions,
+	pub jwt_rotation_options: AppJwtRotationOptions,
 	pub details: CustomerAppList,
 }
 
@@ -329,6 +333,86 @@ pub struct AppTokenRenewOutput
 
 //__________________________________________________________________________________________________
 
+pub const APP_TOKEN_TYPE_PUBLIC: i32 = 0;
+pub const APP_TOKEN_TYPE_SECRET: i32 = 1;
+
+/**
+A named token of an app besides the default public and secret token.
+
+- expires_at = 0 for no expiry
+- scope: the endpoints which can be used with this token. An endpoint is only allowed when it is not 0 in the scope,
+the app options still decide if a public or a secret token is needed. No scope for all endpoints of the app options.
+ */
+#[derive(Serialize, Deserialize)]
+pub struct AppTokenCreateInput
+{
+	pub name: String,
+	pub token_type: i32,
+	pub expires_at: u128,
+	pub scope: Option<AppOptions>,
+	/**
+	No access options to use the access options of the app
+	 */
+	#[serde(default)]
+	pub access_options: Option<AppAccessOptions>,
+}
+
+impl AppTokenCreateInput
+{
+	pub fn to_string(&self) -> serde_json::Result<String>
+	{
+		to_string(self)
+	}
+}
+
+/**
+The token is only returned once, only the hash is stored
+ */
+#[derive(Serialize, Deserialize)]
+pub struct AppTokenCreateOutput
+{
+	pub token_id: String,
+	pub token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AppToken
+{
+	pub token_id: String,
+	pub name: String,
+	pub token_type: i32,
+	pub time: u128,
+	pub expires_at: u128,
+	pub last_used: u128,
+	pub scope: Option<AppOptions>,
+	pub access_options: Option<AppAccessOptions>,
+}
+
+/**
+Restrict from where the app tokens can be used.
+
+- allowed_origins: the origins of the browser requests, e.g. https://example.com. Empty for all origins. Only checked for the public token.
+- allowed_ips: the client ips as single ip or cidr range, e.g. 10.0.0.0/8. Empty for all ips.
+
+The client ip is read from the header of the trusted reverse proxy (CLIENT_IP_HEADER) when it is set, otherwise the address of the connection is used.
+ */
+#[derive(Serialize, Deserialize, Default)]
+pub struct AppAccessOptions
+{
+	pub allowed_origins: Vec<String>,
+	pub allowed_ips: Vec<String>,
+}
+
+impl AppAccessOptions
+{
+	pub fn to_string(&self) -> serde_json::Result<String>
+	{
+		to_string(self)
+	}
+}
+
+//__________________________________________________________________________________________________
+
 //copy from app internal entity but without the db trait impl
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(rustgram_server_util::DB))]
@@ -339,6 +423,50 @@ pub struct AppJwtData
 	pub time: u128,
 	pub sign_key: String,
 	pub verify_key: String,
+	/**
+	When the key was replaced by a rotation, the verify key is only valid until this time. 0 = active
+	 */
+	pub retire_at: u128,
+}
+
+pub const JWT_ROTATION_MAX_DAYS: i32 = 365;
+pub const JWT_ROTATION_GRACE_HOURS_DEFAULT: i32 = 24;
+pub const JWT_ROTATION_GRACE_HOURS_MAX: i32 = 30 * 24;
+
+/**
+Create a new jwt key every n days. The newest key is used to sign the jwt.
+
+The old keys can still verify the jwt of the users for the grace hours, then they are deleted.
+The grace period should cover the lifetime of the jwt and the time until the users refresh it.
+ */
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(rustgram_server_util::DB))]
+pub struct AppJwtRotationOptions
+{
+	/**
+	0 = no automatic rotation
+	 */
+	pub rotation_days: i32,
+	pub grace_hours: i32,
+}
+
+impl Default for AppJwtRotationOptions
+{
+	fn default() -> Self
+	{
+		Self {
+			rotation_days: 0,
+			grace_hours: JWT_ROTATION_GRACE_HOURS_DEFAULT,
+		}
+	}
+}
+
+impl AppJwtRotationOptions
+{
+	pub fn to_string(&self) -> serde_json::Result<String>
+	{
+		to_string(self)
+	}
 }
 
 //__________________________________________________________________________________________________
@@ -347,6 +475,13 @@ pub const FILE_STORAGE_NONE: i32 = -1;
 pub const FILE_STORAGE_SENTC: i32 = 0;
 pub const FILE_STORAGE_OWN: i32 = 1;
 
+/**
+In seconds, 24 h
+ */
+pub const FILE_SESSION_LIFETIME_DEFAULT: i32 = 24 * 60 * 60;
+pub const FILE_SESSION_LIFETIME_MIN: i32 = 60;
+pub const FILE_SESSION_LIFETIME_MAX: i32 = 7 * 24 * 60 * 60;
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(rustgram_server_util::DB))]
 pub struct AppFileOptionsInput
@@ -354,6 +489,37 @@ pub struct AppFileOptionsInput
 	pub file_storage: i32,
 	pub storage_url: Option<String>,
 	pub auth_token: Option<String>,
+	/**
+	The quotas in bytes and in number of files. 0 = no limit
+
+	The storage quotas only count the parts stored by sentc, the size of the parts in an external storage is not known.
+	 */
+	pub max_storage_app: i64,
+	pub max_storage_user: i64,
+	pub max_storage_group: i64,
+	pub max_files_app: i32,
+	pub max_files_user: i32,
+	pub max_files_group: i32,
+	/**
+	Seconds until an upload session expires. Files which are not finished in this time are deleted.
+	 */
+	pub session_lifetime: i32,
+	/**
+	Seconds until a new file expires when the client sets no expiry time. 0 = never
+	 */
+	pub default_ttl: i64,
+	/**
+	Max seconds until a file expires. 0 = no limit
+	 */
+	pub max_ttl: i64,
+	/**
+	How many old versions of a file are kept. 0 = all
+	 */
+	pub version_keep_last: i32,
+	/**
+	Days until an old version of a file is deleted. 0 = never
+	 */
+	pub version_keep_days: i32,
 }
 
 impl Default for AppFileOptionsInput
@@ -364,6 +530,17 @@ impl Default for AppFileOptionsInput
 			file_storage: FILE_STORAGE_SENTC,
 			storage_url: None,
 			auth_token: None,
+			max_storage_app: 0,
+			max_storage_user: 0,
+			max_storage_group: 0,
+			max_files_app: 0,
+			max_files_user: 0,
+			max_files_group: 0,
+			session_lifetime: FILE_SESSION_LIFETIME_DEFAULT,
+			default_ttl: 0,
+			max_ttl: 0,
+			version_keep_last: 0,
+			version_keep_days: 0,
 		}
 	}
 }
@@ -376,10 +553,110 @@ impl AppFileOptionsInput
 			file_storage: FILE_STORAGE_NONE,
 			storage_url: None,
 			auth_token: None,
+			max_storage_app: 0,
+			max_storage_user: 0,
+			max_storage_group: 0,
+			max_files_app: 0,
+			max_files_user: 0,
+			max_files_group: 0,
+			session_lifetime: FILE_SESSION_LIFETIME_DEFAULT,
+			default_ttl: 0,
+			max_ttl: 0,
+			version_keep_last: 0,
+			version_keep_days: 0,
 		}
 	}
 }
 
+/**
+The used storage in bytes and the number of files of an app, a user or a group.
+
+Files count until the file worker deleted them.
+ */
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(rustgram_server_util::DB))]
+pub struct FileUsage
+{
+	pub used_storage: i64,
+	pub file_count: i32,
+}
+
+/**
+The response of the delete endpoint of an external storage.
+
+The endpoint gets the part ids as json array and must respond with the ids of the deleted parts.
+Parts which are not in the list are sent again later.
+ */
+#[derive(Serialize, Deserialize)]
+pub struct FileExternalDeleteOutput
+{
+	pub deleted: Vec<PartId>,
+}
+
+/**
+A part of the external storage which is not deleted yet because the delete request failed.
+ */
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(rustgram_server_util::DB))]
+pub struct FileExternalDeleteFailure
+{
+	pub part_id: PartId,
+	pub time: u128,
+	pub attempts: i32,
+	pub next_try: u128,
+	pub last_error: Option<String>,
+}
+
+//__________________________________________________________________________________________________
+
+pub const APP_STATS_PERIOD_DAY: i32 = 0;
+pub const APP_STATS_PERIOD_MONTH: i32 = 1;
+
+/**
+The usage of an app in one day or month, computed by the app stats worker.
+
+The actions are counted in the period.
+The storage, files, parts, groups and members are the values of the last rollup in the period.
+ */
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(rustgram_server_util::DB))]
+pub struct AppStats
+{
+	pub period_start: u128,
+	pub active_users: i32,
+	pub logins: i32,
+	pub refreshes: i32,
+	pub registrations: i32,
+	pub key_rotations: i32,
+	pub used_storage: i64,
+	pub file_count: i32,
+	pub part_count: i32,
+	pub group_count: i32,
+	pub member_count: i32,
+}
+
+/**
+The billable units of an app or a customer group in a period.
+
+- monthly_active_users is the peak of the active users of a month in the period
+- stored_bytes is the peak of the stored bytes in the period
+- transferred_bytes are the downloaded bytes of the period
+- key_rotation_members are the members of all rotated groups in the period
+ */
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(rustgram_server_util::DB))]
+pub struct AppMetering
+{
+	/**
+	The app id or the customer group id
+	 */
+	pub id: String,
+	pub monthly_active_users: i64,
+	pub stored_bytes: i64,
+	pub transferred_bytes: i64,
+	pub key_rotation_members: i64,
+}
+
 //__________________________________________________________________________________________________
 
 #[derive(Serialize, Deserialize)]
@@ -388,6 +665,13 @@ pub struct AppGroupOption
 {
 	pub max_key_rotation_month: i32,
 	pub min_rank_key_rotation: i32,
+	/**
+	1 = every group (and user group) needs sign keys and the public key must be signed with the verify key
+
+	Missing in the options of older clients, then the keys are not required to be signed
+	 */
+	#[serde(default)]
+	pub require_signed_keys: i32,
 }
 
 impl Default for AppGroupOption
@@ -397,6 +681,205 @@ impl Default for AppGroupOption
 		Self {
 			max_key_rotation_month: 100,
 			min_rank_key_rotation: 4,
+			require_signed_keys: 0,
 		}
 	}
 }
+
+//__________________________________________________________________________________________________
+
+/**
+Fail the request when the key endpoint of the customer is not reachable.
+
+The device keys are only stored at the key endpoint.
+ */
+pub const KEY_ENDPOINT_FALLBACK_NONE: i32 = 0;
+
+/**
+Keep a copy of the device keys in the sentc key store and use it when the key endpoint is not reachable.
+ */
+pub const KEY_ENDPOINT_FALLBACK_LOCAL: i32 = 1;
+
+pub const KEY_ENDPOINT_DEFAULT_TIMEOUT: i32 = 5000;
+pub const KEY_ENDPOINT_MAX_TIMEOUT: i32 = 30000;
+
+/**
+The customer hosted key endpoint.
+
+When an url is set, the api fetches and stores the device keys of the users at this endpoint.
+Every request is signed with the auth token (hmac sha256 over the timestamp and the body).
+
+The timeout is in ms.
+ */
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(rustgram_server_util::DB))]
+pub struct AppKeyEndpointOptionsInput
+{
+	pub key_endpoint_url: Option<String>,
+	pub auth_token: Option<String>,
+	pub timeout: i32,
+	pub fallback: i32,
+}
+
+impl Default for AppKeyEndpointOptionsInput
+{
+	fn default() -> Self
+	{
+		Self {
+			key_endpoint_url: None,
+			auth_token: None,
+			timeout: KEY_ENDPOINT_DEFAULT_TIMEOUT,
+			fallback: KEY_ENDPOINT_FALLBACK_LOCAL,
+		}
+	}
+}
+
+//__________________________________________________________________________________________________
+
+pub const RATE_LIMIT_GROUP_AUTH: i32 = 0;
+pub const RATE_LIMIT_GROUP_GROUP: i32 = 1;
+pub const RATE_LIMIT_GROUP_FILE: i32 = 2;
+pub const RATE_LIMIT_GROUP_KEY: i32 = 3;
+
+/**
+One bucket for every app token
+ */
+pub const RATE_LIMIT_BY_TOKEN: i32 = 0;
+
+/**
+One bucket for every user. Requests without a jwt use the bucket of the client ip.
+ */
+pub const RATE_LIMIT_BY_USER: i32 = 1;
+
+/**
+One bucket for every client ip
+ */
+pub const RATE_LIMIT_BY_IP: i32 = 2;
+
+pub const RATE_LIMIT_MAX_CAPACITY: i32 = 100_000;
+
+/**
+A token bucket for an endpoint group.
+
+The bucket holds at most capacity requests and gets refill_per_minute requests back every minute.
+A capacity of 0 disables the limit.
+ */
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct AppRateLimit
+{
+	pub capacity: i32,
+	pub refill_per_minute: i32,
+	pub limit_by: i32,
+}
+
+/**
+The rate limits of an app, grouped like the endpoints:
+- auth: register, login, refresh and the other user routes
+- group: the group routes
+- file: the file and file part routes
+- key: the key management and public key routes
+ */
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct AppRateLimitOptions
+{
+	pub auth: AppRateLimit,
+	pub group: AppRateLimit,
+	pub file: AppRateLimit,
+	pub key: AppRateLimit,
+}
+
+impl AppRateLimitOptions
+{
+	pub fn to_string(&self) -> serde_json::Result<String>
+	{
+		to_string(self)
+	}
+
+	pub fn get(&self, endpoint_group: i32) -> &AppRateLimit
+	{
+		match endpoint_group {
+			RATE_LIMIT_GROUP_GROUP => &self.group,
+			RATE_LIMIT_GROUP_FILE => &self.file,
+			RATE_LIMIT_GROUP_KEY => &self.key,
+			_ => &self.auth,
+		}
+	}
+
+	pub fn get_mut(&mut self, endpoint_group: i32) -> &mut AppRateLimit
+	{
+		match endpoint_group {
+			RATE_LIMIT_GROUP_GROUP => &mut self.group,
+			RATE_LIMIT_GROUP_FILE => &mut self.file,
+			RATE_LIMIT_GROUP_KEY => &mut self.key,
+			_ => &mut self.auth,
+		}
+	}
+}
+
+/**
+The rejected requests of an endpoint group in one day
+ */
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(rustgram_server_util::DB))]
+pub struct AppRateLimitThrottled
+{
+	pub endpoint_group: i32,
+	pub day: u128,
+	pub count: i64,
+}
+
+//__________________________________________________________________________________________________
+
+/**
+The version of the config document. Increase it when the document changes in a breaking way.
+ */
+pub const APP_CONFIG_VERSION: i32 = 1;
+
+/**
+The whole configuration of an app as a versioned document, e.g. to set up a staging and a production app with the same options.
+
+Tokens, jwt keys and the auth tokens of the own file storage and the key endpoint are never exported.
+When importing into an existing app, the auth tokens of the app are kept if the document contains none and the url is the same.
+ */
+#[derive(Serialize, Deserialize)]
+pub struct AppConfig
+{
+	pub version: i32,
+	pub options: AppOptions,
+	pub file_options: AppFileOptionsInput,
+	pub group_options: AppGroupOption,
+	pub key_endpoint_options: AppKeyEndpointOptionsInput,
+	pub access_options: AppAccessOptions,
+	pub rate_limit_options: AppRateLimitOptions,
+	/**
+	Missing in documents exported before the rotation options, then the rotation is off
+	 */
+	#[serde(default)]
+	pub jwt_rotation_options: AppJwtRotationOptions,
+}
+
+impl AppConfig
+{
+	pub fn to_string(&self) -> serde_json::Result<String>
+	{
+		to_string(self)
+	}
+}
+
+/**
+Create a new app from a config document
+ */
+#[derive(Serialize, Deserialize)]
+pub struct AppConfigCreateInput
+{
+	pub identifier: Option<String>,
+	pub config: AppConfig,
+}
+
+impl AppConfigCreateInput
+{
+	pub fn to_string(&self) -> serde_json::Result<String>
+	{
+		to_string(self)
+	}
+}
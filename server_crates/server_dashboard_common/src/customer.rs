@@ -186,3 +186,20 @@ pub struct CustomerGroupMemberFetch
 	pub group_member: Vec<GroupUserListItem>,
 	pub customer_data: Vec<CustomerList>,
 }
+
+/**
+A change of an app or a customer group.
+
+Before and after only contain the changed values, secrets like auth tokens are not included.
+ */
+#[derive(Serialize, Deserialize)]
+pub struct CustomerAuditLogItem
+{
+	pub id: String,
+	pub actor_id: CustomerId,
+	pub actor_email: Option<String>,
+	pub time: u128,
+	pub action: String,
+	pub before: Option<serde_json::Value>,
+	pub after: Option<serde_json::Value>,
+}